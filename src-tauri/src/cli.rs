@@ -0,0 +1,499 @@
+//! Headless batch conversion: `Pourdown --convert -o <out-dir> <input>...`.
+//!
+//! Runs the same importers as the `import_document` command, but from a
+//! script or cron job without opening the desktop window. Each input becomes
+//! `<out-dir>/<name>.md`, with any extracted images written to
+//! `<out-dir>/<name>.assets/` — the same sidecar layout the editor produces
+//! when an imported document is first saved (see `saveDocument` in
+//! `documentStore.ts`), so the output opens in the editor unchanged. Inputs
+//! that would share a name (`a/report.docx` and `b/report.docx`, or
+//! `report.docx` and `report.pdf`) get `-2`, `-3`, … appended rather than
+//! overwrite each other.
+//!
+//! Inputs may be files, directories (every importable file directly inside
+//! is converted, non-recursively) or wildcard patterns (`*`/`?` in the final
+//! path component), since shells on Windows don't expand globs themselves.
+//!
+//! Anything a converter dropped (see `convert::report`) is printed to stderr
+//! as a `warning:` line under the file it came from; warnings don't affect
+//! the exit status.
+//!
+//! Exit status: 0 when every input converted, 1 if any conversion failed
+//! (with a per-file summary on stderr), 2 for a usage error. Windows release
+//! builds use the GUI subsystem, so stdout/stderr are only visible when
+//! redirected (e.g. `Pourdown.exe --convert ... > convert.log 2>&1`); the
+//! exit code is unaffected.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::convert;
+use crate::convert::media::MediaSink;
+use crate::convert::progress::ImportProgress;
+use crate::convert::report::ImportReport;
+use crate::convert::{ConversionError, ImportOptions};
+
+const USAGE: &str = "Usage: Pourdown --convert -o <out-dir> <input>...
+
+Converts Word (.docx), spreadsheet (.xlsx/.xls/.ods), PDF and PowerPoint
+(.pptx) files to Markdown without opening the editor window.
+
+Each <input> may be a file, a directory (its importable files are converted)
+or a wildcard pattern such as 'reports/*.docx'. Every input is written to
+<out-dir>/<name>.md, with extracted images in <out-dir>/<name>.assets/;
+inputs sharing a name get -2, -3, ... appended.
+
+Options:
+  -o, --out-dir <dir>   Directory to write converted Markdown into (required)
+  -h, --help            Show this help";
+
+/// True if the process was launched in headless conversion mode, i.e. the
+/// first argument (after the program name) is `--convert`.
+pub fn is_convert_invocation(args: &[String]) -> bool {
+    args.first().map(|a| a == "--convert").unwrap_or(false)
+}
+
+/// Parsed `--convert` arguments.
+#[derive(Debug, PartialEq)]
+struct ConvertArgs {
+    out_dir: PathBuf,
+    inputs: Vec<String>,
+}
+
+/// Outcome of parsing the arguments that follow `--convert`.
+#[derive(Debug, PartialEq)]
+enum ParsedArgs {
+    Run(ConvertArgs),
+    Help,
+}
+
+fn parse_args(args: &[String]) -> Result<ParsedArgs, String> {
+    let mut out_dir: Option<PathBuf> = None;
+    let mut inputs: Vec<String> = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(ParsedArgs::Help),
+            "-o" | "--out-dir" => {
+                let dir = iter
+                    .next()
+                    .ok_or_else(|| format!("{} requires a directory argument", arg))?;
+                out_dir = Some(PathBuf::from(dir));
+            }
+            other if other.starts_with("--out-dir=") => {
+                out_dir = Some(PathBuf::from(&other["--out-dir=".len()..]));
+            }
+            other if other.starts_with('-') && other != "-" => {
+                return Err(format!("Unknown option: {}", other));
+            }
+            other => inputs.push(other.to_string()),
+        }
+    }
+
+    let out_dir = out_dir.ok_or_else(|| "Missing required --out-dir <dir>".to_string())?;
+    if inputs.is_empty() {
+        return Err("No input files given".to_string());
+    }
+    Ok(ParsedArgs::Run(ConvertArgs { out_dir, inputs }))
+}
+
+/// Run headless conversion for the arguments following `--convert` with the
+/// user's saved import `options`, and return the process exit code.
+pub fn run(args: &[String], options: &ImportOptions) -> i32 {
+    let parsed = match parse_args(args) {
+        Ok(ParsedArgs::Help) => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        Ok(ParsedArgs::Run(parsed)) => parsed,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    let mut failures: Vec<(String, String)> = Vec::new();
+    let mut inputs: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for input in &parsed.inputs {
+        match expand_input(input) {
+            Ok(paths) if paths.is_empty() => {
+                failures.push((input.clone(), "No matching files".to_string()));
+            }
+            Ok(paths) => {
+                for path in paths {
+                    // A file named twice (say by a pattern and by name) is
+                    // converted once.
+                    let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+                    if !inputs.iter().any(|(_, seen)| *seen == canonical) {
+                        inputs.push((path, canonical));
+                    }
+                }
+            }
+            Err(e) => failures.push((input.clone(), e)),
+        }
+    }
+
+    if let Err(e) = fs::create_dir_all(&parsed.out_dir) {
+        eprintln!(
+            "error: Failed to create output directory {}: {}",
+            parsed.out_dir.display(),
+            e
+        );
+        return 1;
+    }
+
+    let mut converted = 0usize;
+    let mut taken = HashSet::new();
+    for (input, _) in &inputs {
+        let name = match output_name(input, &mut taken) {
+            Ok(name) => name,
+            Err(e) => {
+                failures.push((input.display().to_string(), e.0));
+                continue;
+            }
+        };
+        match convert_file(input, &parsed.out_dir, &name, options) {
+            Ok((md_path, report)) => {
+                converted += 1;
+                println!("{} -> {}", input.display(), md_path.display());
+                for warning in &report.warnings {
+                    eprintln!("  warning: {}: {}", input.display(), warning);
+                }
+            }
+            Err(e) => failures.push((input.display().to_string(), e.0)),
+        }
+    }
+
+    if failures.is_empty() {
+        return 0;
+    }
+
+    eprintln!("\n{} converted, {} failed:", converted, failures.len());
+    for (input, message) in &failures {
+        // Some errors (e.g. the pdfium load diagnostics) span several lines;
+        // indent continuation lines so each failure stays visually grouped.
+        eprintln!(
+            "  {}: {}",
+            input,
+            message.trim_end().replace('\n', "\n      ")
+        );
+    }
+    1
+}
+
+/// The output name for `input`: its file stem, or the stem with `-2`, `-3`,
+/// … appended if an earlier input already took it. Names are compared
+/// case-insensitively, since the default Windows and macOS file systems
+/// would still have one overwrite the other.
+fn output_name(input: &Path, taken: &mut HashSet<String>) -> Result<String, ConversionError> {
+    let stem = input
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| ConversionError("Input file name is not valid UTF-8".to_string()))?;
+    let mut name = stem.to_string();
+    let mut n = 1;
+    while !taken.insert(name.to_lowercase()) {
+        n += 1;
+        name = format!("{}-{}", stem, n);
+    }
+    Ok(name)
+}
+
+/// Convert one input file into `<out_dir>/<name>.md` (+ `<name>.assets/`)
+/// and return the written Markdown path with the converter's report.
+fn convert_file(
+    input: &Path,
+    out_dir: &Path,
+    name: &str,
+    options: &ImportOptions,
+) -> Result<(PathBuf, ImportReport), ConversionError> {
+    let ext = input
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    // The extension is only a hint: the file's content decides the importer
+    // (see `convert::sniff`), so e.g. a PDF saved without an extension works.
+    let format = convert::registry()
+        .importer_for_extension(&ext)
+        .map(|c| c.id());
+    let path = input
+        .to_str()
+        .ok_or_else(|| ConversionError("Input path is not valid UTF-8".to_string()))?;
+
+    let sidecar_dir_name = format!("{}.assets", name);
+    let mut media = MediaSink::new(out_dir.join(&sidecar_dir_name))
+        .with_max_dimension(options.max_image_dimension);
+    let mut report = ImportReport::default();
+
+    let mut markdown = convert::import_to_markdown(
+        format,
+        path,
+        &mut media,
+        options,
+        &mut report,
+        &ImportProgress::default(),
+    )?;
+
+    // Converters emit `assets/...` links (the editor's unsaved staging
+    // layout); point them at the sidecar folder, as the first save does.
+    if !media.is_empty() {
+        markdown = relocate_asset_links(&markdown, &sidecar_dir_name);
+    }
+
+    let md_path = out_dir.join(format!("{}.md", name));
+    fs::write(&md_path, markdown)
+        .map_err(|e| ConversionError(format!("Failed to write {}: {}", md_path.display(), e)))?;
+    Ok((md_path, report))
+}
+
+/// Point the `assets/...` image links converters emit at `dir`: Markdown
+/// `](assets/` links and the `src="assets/` of `<img>` tags in HTML tables.
+/// Must stay in sync with `relocateAssetLinks` in src/lib/assetLinks.ts.
+fn relocate_asset_links(markdown: &str, dir: &str) -> String {
+    markdown
+        .replace("](assets/", &format!("]({}/", dir))
+        .replace("src=\"assets/", &format!("src=\"{}/", dir))
+}
+
+/// Resolve one command-line input to the files it names: a plain file as-is,
+/// a directory's importable files, or a wildcard pattern's matches. Results
+/// are sorted so batch output order is deterministic.
+fn expand_input(input: &str) -> Result<Vec<PathBuf>, String> {
+    let path = Path::new(input);
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+    if file_name.contains(['*', '?']) {
+        let dir = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let mut matches: Vec<PathBuf> = read_dir_files(&dir)?
+            .into_iter()
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| wildcard_match(file_name, n))
+                    .unwrap_or(false)
+            })
+            .collect();
+        matches.sort();
+        return Ok(matches);
+    }
+
+    if path.is_dir() {
+        let mut files: Vec<PathBuf> = read_dir_files(path)?
+            .into_iter()
+            .filter(|p| {
+                p.extension()
+                    .and_then(|e| e.to_str())
+                    .and_then(|e| convert::registry().importer_for_extension(e))
+                    .is_some()
+            })
+            .collect();
+        files.sort();
+        return Ok(files);
+    }
+
+    if !path.is_file() {
+        return Err("File not found".to_string());
+    }
+    Ok(vec![path.to_path_buf()])
+}
+
+/// Non-hidden regular files directly inside `dir`.
+fn read_dir_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+    Ok(entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            !p.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with('.'))
+                .unwrap_or(true)
+        })
+        .collect())
+}
+
+/// Shell-style wildcard match of a single path component: `*` matches any
+/// run of characters (including none), `?` exactly one. Case-sensitive, like
+/// a POSIX shell glob.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let n: Vec<char> = name.chars().collect();
+    let (mut pi, mut ni) = (0usize, 0usize);
+    // Position of the most recent `*` in the pattern, and the name index it
+    // was tried against, for backtracking.
+    let mut star: Option<(usize, usize)> = None;
+
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ni));
+            pi += 1;
+        } else if let Some((star_pi, star_ni)) = star {
+            pi = star_pi + 1;
+            ni = star_ni + 1;
+            star = Some((star_pi, star_ni + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_is_convert_invocation_requires_leading_flag() {
+        assert!(is_convert_invocation(&args(&["--convert", "a.docx"])));
+        assert!(!is_convert_invocation(&args(&["notes.md"])));
+        assert!(!is_convert_invocation(&args(&[])));
+    }
+
+    #[test]
+    fn test_parse_args_collects_inputs_and_out_dir() {
+        let parsed = parse_args(&args(&["-o", "out", "a.docx", "b/*.pdf"])).unwrap();
+        assert_eq!(
+            parsed,
+            ParsedArgs::Run(ConvertArgs {
+                out_dir: PathBuf::from("out"),
+                inputs: vec!["a.docx".to_string(), "b/*.pdf".to_string()],
+            })
+        );
+        let parsed = parse_args(&args(&["a.docx", "--out-dir=md"])).unwrap();
+        assert!(
+            matches!(parsed, ParsedArgs::Run(ConvertArgs { ref out_dir, .. }) if out_dir == Path::new("md"))
+        );
+    }
+
+    #[test]
+    fn test_parse_args_rejects_missing_out_dir_inputs_and_unknown_flags() {
+        assert!(parse_args(&args(&["a.docx"])).is_err());
+        assert!(parse_args(&args(&["-o", "out"])).is_err());
+        assert!(parse_args(&args(&["-o"])).is_err());
+        assert!(parse_args(&args(&["-o", "out", "--verbose", "a.docx"])).is_err());
+        assert_eq!(parse_args(&args(&["--help"])).unwrap(), ParsedArgs::Help);
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.docx", "report.docx"));
+        assert!(wildcard_match("*.docx", ".docx"));
+        assert!(!wildcard_match("*.docx", "report.docx.bak"));
+        assert!(wildcard_match("q?-*.xlsx", "q1-sales.xlsx"));
+        assert!(!wildcard_match("q?-*.xlsx", "q10-sales.xlsx"));
+        assert!(wildcard_match("*a*b", "xxaxxbxb"));
+        assert!(!wildcard_match("*.PDF", "scan.pdf"));
+    }
+
+    #[test]
+    fn test_relocate_asset_links() {
+        let md = "![Chart](assets/image1.png)\n\n<table>\n<tr><td><img src=\"assets/image2.png\" alt=\"\" /></td></tr>\n</table>\n";
+        assert_eq!(
+            relocate_asset_links(md, "report.assets"),
+            "![Chart](report.assets/image1.png)\n\n<table>\n<tr><td><img src=\"report.assets/image2.png\" alt=\"\" /></td></tr>\n</table>\n"
+        );
+    }
+
+    #[test]
+    fn test_convert_file_writes_markdown_and_sidecar_assets() {
+        let input = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.docx");
+        let out = std::env::temp_dir().join(format!("pourdown-cli-test-{}", std::process::id()));
+        fs::create_dir_all(&out).unwrap();
+
+        let (md_path, report) =
+            convert_file(Path::new(input), &out, "sample", &ImportOptions::default())
+                .expect("convert sample.docx");
+
+        assert_eq!(md_path, out.join("sample.md"));
+        let md = fs::read_to_string(&md_path).unwrap();
+        assert!(
+            md.contains("# Sample Heading"),
+            "markdown not written:\n{md}"
+        );
+        assert!(
+            md.contains("![](sample.assets/image1.png)"),
+            "image link not rewritten:\n{md}"
+        );
+        assert!(
+            out.join("sample.assets/image1.png").exists(),
+            "sidecar image missing"
+        );
+        assert_eq!(report.stats.images_written, 1);
+
+        let _ = fs::remove_dir_all(&out);
+    }
+
+    #[test]
+    fn test_convert_file_rejects_unsupported_content() {
+        let dir = std::env::temp_dir().join(format!("pourdown-cli-txt-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("notes.txt");
+        fs::write(&input, "just some notes\nnothing to import\n").unwrap();
+
+        let err = convert_file(&input, &dir, "notes", &ImportOptions::default()).unwrap_err();
+        assert!(err.0.contains("plain text"), "{}", err.0);
+        assert!(!dir.join("notes.md").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_output_name_renames_collisions() {
+        let mut taken = HashSet::new();
+        let name =
+            |path: &str, taken: &mut HashSet<String>| output_name(Path::new(path), taken).unwrap();
+        assert_eq!(name("a/report.docx", &mut taken), "report");
+        assert_eq!(name("b/report.docx", &mut taken), "report-2");
+        assert_eq!(name("Report.pdf", &mut taken), "Report-3");
+        assert_eq!(name("report-2.xlsx", &mut taken), "report-2-2");
+        assert_eq!(name("summary.pptx", &mut taken), "summary");
+    }
+
+    #[test]
+    fn test_run_keeps_same_named_inputs_apart() {
+        let dir = std::env::temp_dir().join(format!("pourdown-cli-names-{}", std::process::id()));
+        let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.docx");
+        for sub in ["a", "b"] {
+            fs::create_dir_all(dir.join(sub)).unwrap();
+            fs::copy(sample, dir.join(sub).join("report.docx")).unwrap();
+        }
+        let out = dir.join("out");
+        let arg = |p: PathBuf| p.to_str().unwrap().to_string();
+        let code = run(
+            &[
+                "-o".to_string(),
+                arg(out.clone()),
+                arg(dir.join("a/report.docx")),
+                arg(dir.join("b/report.docx")),
+                arg(dir.join("a/*.docx")),
+            ],
+            &ImportOptions::default(),
+        );
+
+        assert_eq!(code, 0);
+        assert!(out.join("report.md").exists());
+        assert!(out.join("report.assets/image1.png").exists());
+        let md = fs::read_to_string(out.join("report-2.md")).unwrap();
+        assert!(md.contains("![](report-2.assets/image1.png)"), "{md}");
+        assert!(out.join("report-2.assets/image1.png").exists());
+        // `a/*.docx` names a/report.docx again, which isn't converted twice.
+        assert!(!out.join("report-3.md").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
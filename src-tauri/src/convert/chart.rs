@@ -0,0 +1,441 @@
+//! DrawingML charts (`word/charts/chartN.xml`, `ppt/charts/chartN.xml`) to
+//! Markdown, for the DOCX and PPTX importers. A chart part caches the
+//! values it was last drawn with next to its formulas, so the data can be
+//! recovered without the embedded workbook: each chart becomes its title
+//! plus a GFM table of categories × series, and optionally a Mermaid
+//! `xychart-beta` or `pie` block that redraws it.
+
+use docx_rs::{EventReader, XmlEvent};
+use serde::{Deserialize, Serialize};
+
+use super::inline_fmt::escape_markdown;
+
+/// Chart import options shared by the docx and pptx importers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChartOptions {
+    /// Follow each chart's table with a Mermaid block drawing it (bar, line
+    /// and pie charts only).
+    pub mermaid: bool,
+}
+
+/// How a series is drawn, as far as Mermaid can redraw it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesKind {
+    Bar,
+    Line,
+    Pie,
+    /// Area, scatter, radar, bubble, surface: tabulated only.
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub name: String,
+    pub kind: SeriesKind,
+    /// Cached values by category index, as written (blank when missing).
+    pub values: Vec<String>,
+}
+
+/// The cached data of one chart part.
+#[derive(Debug, Clone, Default)]
+pub struct Chart {
+    pub title: Option<String>,
+    /// Category (or scatter x) labels, from the first series that has them.
+    pub categories: Vec<String>,
+    pub series: Vec<Series>,
+    /// A bar chart with horizontal bars (`c:barDir val="bar"`).
+    pub horizontal: bool,
+}
+
+fn series_kind(chart_element: &str) -> SeriesKind {
+    match chart_element {
+        "barChart" | "bar3DChart" => SeriesKind::Bar,
+        "lineChart" | "line3DChart" | "stockChart" => SeriesKind::Line,
+        "pieChart" | "pie3DChart" | "doughnutChart" | "ofPieChart" => SeriesKind::Pie,
+        _ => SeriesKind::Other,
+    }
+}
+
+/// Points read per series at most; an index past it (or past the cache's
+/// own `c:ptCount`) is dropped rather than allocated up to.
+const MAX_POINTS: usize = 100_000;
+
+/// Store `text` at point `idx` of `points`, growing it as needed.
+fn set_point(points: &mut Vec<String>, idx: usize, text: &str) {
+    if points.len() <= idx {
+        points.resize(idx + 1, String::new());
+    }
+    points[idx].push_str(text);
+}
+
+/// Parse a chart part's title, series names, categories and cached values.
+/// `None` if the XML can't be read or holds no series.
+pub fn parse_chart(xml: &str) -> Option<Chart> {
+    let mut parser = EventReader::new(xml.as_bytes());
+    let mut chart = Chart::default();
+    // Local names of the open elements.
+    let mut stack: Vec<String> = Vec::new();
+    let mut has_title = false;
+    let mut title = String::new();
+    let mut kind = SeriesKind::Other;
+    let mut series: Option<(Series, Vec<String>)> = None;
+    // The current `c:pt`'s index, `None` when it's out of range.
+    let mut point = Some(0);
+    let mut point_count = MAX_POINTS;
+    // `c:lvl` elements opened in the current multi-level category; only
+    // the first (innermost) level is kept.
+    let mut category_levels = 0;
+    loop {
+        match parser.next() {
+            Ok(XmlEvent::StartElement {
+                name, attributes, ..
+            }) => {
+                let attr = |key: &str| {
+                    attributes
+                        .iter()
+                        .find(|a| a.name.local_name == key)
+                        .map(|a| a.value.as_str())
+                };
+                let name = name.local_name;
+                let in_plot_area = stack.iter().any(|n| n == "plotArea");
+                match name.as_str() {
+                    // The chart's own title; axis titles sit in the plot area.
+                    "title" if !in_plot_area && stack.last().is_some_and(|n| n == "chart") => {
+                        has_title = true
+                    }
+                    n if in_plot_area && n.ends_with("Chart") => kind = series_kind(n),
+                    "barDir" => chart.horizontal = attr("val") == Some("bar"),
+                    "ser" => {
+                        series = Some((
+                            Series {
+                                name: String::new(),
+                                kind,
+                                values: Vec::new(),
+                            },
+                            Vec::new(),
+                        ))
+                    }
+                    "ptCount" => {
+                        point_count = attr("val")
+                            .and_then(|n| n.parse().ok())
+                            .map_or(MAX_POINTS, |n: usize| n.min(MAX_POINTS))
+                    }
+                    "pt" => {
+                        point = Some(attr("idx").and_then(|i| i.parse().ok()).unwrap_or(0))
+                            .filter(|&i| i < point_count)
+                    }
+                    "lvl" => category_levels += 1,
+                    // A line break between title paragraphs.
+                    "p" if !title.is_empty()
+                        && stack.iter().any(|n| n == "title")
+                        && !in_plot_area =>
+                    {
+                        title.push(' ')
+                    }
+                    _ => {}
+                }
+                stack.push(name);
+            }
+            Ok(XmlEvent::EndElement { .. }) => match stack.pop().as_deref() {
+                Some("ser") => {
+                    if let Some((series, categories)) = series.take() {
+                        if chart.categories.is_empty() {
+                            chart.categories = categories;
+                        }
+                        chart.series.push(series);
+                    }
+                }
+                Some("cat" | "xVal") => {
+                    category_levels = 0;
+                    point_count = MAX_POINTS;
+                }
+                Some("val" | "yVal" | "tx") => point_count = MAX_POINTS,
+                _ => {}
+            },
+            Ok(XmlEvent::Characters(text)) => {
+                let inside = |name: &str| stack.iter().any(|n| n == name);
+                let current = stack.last().map(String::as_str);
+                if let Some((series, categories)) = &mut series {
+                    if current != Some("v") {
+                        continue;
+                    }
+                    if inside("tx") {
+                        series.name.push_str(&text);
+                    } else if let Some(point) = point {
+                        if (inside("cat") || inside("xVal")) && category_levels <= 1 {
+                            set_point(categories, point, &text);
+                        } else if inside("val") || inside("yVal") {
+                            set_point(&mut series.values, point, &text);
+                        }
+                    }
+                } else if has_title
+                    && inside("title")
+                    && !inside("plotArea")
+                    && matches!(current, Some("t" | "v"))
+                {
+                    title.push_str(&text);
+                }
+            }
+            Ok(XmlEvent::EndDocument) => break,
+            Err(_) => return None,
+            _ => {}
+        }
+    }
+    if chart.series.is_empty() {
+        return None;
+    }
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    chart.title = match (has_title, title.is_empty()) {
+        (true, false) => Some(title),
+        // A title with no text of its own is the automatic title: the
+        // series name, shown for single-series charts.
+        (true, true) if chart.series.len() == 1 => Some(chart.series[0].name.clone()),
+        _ => None,
+    }
+    .filter(|t| !t.trim().is_empty());
+    Some(chart)
+}
+
+/// A cached value as displayed: numbers in their shortest form (Office
+/// writes `4.2999999999999998` for 4.3), anything else as-is.
+fn display_value(value: &str) -> String {
+    match value.trim().parse::<f64>() {
+        Ok(number) if number.is_finite() => number.to_string(),
+        _ => value.trim().to_string(),
+    }
+}
+
+fn table_cell(text: &str) -> String {
+    escape_markdown(&text.split_whitespace().collect::<Vec<_>>().join(" ")).replace('|', "\\|")
+}
+
+/// Render `chart` as its title in bold, a GFM table with a row per
+/// category and a column per series, and (per `options`) a Mermaid block.
+/// Blocks are separated by blank lines, with no trailing newline.
+pub fn chart_to_markdown(chart: &Chart, options: &ChartOptions) -> String {
+    let mut blocks = Vec::new();
+    if let Some(title) = &chart.title {
+        blocks.push(format!("**{}**", escape_markdown(title)));
+    }
+
+    let rows = chart
+        .series
+        .iter()
+        .map(|s| s.values.len())
+        .chain([chart.categories.len()])
+        .max()
+        .unwrap_or(0);
+    let mut table = String::from("| Category |");
+    for (i, series) in chart.series.iter().enumerate() {
+        let name = if series.name.trim().is_empty() {
+            format!("Series {}", i + 1)
+        } else {
+            series.name.clone()
+        };
+        table.push_str(&format!(" {} |", table_cell(&name)));
+    }
+    table.push_str(&format!("\n|{}", " --- |".repeat(chart.series.len() + 1)));
+    for row in 0..rows {
+        let category = chart.categories.get(row).filter(|c| !c.trim().is_empty());
+        let category = category.cloned().unwrap_or_else(|| (row + 1).to_string());
+        table.push_str(&format!("\n| {} |", table_cell(&category)));
+        for series in &chart.series {
+            let value = series
+                .values
+                .get(row)
+                .map(|v| display_value(v))
+                .unwrap_or_default();
+            table.push_str(&format!(" {} |", table_cell(&value)));
+        }
+    }
+    blocks.push(table);
+
+    if options.mermaid {
+        blocks.extend(mermaid(chart));
+    }
+    blocks.join("\n\n")
+}
+
+/// A quoted Mermaid string; Mermaid has no escape for `"`.
+fn mermaid_string(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        text.replace('"', "'")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    )
+}
+
+/// A Mermaid `pie` (pie and doughnut charts: the first series) or
+/// `xychart-beta` (bar and line series) block, or `None` when the chart
+/// has neither or a value isn't a number.
+fn mermaid(chart: &Chart) -> Option<String> {
+    let number = |value: &str| match value.trim() {
+        "" => Some(0.0),
+        v => v.parse::<f64>().ok().filter(|n| n.is_finite()),
+    };
+    let category = |i: usize| {
+        chart
+            .categories
+            .get(i)
+            .filter(|c| !c.trim().is_empty())
+            .cloned()
+            .unwrap_or_else(|| (i + 1).to_string())
+    };
+
+    let mut lines = Vec::new();
+    if chart.series.iter().all(|s| s.kind == SeriesKind::Pie) {
+        let series = chart.series.first()?;
+        lines.push(match &chart.title {
+            Some(title) => format!(
+                "pie title {}",
+                title.split_whitespace().collect::<Vec<_>>().join(" ")
+            ),
+            None => "pie".to_string(),
+        });
+        for (i, value) in series.values.iter().enumerate() {
+            lines.push(format!(
+                "    {} : {}",
+                mermaid_string(&category(i)),
+                number(value)?
+            ));
+        }
+    } else {
+        let plotted: Vec<_> = chart
+            .series
+            .iter()
+            .filter(|s| matches!(s.kind, SeriesKind::Bar | SeriesKind::Line))
+            .collect();
+        if plotted.is_empty() {
+            return None;
+        }
+        let points = plotted
+            .iter()
+            .map(|s| s.values.len())
+            .max()
+            .unwrap_or(0)
+            .max(chart.categories.len());
+        lines.push(
+            if chart.horizontal {
+                "xychart-beta horizontal"
+            } else {
+                "xychart-beta"
+            }
+            .to_string(),
+        );
+        if let Some(title) = &chart.title {
+            lines.push(format!("    title {}", mermaid_string(title)));
+        }
+        let categories: Vec<_> = (0..points).map(|i| mermaid_string(&category(i))).collect();
+        lines.push(format!("    x-axis [{}]", categories.join(", ")));
+        for series in plotted {
+            let mut values = Vec::new();
+            for i in 0..points {
+                values.push(number(series.values.get(i).map_or("", String::as_str))?.to_string());
+            }
+            let mark = if series.kind == SeriesKind::Bar {
+                "bar"
+            } else {
+                "line"
+            };
+            lines.push(format!("    {} [{}]", mark, values.join(", ")));
+        }
+    }
+    Some(format!("```mermaid\n{}\n```", lines.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A clustered column chart with a line series on top, in the shape
+    /// Office writes (`strRef`/`numRef` caches, an axis title that must not
+    /// be taken for the chart title).
+    const COMBO_CHART: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+<c:chart>
+<c:title><c:tx><c:rich><a:p><a:r><a:t>Sales</a:t></a:r></a:p><a:p><a:r><a:t>by quarter</a:t></a:r></a:p></c:rich></c:tx></c:title>
+<c:autoTitleDeleted val="0"/>
+<c:plotArea>
+<c:barChart><c:barDir val="col"/>
+<c:ser><c:idx val="0"/><c:tx><c:strRef><c:f>Sheet1!$B$1</c:f><c:strCache><c:ptCount val="1"/><c:pt idx="0"><c:v>North</c:v></c:pt></c:strCache></c:strRef></c:tx>
+<c:cat><c:strRef><c:f>Sheet1!$A$2:$A$4</c:f><c:strCache><c:ptCount val="3"/><c:pt idx="0"><c:v>Q1</c:v></c:pt><c:pt idx="1"><c:v>Q2</c:v></c:pt><c:pt idx="2"><c:v>Q3 | late</c:v></c:pt></c:strCache></c:strRef></c:cat>
+<c:val><c:numRef><c:f>Sheet1!$B$2:$B$4</c:f><c:numCache><c:formatCode>General</c:formatCode><c:ptCount val="3"/><c:pt idx="0"><c:v>4.2999999999999998</c:v></c:pt><c:pt idx="2"><c:v>3.5</c:v></c:pt></c:numCache></c:numRef></c:val>
+</c:ser>
+</c:barChart>
+<c:lineChart>
+<c:ser><c:idx val="1"/><c:tx><c:strRef><c:strCache><c:pt idx="0"><c:v>Target</c:v></c:pt></c:strCache></c:strRef></c:tx>
+<c:val><c:numRef><c:numCache><c:ptCount val="3"/><c:pt idx="0"><c:v>4</c:v></c:pt><c:pt idx="1"><c:v>4</c:v></c:pt><c:pt idx="2"><c:v>4</c:v></c:pt></c:numCache></c:numRef></c:val>
+</c:ser>
+</c:lineChart>
+<c:valAx><c:title><c:tx><c:rich><a:p><a:r><a:t>Units</a:t></a:r></a:p></c:rich></c:tx></c:title></c:valAx>
+</c:plotArea>
+</c:chart>
+</c:chartSpace>"#;
+
+    #[test]
+    fn test_parse_chart() {
+        let chart = parse_chart(COMBO_CHART).unwrap();
+        assert_eq!(chart.title.as_deref(), Some("Sales by quarter"));
+        assert_eq!(chart.categories, ["Q1", "Q2", "Q3 | late"]);
+        assert_eq!(chart.series.len(), 2);
+        assert_eq!(chart.series[0].name, "North");
+        assert_eq!(chart.series[0].kind, SeriesKind::Bar);
+        assert_eq!(chart.series[0].values, ["4.2999999999999998", "", "3.5"]);
+        assert_eq!(chart.series[1].kind, SeriesKind::Line);
+        assert!(!chart.horizontal);
+    }
+
+    #[test]
+    fn test_chart_to_markdown_table_and_mermaid() {
+        let chart = parse_chart(COMBO_CHART).unwrap();
+        let table = "**Sales by quarter**\n\n\
+            | Category | North | Target |\n| --- | --- | --- |\n\
+            | Q1 | 4.3 | 4 |\n| Q2 |  | 4 |\n| Q3 \\| late | 3.5 | 4 |";
+        assert_eq!(chart_to_markdown(&chart, &ChartOptions::default()), table);
+        assert_eq!(
+            chart_to_markdown(&chart, &ChartOptions { mermaid: true }),
+            format!(
+                "{}\n\n```mermaid\nxychart-beta\n    title \"Sales by quarter\"\n    \
+                 x-axis [\"Q1\", \"Q2\", \"Q3 | late\"]\n    bar [4.3, 0, 3.5]\n    line [4, 4, 4]\n```",
+                table
+            )
+        );
+    }
+
+    #[test]
+    fn test_pie_chart_automatic_title() {
+        let xml = r#"<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart">
+<c:chart><c:title/><c:plotArea><c:pieChart>
+<c:ser><c:tx><c:v>Share</c:v></c:tx>
+<c:cat><c:strLit><c:pt idx="0"><c:v>Mac</c:v></c:pt><c:pt idx="1"><c:v>PC</c:v></c:pt></c:strLit></c:cat>
+<c:val><c:numLit><c:pt idx="0"><c:v>30</c:v></c:pt><c:pt idx="1"><c:v>70</c:v></c:pt></c:numLit></c:val>
+</c:ser></c:pieChart></c:plotArea></c:chart></c:chartSpace>"#;
+        let chart = parse_chart(xml).unwrap();
+        assert_eq!(chart.title.as_deref(), Some("Share"));
+        assert!(chart_to_markdown(&chart, &ChartOptions { mermaid: true })
+            .ends_with("```mermaid\npie title Share\n    \"Mac\" : 30\n    \"PC\" : 70\n```"));
+    }
+
+    #[test]
+    fn test_parse_chart_drops_out_of_range_points() {
+        let xml = r#"<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart">
+<c:chart><c:plotArea><c:barChart>
+<c:ser><c:tx><c:v>Units</c:v></c:tx>
+<c:cat><c:strLit><c:ptCount val="2"/><c:pt idx="0"><c:v>A</c:v></c:pt><c:pt idx="5"><c:v>F</c:v></c:pt></c:strLit></c:cat>
+<c:val><c:numLit><c:pt idx="1"><c:v>2</c:v></c:pt><c:pt idx="4000000000"><c:v>9</c:v></c:pt></c:numLit></c:val>
+</c:ser></c:barChart></c:plotArea></c:chart></c:chartSpace>"#;
+        let chart = parse_chart(xml).unwrap();
+        assert_eq!(chart.categories, ["A"]);
+        assert_eq!(chart.series[0].values, ["", "2"]);
+    }
+
+    #[test]
+    fn test_parse_chart_without_series() {
+        let xml = r#"<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart"><c:chart/></c:chartSpace>"#;
+        assert!(parse_chart(xml).is_none());
+        assert!(parse_chart("<c:chartSpace").is_none());
+    }
+}
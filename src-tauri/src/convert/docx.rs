@@ -1,55 +1,351 @@
-use std::collections::HashMap;
-use std::io::{Cursor, Read};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, Read, Write};
 
 use docx_rs::{
-    read_docx, DocumentChild, Docx, Drawing, DrawingData, HyperlinkData, Paragraph,
-    ParagraphChild, Run, RunChild, StructuredDataTag, StructuredDataTagChild, Table,
-    TableCellContent, TableChild, TableRowChild,
+    read_docx, Comment, CommentChild, DeleteChild, DocumentChild, Docx, Drawing, DrawingData,
+    FieldCharType, FromXML, Header, HeaderChild, HyperlinkData, InsertChild, InstrToC, Level,
+    Paragraph, ParagraphChild, Run, RunChild, RunFonts, StructuredDataTag, StructuredDataTagChild,
+    StyleType, Table, TableCell, TableCellContent, TableCellProperty, TableChild, TableRowChild,
+    TextBoxContentChild,
 };
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
 
-use super::inline_fmt::{apply_inline_fmt, escape_markdown};
+use super::chart::{self, ChartOptions};
+use super::html::{markdown_inline_to_html, spanned_table_html, SpannedCell};
+use super::inline_fmt::{
+    apply_inline_fmt, escape_markdown, format_segments, InlineOptions, RunFormat, VertAlign,
+};
 use super::media::MediaSink;
-use super::ConversionError;
+use super::omml;
+use super::pdf::{escape_alt_text, is_caption_label};
+use super::pptx::xml_decode;
+use super::progress::ImportProgress;
+use super::report::{ImportReport, WarningKind};
+use super::{ConversionError, Converter, ImportOptions};
+
+/// How tracked changes (`w:ins` / `w:del`) are imported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackedChanges {
+    /// Import the document as if every change had been accepted.
+    #[default]
+    Accept,
+    /// Keep both sides as CriticMarkup: `{++inserted++}` / `{--deleted--}`.
+    CriticMarkup,
+}
+
+/// How review comments from `word/comments.xml` are imported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comments {
+    #[default]
+    Drop,
+    /// `{>>author: text<<}` right after the commented range.
+    CriticMarkup,
+    /// A `[^n]` footnote whose definition is `author: text`.
+    Footnotes,
+}
+
+/// What a Word table of contents (a `TOC` field) becomes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableOfContents {
+    /// An `<!-- Table of Contents omitted -->` comment.
+    #[default]
+    Placeholder,
+    /// A nested bulleted list of the imported headings (within the TOC's
+    /// `\o` level range), each linked to its heading's slug.
+    Regenerate,
+}
+
+/// The Markdown block a paragraph style maps to (see
+/// [`DocxOptions::style_map`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StyleBlock {
+    /// A plain paragraph, even if the style would otherwise be detected as
+    /// something else (e.g. by its outline level).
+    Paragraph,
+    Heading1,
+    Heading2,
+    Heading3,
+    Heading4,
+    Heading5,
+    Heading6,
+    /// Consecutive paragraphs are joined into one fenced code block.
+    Code,
+    /// A `>` blockquote.
+    Quote,
+    /// An italic paragraph.
+    Caption,
+}
+
+/// Word knobs in [`ImportOptions`]. The defaults import a mid-review draft
+/// as its accepted final text.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocxOptions {
+    pub tracked_changes: TrackedChanges,
+    pub comments: Comments,
+    /// Start the Markdown with a YAML front-matter block holding the
+    /// document properties (`docProps/core.xml` / `docProps/app.xml`).
+    pub front_matter: bool,
+    /// Paragraph style name (or style ID) → Markdown block, for custom
+    /// templates. Matched ignoring case, spaces, `-` and `_`, and checked
+    /// before the built-in mapping (`Code`, `Quote`, `Caption`, ...).
+    pub style_map: BTreeMap<String, StyleBlock>,
+    /// Append the distinct text of the page headers and footers
+    /// (`word/header*.xml` / `word/footer*.xml`), e.g. document IDs and
+    /// classification labels, after the body.
+    pub headers_footers: bool,
+    pub table_of_contents: TableOfContents,
+}
 
 /// Resolved image relationships for a DOCX: rId → `word/media/...` archive
-/// path, and the raw bytes of every `word/media/*` part.
+/// path, the raw bytes of every `word/media/*` part, rId → the alt text
+/// of the body's pictures (see `parse_image_alt_texts`), and rId → each
+/// chart's cached data. `rels` and `alt_texts` are those of the part being
+/// rendered; `part_images` holds the other parts' (see `in_part`).
 struct DocxMedia {
     rels: HashMap<String, String>,
     parts: HashMap<String, Vec<u8>>,
+    alt_texts: HashMap<String, String>,
+    charts: HashMap<String, chart::Chart>,
+    part_images: HashMap<String, PartImages>,
+}
+
+/// The picture relationships (rId → media path) and alt texts of one part
+/// other than `word/document.xml`, e.g. `word/header1.xml`.
+#[derive(Debug, Clone, Default)]
+struct PartImages {
+    rels: HashMap<String, String>,
+    alt_texts: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum NoteKind {
+    Footnote,
+    Endnote,
+    Comment,
+}
+
+impl NoteKind {
+    /// The part holding this kind of note.
+    fn part(self) -> &'static str {
+        match self {
+            NoteKind::Footnote => "word/footnotes.xml",
+            NoteKind::Endnote => "word/endnotes.xml",
+            NoteKind::Comment => "word/comments.xml",
+        }
+    }
+}
+
+struct Note {
+    /// Comment author, shown as an `author: ` prefix.
+    author: Option<String>,
+    paragraphs: Vec<Paragraph>,
+}
+
+/// Footnote/endnote bodies from `word/footnotes.xml` / `word/endnotes.xml`
+/// and comments from `word/comments.xml`, plus the order the document
+/// references them in — a note's position in `referenced` (1-based) is its
+/// `[^n]` label, so all notes share one sequence in reading order.
+#[derive(Default)]
+struct DocxNotes {
+    bodies: HashMap<(NoteKind, i64), Note>,
+    referenced: Vec<(NoteKind, i64)>,
+}
+
+impl DocxNotes {
+    fn add_comments(&mut self, comments: &[Comment]) {
+        for comment in comments {
+            let paragraphs = comment
+                .children
+                .iter()
+                .filter_map(|child| match child {
+                    CommentChild::Paragraph(para) => Some(para.clone()),
+                    CommentChild::Table(_) => None,
+                })
+                .collect();
+            let note = Note {
+                author: Some(comment.author.clone()),
+                paragraphs,
+            };
+            self.bodies
+                .insert((NoteKind::Comment, comment.id as i64), note);
+        }
+    }
+
+    fn label(&mut self, note: (NoteKind, i64)) -> usize {
+        match self.referenced.iter().position(|n| *n == note) {
+            Some(i) => i + 1,
+            None => {
+                self.referenced.push(note);
+                self.referenced.len()
+            }
+        }
+    }
+}
+
+/// Everything the block/run converters below need besides the node they're
+/// converting: document-wide lookups built once up front, and the sinks
+/// they write media and warnings into.
+struct DocxContext<'a> {
+    options: &'a DocxOptions,
+    inline: &'a InlineOptions,
+    charts: &'a ChartOptions,
+    numbering: ListNumbering,
+    docx_media: DocxMedia,
+    notes: DocxNotes,
+    /// Normalized paragraph style ID (see `style_key`) → the block it maps
+    /// to, for every style that maps to one.
+    style_blocks: HashMap<String, StyleBlock>,
+    anchors: HeadingAnchors,
+    /// How many table cells, notes or headers/footers deep the conversion
+    /// is. Headings count for anchors only at the top, where they're
+    /// headings in the Markdown too.
+    nested: usize,
+    toc_field: TocField,
+    /// Equations lifted out of `word/document.xml`, indexed by their `m`
+    /// placeholder.
+    equations: Vec<Equation>,
+    media: &'a mut MediaSink,
+    report: &'a mut ImportReport,
+}
+
+/// Placeholder text standing in for markup docx-rs drops when reading (see
+/// `lift_unsupported_markup`): a Private Use Area pair around a short tag,
+/// e.g. `\u{E000}f3\u{E001}` for footnote 3. It passes through
+/// `escape_markdown` untouched and is swapped for real Markdown by
+/// `resolve_placeholders` once a paragraph is assembled.
+const PLACEHOLDER_OPEN: char = '\u{E000}';
+const PLACEHOLDER_CLOSE: char = '\u{E001}';
+
+/// Word (`.docx`) importer registered in [`super::FormatRegistry`].
+pub struct DocxConverter;
+
+impl Converter for DocxConverter {
+    fn id(&self) -> &'static str {
+        "docx"
+    }
+    fn name(&self) -> &'static str {
+        "Word Document"
+    }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["docx"]
+    }
+    fn mime_types(&self) -> &'static [&'static str] {
+        &["application/vnd.openxmlformats-officedocument.wordprocessingml.document"]
+    }
+    fn to_markdown(
+        &self,
+        path: &str,
+        media: &mut MediaSink,
+        options: &ImportOptions,
+        report: &mut ImportReport,
+        progress: &ImportProgress,
+    ) -> Result<String, ConversionError> {
+        docx_to_markdown(path, media, options, report, progress)
+    }
 }
 
 /// Convert a DOCX file to Markdown text.
 ///
-/// Known limitations (by design, not surfaced as errors):
-/// - Track changes, comments, footnotes are dropped
-/// - Complex layouts (text boxes, columns) may have scrambled order
+/// Footnote and endnote references become `[^n]` markers (numbered in
+/// reading order) with their definitions appended at the end. Equations
+/// become LaTeX math, `$…$` inline or `$$…$$` for a display equation. Tracked
+/// changes are accepted and comments dropped unless [`DocxOptions`] asks
+/// for CriticMarkup (or footnotes, for comments).
+///
+/// Known limitations (by design, not surfaced as errors — accepted changes,
+/// dropped comments and skipped images are recorded in `report`):
+/// - Text boxes are placed after the paragraph they're anchored to, and
+///   multi-column layouts read column by column, so floating content may
+///   not sit exactly where it appears on the page
+/// - Page headers/footers are left out unless [`DocxOptions`] asks for
+///   them
 /// - TOC fields are replaced with an HTML comment placeholder, but the TOC
 ///   entries themselves (rendered as paragraphs of internal-anchor
 ///   hyperlinks) are preserved as Markdown anchor links (`[text](#anchor)`),
-///   matching MarkItDown's import output. The anchors won't resolve against
-///   Markdown's auto-generated heading slugs, but are kept for fidelity.
-/// - Vector image formats (EMF/WMF) can't render in the webview; a text note
-///   is emitted in their place
-pub fn docx_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, ConversionError> {
+///   matching MarkItDown's import output. [`TableOfContents::Regenerate`]
+///   rebuilds the TOC from the imported headings instead. Anchors naming a
+///   bookmark on a heading are rewritten to that heading's slug (see
+///   `HeadingAnchors`), as are `REF` cross-references; other bookmarks are
+///   kept as-is.
+/// - Pictures the webview can't render are converted by `MediaSink` (EMF/WMF
+///   to SVG, TIFF to PNG); a text note replaces any it can't convert
+pub fn docx_to_markdown(
+    path: &str,
+    media: &mut MediaSink,
+    options: &ImportOptions,
+    report: &mut ImportReport,
+    progress: &ImportProgress,
+) -> Result<String, ConversionError> {
     let bytes =
         std::fs::read(path).map_err(|e| ConversionError(format!("Failed to read file: {}", e)))?;
+    report_dropped_parts(&bytes, &options.docx, report);
+    let (bytes, equations) = lift_unsupported_markup(bytes);
 
-    let docx = read_docx(&bytes)
-        .map_err(|e| ConversionError(format!("Failed to parse DOCX: {:?}", e)))?;
+    let docx =
+        read_docx(&bytes).map_err(|e| ConversionError(format!("Failed to parse DOCX: {:?}", e)))?;
 
-    // Build numId -> is_ordered map from the document's numbering definitions.
-    let num_map = build_numbering_map(&docx);
-
-    // Resolve embedded image relationships by reopening the raw ZIP.
-    let docx_media = load_docx_media(&bytes);
+    let mut notes = load_docx_notes(&bytes);
+    notes.add_comments(docx.comments.inner());
+    let mut ctx = DocxContext {
+        options: &options.docx,
+        inline: &options.inline,
+        charts: &options.charts,
+        numbering: ListNumbering::new(&docx),
+        // Resolve embedded image relationships by reopening the raw ZIP.
+        docx_media: load_docx_media(&bytes),
+        notes,
+        style_blocks: build_style_blocks(&docx, &options.docx),
+        anchors: HeadingAnchors::default(),
+        nested: 0,
+        toc_field: TocField::default(),
+        equations,
+        media,
+        report,
+    };
 
     let mut output = String::new();
+    if options.docx.front_matter {
+        output.push_str(&front_matter(&document_properties(&bytes)));
+    }
     let mut first_block = true;
 
-    for child in &docx.document.children {
+    // Lines of the code block being collected from consecutive code-styled
+    // paragraphs, flushed by the first block that isn't one, and the note
+    // references and the like lifted out of them.
+    let mut code_lines: Vec<String> = Vec::new();
+    let mut code_refs = String::new();
+
+    let total = docx.document.children.len();
+    for (done, child) in docx.document.children.iter().enumerate() {
+        progress.step(done, total)?;
+        if let DocumentChild::Paragraph(para) = child {
+            if paragraph_block(para, &ctx) == Some(StyleBlock::Code) {
+                let (code, refs) = code_text(para, &mut ctx);
+                code_lines.push(code);
+                code_refs.push_str(&refs);
+                continue;
+            }
+        }
+        push_code_block(
+            &mut output,
+            &mut first_block,
+            &mut code_lines,
+            &mut code_refs,
+        );
         match child {
             DocumentChild::Paragraph(para) => {
-                let md = paragraph_to_markdown(para, &num_map, &docx_media, media);
+                let md = match toc_field_paragraph(para, &mut ctx) {
+                    TocParagraph::Outside => paragraph_to_markdown(para, &mut ctx),
+                    TocParagraph::Start(levels) => ctx.anchors.toc_placeholder(levels),
+                    TocParagraph::Inside => continue,
+                };
+                let md = with_caption_alt(md, &docx.document.children, done);
                 if md.trim().is_empty() {
                     if !first_block {
                         output.push('\n');
@@ -67,12 +363,14 @@ pub fn docx_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, Con
                 if !first_block {
                     output.push('\n');
                 }
-                output.push_str(&table_to_markdown(table, &num_map, &docx_media, media));
+                output.push_str(&table_to_markdown(table, &mut ctx));
                 output.push('\n');
                 first_block = false;
+                // A bookmark in the table doesn't mark the heading after it.
+                ctx.anchors.body_text();
             }
             DocumentChild::StructuredDataTag(sdt) => {
-                let md = sdt_to_markdown(sdt, &num_map, &docx_media, media);
+                let md = sdt_to_markdown(sdt, &mut ctx);
                 if !md.trim().is_empty() {
                     if !first_block {
                         output.push('\n');
@@ -81,26 +379,1001 @@ pub fn docx_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, Con
                     first_block = false;
                 }
             }
-            DocumentChild::TableOfContents(_) => {
+            DocumentChild::TableOfContents(toc) => {
                 if !first_block {
                     output.push('\n');
                 }
-                output.push_str("<!-- Table of Contents omitted -->\n");
+                match ctx.options.table_of_contents {
+                    TableOfContents::Placeholder => output.push_str(TOC_PLACEHOLDER),
+                    TableOfContents::Regenerate => {
+                        let levels = toc.instr.heading_styles_range.unwrap_or(ALL_TOC_LEVELS);
+                        output.push_str(&ctx.anchors.toc_placeholder(levels));
+                        output.push('\n');
+                    }
+                }
                 first_block = false;
             }
+            DocumentChild::BookmarkStart(bookmark) => ctx.anchors.bookmark(&bookmark.name),
+            _ => {}
+        }
+    }
+    progress.step(total, total)?;
+    push_code_block(
+        &mut output,
+        &mut first_block,
+        &mut code_lines,
+        &mut code_refs,
+    );
+
+    if ctx.options.headers_footers {
+        let headers_footers = headers_footers_to_markdown(&bytes, &mut ctx);
+        if !headers_footers.is_empty() {
+            if !first_block {
+                output.push('\n');
+            }
+            output.push_str("---\n");
+            for (kind, text) in &headers_footers {
+                output.push_str(&format!("\n**{}:** {}\n", kind, text));
+            }
+            first_block = false;
+        }
+    } else {
+        // Rendering them would extract their images and number their
+        // lists, so only check whether there was any text to leave out.
+        let with_text = header_footer_parts(&bytes)
+            .iter()
+            .filter(|(_, name)| {
+                read_zip_part(&bytes, name)
+                    .is_some_and(|xml| element_positions(&xml, "w:t").next().is_some())
+            })
+            .count();
+        if with_text > 0 {
+            ctx.report.warn(
+                WarningKind::HeadersFootersRemoved,
+                None,
+                format!("{} page header/footer parts were left out", with_text),
+            );
+        }
+    }
+
+    let definitions = notes_to_markdown(&mut ctx);
+    if !definitions.is_empty() {
+        if !first_block {
+            output.push('\n');
+        }
+        output.push_str(&definitions);
+    }
+
+    Ok(ctx.anchors.rewrite_links(&output))
+}
+
+const TOC_PLACEHOLDER: &str = "<!-- Table of Contents omitted -->\n";
+
+/// Heading levels a TOC without a `\o "1-3"` switch covers.
+const ALL_TOC_LEVELS: (usize, usize) = (1, 9);
+
+/// Bookmarks resolved to the slug of the heading they mark, so internal
+/// links (TOC entries, cross-references) can point at the anchor the
+/// editor derives from the heading text instead of at a Word bookmark name
+/// like `_Toc181806136` that doesn't exist in Markdown. Also keeps every
+/// heading for regenerating tables of contents.
+#[derive(Default)]
+struct HeadingAnchors {
+    /// Bookmarks started since the last paragraph with text, waiting for
+    /// the paragraph they mark.
+    pending: Vec<String>,
+    /// Bookmark name → heading slug.
+    slugs: HashMap<String, String>,
+    /// Slugs handed out so far → how many times, for de-duplication.
+    used: HashMap<String, usize>,
+    /// Every heading in order, as (level, plain text, slug).
+    headings: Vec<(usize, String, String)>,
+    /// The `\o` heading-level range of each table of contents to
+    /// regenerate, indexed by its `t` placeholder.
+    tocs: Vec<(usize, usize)>,
+}
+
+impl HeadingAnchors {
+    fn bookmark(&mut self, name: &str) {
+        // `_GoBack` is Word's "last edit position", not a link target.
+        if name != "_GoBack" {
+            self.pending.push(name.to_string());
+        }
+    }
+
+    /// Record a level-`level` heading with plain text `text`: it takes the
+    /// pending bookmarks, and its slug is counted for later duplicates.
+    fn heading(&mut self, level: usize, text: &str) {
+        let slug = heading_slug(text, &mut self.used);
+        for name in self.pending.drain(..) {
+            self.slugs.insert(name, slug.clone());
+        }
+        self.headings.push((level, text.trim().to_string(), slug));
+    }
+
+    /// A placeholder for a regenerated table of contents over heading
+    /// `levels`, to be put on a line of its own; the headings aren't all
+    /// known yet, so `rewrite_links` fills it in.
+    fn toc_placeholder(&mut self, levels: (usize, usize)) -> String {
+        self.tocs.push(levels);
+        format!(
+            "{}t{}{}",
+            PLACEHOLDER_OPEN,
+            self.tocs.len() - 1,
+            PLACEHOLDER_CLOSE
+        )
+    }
+
+    /// A nested bulleted list linking every heading within `levels`, e.g.
+    /// `- [Intro](#intro)` with subsections indented below. Falls back to
+    /// the placeholder comment when there are no such headings.
+    fn table_of_contents(&self, (first, last): (usize, usize)) -> String {
+        let headings: Vec<_> = self
+            .headings
+            .iter()
+            .filter(|(level, text, _)| (first..=last).contains(level) && !text.is_empty())
+            .collect();
+        let Some(top) = headings.iter().map(|(level, _, _)| *level).min() else {
+            return TOC_PLACEHOLDER.to_string();
+        };
+        let mut out = String::new();
+        let mut depth = 0;
+        for (i, (level, text, slug)) in headings.into_iter().enumerate() {
+            // Never nest more than one step below the previous entry, so a
+            // heading that skips a level still lands in a valid sublist.
+            depth = if i == 0 {
+                0
+            } else {
+                (level - top).min(depth + 1)
+            };
+            out.push_str(&format!(
+                "{}- [{}](#{})\n",
+                "  ".repeat(depth),
+                escape_markdown(text),
+                slug
+            ));
+        }
+        out
+    }
+
+    /// A paragraph that isn't a heading: its bookmarks stay unresolved.
+    fn body_text(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Point every `](#bookmark)` link in `markdown` at its heading's slug,
+    /// and fill in the regenerated tables of contents. Links to bookmarks
+    /// that don't mark a heading are left as they are.
+    fn rewrite_links(&self, markdown: &str) -> String {
+        let mut markdown = markdown.to_string();
+        for (i, levels) in self.tocs.iter().enumerate() {
+            let placeholder = format!("{}t{}{}\n", PLACEHOLDER_OPEN, i, PLACEHOLDER_CLOSE);
+            markdown = markdown.replacen(&placeholder, &self.table_of_contents(*levels), 1);
+        }
+        if self.slugs.is_empty() || !markdown.contains("](#") {
+            return markdown;
+        }
+        let markdown = markdown.as_str();
+        let mut out = String::with_capacity(markdown.len());
+        let mut rest = markdown;
+        while let Some(pos) = rest.find("](#") {
+            let target_start = pos + "](#".len();
+            out.push_str(&rest[..target_start]);
+            rest = &rest[target_start..];
+            if let Some(end) = rest.find(')') {
+                if let Some(slug) = self.slugs.get(&rest[..end]) {
+                    out.push_str(slug);
+                    rest = &rest[end..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// The anchor slug for a heading, the way GitHub (and the editor) derive
+/// it: lowercased, punctuation other than `-` and `_` removed, spaces
+/// turned into `-`, and `-1`, `-2`, ... appended to repeats. `used` carries
+/// the slugs handed out so far.
+fn heading_slug(text: &str, used: &mut HashMap<String, usize>) -> String {
+    let base: String = text
+        .trim()
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect();
+    let mut slug = base.clone();
+    while used.contains_key(&slug) {
+        let count = used.entry(base.clone()).or_insert(0);
+        *count += 1;
+        slug = format!("{}-{}", base, count);
+    }
+    used.insert(slug.clone(), 0);
+    slug
+}
+
+/// The plain text of a paragraph as the editor shows it, for its heading
+/// slug: no Markdown escapes or emphasis, and no footnote markers.
+fn paragraph_plain_text(para: &Paragraph) -> String {
+    let mut text = String::new();
+    let mut push_run = |run: &Run| {
+        for child in &run.children {
+            match child {
+                RunChild::Text(t) => text.push_str(&t.text),
+                RunChild::Tab(_) => text.push(' '),
+                _ => {}
+            }
+        }
+    };
+    for child in &para.children {
+        match child {
+            ParagraphChild::Run(run) => push_run(run),
+            ParagraphChild::Insert(insert) => {
+                for c in &insert.children {
+                    if let InsertChild::Run(run) = c {
+                        push_run(run);
+                    }
+                }
+            }
+            ParagraphChild::Hyperlink(hyperlink) => {
+                for c in &hyperlink.children {
+                    if let ParagraphChild::Run(run) = c {
+                        push_run(run);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    // Drop lifted placeholders (footnote references, equations).
+    let mut plain = String::with_capacity(text.len());
+    let mut in_placeholder = false;
+    for c in text.chars() {
+        match c {
+            PLACEHOLDER_OPEN => in_placeholder = true,
+            PLACEHOLDER_CLOSE => in_placeholder = false,
+            c if !in_placeholder => plain.push(c),
+            _ => {}
+        }
+    }
+    plain
+}
+
+/// Tracks a complex field (`w:fldChar` begin/separate/end) across the runs
+/// of a paragraph, so the displayed result of a `REF` cross-reference or a
+/// `HYPERLINK \l` field can be turned into an internal link.
+#[derive(Default)]
+enum FieldState {
+    #[default]
+    Outside,
+    /// Between begin and separate, collecting the instruction text.
+    Instruction(String),
+    /// Between separate and end: the field's target bookmark, if it's a
+    /// link, and where its result segments start.
+    Result {
+        target: Option<String>,
+        start: usize,
+    },
+}
+
+impl FieldState {
+    /// Update the state for `run`, which has already been appended to
+    /// `segments`; at a link field's end its result segments are replaced
+    /// by one `[result](#bookmark)` segment.
+    fn observe(&mut self, run: &Run, segments: &mut Vec<(String, RunFormat)>) {
+        for child in &run.children {
+            match child {
+                RunChild::FieldChar(field_char) => match field_char.field_char_type {
+                    FieldCharType::Begin => *self = FieldState::Instruction(String::new()),
+                    FieldCharType::Separate => {
+                        if let FieldState::Instruction(instruction) = self {
+                            *self = FieldState::Result {
+                                target: field_link_target(instruction),
+                                start: segments.len(),
+                            };
+                        }
+                    }
+                    FieldCharType::End => {
+                        if let FieldState::Result {
+                            target: Some(target),
+                            start,
+                        } = std::mem::take(self)
+                        {
+                            let result: String = segments
+                                .drain(start.min(segments.len())..)
+                                .map(|seg| seg.0)
+                                .collect();
+                            if !result.is_empty() {
+                                segments.push((
+                                    format!("[{}](#{})", result, target),
+                                    RunFormat::default(),
+                                ));
+                            }
+                        }
+                        *self = FieldState::Outside;
+                    }
+                    _ => {}
+                },
+                RunChild::InstrTextString(text) => {
+                    if let FieldState::Instruction(instruction) = self {
+                        instruction.push_str(text);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The bookmark a field instruction links to: `REF _Ref123 \h` or
+/// `HYPERLINK \l "_Toc456"`.
+fn field_link_target(instruction: &str) -> Option<String> {
+    let mut words = instruction.split_whitespace();
+    let target = match words.next()?.to_uppercase().as_str() {
+        "REF" => words.next()?,
+        "HYPERLINK" => {
+            words.by_ref().find(|word| *word == "\\l")?;
+            words.next()?
+        }
+        _ => return None,
+    };
+    let target = target.trim_matches('"');
+    (!target.is_empty()).then(|| target.to_string())
+}
+
+/// Where a paragraph stands relative to a Word `TOC` field, whose cached
+/// entries span several paragraphs.
+enum TocParagraph {
+    Outside,
+    /// The field begins here; it covers these heading levels.
+    Start((usize, usize)),
+    Inside,
+}
+
+/// Tracks `TOC` fields across paragraphs so `TableOfContents::Regenerate`
+/// can replace Word's cached entries (which may be stale) with the
+/// imported headings.
+#[derive(Default)]
+struct TocField {
+    /// Instruction of a top-level field begun but not yet separated.
+    instruction: Option<String>,
+    /// Fields open inside the TOC field, itself included; 0 outside one.
+    depth: usize,
+}
+
+impl TocField {
+    fn observe(&mut self, run: &Run, start: &mut Option<(usize, usize)>) {
+        for child in &run.children {
+            match child {
+                RunChild::FieldChar(field_char) => match field_char.field_char_type {
+                    FieldCharType::Begin if self.depth > 0 => self.depth += 1,
+                    FieldCharType::Begin => self.instruction = Some(String::new()),
+                    FieldCharType::Separate => {
+                        let Some(instruction) = self.instruction.take() else {
+                            continue;
+                        };
+                        let mut words = instruction.split_whitespace();
+                        if words
+                            .next()
+                            .is_some_and(|word| word.eq_ignore_ascii_case("TOC"))
+                        {
+                            let instr = instruction.parse::<InstrToC>().unwrap_or_default();
+                            *start = Some(instr.heading_styles_range.unwrap_or(ALL_TOC_LEVELS));
+                            self.depth = 1;
+                        }
+                    }
+                    FieldCharType::End => {
+                        self.instruction = None;
+                        self.depth = self.depth.saturating_sub(1);
+                    }
+                    _ => {}
+                },
+                RunChild::InstrTextString(text) => {
+                    if let Some(instruction) = &mut self.instruction {
+                        instruction.push_str(text);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Classify `para` against the `TOC` field being tracked. Only done when
+/// regenerating tables of contents; otherwise the cached entries are
+/// imported like any other paragraphs.
+fn toc_field_paragraph(para: &Paragraph, ctx: &mut DocxContext) -> TocParagraph {
+    if ctx.options.table_of_contents != TableOfContents::Regenerate {
+        return TocParagraph::Outside;
+    }
+    let inside = ctx.toc_field.depth > 0;
+    let mut start = None;
+    for child in &para.children {
+        match child {
+            ParagraphChild::Run(run) => ctx.toc_field.observe(run, &mut start),
+            ParagraphChild::Hyperlink(hyperlink) => {
+                for c in &hyperlink.children {
+                    if let ParagraphChild::Run(run) = c {
+                        ctx.toc_field.observe(run, &mut start);
+                    }
+                }
+            }
             _ => {}
         }
     }
+    match (inside, start) {
+        (false, Some(levels)) => TocParagraph::Start(levels),
+        (false, None) => TocParagraph::Outside,
+        (true, _) => TocParagraph::Inside,
+    }
+}
+
+/// An `m:oMath` (inline) or `m:oMathPara` (display) equation translated to
+/// LaTeX, without its `$` delimiters.
+struct Equation {
+    latex: String,
+    display: bool,
+}
+
+/// docx-rs's reader silently drops some run content we need to keep (the
+/// `w:footnoteReference`/`w:endnoteReference`/`w:commentReference` markers,
+/// Office Math equations and chart drawings) and hides deleted text
+/// (`w:delText`) behind a private field. Rewrite `word/document.xml` so
+/// references, equations and charts become placeholder text docx-rs does
+/// keep, resolved later by
+/// `resolve_placeholders`, and deleted text reads as ordinary `w:t` inside
+/// its `w:del`. Returns the input unchanged if there's nothing to rewrite
+/// or the archive can't be rebuilt.
+fn lift_unsupported_markup(bytes: Vec<u8>) -> (Vec<u8>, Vec<Equation>) {
+    let Some(document_xml) = read_zip_part(&bytes, "word/document.xml") else {
+        return (bytes, Vec::new());
+    };
+    let mut equations = Vec::new();
+    let lifted = lift_equations(&document_xml, &mut equations);
+    let lifted = lift_deleted_text(&lift_note_references(&lift_charts(&lifted)));
+    if lifted == document_xml {
+        return (bytes, equations);
+    }
+    match replace_zip_part(&bytes, "word/document.xml", &lifted) {
+        Some(rebuilt) => (rebuilt, equations),
+        None => (bytes, Vec::new()),
+    }
+}
+
+/// Replace each equation (a paragraph-level `m:oMathPara`, then any
+/// remaining inline `m:oMath`) with a run holding an `m{index}`
+/// placeholder into `equations`. Equations that can't be parsed are
+/// dropped, as docx-rs would have done.
+fn lift_equations(xml: &str, equations: &mut Vec<Equation>) -> String {
+    let mut lift = |element: &str, display: bool| match omml::omml_to_latex(element) {
+        Some(latex) if !latex.is_empty() => {
+            equations.push(Equation { latex, display });
+            format!(
+                "<w:r><w:t>{}m{}{}</w:t></w:r>",
+                PLACEHOLDER_OPEN,
+                equations.len() - 1,
+                PLACEHOLDER_CLOSE
+            )
+        }
+        _ => String::new(),
+    };
+    let lifted = replace_elements(xml, "m:oMathPara", |element| lift(element, true));
+    replace_elements(&lifted, "m:oMath", |element| lift(element, false))
+}
+
+/// Replace each drawing that holds a chart (`c:chart r:id="rId5"`) with a
+/// `g{rId}` placeholder; `DocxMedia::charts` has the chart it points to.
+fn lift_charts(xml: &str) -> String {
+    replace_elements(xml, "w:drawing", |drawing| {
+        // A text box's drawing holds the drawings in its text: lift the
+        // charts among those and keep the text box.
+        if element_positions(drawing, "w:drawing").nth(1).is_some() {
+            let inner = drawing.find('>').map_or(drawing.len(), |i| i + 1);
+            return format!("{}{}", &drawing[..inner], lift_charts(&drawing[inner..]));
+        }
+        let chart = drawing.find("<c:chart ").map(|i| &drawing[i..]);
+        match chart.and_then(|chart| get_rels_attr(chart, "r:id")) {
+            Some(id) => format!(
+                "<w:t>{}g{}{}</w:t>",
+                PLACEHOLDER_OPEN, id, PLACEHOLDER_CLOSE
+            ),
+            None => drawing.to_string(),
+        }
+    })
+}
+
+fn lift_note_references(xml: &str) -> String {
+    let lifted = replace_elements(xml, "w:footnoteReference", |tag| note_placeholder(tag, 'f'));
+    let lifted = replace_elements(&lifted, "w:endnoteReference", |tag| {
+        note_placeholder(tag, 'e')
+    });
+    replace_elements(&lifted, "w:commentReference", |tag| {
+        note_placeholder(tag, 'c')
+    })
+}
+
+fn lift_deleted_text(xml: &str) -> String {
+    xml.replace("<w:delText>", "<w:t>")
+        .replace("<w:delText ", "<w:t ")
+        .replace("</w:delText>", "</w:t>")
+}
+
+fn note_placeholder(element: &str, kind: char) -> String {
+    match get_rels_attr(element, "w:id") {
+        Some(id) => format!(
+            "<w:t>{}{}{}{}</w:t>",
+            PLACEHOLDER_OPEN, kind, id, PLACEHOLDER_CLOSE
+        ),
+        None => String::new(),
+    }
+}
+
+/// Replace every `<tag .../>` (or `<tag ...>...</tag>`) element in `xml`
+/// with `replace(element)`. An element holding others of the same name is
+/// passed whole, up to its own end tag.
+fn replace_elements(xml: &str, tag: &str, mut replace: impl FnMut(&str) -> String) -> String {
+    let mut out = String::with_capacity(xml.len());
+    let mut rest = xml;
+    while let Some(pos) = element_positions(rest, tag).next() {
+        let Some(tag_len) = rest[pos..].find('>').map(|i| i + 1) else {
+            break;
+        };
+        let start_tag = &rest[pos..pos + tag_len];
+        let end = if start_tag.ends_with("/>") {
+            pos + tag_len
+        } else {
+            match element_len(&rest[pos..], tag) {
+                Some(len) => pos + len,
+                None => break,
+            }
+        };
+        out.push_str(&rest[..pos]);
+        out.push_str(&replace(&rest[pos..end]));
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Length of the `tag` element `xml` starts with, through the end tag that
+/// matches it rather than that of an element of the same name inside.
+fn element_len(xml: &str, tag: &str) -> Option<usize> {
+    let close = format!("</{}>", tag);
+    let mut opens = element_positions(xml, tag)
+        .filter(|&pos| {
+            xml[pos..]
+                .find('>')
+                .is_some_and(|i| !xml[pos..pos + i].ends_with('/'))
+        })
+        .peekable();
+    let mut depth = 0;
+    for (pos, _) in xml.match_indices(close.as_str()) {
+        while opens.next_if(|&open| open < pos).is_some() {
+            depth += 1;
+        }
+        depth -= 1;
+        if depth == 0 {
+            return Some(pos + close.len());
+        }
+    }
+    None
+}
+
+/// Document properties worth keeping as provenance, in front-matter order:
+/// YAML key, part, and element in that part. `keywords` is split into a
+/// list by `front_matter`.
+const DOCUMENT_PROPERTIES: &[(&str, &str, &str)] = &[
+    ("title", "docProps/core.xml", "dc:title"),
+    ("author", "docProps/core.xml", "dc:creator"),
+    ("subject", "docProps/core.xml", "dc:subject"),
+    ("description", "docProps/core.xml", "dc:description"),
+    ("keywords", "docProps/core.xml", "cp:keywords"),
+    ("created", "docProps/core.xml", "dcterms:created"),
+    ("modified", "docProps/core.xml", "dcterms:modified"),
+    ("last_modified_by", "docProps/core.xml", "cp:lastModifiedBy"),
+    ("revision", "docProps/core.xml", "cp:revision"),
+    ("company", "docProps/app.xml", "Company"),
+    ("application", "docProps/app.xml", "Application"),
+];
+
+/// Read the non-empty [`DOCUMENT_PROPERTIES`] from the raw ZIP (docx-rs
+/// doesn't parse `docProps/*` when reading), as (key, decoded text) pairs.
+fn document_properties(bytes: &[u8]) -> Vec<(&'static str, String)> {
+    let core = read_zip_part(bytes, "docProps/core.xml").unwrap_or_default();
+    let app = read_zip_part(bytes, "docProps/app.xml").unwrap_or_default();
+    DOCUMENT_PROPERTIES
+        .iter()
+        .filter_map(|&(key, part, element)| {
+            let xml = if part == "docProps/app.xml" {
+                &app
+            } else {
+                &core
+            };
+            let text = element_text(xml, element)?;
+            let text = xml_decode(text.trim());
+            (!text.is_empty()).then_some((key, text))
+        })
+        .collect()
+}
+
+/// Text content of the first `<tag>...</tag>` element in `xml`.
+fn element_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let pos = element_positions(xml, tag).next()?;
+    let start = pos + xml[pos..].find('>')? + 1;
+    if xml[..start].ends_with("/>") {
+        return None;
+    }
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    Some(&xml[start..end])
+}
+
+/// Render document properties as a YAML front-matter block (empty when
+/// there are none). Strings are double-quoted so titles like `Q3: Plan`
+/// stay valid YAML; dates and the revision number are left bare.
+fn front_matter(properties: &[(&str, String)]) -> String {
+    if properties.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("---\n");
+    for (key, value) in properties {
+        let value = match *key {
+            "created" | "modified" => value.clone(),
+            "revision" if value.chars().all(|c| c.is_ascii_digit()) => value.clone(),
+            "keywords" => {
+                let keywords: Vec<String> = value
+                    .split([',', ';'])
+                    .map(str::trim)
+                    .filter(|k| !k.is_empty())
+                    .map(yaml_quote)
+                    .collect();
+                format!("[{}]", keywords.join(", "))
+            }
+            _ => yaml_quote(value),
+        };
+        out.push_str(&format!("{}: {}\n", key, value));
+    }
+    out.push_str("---\n\n");
+    out
+}
+
+fn yaml_quote(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Swap the placeholders `lift_unsupported_markup` left in `text` for their
+/// Markdown: footnote/endnote references become `[^n]` markers, comment
+/// references whatever [`DocxOptions::comments`] asks for, and equations
+/// `$…$` (inline) or `$$…$$` (display) math.
+fn resolve_placeholders(text: &str, ctx: &mut DocxContext) -> String {
+    if !text.contains(PLACEHOLDER_OPEN) {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(PLACEHOLDER_OPEN) {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + PLACEHOLDER_OPEN.len_utf8()..];
+        let Some(end) = after.find(PLACEHOLDER_CLOSE) else {
+            rest = after;
+            continue;
+        };
+        let tag = &after[..end];
+        if let Some(id) = tag.strip_prefix('g') {
+            // A chart is a block of its own, split off from the paragraph's
+            // text (see `paragraph_to_markdown`).
+            if let Some(chart) = ctx.docx_media.charts.get(id) {
+                out.push_str(&format!(
+                    "\n\n{}\n\n",
+                    chart::chart_to_markdown(chart, ctx.charts)
+                ));
+            }
+            rest = &after[end + PLACEHOLDER_CLOSE.len_utf8()..];
+            continue;
+        }
+        if let Some(index) = tag.strip_prefix('m') {
+            if let Some(eq) = index
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| ctx.equations.get(i))
+            {
+                let fence = if eq.display { "$$" } else { "$" };
+                out.push_str(&format!("{}{}{}", fence, eq.latex, fence));
+            }
+            rest = &after[end + PLACEHOLDER_CLOSE.len_utf8()..];
+            continue;
+        }
+        let kind = match tag.chars().next() {
+            Some('f') => Some(NoteKind::Footnote),
+            Some('e') => Some(NoteKind::Endnote),
+            Some('c') => Some(NoteKind::Comment),
+            _ => None,
+        };
+        if let (Some(kind), Ok(id)) = (kind, tag[1..].parse::<i64>()) {
+            let as_footnote = match kind {
+                NoteKind::Comment => ctx.options.comments == Comments::Footnotes,
+                _ => true,
+            };
+            if as_footnote {
+                out.push_str(&format!("[^{}]", ctx.notes.label((kind, id))));
+            } else if ctx.options.comments == Comments::CriticMarkup {
+                if let Some(blocks) = note_blocks((kind, id), ctx) {
+                    out.push_str(&format!("{{>>{}<<}}", blocks.join(" ")));
+                }
+            }
+        }
+        rest = &after[end + PLACEHOLDER_CLOSE.len_utf8()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_placeholder(text: &str) -> bool {
+    text.starts_with(PLACEHOLDER_OPEN)
+        && text.ends_with(PLACEHOLDER_CLOSE)
+        && text.matches(PLACEHOLDER_OPEN).count() == 1
+}
+
+/// Render the definition of every referenced note, in label order, e.g.
+/// `[^1]: The note text.` Continuation paragraphs are indented four spaces
+/// so they stay inside the footnote. Notes referenced only from inside
+/// another note are picked up too, since the loop re-reads `referenced`.
+fn notes_to_markdown(ctx: &mut DocxContext) -> String {
+    let mut out = String::new();
+    let mut index = 0;
+    while let Some(&note) = ctx.notes.referenced.get(index) {
+        index += 1;
+        if let Some(blocks) = note_blocks(note, ctx) {
+            out.push_str(&format!("[^{}]: {}\n", index, blocks.join("\n\n    ")));
+        }
+    }
+    out
+}
+
+/// A note's non-empty paragraphs as Markdown, the first prefixed with the
+/// comment author if any. `None` (with a warning) if the note's body is
+/// missing from its part.
+fn note_blocks(note: (NoteKind, i64), ctx: &mut DocxContext) -> Option<Vec<String>> {
+    let (kind, id) = note;
+    let Some(body) = ctx.notes.bodies.get(&note) else {
+        let (what, warning) = match kind {
+            NoteKind::Footnote => ("Footnote", WarningKind::Footnotes),
+            NoteKind::Endnote => ("Endnote", WarningKind::Footnotes),
+            NoteKind::Comment => ("Comment", WarningKind::Comments),
+        };
+        ctx.report.warn(
+            warning,
+            None,
+            format!("{} {} is referenced but its text is missing", what, id),
+        );
+        return None;
+    };
+    let author = body.author.clone();
+    let paragraphs = body.paragraphs.clone();
+    ctx.nested += 1;
+    let mut blocks: Vec<String> = in_part(ctx, kind.part(), |ctx| {
+        paragraphs
+            .iter()
+            .map(|para| paragraph_to_markdown(para, ctx).trim().to_string())
+            .filter(|md| !md.is_empty())
+            .collect()
+    });
+    ctx.nested -= 1;
+    if let Some(author) = author.filter(|a| !a.is_empty()) {
+        match blocks.first_mut() {
+            Some(first) => *first = format!("{}: {}", escape_markdown(&author), first),
+            None => blocks.push(escape_markdown(&author)),
+        }
+    }
+    Some(blocks)
+}
+
+/// Read the footnote and endnote bodies from the raw DOCX ZIP (docx-rs
+/// doesn't read either part).
+fn load_docx_notes(bytes: &[u8]) -> DocxNotes {
+    let mut notes = DocxNotes::default();
+    let parts = [
+        ("w:footnotes", "w:footnote", NoteKind::Footnote),
+        ("w:endnotes", "w:endnote", NoteKind::Endnote),
+    ];
+    for (root, element, kind) in parts {
+        if let Some(xml) = read_zip_part(bytes, kind.part()) {
+            for (id, paragraphs) in parse_notes(&xml, root, element) {
+                let note = Note {
+                    author: None,
+                    paragraphs,
+                };
+                notes.bodies.insert((kind, id), note);
+            }
+        }
+    }
+    notes
+}
+
+/// The distinct, non-empty text of each header and footer part, in part
+/// order, as `("Header" | "Footer", text)`. A part's paragraphs (and the
+/// cells of layout tables, common in letterhead headers) are flattened onto
+/// one line joined by ` · `; first-page/even-page variants repeating the
+/// default header's text appear once.
+fn headers_footers_to_markdown(bytes: &[u8], ctx: &mut DocxContext) -> Vec<(&'static str, String)> {
+    let mut seen = Vec::new();
+    ctx.nested += 1;
+    for (kind, name) in header_footer_parts(bytes) {
+        let Some(xml) = read_zip_part(bytes, &name) else {
+            continue;
+        };
+        // Footers share the header content model; parse them as one.
+        let xml = xml
+            .replacen("<w:ftr", "<w:hdr", 1)
+            .replace("</w:ftr>", "</w:hdr>");
+        let Ok(header) = Header::from_xml(xml.as_bytes()) else {
+            continue;
+        };
+        let pieces = in_part(ctx, &name, |ctx| {
+            let mut pieces = Vec::new();
+            for child in &header.children {
+                match child {
+                    HeaderChild::Paragraph(para) => pieces.push(paragraph_to_markdown(para, ctx)),
+                    HeaderChild::Table(table) => {
+                        for row in &table.rows {
+                            let TableChild::TableRow(row) = row;
+                            for cell in &row.cells {
+                                let TableRowChild::TableCell(cell) = cell;
+                                pieces.push(cell_to_markdown(cell, ctx));
+                            }
+                        }
+                    }
+                    HeaderChild::StructuredDataTag(sdt) => pieces.push(sdt_to_markdown(sdt, ctx)),
+                }
+            }
+            pieces
+        });
+        let text = pieces
+            .iter()
+            .flat_map(|piece| piece.lines())
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" · ");
+        if !text.is_empty() && !seen.iter().any(|(_, seen_text)| *seen_text == text) {
+            seen.push((kind, text));
+        }
+    }
+    ctx.nested -= 1;
+    seen
+}
 
-    Ok(output)
+/// The `word/header*.xml` and `word/footer*.xml` parts, as
+/// `("Header" | "Footer", part name)`: headers before footers, each in
+/// part-number order.
+fn header_footer_parts(bytes: &[u8]) -> Vec<(&'static str, String)> {
+    let Ok(archive) = zip::ZipArchive::new(Cursor::new(bytes)) else {
+        return Vec::new();
+    };
+    let mut parts: Vec<(bool, usize, &'static str, String)> = archive
+        .file_names()
+        .filter_map(|name| {
+            let stem = name.strip_prefix("word/")?.strip_suffix(".xml")?;
+            let (kind, number) = match stem.strip_prefix("header") {
+                Some(number) => ("Header", number),
+                None => ("Footer", stem.strip_prefix("footer")?),
+            };
+            let number = if number.is_empty() {
+                0
+            } else {
+                number.parse().ok()?
+            };
+            Some((kind == "Footer", number, kind, name.to_string()))
+        })
+        .collect();
+    parts.sort();
+    parts
+        .into_iter()
+        .map(|(_, _, kind, name)| (kind, name))
+        .collect()
+}
+
+/// Split a footnotes/endnotes part into `(id, paragraphs)` per note,
+/// skipping the separator pseudo-notes Word stores alongside real ones.
+/// Each note's content is parsed with docx-rs by wrapping it as a header
+/// part, which has the same paragraph/table content model; the root
+/// element's attributes are carried over for their namespace declarations.
+fn parse_notes(xml: &str, root: &str, element: &str) -> Vec<(i64, Vec<Paragraph>)> {
+    let Some(root_pos) = element_positions(xml, root).next() else {
+        return Vec::new();
+    };
+    let Some(root_end) = xml[root_pos..].find('>').map(|i| root_pos + i) else {
+        return Vec::new();
+    };
+    let namespaces = xml[root_pos + 1 + root.len()..root_end].trim_end_matches('/');
+    let close = format!("</{}>", element);
+
+    let mut notes = Vec::new();
+    for pos in element_positions(xml, element) {
+        let Some(tag_end) = xml[pos..].find('>').map(|i| pos + i) else {
+            continue;
+        };
+        let start_tag = &xml[pos..=tag_end];
+        if get_rels_attr(start_tag, "w:type").is_some_and(|t| t != "normal") {
+            continue;
+        }
+        let Some(id) = get_rels_attr(start_tag, "w:id").and_then(|id| id.parse().ok()) else {
+            continue;
+        };
+        let inner = if start_tag.ends_with("/>") {
+            ""
+        } else {
+            match xml[tag_end + 1..].find(&close) {
+                Some(len) => &xml[tag_end + 1..tag_end + 1 + len],
+                None => continue,
+            }
+        };
+        let wrapped = format!("<w:hdr{}>{}</w:hdr>", namespaces, inner);
+        let Ok(header) = Header::from_xml(wrapped.as_bytes()) else {
+            continue;
+        };
+        let paragraphs = header
+            .children
+            .into_iter()
+            .filter_map(|child| match child {
+                HeaderChild::Paragraph(para) => Some(*para),
+                _ => None,
+            })
+            .collect();
+        notes.push((id, paragraphs));
+    }
+    notes
+}
+
+fn read_zip_part(bytes: &[u8], name: &str) -> Option<String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).ok()?;
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+/// Rebuild the ZIP in `bytes` with part `name` replaced by `content`; every
+/// other entry is copied through without recompressing.
+fn replace_zip_part(bytes: &[u8], name: &str, content: &str) -> Option<Vec<u8>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).ok()?;
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i).ok()?;
+        if entry.name() == name {
+            drop(entry);
+            writer.start_file(name, SimpleFileOptions::default()).ok()?;
+            writer.write_all(content.as_bytes()).ok()?;
+        } else {
+            writer.raw_copy_file(entry).ok()?;
+        }
+    }
+    Some(writer.finish().ok()?.into_inner())
 }
 
 /// Reopen the raw DOCX ZIP to resolve `word/_rels/document.xml.rels`
-/// (rId → media target) and read every `word/media/*` part's bytes.
+/// (rId → media target) and read every `word/media/*` part's bytes, along
+/// with the picture relationships of headers, footers and notes.
 /// docx-rs itself doesn't expose these OOXML parts.
 fn load_docx_media(bytes: &[u8]) -> DocxMedia {
     let mut rels = HashMap::new();
     let mut parts = HashMap::new();
+    let mut alt_texts = HashMap::new();
+    let mut chart_parts = HashMap::new();
+    let mut part_images: HashMap<String, PartImages> = HashMap::new();
 
     if let Ok(mut archive) = zip::ZipArchive::new(Cursor::new(bytes)) {
         for i in 0..archive.len() {
@@ -111,6 +1384,33 @@ fn load_docx_media(bytes: &[u8]) -> DocxMedia {
                     if entry.read_to_string(&mut content).is_ok() {
                         rels = parse_document_rels(&content);
                     }
+                } else if name == "word/document.xml" {
+                    let mut content = String::new();
+                    if entry.read_to_string(&mut content).is_ok() {
+                        alt_texts = parse_image_alt_texts(&content);
+                    }
+                } else if let Some(part) = name
+                    .strip_prefix("word/_rels/")
+                    .and_then(|rels| rels.strip_suffix(".rels"))
+                {
+                    let mut content = String::new();
+                    if entry.read_to_string(&mut content).is_ok() {
+                        part_images
+                            .entry(format!("word/{}", part))
+                            .or_default()
+                            .rels = parse_document_rels(&content);
+                    }
+                } else if is_story_part(&name) {
+                    let mut content = String::new();
+                    if entry.read_to_string(&mut content).is_ok() {
+                        part_images.entry(name).or_default().alt_texts =
+                            parse_image_alt_texts(&content);
+                    }
+                } else if name.starts_with("word/charts/") && name.ends_with(".xml") {
+                    let mut content = String::new();
+                    if entry.read_to_string(&mut content).is_ok() {
+                        chart_parts.insert(name, content);
+                    }
                 } else if name.starts_with("word/media/") {
                     let mut buf = Vec::new();
                     if entry.read_to_end(&mut buf).is_ok() {
@@ -121,18 +1421,197 @@ fn load_docx_media(bytes: &[u8]) -> DocxMedia {
         }
     }
 
-    DocxMedia { rels, parts }
+    let charts = rels
+        .iter()
+        .filter_map(|(id, path)| Some((id.clone(), chart::parse_chart(chart_parts.get(path)?)?)))
+        .collect();
+    DocxMedia {
+        rels,
+        parts,
+        alt_texts,
+        charts,
+        part_images,
+    }
+}
+
+/// Whether `name` is a header, footer or notes part, whose pictures have
+/// relationships of their own.
+fn is_story_part(name: &str) -> bool {
+    let Some(stem) = name
+        .strip_prefix("word/")
+        .and_then(|n| n.strip_suffix(".xml"))
+    else {
+        return false;
+    };
+    stem.starts_with("header")
+        || stem.starts_with("footer")
+        || matches!(stem, "footnotes" | "endnotes" | "comments")
+}
+
+/// Run `render` with the picture relationships of `part` in place of the
+/// document's: the `r:embed` ids in a header, footer or note point into
+/// that part's own `.rels`, not `word/_rels/document.xml.rels`.
+fn in_part<T>(ctx: &mut DocxContext, part: &str, render: impl FnOnce(&mut DocxContext) -> T) -> T {
+    let images = ctx
+        .docx_media
+        .part_images
+        .get(part)
+        .cloned()
+        .unwrap_or_default();
+    let rels = std::mem::replace(&mut ctx.docx_media.rels, images.rels);
+    let alt_texts = std::mem::replace(&mut ctx.docx_media.alt_texts, images.alt_texts);
+    let out = render(ctx);
+    ctx.docx_media.rels = rels;
+    ctx.docx_media.alt_texts = alt_texts;
+    out
+}
+
+/// The accessibility description Word stores on each picture (`wp:docPr`'s
+/// `descr`, else its `title`), keyed by the picture's `r:embed` rId, with
+/// line breaks folded to spaces. docx-rs doesn't keep `wp:docPr`, so it's
+/// read from the raw XML; when one image is placed twice, the first
+/// placement's text wins.
+fn parse_image_alt_texts(document_xml: &str) -> HashMap<String, String> {
+    let mut alt_texts = HashMap::new();
+    for drawing in document_xml.split("<w:drawing>").skip(1) {
+        let drawing = &drawing[..drawing.find("</w:drawing>").unwrap_or(drawing.len())];
+        let (Some(doc_pr), Some(embed)) = (
+            drawing.find("<wp:docPr "),
+            get_rels_attr(drawing, "r:embed"),
+        ) else {
+            continue;
+        };
+        let doc_pr = &drawing[doc_pr..];
+        let doc_pr = &doc_pr[..doc_pr.find('>').unwrap_or(doc_pr.len())];
+        let text = |attr: &str| {
+            get_rels_attr(doc_pr, attr)
+                .map(|v| xml_decode(&v.replace("&#xA;", " ").replace("&#10;", " ")))
+                .map(|v| v.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|v| !v.is_empty())
+        };
+        if let Some(alt) = text(" descr").or_else(|| text(" title")) {
+            alt_texts.entry(embed).or_insert(alt);
+        }
+    }
+    alt_texts
+}
+
+/// Give a bare `![]()` image (one without a description) the text of an
+/// adjacent "Figure N" caption paragraph as alt text, preferring the one
+/// after it, like the PDF importer's `with_caption_alt`. The caption
+/// paragraph itself is still rendered.
+fn with_caption_alt(md: String, children: &[DocumentChild], i: usize) -> String {
+    if !md.starts_with("![](") {
+        return md;
+    }
+    let caption = |j: Option<usize>| match j.and_then(|j| children.get(j)) {
+        Some(DocumentChild::Paragraph(para)) => Some(paragraph_plain_text(para).trim().to_string())
+            .filter(|text| is_caption_label(text)),
+        _ => None,
+    };
+    match caption(i.checked_add(1)).or_else(|| caption(i.checked_sub(1))) {
+        Some(cap) => md.replacen("![](", &format!("![{}](", escape_alt_text(&cap)), 1),
+        None => md,
+    }
+}
+
+/// Record in `report` the review content `options` leaves out of the
+/// Markdown (accepted tracked changes, dropped comments), plus the page
+/// count Word saved in `docProps/app.xml`. Counted from the raw parts since
+/// docx-rs doesn't surface all of them.
+fn report_dropped_parts(bytes: &[u8], options: &DocxOptions, report: &mut ImportReport) {
+    let Ok(mut archive) = zip::ZipArchive::new(Cursor::new(bytes)) else {
+        return;
+    };
+    let mut read_part = |name: &str| -> Option<String> {
+        let mut entry = archive.by_name(name).ok()?;
+        let mut content = String::new();
+        entry.read_to_string(&mut content).ok()?;
+        Some(content)
+    };
+
+    if let Some(app_xml) = read_part("docProps/app.xml") {
+        report.stats.pages = app_xml
+            .split_once("<Pages>")
+            .and_then(|(_, rest)| rest.split_once("</Pages>"))
+            .and_then(|(n, _)| n.trim().parse().ok());
+    }
+
+    let Some(document_xml) = read_part("word/document.xml") else {
+        return;
+    };
+    let tracked = count_revisions(&document_xml, "w:ins") + count_revisions(&document_xml, "w:del");
+    if tracked > 0 && options.tracked_changes == TrackedChanges::Accept {
+        report.warn(
+            WarningKind::TrackedChanges,
+            None,
+            format!(
+                "{} tracked changes (insertions/deletions) were accepted",
+                tracked
+            ),
+        );
+    }
+    let comments = count_elements(&document_xml, "w:commentReference");
+    if comments > 0 && options.comments == Comments::Drop {
+        report.warn(
+            WarningKind::Comments,
+            None,
+            format!("{} comments were dropped", comments),
+        );
+    }
+}
+
+/// Count `<tag ...>` start (or empty) elements in `xml`.
+fn count_elements(xml: &str, tag: &str) -> usize {
+    element_positions(xml, tag).count()
+}
+
+/// Count the `w:ins`/`w:del` (`tag`) elements in `xml` that wrap runs. The
+/// empty marks of the same name in `w:rPr` and `w:trPr` record a changed
+/// paragraph mark or table row, and aren't counted.
+fn count_revisions(xml: &str, tag: &str) -> usize {
+    element_positions(xml, tag)
+        .filter(|&pos| {
+            let rest = &xml[pos..];
+            if rest.find('>').is_none_or(|end| rest[..end].ends_with('/')) {
+                return false;
+            }
+            let element = element_len(rest, tag).map_or("", |len| &rest[..len]);
+            element_positions(element, "w:r").next().is_some()
+        })
+        .count()
+}
+
+/// Byte offsets of every `<tag ...>` start (or empty) element in `xml`,
+/// requiring the name to end at the tag name so `w:ins` doesn't also match
+/// `w:instrText`.
+fn element_positions<'a>(xml: &'a str, tag: &str) -> impl Iterator<Item = usize> + 'a {
+    let open = format!("<{}", tag);
+    let open_len = open.len();
+    xml.match_indices(open.as_str())
+        .map(|(pos, _)| pos)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .filter(move |pos| {
+            matches!(
+                xml.as_bytes().get(pos + open_len),
+                Some(b' ' | b'>' | b'/' | b'\t' | b'\r' | b'\n')
+            )
+        })
 }
 
 /// Parse `word/_rels/document.xml.rels` into rId → resolved `word/media/...`
-/// path. `Target` is relative to the `word/` directory (e.g. `media/image1.png`).
+/// (or `word/charts/...`) path. `Target` is relative to the `word/`
+/// directory (e.g. `media/image1.png`).
 fn parse_document_rels(rels_xml: &str) -> HashMap<String, String> {
     let mut map = HashMap::new();
     for chunk in rels_xml.split("<Relationship ") {
-        if !chunk.contains("/image") {
+        if !chunk.contains("/image") && !chunk.contains("/chart\"") {
             continue;
         }
-        if let (Some(id), Some(target)) = (get_rels_attr(chunk, "Id"), get_rels_attr(chunk, "Target")) {
+        if let (Some(id), Some(target)) =
+            (get_rels_attr(chunk, "Id"), get_rels_attr(chunk, "Target"))
+        {
             map.insert(id, resolve_word_relative_path(&target));
         }
     }
@@ -164,9 +1643,9 @@ fn resolve_word_relative_path(target: &str) -> String {
 }
 
 /// If `run` contains an embedded picture, extract it via `media` and return a
-/// Markdown image link (or an "(unsupported image)" note for non-renderable
-/// formats like EMF/WMF).
-fn run_image_markdown(run: &Run, docx_media: &DocxMedia, media: &mut MediaSink) -> Option<String> {
+/// Markdown image link (or an "(unsupported image)" note for formats that
+/// can't be rendered or converted, recorded in `report`).
+fn run_image_markdown(run: &Run, ctx: &mut DocxContext) -> Option<String> {
     for child in &run.children {
         if let RunChild::Drawing(drawing) = child {
             if let Drawing {
@@ -174,19 +1653,35 @@ fn run_image_markdown(run: &Run, docx_media: &DocxMedia, media: &mut MediaSink)
                 ..
             } = drawing.as_ref()
             {
-                let media_path = docx_media.rels.get(&pic.id)?;
-                return Some(match docx_media.parts.get(media_path) {
-                    Some(bytes) => match media.add(media_path, bytes) {
-                        Some(rel_path) => format!("![]({})", rel_path),
-                        None => format!(
-                            "*(unsupported image: {})*",
-                            media_path.rsplit('/').next().unwrap_or(media_path)
-                        ),
+                let media_path = ctx.docx_media.rels.get(&pic.id)?;
+                let file_name = media_path.rsplit('/').next().unwrap_or(media_path);
+                return Some(match ctx.docx_media.parts.get(media_path) {
+                    Some(bytes) => match ctx.media.add(media_path, bytes) {
+                        Some(rel_path) => {
+                            let alt = ctx
+                                .docx_media
+                                .alt_texts
+                                .get(&pic.id)
+                                .map(|alt| escape_alt_text(alt));
+                            format!("![{}]({})", alt.unwrap_or_default(), rel_path)
+                        }
+                        None => {
+                            ctx.report.skip_image(
+                                WarningKind::UnsupportedImage,
+                                None,
+                                format!("Unsupported image format: {}", file_name),
+                            );
+                            format!("*(unsupported image: {})*", file_name)
+                        }
                     },
-                    None => format!(
-                        "*(unsupported image: {})*",
-                        media_path.rsplit('/').next().unwrap_or(media_path)
-                    ),
+                    None => {
+                        ctx.report.skip_image(
+                            WarningKind::MissingImage,
+                            None,
+                            format!("Image part missing from the document: {}", media_path),
+                        );
+                        format!("*(unsupported image: {})*", file_name)
+                    }
                 });
             }
         }
@@ -194,34 +1689,153 @@ fn run_image_markdown(run: &Run, docx_media: &DocxMedia, media: &mut MediaSink)
     None
 }
 
-/// Build a lookup from numbering id → is_ordered (true = numbered list, false = bullet).
-/// Resolves via abstract_num_id → level 0 format.
-fn build_numbering_map(docx: &Docx) -> HashMap<usize, bool> {
-    // abstract_num_id → is_ordered
-    let abstract_map: HashMap<usize, bool> = docx
-        .numberings
-        .abstract_nums
-        .iter()
-        .map(|abs| {
-            let ordered = abs
-                .levels
-                .first()
-                .map(|l| is_ordered_format(&l.format.val))
-                .unwrap_or(false);
-            (abs.id, ordered)
-        })
-        .collect();
+/// One level of a list definition (`w:lvl`): its number format and start.
+#[derive(Debug, Clone)]
+struct ListLevel {
+    format: String,
+    start: usize,
+    /// `w:lvlRestart`: restart after a paragraph at this (1-based) level or
+    /// higher; `0` never restarts. `None` restarts after any higher level.
+    restart: Option<usize>,
+}
 
-    docx.numberings
-        .numberings
-        .iter()
-        .map(|n| {
-            let ordered = abstract_map.get(&n.abstract_num_id).copied().unwrap_or(false);
-            (n.id, ordered)
-        })
-        .collect()
+/// Resolved list definitions from `word/numbering.xml` plus the running
+/// counters, so each list paragraph gets the ordinal Word would show.
+///
+/// Lists sharing an abstract definition continue each other's numbering
+/// (that's how Word continues a list across intervening paragraphs) unless
+/// the `w:num` overrides a start value (`w:lvlOverride`/`w:startOverride`,
+/// what "Restart at 1" writes), which gives it a sequence of its own.
+#[derive(Default)]
+struct ListNumbering {
+    /// (numId, level) → the level's definition, overrides applied.
+    levels: HashMap<(usize, usize), ListLevel>,
+    /// numId → key of the counter sequence it advances.
+    sequences: HashMap<usize, String>,
+    /// Counter sequence → current value per level (`None` until used).
+    counters: HashMap<String, Vec<Option<usize>>>,
+    /// Width of the most recent marker at each level, for indenting the
+    /// levels below it so they nest under CommonMark's rules.
+    marker_widths: Vec<usize>,
+}
+
+impl ListNumbering {
+    fn new(docx: &Docx) -> Self {
+        let abstract_levels: HashMap<usize, &Vec<Level>> = docx
+            .numberings
+            .abstract_nums
+            .iter()
+            .map(|abs| (abs.id, &abs.levels))
+            .collect();
+
+        let mut numbering = ListNumbering::default();
+        for num in &docx.numberings.numberings {
+            let overridden = num
+                .level_overrides
+                .iter()
+                .any(|o| o.override_start.is_some() || o.override_level.is_some());
+            let sequence = if overridden {
+                format!("num{}", num.id)
+            } else {
+                format!("abstract{}", num.abstract_num_id)
+            };
+            numbering.sequences.insert(num.id, sequence);
+
+            for level in abstract_levels
+                .get(&num.abstract_num_id)
+                .into_iter()
+                .flat_map(|l| l.iter())
+            {
+                numbering
+                    .levels
+                    .insert((num.id, level.level), list_level(level));
+            }
+            for o in &num.level_overrides {
+                let mut level = match &o.override_level {
+                    Some(level) => list_level(level),
+                    None => match numbering.levels.get(&(num.id, o.level)) {
+                        Some(level) => level.clone(),
+                        None => continue,
+                    },
+                };
+                if let Some(start) = o.override_start {
+                    level.start = start;
+                }
+                numbering.levels.insert((num.id, o.level), level);
+            }
+        }
+        numbering
+    }
+
+    /// The list marker and indent for a paragraph at `level` of list
+    /// `num_id`, advancing the list's counters: `"  - "`, `"5. "`, ...
+    /// `None` for numId 0, which means "not a list".
+    fn prefix(&mut self, num_id: usize, level: usize) -> Option<String> {
+        if num_id == 0 {
+            return None;
+        }
+        let marker = match self.levels.get(&(num_id, level)).cloned() {
+            Some(def) if is_ordered_format(&def.format) => {
+                let ordinal = self.advance(num_id, level, &def);
+                format!("{}. ", ordinal)
+            }
+            _ => "- ".to_string(),
+        };
+        if self.marker_widths.len() <= level {
+            self.marker_widths.resize(level + 1, 2);
+        }
+        let indent: usize = self.marker_widths[..level].iter().sum();
+        self.marker_widths[level] = marker.len();
+        Some(format!("{}{}", " ".repeat(indent), marker))
+    }
+
+    fn advance(&mut self, num_id: usize, level: usize, def: &ListLevel) -> usize {
+        let sequence = self
+            .sequences
+            .get(&num_id)
+            .cloned()
+            .unwrap_or_else(|| format!("num{}", num_id));
+        let restarts: Vec<Option<usize>> = (0..9)
+            .map(|l| self.levels.get(&(num_id, l)).and_then(|d| d.restart))
+            .collect();
+        let counters = self.counters.entry(sequence).or_default();
+        if counters.len() <= level {
+            counters.resize(level + 1, None);
+        }
+        let ordinal = counters[level].map_or(def.start, |n| n + 1);
+        counters[level] = Some(ordinal);
+        // Deeper levels restart after this one, unless their `w:lvlRestart`
+        // says to restart only after a higher level (or never).
+        for (deeper, counter) in counters.iter_mut().enumerate().skip(level + 1) {
+            let restart_after = restarts.get(deeper).copied().flatten().unwrap_or(deeper);
+            if level < restart_after {
+                *counter = None;
+            }
+        }
+        ordinal
+    }
+}
+
+fn list_level(level: &Level) -> ListLevel {
+    // Start and LevelRestart keep their values private; read them back
+    // through serde.
+    let number =
+        |value: Option<serde_json::Value>| value.and_then(|v| v.as_u64()).map(|n| n as usize);
+    ListLevel {
+        format: level.format.val.clone(),
+        start: number(serde_json::to_value(&level.start).ok()).unwrap_or(1),
+        restart: number(
+            level
+                .level_restart
+                .as_ref()
+                .and_then(|r| serde_json::to_value(r).ok()),
+        ),
+    }
 }
 
+/// Number formats rendered as an ordered list. Markdown only has decimal
+/// ordinals, so letters, roman numerals and spelled-out numbers fall back
+/// to the decimal value at the same position (`c.` → `3.`).
 fn is_ordered_format(val: &str) -> bool {
     matches!(
         val,
@@ -240,16 +1854,10 @@ fn is_ordered_format(val: &str) -> bool {
     )
 }
 
-fn paragraph_to_markdown(
-    para: &Paragraph,
-    num_map: &HashMap<usize, bool>,
-    docx_media: &DocxMedia,
-    media: &mut MediaSink,
-) -> String {
-    let style_id = para.property.style.as_ref().map(|s| s.val.to_lowercase());
-    let style_str = style_id.as_deref().unwrap_or("");
-
-    let heading_prefix: &str = match style_str {
+/// The heading marker of a built-in heading style ID (lowercased), e.g.
+/// `"## "` for `heading2`; empty for any other style.
+fn style_heading_prefix(style: &str) -> &'static str {
+    match style {
         "heading1" | "heading 1" | "1" => "# ",
         "heading2" | "heading 2" | "2" => "## ",
         "heading3" | "heading 3" | "3" => "### ",
@@ -258,116 +1866,499 @@ fn paragraph_to_markdown(
         "heading6" | "heading 6" | "6" => "###### ",
         "subtitle" => "## ",
         _ => "",
+    }
+}
+
+/// The heading marker for a paragraph's outline level; empty without one.
+fn outline_heading_prefix(para: &Paragraph) -> &'static str {
+    para.property
+        .outline_lvl
+        .as_ref()
+        .map(|o| match o.v {
+            0 => "# ",
+            1 => "## ",
+            2 => "### ",
+            3 => "#### ",
+            4 => "##### ",
+            5 => "###### ",
+            _ => "", // levels 6–9 are body text in DOCX, not headings
+        })
+        .unwrap_or("")
+}
+
+fn paragraph_to_markdown(para: &Paragraph, ctx: &mut DocxContext) -> String {
+    let block = paragraph_block(para, ctx);
+    if block == Some(StyleBlock::Code) {
+        // Outside the document body (table cells, notes) a code paragraph
+        // can't become a fenced block, so it's an inline code span instead.
+        let (code, refs) = code_text(para, ctx);
+        return if code.trim().is_empty() {
+            refs
+        } else {
+            code_span(code.trim()) + &refs
+        };
+    }
+
+    let style_id = para.property.style.as_ref().map(|s| s.val.to_lowercase());
+    let style_str = style_id.as_deref().unwrap_or("");
+
+    let heading_prefix: &str = match block {
+        Some(StyleBlock::Heading1) => "# ",
+        Some(StyleBlock::Heading2) => "## ",
+        Some(StyleBlock::Heading3) => "### ",
+        Some(StyleBlock::Heading4) => "#### ",
+        Some(StyleBlock::Heading5) => "##### ",
+        Some(StyleBlock::Heading6) => "###### ",
+        Some(_) => "",
+        None => style_heading_prefix(style_str),
     };
 
     // "title" style renders as bold text, not as a heading level
-    let is_title = style_str == "title";
+    let is_title = block.is_none() && style_str == "title";
 
     // Fallback: use outline level when the style ID isn't a known heading
-    let heading_prefix = if heading_prefix.is_empty() && !is_title {
-        para.property
-            .outline_lvl
-            .as_ref()
-            .map(|o| match o.v {
-                0 => "# ",
-                1 => "## ",
-                2 => "### ",
-                3 => "#### ",
-                4 => "##### ",
-                5 => "###### ",
-                _ => "", // levels 6–9 are body text in DOCX, not headings
-            })
-            .unwrap_or("")
+    let heading_prefix = if heading_prefix.is_empty() && !is_title && block.is_none() {
+        outline_heading_prefix(para)
     } else {
         heading_prefix
     };
 
-    let list_prefix = if heading_prefix.is_empty() && !is_title {
-        para.property.numbering_property.as_ref().map(|np| {
+    let list_prefix = if heading_prefix.is_empty()
+        && !is_title
+        && matches!(block, None | Some(StyleBlock::Paragraph))
+    {
+        para.property.numbering_property.as_ref().and_then(|np| {
             let num_id = np.id.as_ref().map(|i| i.id).unwrap_or(0);
             let level = np.level.as_ref().map(|l| l.val).unwrap_or(0);
-            let indent = "  ".repeat(level);
-            let marker = if *num_map.get(&num_id).unwrap_or(&false) {
-                "1. "
-            } else {
-                "- "
-            };
-            format!("{}{}", indent, marker)
+            ctx.numbering.prefix(num_id, level)
         })
     } else {
         None
     };
 
-    // Collect (text, bold, italic, strike) segments from all paragraph children
-    let mut segments: Vec<(String, bool, bool, bool)> = Vec::new();
+    // Collect (text, format) segments from all paragraph children
+    let mut segments: Vec<(String, RunFormat)> = Vec::new();
+    let mut field = FieldState::default();
+
+    for child in &para.children {
+        match child {
+            ParagraphChild::Run(run) => {
+                segments.extend(run_segment(run, is_title, ctx));
+                field.observe(run, &mut segments);
+            }
+            ParagraphChild::BookmarkStart(bookmark) => ctx.anchors.bookmark(&bookmark.name),
+            ParagraphChild::Insert(insert) => {
+                let mut inserted = Vec::new();
+                let mut deleted = Vec::new();
+                for c in &insert.children {
+                    match c {
+                        InsertChild::Run(run) => inserted.extend(run_segment(run, is_title, ctx)),
+                        // Inserted, then deleted by a later reviewer.
+                        InsertChild::Delete(delete) => {
+                            for d in &delete.children {
+                                if let DeleteChild::Run(run) = d {
+                                    deleted.extend(run_segment(run, is_title, ctx));
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                push_tracked_change(&mut segments, inserted, "{++", "++}", ctx.options);
+                if ctx.options.tracked_changes == TrackedChanges::CriticMarkup {
+                    push_tracked_change(&mut segments, deleted, "{--", "--}", ctx.options);
+                }
+            }
+            ParagraphChild::Delete(delete)
+                if ctx.options.tracked_changes == TrackedChanges::CriticMarkup =>
+            {
+                let mut deleted = Vec::new();
+                for c in &delete.children {
+                    if let DeleteChild::Run(run) = c {
+                        deleted.extend(run_segment(run, is_title, ctx));
+                    }
+                }
+                push_tracked_change(&mut segments, deleted, "{--", "--}", ctx.options);
+            }
+            ParagraphChild::Hyperlink(hyperlink) => {
+                let mut inner = String::new();
+                for c in &hyperlink.children {
+                    match c {
+                        ParagraphChild::Run(r) => inner.push_str(&run_raw_text(r)),
+                        // Link text is kept as accepted, whatever the mode.
+                        ParagraphChild::Insert(insert) => {
+                            for i in &insert.children {
+                                if let InsertChild::Run(r) = i {
+                                    inner.push_str(&run_raw_text(r));
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                if !inner.is_empty() {
+                    let linked = match &hyperlink.link {
+                        HyperlinkData::External { path, .. } => {
+                            format!("[{}]({})", inner, path)
+                        }
+                        // Internal anchor (e.g. TOC entries): keep as a Markdown
+                        // anchor link to match MarkItDown's import output. A
+                        // bookmark on a heading is rewritten to the heading's
+                        // slug once the whole document is converted.
+                        HyperlinkData::Anchor { anchor } if !anchor.is_empty() => {
+                            format!("[{}](#{})", inner, anchor)
+                        }
+                        HyperlinkData::Anchor { .. } => inner, // empty anchor -> plain text
+                    };
+                    segments.push((linked, RunFormat::default()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if block == Some(StyleBlock::Caption) {
+        for seg in segments.iter_mut().filter(|seg| !is_placeholder(&seg.0)) {
+            seg.1.italic = true;
+        }
+    }
+
+    // Merge adjacent segments with identical formatting to prevent `****` artifacts
+    let text = format_segments(segments, ctx.inline);
+    let text = resolve_placeholders(&text, ctx);
+    // A paragraph holding just a chart starts and ends with its block breaks.
+    let text = text.trim_matches('\n').to_string();
+    if !text.is_empty() && ctx.nested == 0 {
+        if list_prefix.is_none() && !heading_prefix.is_empty() {
+            let level = heading_prefix.trim_end().len();
+            ctx.anchors.heading(level, &paragraph_plain_text(para));
+        } else {
+            ctx.anchors.body_text();
+        }
+    }
+    let text_boxes = text_boxes_to_markdown(para, ctx);
+
+    if text.is_empty() {
+        return text_boxes;
+    }
+
+    let page_break = if para.property.page_break_before == Some(true) {
+        "---\n\n"
+    } else {
+        ""
+    };
+
+    let md = match list_prefix {
+        Some(prefix) => format!("{}{}{}", page_break, prefix, text),
+        None if block == Some(StyleBlock::Quote) => format!("{}> {}", page_break, text),
+        None => format!("{}{}{}", page_break, heading_prefix, text),
+    };
+    if text_boxes.is_empty() {
+        md
+    } else {
+        format!("{}\n\n{}", md, text_boxes)
+    }
+}
+
+/// The content of the text boxes (`wps:txbx`) anchored in `para`, as blocks
+/// to place right after it: a floating box has no position in the text
+/// flow, so its anchor paragraph is the closest stand-in for where it sits.
+/// Empty if the paragraph anchors none.
+fn text_boxes_to_markdown(para: &Paragraph, ctx: &mut DocxContext) -> String {
+    let mut blocks = Vec::new();
+    for child in &para.children {
+        let ParagraphChild::Run(run) = child else {
+            continue;
+        };
+        for run_child in &run.children {
+            let RunChild::Drawing(drawing) = run_child else {
+                continue;
+            };
+            let Some(DrawingData::TextBox(text_box)) = &drawing.data else {
+                continue;
+            };
+            for content in &text_box.children {
+                let md = match content {
+                    TextBoxContentChild::Paragraph(para) => paragraph_to_markdown(para, ctx),
+                    TextBoxContentChild::Table(table) => {
+                        table_to_markdown(table, ctx).trim_end().to_string()
+                    }
+                };
+                if !md.trim().is_empty() {
+                    blocks.push(md);
+                }
+            }
+        }
+    }
+    blocks.join("\n\n")
+}
+
+/// Styles whose paragraphs become code blocks, quotes and captions without
+/// any configuration, by normalized style name or ID (see `style_key`).
+const BUILTIN_STYLE_BLOCKS: &[(&str, StyleBlock)] = &[
+    ("code", StyleBlock::Code),
+    ("sourcecode", StyleBlock::Code),
+    ("htmlpreformatted", StyleBlock::Code),
+    ("preformattedtext", StyleBlock::Code),
+    ("quote", StyleBlock::Quote),
+    ("intensequote", StyleBlock::Quote),
+    ("caption", StyleBlock::Caption),
+];
+
+/// Font families treated as monospace, matched as lowercase prefixes of
+/// the run's `w:rFonts` (families ending in " mono" match too).
+const MONOSPACE_FONTS: &[&str] = &[
+    "courier",
+    "consolas",
+    "menlo",
+    "monaco",
+    "lucida console",
+    "lucida sans typewriter",
+    "andale mono",
+    "source code",
+    "fira code",
+    "jetbrains mono",
+    "cascadia",
+    "inconsolata",
+    "ocr a",
+];
+
+/// Normalize a style name or ID for matching: `Intense Quote`,
+/// `IntenseQuote` and `intense-quote` all become `intensequote`.
+fn style_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-' && *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The block a style named `name` maps to: the user's
+/// [`DocxOptions::style_map`] first, then [`BUILTIN_STYLE_BLOCKS`].
+fn named_style_block(name: &str, options: &DocxOptions) -> Option<StyleBlock> {
+    let key = style_key(name);
+    options
+        .style_map
+        .iter()
+        .find(|(name, _)| style_key(name) == key)
+        .map(|(_, block)| *block)
+        .or_else(|| {
+            BUILTIN_STYLE_BLOCKS
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, block)| *block)
+        })
+}
+
+/// Map each paragraph style in `styles.xml` to its block, by name then ID;
+/// a style whose own font is monospace maps to code.
+fn build_style_blocks(docx: &Docx, options: &DocxOptions) -> HashMap<String, StyleBlock> {
+    let mut map = HashMap::new();
+    for style in &docx.styles.styles {
+        if style.style_type != StyleType::Paragraph {
+            continue;
+        }
+        let name = serde_json::to_value(&style.name)
+            .ok()
+            .and_then(|v| v.as_str().map(xml_decode))
+            .unwrap_or_default();
+        let block = named_style_block(&name, options)
+            .or_else(|| named_style_block(&style.style_id, options))
+            .or_else(|| {
+                run_fonts_monospace(style.run_property.fonts.as_ref()).then_some(StyleBlock::Code)
+            });
+        if let Some(block) = block {
+            map.insert(style_key(&style.style_id), block);
+        }
+    }
+    map
+}
+
+/// The block `para` maps to through its style, or — for text that isn't a
+/// heading and is set entirely in a monospace font — code. `None` leaves it
+/// to the heading/title/list detection in `paragraph_to_markdown`. A
+/// paragraph holding a link or a picture is never code, since a code block
+/// would drop them.
+fn paragraph_block(para: &Paragraph, ctx: &DocxContext) -> Option<StyleBlock> {
+    let inline_content = has_links_or_pictures(para);
+    if let Some(style) = &para.property.style {
+        let block = ctx
+            .style_blocks
+            .get(&style_key(&style.val))
+            .copied()
+            .or_else(|| named_style_block(&style.val, ctx.options));
+        if block.is_some() {
+            return block.filter(|&block| block != StyleBlock::Code || !inline_content);
+        }
+        let style = style.val.to_lowercase();
+        if !style_heading_prefix(&style).is_empty() || style == "title" {
+            return None;
+        }
+    }
+    if inline_content || !outline_heading_prefix(para).is_empty() {
+        return None;
+    }
+    let mut text_runs = para.children.iter().filter_map(|child| match child {
+        ParagraphChild::Run(run) if !run_raw_text(run).trim().is_empty() => Some(run.as_ref()),
+        _ => None,
+    });
+    let first = text_runs.next()?;
+    let monospace = |run: &Run| run_fonts_monospace(run.run_property.fonts.as_ref());
+    (monospace(first) && text_runs.all(monospace)).then_some(StyleBlock::Code)
+}
+
+/// Whether `para` holds a hyperlink, or a run with a drawing (picture,
+/// chart or text box).
+fn has_links_or_pictures(para: &Paragraph) -> bool {
+    let has_drawing = |run: &Run| {
+        run.children
+            .iter()
+            .any(|c| matches!(c, RunChild::Drawing(_)))
+    };
+    para.children.iter().any(|child| match child {
+        ParagraphChild::Hyperlink(_) => true,
+        ParagraphChild::Run(run) => has_drawing(run),
+        ParagraphChild::Insert(insert) => insert
+            .children
+            .iter()
+            .any(|c| matches!(c, InsertChild::Run(run) if has_drawing(run))),
+        _ => false,
+    })
+}
+
+fn run_fonts_monospace(fonts: Option<&RunFonts>) -> bool {
+    // RunFonts keeps its fields private; read them back through serde.
+    let Some(fonts) = fonts.and_then(|f| serde_json::to_value(f).ok()) else {
+        return false;
+    };
+    let family = fonts.get("ascii").or_else(|| fonts.get("hiAnsi"));
+    family.and_then(|f| f.as_str()).is_some_and(|family| {
+        let family = family.to_lowercase();
+        family.ends_with(" mono") || MONOSPACE_FONTS.iter().any(|f| family.starts_with(f))
+    })
+}
 
+/// The literal text of a code paragraph: no escaping or emphasis, tabs and
+/// line breaks kept. Placeholders can't be resolved inside code, so they
+/// are taken out and returned resolved on their own, to follow the code.
+fn code_text(para: &Paragraph, ctx: &mut DocxContext) -> (String, String) {
+    let mut text = String::new();
     for child in &para.children {
-        match child {
-            ParagraphChild::Run(run) => {
-                if let Some(img_md) = run_image_markdown(run, docx_media, media) {
-                    segments.push((img_md, false, false, false));
-                } else if let Some(seg) = run_to_segment(run, is_title) {
-                    segments.push(seg);
-                }
-            }
-            ParagraphChild::Hyperlink(hyperlink) => {
-                let mut inner = String::new();
-                for c in &hyperlink.children {
-                    if let ParagraphChild::Run(r) = c {
-                        inner.push_str(&run_raw_text(r));
-                    }
-                }
-                if !inner.is_empty() {
-                    let linked = match &hyperlink.link {
-                        HyperlinkData::External { path, .. } => {
-                            format!("[{}]({})", inner, path)
-                        }
-                        // Internal anchor (e.g. TOC entries): keep as a Markdown
-                        // anchor link to match MarkItDown's import output. The
-                        // target won't resolve against Markdown's auto-generated
-                        // heading slugs, but we preserve it for fidelity.
-                        HyperlinkData::Anchor { anchor } if !anchor.is_empty() => {
-                            format!("[{}](#{})", inner, anchor)
-                        }
-                        HyperlinkData::Anchor { .. } => inner, // empty anchor -> plain text
-                    };
-                    segments.push((linked, false, false, false));
+        let runs: Vec<&Run> = match child {
+            ParagraphChild::Run(run) => vec![run],
+            ParagraphChild::Insert(insert) => insert
+                .children
+                .iter()
+                .filter_map(|c| match c {
+                    InsertChild::Run(run) => Some(run.as_ref()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        for run in runs {
+            for child in &run.children {
+                match child {
+                    RunChild::Text(t) => text.push_str(&t.text),
+                    RunChild::Tab(_) => text.push('\t'),
+                    RunChild::Break(_) => text.push('\n'),
+                    _ => {}
                 }
             }
-            _ => {}
         }
     }
-
-    // Merge adjacent segments with identical formatting to prevent `****` artifacts
-    let mut merged: Vec<(String, bool, bool, bool)> = Vec::new();
-    for seg in segments {
-        if let Some(last) = merged.last_mut() {
-            if last.1 == seg.1 && last.2 == seg.2 && last.3 == seg.3 {
-                last.0.push_str(&seg.0);
-                continue;
-            }
+    let mut code = String::with_capacity(text.len());
+    let mut placeholders = String::new();
+    let mut in_placeholder = false;
+    for c in text.chars() {
+        match c {
+            PLACEHOLDER_OPEN => in_placeholder = true,
+            PLACEHOLDER_CLOSE => in_placeholder = false,
+            _ => {}
+        }
+        if in_placeholder || c == PLACEHOLDER_CLOSE {
+            placeholders.push(c);
+        } else {
+            code.push(c);
         }
-        merged.push(seg);
     }
+    (code, resolve_placeholders(&placeholders, ctx))
+}
 
-    let mut text = String::new();
-    for (t, bold, italic, strike) in merged {
-        text.push_str(&apply_inline_fmt(&t, bold, italic, strike));
+/// A backtick fence (or inline span delimiter) longer than any backtick
+/// run in `text`.
+fn backtick_fence(text: &str, min: usize) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(min.max(longest + 1))
+}
+
+fn code_span(code: &str) -> String {
+    let fence = backtick_fence(code, 1);
+    if code.starts_with('`') || code.ends_with('`') {
+        format!("{} {} {}", fence, code, fence)
+    } else {
+        format!("{}{}{}", fence, code, fence)
     }
+}
 
-    if text.is_empty() {
-        return String::new();
+/// Emit the code block collected in `lines` (if any) as a fenced block,
+/// followed by the references in `refs` as a paragraph of their own, and
+/// clear both. Leading and trailing blank lines are dropped.
+fn push_code_block(
+    output: &mut String,
+    first_block: &mut bool,
+    lines: &mut Vec<String>,
+    refs: &mut String,
+) {
+    let start = lines.iter().position(|l| !l.trim().is_empty());
+    let end = lines.iter().rposition(|l| !l.trim().is_empty());
+    if let (Some(start), Some(end)) = (start, end) {
+        let code = lines[start..=end].join("\n");
+        let fence = backtick_fence(&code, 3);
+        if !*first_block {
+            output.push('\n');
+        }
+        output.push_str(&format!("{}\n{}\n{}\n", fence, code, fence));
+        *first_block = false;
+    }
+    if !refs.trim().is_empty() {
+        if !*first_block {
+            output.push('\n');
+        }
+        output.push_str(refs.trim());
+        output.push('\n');
+        *first_block = false;
     }
+    lines.clear();
+    refs.clear();
+}
 
-    let page_break = if para.property.page_break_before == Some(true) {
-        "---\n\n"
-    } else {
-        ""
-    };
+/// The segment for one run: its image link, or its formatted text.
+fn run_segment(run: &Run, is_title: bool, ctx: &mut DocxContext) -> Option<(String, RunFormat)> {
+    match run_image_markdown(run, ctx) {
+        Some(img_md) => Some((img_md, RunFormat::default())),
+        None => run_to_segment(run, is_title),
+    }
+}
 
-    match list_prefix {
-        Some(prefix) => format!("{}{}{}", page_break, prefix, text),
-        None => format!("{}{}{}", page_break, heading_prefix, text),
+/// Append the segments of a tracked insertion or deletion: as-is when
+/// changes are accepted, between CriticMarkup `open`/`close` otherwise.
+fn push_tracked_change(
+    segments: &mut Vec<(String, RunFormat)>,
+    changed: Vec<(String, RunFormat)>,
+    open: &str,
+    close: &str,
+    options: &DocxOptions,
+) {
+    if changed.is_empty() {
+        return;
+    }
+    let markup = options.tracked_changes == TrackedChanges::CriticMarkup;
+    if markup {
+        segments.push((open.to_string(), RunFormat::default()));
+    }
+    segments.extend(changed);
+    if markup {
+        segments.push((close.to_string(), RunFormat::default()));
     }
 }
 
@@ -385,56 +2376,103 @@ fn run_raw_text(run: &Run) -> String {
     text
 }
 
-/// Return a single (text, bold, italic, strike) segment for a run, or None if empty.
-fn run_to_segment(run: &Run, force_bold: bool) -> Option<(String, bool, bool, bool)> {
+/// Return a single (text, format) segment for a run, or None if empty.
+fn run_to_segment(run: &Run, force_bold: bool) -> Option<(String, RunFormat)> {
     let text = run_raw_text(run);
     if text.is_empty() {
         return None;
     }
-    let bold = force_bold || run.run_property.bold.is_some();
-    let italic = run.run_property.italic.is_some();
-    let strike = run.run_property.strike.as_ref().map(|s| s.val).unwrap_or(false)
-        || run.run_property.dstrike.as_ref().map(|d| d.val).unwrap_or(false);
-    Some((text, bold, italic, strike))
+    // A lifted note reference keeps its run's (superscript) formatting,
+    // which the `[^n]` marker it becomes mustn't inherit.
+    if is_placeholder(&text) {
+        return Some((text, RunFormat::default()));
+    }
+    let property = &run.run_property;
+    // docx-rs keeps these values private, but serializes each as its string.
+    let value = |element: Option<serde_json::Value>| {
+        element
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default()
+    };
+    let underline = value(
+        property
+            .underline
+            .as_ref()
+            .and_then(|u| serde_json::to_value(u).ok()),
+    );
+    let highlight = value(
+        property
+            .highlight
+            .as_ref()
+            .and_then(|h| serde_json::to_value(h).ok()),
+    );
+    let vert_align = value(
+        property
+            .vert_align
+            .as_ref()
+            .and_then(|v| serde_json::to_value(v).ok()),
+    );
+    let color = value(
+        property
+            .color
+            .as_ref()
+            .and_then(|c| serde_json::to_value(c).ok()),
+    );
+    let format = RunFormat {
+        bold: force_bold || property.bold.is_some(),
+        italic: property.italic.is_some(),
+        strike: property.strike.as_ref().map(|s| s.val).unwrap_or(false)
+            || property.dstrike.as_ref().map(|d| d.val).unwrap_or(false),
+        underline: !underline.is_empty() && underline != "none",
+        highlight: !highlight.is_empty() && highlight != "none",
+        vert_align: match vert_align.as_str() {
+            "superscript" => VertAlign::Superscript,
+            "subscript" => VertAlign::Subscript,
+            _ => VertAlign::Baseline,
+        },
+        color: RunFormat::hex_color(&color),
+    };
+    Some((text, format))
 }
 
-fn run_to_markdown(run: &Run, docx_media: &DocxMedia, media: &mut MediaSink) -> String {
-    if let Some(img_md) = run_image_markdown(run, docx_media, media) {
+fn run_to_markdown(run: &Run, ctx: &mut DocxContext) -> String {
+    if let Some(img_md) = run_image_markdown(run, ctx) {
         return img_md;
     }
     match run_to_segment(run, false) {
         None => String::new(),
-        Some((text, bold, italic, strike)) => apply_inline_fmt(&text, bold, italic, strike),
+        Some((text, format)) => {
+            resolve_placeholders(&apply_inline_fmt(&text, &format, ctx.inline), ctx)
+        }
     }
 }
 
-fn sdt_to_markdown(
-    sdt: &StructuredDataTag,
-    num_map: &HashMap<usize, bool>,
-    docx_media: &DocxMedia,
-    media: &mut MediaSink,
-) -> String {
+fn sdt_to_markdown(sdt: &StructuredDataTag, ctx: &mut DocxContext) -> String {
     let mut output = String::new();
     for child in &sdt.children {
         match child {
             StructuredDataTagChild::Paragraph(para) => {
-                let md = paragraph_to_markdown(para, num_map, docx_media, media);
+                let md = match toc_field_paragraph(para, ctx) {
+                    TocParagraph::Outside => paragraph_to_markdown(para, ctx),
+                    TocParagraph::Start(levels) => ctx.anchors.toc_placeholder(levels),
+                    TocParagraph::Inside => continue,
+                };
                 if !md.trim().is_empty() {
                     output.push_str(&md);
                     output.push('\n');
                 }
             }
             StructuredDataTagChild::Table(table) => {
-                output.push_str(&table_to_markdown(table, num_map, docx_media, media));
+                output.push_str(&table_to_markdown(table, ctx));
             }
             StructuredDataTagChild::Run(run) => {
-                let md = run_to_markdown(run, docx_media, media);
+                let md = run_to_markdown(run, ctx);
                 if !md.is_empty() {
                     output.push_str(&md);
                 }
             }
             StructuredDataTagChild::StructuredDataTag(nested) => {
-                let md = sdt_to_markdown(nested, num_map, docx_media, media);
+                let md = sdt_to_markdown(nested, ctx);
                 if !md.is_empty() {
                     output.push_str(&md);
                 }
@@ -445,38 +2483,92 @@ fn sdt_to_markdown(
     output
 }
 
-fn table_to_markdown(
-    table: &Table,
-    num_map: &HashMap<usize, bool>,
-    docx_media: &DocxMedia,
-    media: &mut MediaSink,
-) -> String {
-    let mut rows: Vec<Vec<String>> = Vec::new();
+/// A cell of a DOCX table placed on the table's real grid.
+struct GridCell {
+    text: String,
+    colspan: usize,
+    rowspan: usize,
+}
+
+/// Convert a table to a GFM table, or — when it has merged cells, which GFM
+/// can't express — to an HTML `<table>` with `colspan`/`rowspan`.
+fn table_to_markdown(table: &Table, ctx: &mut DocxContext) -> String {
+    // Rebuild the grid: a `gridSpan` cell covers several columns, and a
+    // `vMerge="continue"` cell extends the cell above it rather than
+    // starting a new one. `merge_origin[col]` is the (row, index) of the
+    // `vMerge="restart"` cell currently open in that grid column.
+    let mut rows: Vec<Vec<GridCell>> = Vec::new();
+    let mut merge_origin: Vec<Option<(usize, usize)>> = Vec::new();
 
     for row_child in &table.rows {
         let TableChild::TableRow(table_row) = row_child;
-        let mut cells: Vec<String> = Vec::new();
+        if table_row.cells.is_empty() {
+            continue;
+        }
+        let row_idx = rows.len();
+        let mut cells: Vec<GridCell> = Vec::new();
+        let mut col = 0;
         for cell_child in &table_row.cells {
             let TableRowChild::TableCell(table_cell) = cell_child;
-            let mut cell_text = String::new();
-            for content in &table_cell.children {
-                if let TableCellContent::Paragraph(para) = content {
-                    let p = paragraph_to_markdown(para, num_map, docx_media, media);
-                    if !p.is_empty() {
-                        if !cell_text.is_empty() {
-                            cell_text.push(' ');
+            let (colspan, vmerge) = cell_merge(&table_cell.property);
+            let text = cell_to_markdown(table_cell, ctx);
+            if merge_origin.len() < col + colspan {
+                merge_origin.resize(col + colspan, None);
+            }
+
+            if vmerge.as_deref() == Some("continue") {
+                if let Some((r, i)) = merge_origin[col] {
+                    let origin = &mut rows[r][i];
+                    origin.rowspan += 1;
+                    if !text.is_empty() {
+                        if !origin.text.is_empty() {
+                            origin.text.push(' ');
                         }
-                        cell_text.push_str(p.trim());
+                        origin.text.push_str(&text);
                     }
+                    col += colspan;
+                    continue;
                 }
             }
-            cells.push(cell_text);
-        }
-        if !cells.is_empty() {
-            rows.push(cells);
+
+            let origin = (vmerge.as_deref() == Some("restart")).then_some((row_idx, cells.len()));
+            merge_origin[col..col + colspan].fill(origin);
+            cells.push(GridCell {
+                text,
+                colspan,
+                rowspan: 1,
+            });
+            col += colspan;
         }
+        rows.push(cells);
+    }
+
+    let merged = rows
+        .iter()
+        .flatten()
+        .any(|cell| cell.colspan > 1 || cell.rowspan > 1);
+    if merged {
+        ctx.report.stats.tables += 1;
+        let html_rows: Vec<Vec<SpannedCell>> = rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|cell| SpannedCell {
+                        html: markdown_inline_to_html(&cell.text),
+                        colspan: cell.colspan,
+                        rowspan: cell.rowspan,
+                    })
+                    .collect()
+            })
+            .collect();
+        return spanned_table_html(&html_rows);
     }
 
+    let rows: Vec<Vec<String>> = rows
+        .into_iter()
+        .map(|row| row.into_iter().map(|cell| cell.text).collect())
+        .collect();
+
     if rows.is_empty() {
         return String::new();
     }
@@ -486,6 +2578,7 @@ fn table_to_markdown(
         return String::new();
     }
 
+    ctx.report.stats.tables += 1;
     let mut md = String::new();
 
     // Header row
@@ -517,14 +2610,81 @@ fn table_to_markdown(
     md
 }
 
+/// A cell's paragraphs as one line of Markdown.
+fn cell_to_markdown(table_cell: &TableCell, ctx: &mut DocxContext) -> String {
+    ctx.nested += 1;
+    let mut cell_text = String::new();
+    for content in &table_cell.children {
+        if let TableCellContent::Paragraph(para) = content {
+            let p = paragraph_to_markdown(para, ctx);
+            // Text boxes anchored in the paragraph come back as extra
+            // blocks, which a table row can only hold on one line.
+            for line in p.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                if !cell_text.is_empty() {
+                    cell_text.push(' ');
+                }
+                cell_text.push_str(line);
+            }
+        }
+    }
+    ctx.nested -= 1;
+    cell_text
+}
+
+/// `(gridSpan, vMerge)` of a cell, e.g. `(2, Some("restart"))`. docx-rs
+/// keeps both fields private, so they're read back through the property's
+/// `Serialize` impl.
+fn cell_merge(property: &TableCellProperty) -> (usize, Option<String>) {
+    let json = serde_json::to_value(property).unwrap_or_default();
+    let span = json["gridSpan"]
+        .as_u64()
+        .map_or(1, |span| span.max(1) as usize);
+    let vmerge = json["verticalMerge"].as_str().map(str::to_string);
+    (span, vmerge)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::convert::inline_fmt::ScriptSyntax;
+
+    static DEFAULT_OPTIONS: DocxOptions = DocxOptions {
+        tracked_changes: TrackedChanges::Accept,
+        comments: Comments::Drop,
+        front_matter: false,
+        style_map: BTreeMap::new(),
+        headers_footers: false,
+        table_of_contents: TableOfContents::Placeholder,
+    };
+
+    static DEFAULT_INLINE: InlineOptions = InlineOptions {
+        script: ScriptSyntax::Html,
+        color: false,
+    };
+
+    static DEFAULT_CHARTS: ChartOptions = ChartOptions { mermaid: false };
 
-    fn empty_media() -> DocxMedia {
-        DocxMedia {
-            rels: HashMap::new(),
-            parts: HashMap::new(),
+    fn test_ctx<'a>(media: &'a mut MediaSink, report: &'a mut ImportReport) -> DocxContext<'a> {
+        DocxContext {
+            options: &DEFAULT_OPTIONS,
+            inline: &DEFAULT_INLINE,
+            charts: &DEFAULT_CHARTS,
+            numbering: ListNumbering::default(),
+            docx_media: DocxMedia {
+                rels: HashMap::new(),
+                parts: HashMap::new(),
+                alt_texts: HashMap::new(),
+                charts: HashMap::new(),
+                part_images: HashMap::new(),
+            },
+            notes: DocxNotes::default(),
+            style_blocks: HashMap::new(),
+            anchors: HeadingAnchors::default(),
+            nested: 0,
+            toc_field: TocField::default(),
+            equations: Vec::new(),
+            media,
+            report,
         }
     }
 
@@ -532,7 +2692,8 @@ mod tests {
     fn test_run_to_markdown_bold() {
         let run = Run::new().add_text("hello").bold();
         let mut sink = MediaSink::new(std::env::temp_dir());
-        let result = run_to_markdown(&run, &empty_media(), &mut sink);
+        let mut report = ImportReport::default();
+        let result = run_to_markdown(&run, &mut test_ctx(&mut sink, &mut report));
         assert_eq!(result, "**hello**");
     }
 
@@ -540,7 +2701,8 @@ mod tests {
     fn test_run_to_markdown_plain() {
         let run = Run::new().add_text("hello");
         let mut sink = MediaSink::new(std::env::temp_dir());
-        let result = run_to_markdown(&run, &empty_media(), &mut sink);
+        let mut report = ImportReport::default();
+        let result = run_to_markdown(&run, &mut test_ctx(&mut sink, &mut report));
         assert_eq!(result, "hello");
     }
 
@@ -552,15 +2714,25 @@ mod tests {
                 .add_run(Run::new().add_text("TABLE OF CONTENTS 1")),
         );
         let mut sink = MediaSink::new(std::env::temp_dir());
-        let md = paragraph_to_markdown(&para, &HashMap::new(), &empty_media(), &mut sink);
+        let mut report = ImportReport::default();
+        let md = paragraph_to_markdown(&para, &mut test_ctx(&mut sink, &mut report));
         assert_eq!(md, "[TABLE OF CONTENTS 1](#_Toc181806136)");
     }
 
+    #[test]
+    fn test_count_elements_matches_whole_tag_names() {
+        let xml = r#"<w:p><w:ins w:id="1"><w:r><w:instrText>REF</w:instrText></w:r></w:ins><w:del w:id="2"/><w:ins>x</w:ins></w:p>"#;
+        assert_eq!(count_elements(xml, "w:ins"), 2);
+        assert_eq!(count_elements(xml, "w:del"), 1);
+        assert_eq!(count_elements(xml, "w:instrText"), 1);
+    }
+
     #[test]
     fn test_run_to_markdown_whitespace_bold_not_wrapped() {
         let run = Run::new().add_text("   ").bold();
         let mut sink = MediaSink::new(std::env::temp_dir());
-        let result = run_to_markdown(&run, &empty_media(), &mut sink);
+        let mut report = ImportReport::default();
+        let result = run_to_markdown(&run, &mut test_ctx(&mut sink, &mut report));
         assert_eq!(result, "   ");
     }
 
@@ -572,34 +2744,890 @@ mod tests {
     fn test_run_to_markdown_bold_with_leading_whitespace_and_literal_asterisk() {
         let run = Run::new().add_text("  * means mandatory").bold();
         let mut sink = MediaSink::new(std::env::temp_dir());
-        let result = run_to_markdown(&run, &empty_media(), &mut sink);
+        let mut report = ImportReport::default();
+        let result = run_to_markdown(&run, &mut test_ctx(&mut sink, &mut report));
         assert_eq!(result, "  **\\* means mandatory**");
     }
 
+    #[test]
+    fn test_lift_note_references_becomes_placeholder_text() {
+        let xml = r#"<w:r><w:rPr><w:vertAlign w:val="superscript"/></w:rPr><w:footnoteReference w:id="2"/></w:r><w:r><w:endnoteReference w:id="1"></w:endnoteReference></w:r>"#;
+        assert_eq!(
+            lift_note_references(xml),
+            "<w:r><w:rPr><w:vertAlign w:val=\"superscript\"/></w:rPr><w:t>\u{E000}f2\u{E001}</w:t></w:r><w:r><w:t>\u{E000}e1\u{E001}</w:t></w:r>"
+        );
+    }
+
+    #[test]
+    fn test_lift_charts_inside_text_box_drawing() {
+        let xml = r#"<w:r><w:drawing><wp:anchor><w:txbxContent><w:p><w:r><w:drawing><c:chart r:id="rId5"/></w:drawing></w:r><w:r><w:drawing><pic:pic/></w:drawing></w:r></w:p></w:txbxContent></wp:anchor></w:drawing></w:r>"#;
+        assert_eq!(
+            lift_charts(xml),
+            "<w:r><w:drawing><wp:anchor><w:txbxContent><w:p><w:r><w:t>\u{E000}grId5\u{E001}</w:t></w:r>\
+             <w:r><w:drawing><pic:pic/></w:drawing></w:r></w:p></w:txbxContent></wp:anchor></w:drawing></w:r>"
+        );
+    }
+
+    #[test]
+    fn test_parse_notes_skips_separators_and_keeps_formatting() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:endnotes xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:endnote w:type="separator" w:id="-1"><w:p><w:r><w:separator/></w:r></w:p></w:endnote>
+<w:endnote w:id="1"><w:p><w:r><w:endnoteRef/></w:r><w:r><w:t xml:space="preserve"> See </w:t></w:r><w:r><w:rPr><w:i/></w:rPr><w:t>Smith v. Jones</w:t></w:r></w:p></w:endnote>
+</w:endnotes>"#;
+        let notes = parse_notes(xml, "w:endnotes", "w:endnote");
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].0, 1);
+
+        let mut sink = MediaSink::new(std::env::temp_dir());
+        let mut report = ImportReport::default();
+        let md = paragraph_to_markdown(&notes[0].1[0], &mut test_ctx(&mut sink, &mut report));
+        assert_eq!(md.trim(), "See *Smith v. Jones*");
+    }
+
+    /// Pack `docx` to a temp file and import it with `options`.
+    fn import_built_docx(
+        name: &str,
+        docx: Docx,
+        options: &ImportOptions,
+    ) -> (String, ImportReport) {
+        import_docx_bytes(name, &pack_docx(docx), options)
+    }
+
+    fn pack_docx(docx: Docx) -> Vec<u8> {
+        let mut buf = Cursor::new(Vec::new());
+        docx.build().pack(&mut buf).unwrap();
+        buf.into_inner()
+    }
+
+    fn import_docx_bytes(
+        name: &str,
+        bytes: &[u8],
+        options: &ImportOptions,
+    ) -> (String, ImportReport) {
+        let dir =
+            std::env::temp_dir().join(format!("pourdown-docx-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("{}.docx", name));
+        std::fs::write(&path, bytes).unwrap();
+
+        let mut sink = MediaSink::new(dir.join("assets"));
+        let mut report = ImportReport::default();
+        let md = docx_to_markdown(
+            path.to_str().unwrap(),
+            &mut sink,
+            options,
+            &mut report,
+            &ImportProgress::default(),
+        )
+        .unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        (md, report)
+    }
+
+    #[test]
+    fn test_docx_to_markdown_footnotes() {
+        use docx_rs::Footnote;
+        let note = |text: &str| {
+            Footnote::new().add_content(
+                Paragraph::new()
+                    .add_run(Run::new().add_text("Per "))
+                    .add_run(Run::new().add_text(text).bold()),
+            )
+        };
+        let docx = Docx::new().add_paragraph(
+            Paragraph::new()
+                .add_run(Run::new().add_text("Claim one"))
+                .add_run(Run::new().add_footnote_reference(note("statute")))
+                .add_run(Run::new().add_text(" and two"))
+                .add_run(Run::new().add_footnote_reference(note("case law"))),
+        );
+        let (md, report) = import_built_docx("footnotes", docx, &ImportOptions::default());
+
+        assert!(md.starts_with("Claim one[^1] and two[^2]\n"), "{md}");
+        assert!(
+            md.contains("\n[^1]: Per **statute**\n[^2]: Per **case law**\n"),
+            "{md}"
+        );
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
+    #[test]
+    fn test_docx_underline_highlight_scripts_and_color() {
+        use docx_rs::{Footnote, VertAlignType};
+        let script = |run: Run, kind| {
+            let mut run = run;
+            run.run_property = run.run_property.vert_align(kind);
+            run
+        };
+        let note =
+            Footnote::new().add_content(Paragraph::new().add_run(Run::new().add_text("Source")));
+        let docx = Docx::new().add_paragraph(
+            Paragraph::new()
+                .add_run(Run::new().add_text("H"))
+                .add_run(script(Run::new().add_text("2"), VertAlignType::SubScript))
+                .add_run(Run::new().add_text("O is "))
+                .add_run(Run::new().add_text("key").underline("single"))
+                .add_run(Run::new().add_text(" and "))
+                .add_run(
+                    Run::new()
+                        .add_text("new")
+                        .highlight("yellow")
+                        .color("FF0000"),
+                )
+                .add_run(Run::new().add_text(" x"))
+                .add_run(script(Run::new().add_text("2"), VertAlignType::SuperScript))
+                // A superscript note reference stays a bare `[^1]`.
+                .add_run(script(
+                    Run::new().add_footnote_reference(note),
+                    VertAlignType::SuperScript,
+                )),
+        );
+        let bytes = pack_docx(docx);
+
+        let (md, _) = import_docx_bytes("inline-default", &bytes, &ImportOptions::default());
+        assert!(
+            md.starts_with("H<sub>2</sub>O is <u>key</u> and <mark>new</mark> x<sup>2</sup>[^1]\n"),
+            "{md}"
+        );
+
+        let mut options = ImportOptions::default();
+        options.inline.script = ScriptSyntax::Pandoc;
+        options.inline.color = true;
+        let (md, _) = import_docx_bytes("inline-options", &bytes, &options);
+        assert!(
+            md.starts_with(
+                "H~2~O is <u>key</u> and <span style=\"color: #FF0000\"><mark>new</mark></span> x^2^[^1]\n"
+            ),
+            "{md}"
+        );
+    }
+
+    #[test]
+    fn test_parse_image_alt_texts() {
+        let xml = concat!(
+            r#"<w:drawing><wp:inline><wp:docPr id="1" name="Picture 1" descr="Sales [Q3]&#xA;by region" title="Chart"/>"#,
+            r#"<a:blip r:embed="rId5"/></wp:inline></w:drawing>"#,
+            r#"<w:drawing><wp:inline><wp:docPr id="2" name="Picture 2" title="Logo &amp; mark"/>"#,
+            r#"<a:blip r:embed="rId6"/></wp:inline></w:drawing>"#,
+            r#"<w:drawing><wp:inline><wp:docPr id="3" name="Picture 3" descr=" "/>"#,
+            r#"<a:blip r:embed="rId7"/></wp:inline></w:drawing>"#,
+        );
+        let alts = parse_image_alt_texts(xml);
+        assert_eq!(alts["rId5"], "Sales [Q3] by region");
+        assert_eq!(alts["rId6"], "Logo & mark");
+        assert!(!alts.contains_key("rId7"));
+    }
+
+    #[test]
+    fn test_docx_image_alt_text_from_description_or_caption() {
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(image::RgbImage::new(2, 2))
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let docx = Docx::new()
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_image(docx_rs::Pic::new(&png))))
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Figure 1: Red [square]")));
+        let bytes = pack_docx(docx);
+
+        let (md, _) = import_docx_bytes("alt-caption", &bytes, &ImportOptions::default());
+        assert!(md.starts_with("![Figure 1: Red (square)](assets/"), "{md}");
+        assert!(md.contains("\n\nFigure 1: Red [square]\n"), "{md}");
+
+        let xml = read_zip_part(&bytes, "word/document.xml").unwrap();
+        let xml = xml.replacen("<wp:docPr ", r#"<wp:docPr descr="A red square" "#, 1);
+        let bytes = replace_zip_part(&bytes, "word/document.xml", &xml).unwrap();
+        let (md, _) = import_docx_bytes("alt-descr", &bytes, &ImportOptions::default());
+        assert!(md.starts_with("![A red square](assets/"), "{md}");
+    }
+
+    /// Build a document with docx-rs (which can't write equations or text
+    /// boxes), then swap each run whose text is `XML` for the next element
+    /// of `elements`.
+    fn docx_with_xml(docx: Docx, elements: &[&str]) -> Vec<u8> {
+        let bytes = pack_docx(docx);
+        let mut xml = read_zip_part(&bytes, "word/document.xml").unwrap();
+        for element in elements {
+            let text = xml.find(">XML<").unwrap();
+            let start = xml[..text].rfind("<w:r>").unwrap();
+            let end = text + xml[text..].find("</w:r>").unwrap() + "</w:r>".len();
+            xml.replace_range(start..end, element);
+        }
+        let xml = xml.replacen(
+            "<w:document ",
+            "<w:document xmlns:m=\"http://schemas.openxmlformats.org/officeDocument/2006/math\" ",
+            1,
+        );
+        replace_zip_part(&bytes, "word/document.xml", &xml).unwrap()
+    }
+
+    #[test]
+    fn test_docx_chart_becomes_table() {
+        const CHART: &str = r#"<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart">
+<c:chart><c:title><c:tx><c:strRef><c:strCache><c:pt idx="0"><c:v>Revenue</c:v></c:pt></c:strCache></c:strRef></c:tx></c:title>
+<c:plotArea><c:barChart><c:ser><c:tx><c:v>2024</c:v></c:tx>
+<c:cat><c:strLit><c:pt idx="0"><c:v>EU</c:v></c:pt><c:pt idx="1"><c:v>US</c:v></c:pt></c:strLit></c:cat>
+<c:val><c:numLit><c:pt idx="0"><c:v>12</c:v></c:pt><c:pt idx="1"><c:v>30</c:v></c:pt></c:numLit></c:val>
+</c:ser></c:barChart></c:plotArea></c:chart></c:chartSpace>"#;
+        let docx = Docx::new()
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Before")))
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("XML")))
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("After")));
+        let bytes = docx_with_xml(
+            docx,
+            &[
+                r#"<w:r><w:drawing><wp:inline><a:graphic><a:graphicData><c:chart r:id="rIdChart1"/></a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"#,
+            ],
+        );
+        let rels = read_zip_part(&bytes, "word/_rels/document.xml.rels").unwrap().replacen(
+            "</Relationships>",
+            r#"<Relationship Id="rIdChart1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart" Target="charts/chart1.xml"/></Relationships>"#,
+            1,
+        );
+        let bytes = replace_zip_part(&bytes, "word/_rels/document.xml.rels", &rels).unwrap();
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let mut archive = zip::ZipArchive::new(Cursor::new(&bytes)).unwrap();
+        for i in 0..archive.len() {
+            writer
+                .raw_copy_file(archive.by_index_raw(i).unwrap())
+                .unwrap();
+        }
+        writer
+            .start_file("word/charts/chart1.xml", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(CHART.as_bytes()).unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let table = "**Revenue**\n\n| Category | 2024 |\n| --- | --- |\n| EU | 12 |\n| US | 30 |";
+        let (md, _) = import_docx_bytes("chart", &bytes, &ImportOptions::default());
+        assert_eq!(md, format!("Before\n\n{}\n\nAfter\n", table));
+
+        let mut options = ImportOptions::default();
+        options.charts.mermaid = true;
+        let (md, _) = import_docx_bytes("chart-mermaid", &bytes, &options);
+        assert!(
+            md.contains("```mermaid\nxychart-beta\n    title \"Revenue\"\n"),
+            "{md}"
+        );
+    }
+
+    #[test]
+    fn test_docx_equations_to_latex() {
+        let docx = Docx::new()
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_text("Area is "))
+                    .add_run(Run::new().add_text("XML"))
+                    .add_run(Run::new().add_text(".")),
+            )
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("XML")));
+        let inline = "<m:oMath><m:r><m:t>π</m:t></m:r><m:sSup><m:e><m:r><m:t>r</m:t></m:r></m:e>\
+                      <m:sup><m:r><m:t>2</m:t></m:r></m:sup></m:sSup></m:oMath>";
+        let display = "<m:oMathPara><m:oMath><m:f><m:num><m:r><m:t>a</m:t></m:r></m:num>\
+                       <m:den><m:r><m:t>b</m:t></m:r></m:den></m:f></m:oMath></m:oMathPara>";
+        let bytes = docx_with_xml(docx, &[inline, display]);
+        let (md, _) = import_docx_bytes("equations", &bytes, &ImportOptions::default());
+        assert_eq!(md, "Area is $\\pi r^{2}$.\n\n$$\\frac{a}{b}$$\n");
+    }
+
+    #[test]
+    fn test_docx_front_matter_from_document_properties() {
+        let docx = Docx::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text("Body")));
+        let core = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><dc:title>Q3: Plan &amp; "Budget"</dc:title><dc:creator>Ada Lovelace</dc:creator><dc:subject/><cp:keywords>finance; planning</cp:keywords><dcterms:created xsi:type="dcterms:W3CDTF">2024-03-01T09:30:00Z</dcterms:created><cp:revision>7</cp:revision></cp:coreProperties>"#;
+        let bytes = replace_zip_part(&pack_docx(docx), "docProps/core.xml", core).unwrap();
+
+        let (md, _) = import_docx_bytes("props-default", &bytes, &ImportOptions::default());
+        assert_eq!(md, "Body\n");
+
+        let mut options = ImportOptions::default();
+        options.docx.front_matter = true;
+        let (md, _) = import_docx_bytes("props-front-matter", &bytes, &options);
+        assert!(
+            md.starts_with(concat!(
+                "---\n",
+                "title: \"Q3: Plan & \\\"Budget\\\"\"\n",
+                "author: \"Ada Lovelace\"\n",
+                "keywords: [\"finance\", \"planning\"]\n",
+                "created: 2024-03-01T09:30:00Z\n",
+                "revision: 7\n",
+            )),
+            "{}",
+            md
+        );
+        assert!(md.ends_with("---\n\nBody\n"), "{}", md);
+    }
+
+    #[test]
+    fn test_docx_code_quote_and_caption_styles() {
+        use docx_rs::{Hyperlink, HyperlinkType, RunFonts, Style, StyleType};
+        let styled = |style: &str, text: &str| {
+            Paragraph::new()
+                .style(style)
+                .add_run(Run::new().add_text(text))
+        };
+        let docx = Docx::new()
+            .add_style(Style::new("SourceCode", StyleType::Paragraph).name("Source Code"))
+            .add_style(Style::new("PullQuote", StyleType::Paragraph).name("Pull Quote"))
+            .add_paragraph(styled("Normal", "Intro"))
+            .add_paragraph(styled("SourceCode", "fn main() {"))
+            .add_paragraph(styled("SourceCode", "    println!(\"*hi*\");"))
+            .add_paragraph(styled("SourceCode", "}"))
+            .add_paragraph(styled("IntenseQuote", "Stay *calm*."))
+            .add_paragraph(styled("Caption", "Figure 1: Revenue"))
+            .add_paragraph(
+                Paragraph::new().add_run(
+                    Run::new()
+                        .add_text("ls -la")
+                        .fonts(RunFonts::new().ascii("Consolas")),
+                ),
+            )
+            .add_paragraph(
+                Paragraph::new().style("Heading2").add_run(
+                    Run::new()
+                        .add_text("Config")
+                        .fonts(RunFonts::new().ascii("Consolas")),
+                ),
+            )
+            .add_paragraph(
+                Paragraph::new().style("SourceCode").add_hyperlink(
+                    Hyperlink::new("Setup", HyperlinkType::Anchor)
+                        .add_run(Run::new().add_text("see Setup")),
+                ),
+            )
+            .add_paragraph(styled("PullQuote", "Custom"));
+
+        let mut options = ImportOptions::default();
+        options
+            .docx
+            .style_map
+            .insert("pull quote".into(), StyleBlock::Heading2);
+        let (md, _) = import_built_docx("styles", docx, &options);
+        assert_eq!(
+            md,
+            "Intro\n\n```\nfn main() {\n    println!(\"*hi*\");\n}\n```\n\n> Stay \\*calm\\*.\n\n\
+             *Figure 1: Revenue*\n\n```\nls -la\n```\n\n## Config\n\n\
+             [see Setup](#Setup)\n\n## Custom\n"
+        );
+    }
+
+    #[test]
+    fn test_docx_code_paragraph_keeps_references_out_of_the_code() {
+        use docx_rs::{Footnote, Style, StyleType};
+        let docx = Docx::new()
+            .add_style(Style::new("SourceCode", StyleType::Paragraph).name("Source Code"))
+            .add_paragraph(
+                Paragraph::new()
+                    .style("SourceCode")
+                    .add_comment_start(
+                        Comment::new(1)
+                            .author("Ada")
+                            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Why?"))),
+                    )
+                    .add_run(Run::new().add_text("make all"))
+                    .add_comment_end(1)
+                    .add_run(
+                        Run::new().add_footnote_reference(Footnote::new().add_content(
+                            Paragraph::new().add_run(Run::new().add_text("Needs GNU make")),
+                        )),
+                    ),
+            );
+        let (md, _) = import_built_docx("code-refs", docx.clone(), &ImportOptions::default());
+        assert_eq!(md, "```\nmake all\n```\n\n[^1]\n\n[^1]: Needs GNU make\n");
+
+        let mut options = ImportOptions::default();
+        options.docx.comments = Comments::Footnotes;
+        let (md, _) = import_built_docx("code-refs-comments", docx, &options);
+        assert_eq!(
+            md,
+            "```\nmake all\n```\n\n[^1][^2]\n\n[^1]: Ada: Why?\n[^2]: Needs GNU make\n"
+        );
+    }
+
+    #[test]
+    fn test_docx_text_box_after_its_anchor() {
+        let docx = Docx::new()
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_text("Main text"))
+                    .add_run(Run::new().add_text("XML")),
+            )
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("After")));
+        let text_box =
+            "<w:r><w:drawing><wp:anchor simplePos=\"0\" relativeHeight=\"1\" allowOverlap=\"1\">\
+            <wp:simplePos x=\"0\" y=\"0\"/><wp:extent cx=\"914400\" cy=\"457200\"/>\
+            <wp:docPr id=\"1\" name=\"Text Box 1\"/><a:graphic><a:graphicData \
+            uri=\"http://schemas.microsoft.com/office/word/2010/wordprocessingShape\"><wps:wsp>\
+            <wps:txbx><w:txbxContent><w:p><w:r><w:rPr><w:b/></w:rPr><w:t>Sidebar</w:t></w:r></w:p>\
+            <w:p><w:r><w:t>Note</w:t></w:r></w:p></w:txbxContent></wps:txbx></wps:wsp>\
+            </a:graphicData></a:graphic></wp:anchor></w:drawing></w:r>";
+        let bytes = docx_with_xml(docx, &[text_box]);
+        let (md, _) = import_docx_bytes("text-box", &bytes, &ImportOptions::default());
+        assert_eq!(md, "Main text\n\n**Sidebar**\n\nNote\n\nAfter\n");
+    }
+
+    #[test]
+    fn test_docx_headers_and_footers_opt_in() {
+        use docx_rs::{Footer, Header};
+        let docx = Docx::new()
+            .header(
+                Header::new()
+                    .add_paragraph(Paragraph::new().add_run(Run::new().add_text("DOC-42"))),
+            )
+            .first_header(
+                Header::new()
+                    .add_paragraph(Paragraph::new().add_run(Run::new().add_text("DOC-42"))),
+            )
+            .footer(
+                Footer::new()
+                    .add_paragraph(Paragraph::new().add_run(Run::new().add_text("CONFIDENTIAL")))
+                    .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Acme Corp"))),
+            )
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Body")));
+        let bytes = pack_docx(docx);
+
+        let (md, report) = import_docx_bytes("headers-default", &bytes, &ImportOptions::default());
+        assert_eq!(md, "Body\n");
+        let kinds: Vec<_> = report.warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(kinds, [WarningKind::HeadersFootersRemoved]);
+
+        let mut options = ImportOptions::default();
+        options.docx.headers_footers = true;
+        let (md, report) = import_docx_bytes("headers-on", &bytes, &options);
+        assert_eq!(
+            md,
+            "Body\n\n---\n\n**Header:** DOC-42\n\n**Footer:** CONFIDENTIAL · Acme Corp\n"
+        );
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
+    /// Swap the `XML` text run in `part` for a copy of the body's picture,
+    /// described as `alt`, whose rId the part's own relationships point at
+    /// a different image than the body's.
+    fn with_part_picture(bytes: &[u8], part: &str, alt: &str) -> Vec<u8> {
+        let body = read_zip_part(bytes, "word/document.xml").unwrap();
+        let start = body.find("<w:drawing>").unwrap();
+        let end = body.find("</w:drawing>").unwrap() + "</w:drawing>".len();
+        let drawing =
+            body[start..end].replacen("<wp:docPr ", &format!("<wp:docPr descr=\"{}\" ", alt), 1);
+        let xml = read_zip_part(bytes, part).unwrap().replacen(
+            "<w:t xml:space=\"preserve\">XML</w:t>",
+            &drawing,
+            1,
+        );
+        let id = get_rels_attr(&drawing, "r:embed").unwrap();
+        let (dir, file) = part.rsplit_once('/').unwrap();
+        let stem = file.trim_end_matches(".xml");
+        let rels = format!(
+            "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+             <Relationship Id=\"{}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/image\" \
+             Target=\"media/{}.png\"/></Relationships>",
+            id, stem
+        );
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(image::RgbImage::new(3, 3))
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let rels_name = format!("{}/_rels/{}.rels", dir, file);
+        let media_name = format!("{}/media/{}.png", dir, stem);
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        for i in 0..archive.len() {
+            let entry = archive.by_index_raw(i).unwrap();
+            if entry.name() != part && entry.name() != rels_name {
+                writer.raw_copy_file(entry).unwrap();
+            }
+        }
+        for (name, content) in [
+            (part, xml.as_bytes()),
+            (&rels_name, rels.as_bytes()),
+            (&media_name, &png),
+        ] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn two_by_two_png() -> Vec<u8> {
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(image::RgbImage::new(2, 2))
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    #[test]
+    fn test_docx_header_pictures_use_the_header_relationships() {
+        use docx_rs::Header;
+        let docx = Docx::new()
+            .header(
+                Header::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text("XML"))),
+            )
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_image(docx_rs::Pic::new(&two_by_two_png()))),
+            );
+        let bytes = with_part_picture(&pack_docx(docx), "word/header1.xml", "Letterhead");
+
+        let mut options = ImportOptions::default();
+        options.docx.headers_footers = true;
+        let (md, report) = import_docx_bytes("header-picture", &bytes, &options);
+        assert_eq!(
+            md,
+            "![](assets/image1.png)\n\n---\n\n**Header:** ![Letterhead](assets/image2.png)\n"
+        );
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
+    #[test]
+    fn test_docx_note_pictures_use_the_note_relationships() {
+        use docx_rs::Footnote;
+        let note =
+            Footnote::new().add_content(Paragraph::new().add_run(Run::new().add_text("XML")));
+        let docx = Docx::new()
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_image(docx_rs::Pic::new(&two_by_two_png()))),
+            )
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_text("Scan"))
+                    .add_run(Run::new().add_footnote_reference(note)),
+            );
+        let bytes = with_part_picture(&pack_docx(docx), "word/footnotes.xml", "Receipt");
+
+        let (md, report) = import_docx_bytes("note-picture", &bytes, &ImportOptions::default());
+        assert_eq!(
+            md,
+            "![](assets/image1.png)\n\nScan[^1]\n\n[^1]: ![Receipt](assets/image2.png)\n"
+        );
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
+    #[test]
+    fn test_docx_bookmark_links_resolve_to_heading_slugs() {
+        use docx_rs::{FieldCharType, Hyperlink, HyperlinkType, InstrText};
+        let heading = |bookmark: &str, id: usize, text: &str| {
+            Paragraph::new()
+                .style("Heading1")
+                .add_bookmark_start(id, bookmark)
+                .add_run(Run::new().add_text(text))
+                .add_bookmark_end(id)
+        };
+        let docx = Docx::new()
+            .add_paragraph(
+                Paragraph::new().add_hyperlink(
+                    Hyperlink::new("_Toc1", HyperlinkType::Anchor)
+                        .add_run(Run::new().add_text("Getting Started")),
+                ),
+            )
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_text("See "))
+                    .add_run(Run::new().add_field_char(FieldCharType::Begin, false))
+                    .add_run(
+                        Run::new().add_instr_text(InstrText::Unsupported(" REF _Ref2 \\h ".into())),
+                    )
+                    .add_run(Run::new().add_field_char(FieldCharType::Separate, false))
+                    .add_run(Run::new().add_text("Setup, Part 2"))
+                    .add_run(Run::new().add_field_char(FieldCharType::End, false))
+                    .add_run(Run::new().add_text(".")),
+            )
+            .add_paragraph(heading("_Toc1", 1, "Getting Started"))
+            .add_paragraph(heading("_Ref9", 9, "Setup, Part 2"))
+            .add_paragraph(heading("_Ref2", 2, "Setup, Part 2"));
+        let (md, _) = import_built_docx("bookmarks", docx, &ImportOptions::default());
+        assert_eq!(
+            md,
+            "[Getting Started](#getting-started)\n\nSee [Setup, Part 2](#setup-part-2-1).\n\n\
+             # Getting Started\n\n# Setup, Part 2\n\n# Setup, Part 2\n"
+        );
+    }
+
+    #[test]
+    fn test_docx_headings_in_tables_get_no_anchor() {
+        use docx_rs::{Hyperlink, HyperlinkType, Table, TableCell, TableRow};
+        let heading = Paragraph::new()
+            .style("Heading1")
+            .add_bookmark_start(1, "_Toc1")
+            .add_run(Run::new().add_text("Intro"))
+            .add_bookmark_end(1);
+        let docx = Docx::new()
+            .add_paragraph(
+                Paragraph::new().add_hyperlink(
+                    Hyperlink::new("_Toc1", HyperlinkType::Anchor)
+                        .add_run(Run::new().add_text("Intro")),
+                ),
+            )
+            .add_table(Table::new(vec![TableRow::new(vec![TableCell::new()
+                .add_paragraph(
+                    Paragraph::new()
+                        .style("Heading1")
+                        .add_run(Run::new().add_text("Intro")),
+                )])]))
+            .add_paragraph(heading);
+        let (md, _) = import_built_docx("table-heading", docx, &ImportOptions::default());
+        // The editor sees one "Intro" heading, so its slug has no suffix.
+        assert!(md.starts_with("[Intro](#intro)\n"), "{md}");
+        assert!(md.ends_with("\n# Intro\n"), "{md}");
+    }
+
+    #[test]
+    fn test_docx_toc_field_regenerated_from_headings() {
+        use docx_rs::InstrText;
+        let field_char = |kind| Run::new().add_field_char(kind, false);
+        let instr = |text: &str| Run::new().add_instr_text(InstrText::Unsupported(text.into()));
+        let heading = |style: &str, text: &str| {
+            Paragraph::new()
+                .style(style)
+                .add_run(Run::new().add_text(text))
+        };
+        let docx = Docx::new()
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Contents")))
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(field_char(FieldCharType::Begin))
+                    .add_run(instr(r#" TOC \o "1-2" \h \z \u "#))
+                    .add_run(field_char(FieldCharType::Separate))
+                    .add_run(field_char(FieldCharType::Begin))
+                    .add_run(instr(r#" HYPERLINK \l "_Toc1" "#))
+                    .add_run(field_char(FieldCharType::Separate))
+                    .add_run(Run::new().add_text("Introduction"))
+                    .add_run(field_char(FieldCharType::End)),
+            )
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Stale entry")))
+            .add_paragraph(Paragraph::new().add_run(field_char(FieldCharType::End)))
+            .add_paragraph(heading("Heading1", "Intro"))
+            .add_paragraph(heading("Heading2", "Scope & *Goals*"))
+            .add_paragraph(heading("Heading3", "Too Deep"))
+            .add_paragraph(heading("Heading1", "Intro"));
+
+        let (md, _) = import_built_docx("toc-default", docx.clone(), &ImportOptions::default());
+        assert!(md.contains("Stale entry"), "{md}");
+
+        let mut options = ImportOptions::default();
+        options.docx.table_of_contents = TableOfContents::Regenerate;
+        let (md, _) = import_built_docx("toc-regenerate", docx, &options);
+        assert_eq!(
+            md,
+            "Contents\n\n- [Intro](#intro)\n  - [Scope & \\*Goals\\*](#scope--goals)\n- [Intro](#intro-1)\n\n\
+             # Intro\n\n## Scope & \\*Goals\\*\n\n### Too Deep\n\n# Intro\n"
+        );
+    }
+
+    #[test]
+    fn test_regenerated_toc_nesting() {
+        let mut anchors = HeadingAnchors::default();
+        assert_eq!(anchors.table_of_contents(ALL_TOC_LEVELS), TOC_PLACEHOLDER);
+        for (level, text) in [(2, "A"), (4, "B"), (3, "C"), (2, "D")] {
+            anchors.heading(level, text);
+        }
+        assert_eq!(
+            anchors.table_of_contents(ALL_TOC_LEVELS),
+            "- [A](#a)\n  - [B](#b)\n  - [C](#c)\n- [D](#d)\n"
+        );
+    }
+
+    #[test]
+    fn test_heading_slug() {
+        let mut used = HashMap::new();
+        assert_eq!(heading_slug("Hello, World!", &mut used), "hello-world");
+        assert_eq!(heading_slug("Hello World", &mut used), "hello-world-1");
+        assert_eq!(heading_slug("Hello World", &mut used), "hello-world-2");
+        assert_eq!(
+            heading_slug("2.1 Über_alles – now", &mut used),
+            "21-über_alles--now"
+        );
+        assert_eq!(heading_slug("a-1", &mut used), "a-1");
+        assert_eq!(heading_slug("a", &mut used), "a");
+        assert_eq!(heading_slug("a", &mut used), "a-2");
+    }
+
+    /// "The " + inserted "quick " + deleted "slow " + "fox", with a comment
+    /// from Ada on "fox".
+    fn review_docx() -> Docx {
+        use docx_rs::{Delete, Insert};
+        Docx::new().add_paragraph(
+            Paragraph::new()
+                .add_run(Run::new().add_text("The "))
+                .add_insert(Insert::new(Run::new().add_text("quick ")).author("Ada"))
+                .add_delete(Delete::new().add_run(Run::new().add_delete_text("slow ")))
+                .add_comment_start(
+                    Comment::new(1)
+                        .author("Ada")
+                        .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Which fox?"))),
+                )
+                .add_run(Run::new().add_text("fox"))
+                .add_comment_end(1),
+        )
+    }
+
+    #[test]
+    fn test_docx_tracked_changes_accepted_by_default() {
+        let (md, report) =
+            import_built_docx("review-default", review_docx(), &ImportOptions::default());
+        assert_eq!(md, "The quick fox\n");
+        let kinds: Vec<_> = report.warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(kinds, [WarningKind::TrackedChanges, WarningKind::Comments]);
+    }
+
+    #[test]
+    fn test_count_revisions_skips_property_marks() {
+        let xml = r#"<w:p><w:pPr><w:rPr><w:ins w:id="1" w:author="Ada"/></w:rPr></w:pPr>
+<w:ins w:id="2"><w:r><w:t>new</w:t></w:r></w:ins><w:del w:id="3"><w:r><w:delText>old</w:delText></w:r></w:del></w:p>
+<w:tbl><w:tr><w:trPr><w:del w:id="4" w:author="Ada"/></w:trPr></w:tr></w:tbl>"#;
+        assert_eq!(count_revisions(xml, "w:ins"), 1);
+        assert_eq!(count_revisions(xml, "w:del"), 1);
+    }
+
+    #[test]
+    fn test_docx_tracked_changes_as_critic_markup() {
+        let mut options = ImportOptions::default();
+        options.docx.tracked_changes = TrackedChanges::CriticMarkup;
+        options.docx.comments = Comments::CriticMarkup;
+        let (md, report) = import_built_docx("review-critic", review_docx(), &options);
+        assert_eq!(md, "The {++quick ++}{--slow --}fox{>>Ada: Which fox?<<}\n");
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
+    #[test]
+    fn test_docx_comments_as_footnotes() {
+        let mut options = ImportOptions::default();
+        options.docx.comments = Comments::Footnotes;
+        let (md, _) = import_built_docx("review-footnotes", review_docx(), &options);
+        assert_eq!(md, "The quick fox[^1]\n\n[^1]: Ada: Which fox?\n");
+    }
+
+    #[test]
+    fn test_docx_merged_cells_become_html_table() {
+        use docx_rs::{TableCell, TableRow, VMergeType};
+        let cell = |text: &str| {
+            TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text(text)))
+        };
+        let table = Table::new(vec![
+            TableRow::new(vec![cell("Region"), cell("Sales").grid_span(2)]),
+            TableRow::new(vec![
+                cell("North").vertical_merge(VMergeType::Restart),
+                cell("Q1"),
+                cell("**Q2**"),
+            ]),
+            TableRow::new(vec![
+                TableCell::new().vertical_merge(VMergeType::Continue),
+                cell("10"),
+                cell("20"),
+            ]),
+        ]);
+        let (md, report) = import_built_docx(
+            "merged",
+            Docx::new().add_table(table),
+            &ImportOptions::default(),
+        );
+        assert_eq!(
+            md,
+            "<table>\n<tr><th>Region</th><th colspan=\"2\">Sales</th></tr>\n<tr><td rowspan=\"2\">North</td><td>Q1</td><td>**Q2**</td></tr>\n<tr><td>10</td><td>20</td></tr>\n</table>\n\n"
+        );
+        assert_eq!(report.stats.tables, 1);
+    }
+
+    #[test]
+    fn test_docx_merged_table_keeps_pictures() {
+        use docx_rs::{TableCell, TableRow};
+        let picture =
+            Paragraph::new().add_run(Run::new().add_image(docx_rs::Pic::new(&two_by_two_png())));
+        let table = Table::new(vec![
+            TableRow::new(vec![TableCell::new()
+                .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Logo")))
+                .grid_span(2)]),
+            TableRow::new(vec![
+                TableCell::new().add_paragraph(picture),
+                TableCell::new()
+                    .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Acme"))),
+            ]),
+        ]);
+        let (md, report) = import_built_docx(
+            "merged-picture",
+            Docx::new().add_table(table),
+            &ImportOptions::default(),
+        );
+        assert_eq!(
+            md,
+            "<table>\n<tr><th colspan=\"2\">Logo</th></tr>\n<tr><td><img src=\"assets/image1.png\" alt=\"\" /></td><td>Acme</td></tr>\n</table>\n\n"
+        );
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
     /// End-to-end regression test against `tests/fixtures/sample.docx`
     /// (see `src/fixture_gen.rs` for how it's generated). Covers heading
-    /// detection, bold/italic/strike, bullet + numbered lists, tables, and
+    /// detection, bold/italic/strike, bullet + numbered lists (nesting,
+    /// start values and restarts), tables, and
     /// embedded images together, since those are the documented per-format
     /// behaviors in markdown-import.md.
     #[test]
     fn test_docx_to_markdown_fixture() {
         let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.docx");
-        let dir = std::env::temp_dir().join(format!("pourdown-docx-fixture-{}", std::process::id()));
+        let dir =
+            std::env::temp_dir().join(format!("pourdown-docx-fixture-{}", std::process::id()));
         let mut sink = MediaSink::new(dir.clone());
+        let mut report = ImportReport::default();
 
-        let md = docx_to_markdown(path, &mut sink).expect("docx_to_markdown should succeed");
+        let md = docx_to_markdown(
+            path,
+            &mut sink,
+            &ImportOptions::default(),
+            &mut report,
+            &ImportProgress::default(),
+        )
+        .expect("docx_to_markdown should succeed");
 
-        assert!(md.contains("# Sample Heading"), "heading not detected:\n{md}");
+        assert!(
+            md.contains("# Sample Heading"),
+            "heading not detected:\n{md}"
+        );
         assert!(md.contains("**bold**"), "bold run not detected:\n{md}");
         assert!(md.contains("*italic*"), "italic run not detected:\n{md}");
         assert!(md.contains("~~struck~~"), "strike run not detected:\n{md}");
-        assert!(md.contains("- First bullet"), "bullet list not detected:\n{md}");
-        assert!(md.contains("1. First step"), "numbered list not detected:\n{md}");
-        assert!(md.contains("| Name |") && md.contains("| Ada |"), "table not detected:\n{md}");
-        assert!(md.contains("![](assets/image1.png)"), "image link not detected:\n{md}");
+        assert!(
+            md.contains("- First bullet"),
+            "bullet list not detected:\n{md}"
+        );
+        assert!(
+            md.contains("1. First step"),
+            "numbered list not detected:\n{md}"
+        );
+        assert!(
+            md.contains("   - Step detail"),
+            "nested bullet not indented under its item:\n{md}"
+        );
+        assert!(
+            md.contains("2. Second step"),
+            "numbered list not continued:\n{md}"
+        );
+        assert!(
+            md.contains("1. Restarted step"),
+            "lvlOverride restart not honored:\n{md}"
+        );
+        assert!(md.contains("5. Fifth item"), "w:start not honored:\n{md}");
+        assert!(
+            md.contains("| Name |") && md.contains("| Ada |"),
+            "table not detected:\n{md}"
+        );
+        assert!(
+            md.contains("![](assets/image1.png)"),
+            "image link not detected:\n{md}"
+        );
         // MediaSink's assets_dir *is* the assets folder (see `assets_dir` in
         // main.rs), so the file lands directly under `dir`, not `dir/assets`.
-        assert!(dir.join("image1.png").exists(), "image sidecar file not written");
+        assert!(
+            dir.join("image1.png").exists(),
+            "image sidecar file not written"
+        );
+        assert_eq!(report.stats.tables, 1);
+        assert_eq!(report.stats.images_skipped, 0);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
 
         let _ = std::fs::remove_dir_all(&dir);
     }
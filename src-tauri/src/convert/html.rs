@@ -1,7 +1,7 @@
 use pulldown_cmark::{html, Options, Parser};
 use std::path::Path;
 
-use super::ConversionError;
+use super::{ConversionError, Exporter};
 
 const STYLE: &str = r#"
     body {
@@ -43,14 +43,87 @@ const STYLE: &str = r#"
     }
 "#;
 
+/// Standalone HTML exporter registered in [`super::FormatRegistry`].
+pub struct HtmlExporter;
+
+impl Exporter for HtmlExporter {
+    fn id(&self) -> &'static str {
+        "html"
+    }
+    fn name(&self) -> &'static str {
+        "HTML Document"
+    }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["html", "htm"]
+    }
+    fn mime_types(&self) -> &'static [&'static str] {
+        &["text/html"]
+    }
+    fn export(&self, markdown: &str, path: &str) -> Result<(), ConversionError> {
+        markdown_to_html(markdown, path)
+    }
+}
+
 /// HTML-escape text for safe placement inside a `<title>` element.
-fn escape_html(s: &str) -> String {
+pub(crate) fn escape_html(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
         .replace('"', "&quot;")
 }
 
+/// A cell of [`spanned_table_html`]: HTML content plus how many grid
+/// columns/rows it covers.
+pub struct SpannedCell {
+    pub html: String,
+    pub colspan: usize,
+    pub rowspan: usize,
+}
+
+/// Render a table with merged cells as an HTML `<table>` (first row as
+/// header cells) — the fallback importers emit when a table can't be
+/// represented as a GFM table. Written one row per line with no blank
+/// lines, so the whole table stays a single raw HTML block in Markdown.
+pub fn spanned_table_html(rows: &[Vec<SpannedCell>]) -> String {
+    let mut out = String::from("<table>\n");
+    for (i, row) in rows.iter().enumerate() {
+        let tag = if i == 0 { "th" } else { "td" };
+        out.push_str("<tr>");
+        for cell in row {
+            out.push('<');
+            out.push_str(tag);
+            if cell.colspan > 1 {
+                out.push_str(&format!(" colspan=\"{}\"", cell.colspan));
+            }
+            if cell.rowspan > 1 {
+                out.push_str(&format!(" rowspan=\"{}\"", cell.rowspan));
+            }
+            out.push('>');
+            out.push_str(&cell.html);
+            out.push_str(&format!("</{}>", tag));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+/// Render a Markdown fragment (e.g. an imported table cell's text) as
+/// inline HTML: no wrapping `<p>`, and on one line so it can sit inside a
+/// raw HTML block.
+pub fn markdown_inline_to_html(markdown: &str) -> String {
+    let parser = Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH);
+    let mut html_out = String::new();
+    html::push_html(&mut html_out, parser);
+    let trimmed = html_out.trim();
+    let inner = trimmed
+        .strip_prefix("<p>")
+        .and_then(|s| s.strip_suffix("</p>"))
+        .filter(|s| !s.contains("<p>"))
+        .unwrap_or(trimmed);
+    inner.replace('\n', " ")
+}
+
 /// Convert Markdown to a standalone HTML5 document.
 /// The document embeds minimal CSS for readable typography, tables, and code
 /// blocks so it renders and prints well on its own (for web sharing/embedding).
@@ -107,6 +180,33 @@ mod tests {
         assert_eq!(escape_html("A & B <tag>"), "A &amp; B &lt;tag&gt;");
     }
 
+    #[test]
+    fn test_spanned_table_html() {
+        let cell = |html: &str, colspan, rowspan| SpannedCell {
+            html: html.to_string(),
+            colspan,
+            rowspan,
+        };
+        let rows = vec![
+            vec![cell("Region", 1, 1), cell("Sales", 2, 1)],
+            vec![cell("North", 1, 2), cell("Q1", 1, 1), cell("Q2", 1, 1)],
+            vec![cell("1", 1, 1), cell("2", 1, 1)],
+        ];
+        assert_eq!(
+            spanned_table_html(&rows),
+            "<table>\n<tr><th>Region</th><th colspan=\"2\">Sales</th></tr>\n<tr><td rowspan=\"2\">North</td><td>Q1</td><td>Q2</td></tr>\n<tr><td>1</td><td>2</td></tr>\n</table>\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_inline_to_html() {
+        assert_eq!(
+            markdown_inline_to_html("**bold** & ~~gone~~"),
+            "<strong>bold</strong> &amp; <del>gone</del>"
+        );
+        assert_eq!(markdown_inline_to_html(""), "");
+    }
+
     #[test]
     fn test_markdown_to_html_writes_standalone_document() {
         let dir = std::env::temp_dir().join(format!("pourdown-html-test-{}", std::process::id()));
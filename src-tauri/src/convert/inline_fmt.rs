@@ -6,6 +6,61 @@
 //! immediately touching whitespace). This module holds that shared, subtle
 //! logic in one place so the two importers can't drift out of sync.
 
+use serde::{Deserialize, Serialize};
+
+/// How superscript and subscript runs are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptSyntax {
+    /// `<sup>2</sup>` / `<sub>2</sub>`, which any renderer understands.
+    #[default]
+    Html,
+    /// Pandoc's `^2^` / `~2~`. Note that GFM reads `~2~` as strikethrough.
+    Pandoc,
+}
+
+/// Inline formatting options shared by the docx and pptx importers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InlineOptions {
+    pub script: ScriptSyntax,
+    /// Keep run font colors as `<span style="color: #RRGGBB">`.
+    pub color: bool,
+}
+
+/// A run's vertical alignment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VertAlign {
+    #[default]
+    Baseline,
+    Superscript,
+    Subscript,
+}
+
+/// The formatting of one run of text. Adjacent runs are merged only when
+/// all of it that gets rendered matches (see [`format_segments`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunFormat {
+    pub bold: bool,
+    pub italic: bool,
+    pub strike: bool,
+    pub underline: bool,
+    pub highlight: bool,
+    pub vert_align: VertAlign,
+    /// Font color as `RRGGBB` hex; `None` for the automatic (black) color.
+    pub color: Option<String>,
+}
+
+impl RunFormat {
+    /// Normalize a hex color attribute, treating `auto` and black as no
+    /// color.
+    pub fn hex_color(value: &str) -> Option<String> {
+        let value = value.trim().trim_start_matches('#');
+        let is_hex = value.len() == 6 && value.chars().all(|c| c.is_ascii_hexdigit());
+        (is_hex && !value.eq_ignore_ascii_case("000000")).then(|| value.to_uppercase())
+    }
+}
+
 /// Escape literal Markdown inline-emphasis/code characters that appear in raw
 /// source text, so author-typed `*`, `_`, backticks aren't reinterpreted as
 /// Markdown (e.g. a leading `*` becoming a bullet). Backslash is escaped first.
@@ -23,16 +78,26 @@ pub fn escape_markdown(text: &str) -> String {
     out
 }
 
-/// Apply bold/italic/strikethrough markers, skipping whitespace-only text.
+/// Apply a run's formatting, skipping whitespace-only text: bold/italic and
+/// strikethrough as Markdown markers, then superscript/subscript (per
+/// [`InlineOptions::script`]), highlight (`<mark>`), underline (`<u>`) and,
+/// if enabled, color as HTML around them.
 ///
 /// CommonMark requires an emphasis opener/closer to hug its text — `**`
 /// immediately followed by whitespace is not a valid opener, so a run like
 /// `"  Title"` wrapped naively as `"**  Title**"` renders as literal
 /// asterisks. Leading/trailing whitespace is moved outside the markers so
 /// emphasis stays valid while inter-run spacing (e.g. between adjacent runs
-/// in the same paragraph) is preserved.
-pub fn apply_inline_fmt(text: &str, bold: bool, italic: bool, strike: bool) -> String {
-    if text.trim().is_empty() || (!bold && !italic && !strike) {
+/// in the same paragraph) is preserved. HTML tags go outside the emphasis
+/// markers for the same reason: `x**<u>y</u>**` isn't bold, since an opener
+/// followed by punctuation must not follow a letter.
+pub fn apply_inline_fmt(text: &str, fmt: &RunFormat, options: &InlineOptions) -> String {
+    let color = fmt.color.as_ref().filter(|_| options.color);
+    let plain = RunFormat {
+        color: None,
+        ..fmt.clone()
+    } == RunFormat::default();
+    if text.trim().is_empty() || (plain && color.is_none()) {
         return text.to_string();
     }
     let trimmed_start = text.trim_start();
@@ -40,24 +105,85 @@ pub fn apply_inline_fmt(text: &str, bold: bool, italic: bool, strike: bool) -> S
     let core = trimmed_start.trim_end();
     let trailing = &trimmed_start[core.len()..];
 
-    let s = if strike {
+    let s = if fmt.strike {
         format!("~~{}~~", core)
     } else {
         core.to_string()
     };
-    let wrapped = match (bold, italic) {
+    let mut wrapped = match (fmt.bold, fmt.italic) {
         (true, true) => format!("***{}***", s),
         (true, false) => format!("**{}**", s),
         (false, true) => format!("*{}*", s),
         (false, false) => s,
     };
+    wrapped = match (fmt.vert_align, options.script) {
+        (VertAlign::Baseline, _) => wrapped,
+        (VertAlign::Superscript, ScriptSyntax::Html) => format!("<sup>{}</sup>", wrapped),
+        (VertAlign::Subscript, ScriptSyntax::Html) => format!("<sub>{}</sub>", wrapped),
+        // Pandoc ends a super/subscript at an unescaped space.
+        (VertAlign::Superscript, ScriptSyntax::Pandoc) => {
+            format!("^{}^", wrapped.replace(' ', "\\ "))
+        }
+        (VertAlign::Subscript, ScriptSyntax::Pandoc) => {
+            format!("~{}~", wrapped.replace(' ', "\\ "))
+        }
+    };
+    if fmt.highlight {
+        wrapped = format!("<mark>{}</mark>", wrapped);
+    }
+    if fmt.underline {
+        wrapped = format!("<u>{}</u>", wrapped);
+    }
+    if let Some(color) = color {
+        wrapped = format!("<span style=\"color: #{}\">{}</span>", color, wrapped);
+    }
     format!("{}{}{}", leading, wrapped, trailing)
 }
 
+/// Merge adjacent segments with identical formatting, then format each.
+/// Colors only count when [`InlineOptions::color`] keeps them.
+///
+/// Wrapping each run independently would produce artifacts like `**a**` +
+/// `**b**` = `**a****b**` (invalid/ambiguous CommonMark) or `<u>a</u><u>b</u>`
+/// whenever a formatting run happens to be split across two runs.
+pub fn format_segments(segments: Vec<(String, RunFormat)>, options: &InlineOptions) -> String {
+    let mut merged: Vec<(String, RunFormat)> = Vec::new();
+    for mut seg in segments {
+        if !options.color {
+            seg.1.color = None;
+        }
+        if let Some(last) = merged.last_mut() {
+            if last.1 == seg.1 {
+                last.0.push_str(&seg.0);
+                continue;
+            }
+        }
+        merged.push(seg);
+    }
+    merged
+        .iter()
+        .map(|(text, fmt)| apply_inline_fmt(text, fmt, options))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const BOLD: RunFormat = RunFormat {
+        bold: true,
+        italic: false,
+        strike: false,
+        underline: false,
+        highlight: false,
+        vert_align: VertAlign::Baseline,
+        color: None,
+    };
+
+    fn fmt(text: &str, format: &RunFormat) -> String {
+        apply_inline_fmt(text, format, &InlineOptions::default())
+    }
+
     #[test]
     fn test_escape_markdown() {
         assert_eq!(escape_markdown("a*b_c`d\\e"), "a\\*b\\_c\\`d\\\\e");
@@ -65,24 +191,142 @@ mod tests {
 
     #[test]
     fn test_apply_inline_fmt_moves_leading_whitespace_outside_markers() {
-        assert_eq!(apply_inline_fmt("  Foo (FSD)", true, false, false), "  **Foo (FSD)**");
-        assert_eq!(apply_inline_fmt(" Version : 1.0", true, false, false), " **Version : 1.0**");
+        assert_eq!(fmt("  Foo (FSD)", &BOLD), "  **Foo (FSD)**");
+        assert_eq!(fmt(" Version : 1.0", &BOLD), " **Version : 1.0**");
     }
 
     #[test]
     fn test_apply_inline_fmt_moves_trailing_whitespace_outside_markers() {
-        assert_eq!(apply_inline_fmt("bold ", true, false, false), "**bold** ");
+        assert_eq!(fmt("bold ", &BOLD), "**bold** ");
     }
 
     #[test]
     fn test_apply_inline_fmt_whitespace_only_not_wrapped() {
-        assert_eq!(apply_inline_fmt("   ", true, false, false), "   ");
+        assert_eq!(fmt("   ", &BOLD), "   ");
     }
 
     #[test]
     fn test_apply_inline_fmt_italic_and_strike() {
-        assert_eq!(apply_inline_fmt("hi", false, true, false), "*hi*");
-        assert_eq!(apply_inline_fmt("hi", false, false, true), "~~hi~~");
-        assert_eq!(apply_inline_fmt("hi", true, true, false), "***hi***");
+        let italic = RunFormat {
+            italic: true,
+            ..RunFormat::default()
+        };
+        assert_eq!(fmt("hi", &italic), "*hi*");
+        assert_eq!(
+            fmt(
+                "hi",
+                &RunFormat {
+                    strike: true,
+                    ..RunFormat::default()
+                }
+            ),
+            "~~hi~~"
+        );
+        assert_eq!(
+            fmt(
+                "hi",
+                &RunFormat {
+                    italic: true,
+                    ..BOLD
+                }
+            ),
+            "***hi***"
+        );
+    }
+
+    #[test]
+    fn test_apply_inline_fmt_html_outside_emphasis() {
+        let all = RunFormat {
+            underline: true,
+            highlight: true,
+            vert_align: VertAlign::Superscript,
+            color: Some("FF0000".into()),
+            ..BOLD
+        };
+        assert_eq!(fmt(" x ", &all), " <u><mark><sup>**x**</sup></mark></u> ");
+        let options = InlineOptions {
+            color: true,
+            ..InlineOptions::default()
+        };
+        assert_eq!(
+            apply_inline_fmt("x", &all, &options),
+            "<span style=\"color: #FF0000\"><u><mark><sup>**x**</sup></mark></u></span>"
+        );
+        // A color alone is dropped unless colors are kept.
+        let red = RunFormat {
+            color: Some("FF0000".into()),
+            ..RunFormat::default()
+        };
+        assert_eq!(fmt("x", &red), "x");
+    }
+
+    #[test]
+    fn test_apply_inline_fmt_pandoc_scripts() {
+        let options = InlineOptions {
+            script: ScriptSyntax::Pandoc,
+            ..InlineOptions::default()
+        };
+        let sub = RunFormat {
+            vert_align: VertAlign::Subscript,
+            ..RunFormat::default()
+        };
+        let sup = RunFormat {
+            vert_align: VertAlign::Superscript,
+            ..RunFormat::default()
+        };
+        assert_eq!(apply_inline_fmt("2", &sub, &options), "~2~");
+        assert_eq!(apply_inline_fmt("a b", &sup, &options), "^a\\ b^");
+    }
+
+    #[test]
+    fn test_format_segments_merges_identical_formats() {
+        let sub = RunFormat {
+            vert_align: VertAlign::Subscript,
+            ..RunFormat::default()
+        };
+        let segments = vec![
+            ("H".to_string(), RunFormat::default()),
+            ("2".to_string(), sub.clone()),
+            ("O and CO".to_string(), RunFormat::default()),
+            ("2".to_string(), sub),
+            (" is ".to_string(), BOLD),
+            ("gas".to_string(), BOLD),
+        ];
+        assert_eq!(
+            format_segments(segments, &InlineOptions::default()),
+            "H<sub>2</sub>O and CO<sub>2</sub> **is gas**"
+        );
+    }
+
+    #[test]
+    fn test_format_segments_ignores_colors_unless_kept() {
+        let red = RunFormat {
+            color: Some("FF0000".into()),
+            ..BOLD
+        };
+        let blue = RunFormat {
+            color: Some("0000FF".into()),
+            ..BOLD
+        };
+        let segments = vec![("a".to_string(), red), ("b".to_string(), blue)];
+        assert_eq!(
+            format_segments(segments.clone(), &InlineOptions::default()),
+            "**ab**"
+        );
+        let options = InlineOptions {
+            color: true,
+            ..InlineOptions::default()
+        };
+        assert_eq!(
+            format_segments(segments, &options),
+            "<span style=\"color: #FF0000\">**a**</span><span style=\"color: #0000FF\">**b**</span>"
+        );
+    }
+
+    #[test]
+    fn test_hex_color() {
+        assert_eq!(RunFormat::hex_color("ff0000"), Some("FF0000".into()));
+        assert_eq!(RunFormat::hex_color("auto"), None);
+        assert_eq!(RunFormat::hex_color("000000"), None);
     }
 }
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
+use std::io::Cursor;
 use std::path::PathBuf;
 
+use super::metafile;
+
 /// Collects images extracted during import and writes them as sidecar files
 /// under `<import_dir>/assets/`, returning relative Markdown paths that stay
 /// valid regardless of where the import directory is eventually relocated to.
@@ -19,6 +22,25 @@ pub struct MediaSink {
 /// Extensions that the Tauri webview can render directly as `<img>` sources.
 const RENDERABLE_EXTS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp"];
 
+/// Re-encode a format the webview can't show into one it can, returning the
+/// new extension and bytes: EMF/WMF metafiles are rendered to SVG, TIFF is
+/// decoded and written as PNG. `None` if the format is unknown or the data
+/// can't be converted.
+fn convert_unrenderable(ext: &str, bytes: &[u8]) -> Option<(&'static str, Vec<u8>)> {
+    match ext {
+        "emf" => metafile::emf_to_svg(bytes).map(|svg| ("svg", svg.into_bytes())),
+        "wmf" => metafile::wmf_to_svg(bytes).map(|svg| ("svg", svg.into_bytes())),
+        "tif" | "tiff" => {
+            let img = image::load_from_memory_with_format(bytes, image::ImageFormat::Tiff).ok()?;
+            let mut png = Vec::new();
+            img.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+                .ok()?;
+            Some(("png", png))
+        }
+        _ => None,
+    }
+}
+
 impl MediaSink {
     pub fn new(assets_dir: PathBuf) -> Self {
         MediaSink {
@@ -39,27 +61,29 @@ impl MediaSink {
     /// Register an image part. `orig_name` is the archive/part name (used both
     /// for de-duplication and to infer the file extension). Returns the
     /// relative Markdown image path (e.g. `assets/image1.png`) on success, or
-    /// `None` if the format isn't renderable in the webview (e.g. EMF/WMF) —
-    /// callers should fall back to a text note in that case.
+    /// `None` if the format isn't renderable in the webview and couldn't be
+    /// converted (see [`convert_unrenderable`]) — callers should fall back to
+    /// a text note in that case.
     pub fn add(&mut self, orig_name: &str, bytes: &[u8]) -> Option<String> {
         if let Some(existing) = self.written.get(orig_name) {
             return Some(existing.clone());
         }
 
-        let ext = orig_name
-            .rsplit('.')
-            .next()
-            .unwrap_or("")
-            .to_lowercase();
+        let ext = orig_name.rsplit('.').next().unwrap_or("").to_lowercase();
 
-        if !RENDERABLE_EXTS.contains(&ext.as_str()) {
-            return None;
-        }
+        let converted;
+        let (ext, bytes) = if RENDERABLE_EXTS.contains(&ext.as_str()) {
+            (ext.as_str(), bytes)
+        } else {
+            converted = convert_unrenderable(&ext, bytes)?;
+            (converted.0, converted.1.as_slice())
+        };
 
         let filename = format!("image{}.{}", self.next_index, ext);
         let dest = self.assets_dir.join(&filename);
 
-        let write_result = if let Some(max_dim) = self.max_dimension {
+        // SVG (a rendered metafile) is vector: nothing to downscale.
+        let write_result = if let (Some(max_dim), false) = (self.max_dimension, ext == "svg") {
             self.write_downscaled(&dest, bytes, max_dim)
         } else {
             std::fs::create_dir_all(&self.assets_dir).and_then(|_| std::fs::write(&dest, bytes))
@@ -75,12 +99,7 @@ impl MediaSink {
         Some(rel_path)
     }
 
-    fn write_downscaled(
-        &self,
-        dest: &PathBuf,
-        bytes: &[u8],
-        max_dim: u32,
-    ) -> std::io::Result<()> {
+    fn write_downscaled(&self, dest: &PathBuf, bytes: &[u8], max_dim: u32) -> std::io::Result<()> {
         std::fs::create_dir_all(&self.assets_dir)?;
         match image::load_from_memory(bytes) {
             Ok(img) => {
@@ -98,9 +117,44 @@ impl MediaSink {
         }
     }
 
+    /// Number of distinct images written so far (a part added more than once
+    /// counts once).
+    pub fn written_count(&self) -> usize {
+        self.written.len()
+    }
+
     /// True if any image was actually written (used to decide whether the
     /// `assets/` directory should be kept or cleaned up).
     pub fn is_empty(&self) -> bool {
         self.written.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiff_and_metafiles_converted_on_write() {
+        let dir = std::env::temp_dir().join(format!("pourdown_media_{}", std::process::id()));
+        let mut sink = MediaSink::new(dir.clone());
+
+        let mut tiff = Vec::new();
+        image::RgbImage::from_pixel(3, 2, image::Rgb([200, 10, 10]))
+            .write_to(&mut Cursor::new(&mut tiff), image::ImageFormat::Tiff)
+            .unwrap();
+        assert_eq!(
+            sink.add("word/media/scan.tiff", &tiff).as_deref(),
+            Some("assets/image1.png")
+        );
+        let png = image::open(dir.join("image1.png")).unwrap();
+        assert_eq!((png.width(), png.height()), (3, 2));
+
+        // Unreadable metafiles stay unsupported and don't take an index.
+        assert_eq!(sink.add("word/media/broken.emf", b"garbage"), None);
+        assert_eq!(sink.add("word/media/broken.wmf", b"garbage"), None);
+        assert_eq!(sink.written_count(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
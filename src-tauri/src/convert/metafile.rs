@@ -0,0 +1,1686 @@
+//! Windows metafiles (EMF and WMF) to SVG, for [`MediaSink`]. Office files
+//! embed Visio diagrams and legacy clip art as metafiles, which the webview
+//! can't display. This replays the common GDI record subset — lines,
+//! polylines, polygons, Béziers, rectangles, ellipses, arcs, path brackets,
+//! text and embedded DIBs — against a minimal device context and writes
+//! each drawing call out as an SVG element. Clipping, raster operations,
+//! pattern brushes and EMF+ records are ignored; a metafile that draws
+//! nothing renders as `None`, so callers keep their "unsupported" note.
+//!
+//! [`MediaSink`]: super::media::MediaSink
+
+use std::f64::consts::{PI, TAU};
+use std::fmt::Write;
+use std::io::Cursor;
+
+use base64::Engine;
+
+use super::html::escape_html;
+
+/// A 2×3 affine matrix in SVG `matrix(a b c d e f)` order, mapping `(x, y)`
+/// to `(a·x + c·y + e, b·x + d·y + f)`. GDI's `XFORM` uses the same layout.
+type Matrix = [f64; 6];
+
+type Point = (f64, f64);
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// `m` followed by `n`.
+fn multiply(m: &Matrix, n: &Matrix) -> Matrix {
+    [
+        m[0] * n[0] + m[1] * n[2],
+        m[0] * n[1] + m[1] * n[3],
+        m[2] * n[0] + m[3] * n[2],
+        m[2] * n[1] + m[3] * n[3],
+        m[4] * n[0] + m[5] * n[2] + n[4],
+        m[4] * n[1] + m[5] * n[3] + n[5],
+    ]
+}
+
+fn apply(m: &Matrix, (x, y): (f64, f64)) -> (f64, f64) {
+    (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5])
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Color(u8, u8, u8);
+
+impl Color {
+    /// A GDI `COLORREF`: red in the low byte.
+    fn from_colorref(value: u32) -> Self {
+        Color(value as u8, (value >> 8) as u8, (value >> 16) as u8)
+    }
+
+    fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+const BLACK: Color = Color(0, 0, 0);
+const WHITE: Color = Color(255, 255, 255);
+
+#[derive(Clone)]
+struct Pen {
+    color: Color,
+    /// Logical width; `0` (or a cosmetic pen) is one device pixel wide.
+    width: f64,
+    /// `PS_*` style bits: dash pattern, end cap and join.
+    style: u32,
+}
+
+#[derive(Clone)]
+struct Font {
+    /// `LOGFONT.lfHeight`: negative for the em height, positive for the cell
+    /// height (em plus internal leading), zero for the device default.
+    height: f64,
+    weight: i32,
+    italic: bool,
+    underline: bool,
+    strike_out: bool,
+    /// Baseline angle, tenths of a degree counter-clockwise.
+    escapement: f64,
+    face: String,
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Font {
+            height: 0.0,
+            weight: 400,
+            italic: false,
+            underline: false,
+            strike_out: false,
+            escapement: 0.0,
+            face: String::new(),
+        }
+    }
+}
+
+/// An entry of the metafile's object table. A `None` pen or brush is
+/// `PS_NULL` / `BS_NULL`; pattern brushes are treated as null too.
+#[derive(Clone)]
+enum Object {
+    Pen(Option<Pen>),
+    Brush(Option<Color>),
+    Font(Font),
+    Other,
+}
+
+/// `GetStockObject` entries, which `SelectObject` addresses with the high
+/// bit set.
+fn stock_object(index: u32) -> Object {
+    let gray = |v| Object::Brush(Some(Color(v, v, v)));
+    match index {
+        0 | 18 => Object::Brush(Some(WHITE)),
+        1 => gray(0xC0),
+        2 => gray(0x80),
+        3 => gray(0x40),
+        4 => Object::Brush(Some(BLACK)),
+        5 => Object::Brush(None),
+        6 => Object::Pen(Some(Pen {
+            color: WHITE,
+            width: 0.0,
+            style: 0,
+        })),
+        7 | 19 => Object::Pen(Some(Pen {
+            color: BLACK,
+            width: 0.0,
+            style: 0,
+        })),
+        8 => Object::Pen(None),
+        10..=14 | 16 | 17 => Object::Font(Font::default()),
+        _ => Object::Other,
+    }
+}
+
+/// The parts of a GDI device context the supported records read or change.
+#[derive(Clone)]
+struct Dc {
+    map_mode: u32,
+    window_org: (f64, f64),
+    window_ext: (f64, f64),
+    /// Whether the metafile set a window extent; WMF output maps it to the
+    /// SVG viewport.
+    window_set: bool,
+    viewport_org: (f64, f64),
+    viewport_ext: (f64, f64),
+    world: Matrix,
+    pen: Option<Pen>,
+    brush: Option<Color>,
+    font: Font,
+    text_color: Color,
+    text_align: u32,
+    /// `WINDING` rather than `ALTERNATE` polygon fill.
+    winding: bool,
+    clockwise: bool,
+    position: (f64, f64),
+}
+
+impl Default for Dc {
+    fn default() -> Self {
+        Dc {
+            map_mode: MM_TEXT,
+            window_org: (0.0, 0.0),
+            window_ext: (1.0, 1.0),
+            window_set: false,
+            viewport_org: (0.0, 0.0),
+            viewport_ext: (1.0, 1.0),
+            world: IDENTITY,
+            pen: Some(Pen {
+                color: BLACK,
+                width: 0.0,
+                style: 0,
+            }),
+            brush: Some(WHITE),
+            font: Font::default(),
+            text_color: BLACK,
+            text_align: 0,
+            winding: false,
+            clockwise: false,
+            position: (0.0, 0.0),
+        }
+    }
+}
+
+const MM_TEXT: u32 = 1;
+const MM_ISOTROPIC: u32 = 7;
+const MM_ANISOTROPIC: u32 = 8;
+
+const TA_UPDATECP: u32 = 1;
+const TA_RIGHT: u32 = 2;
+const TA_CENTER: u32 = 6;
+const TA_BOTTOM: u32 = 8;
+const TA_BASELINE: u32 = 24;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Emf,
+    Wmf,
+}
+
+/// Replays drawing records into SVG elements.
+struct Renderer {
+    format: Format,
+    dc: Dc,
+    saved: Vec<Dc>,
+    objects: Vec<Option<Object>>,
+    /// Reference-device resolution from the EMF header, for the metric and
+    /// English mapping modes.
+    px_per_mm: (f64, f64),
+    body: String,
+    /// Inside an EMF `BeginPath`/`EndPath` bracket, drawing calls add to
+    /// `path` instead of painting.
+    in_path: bool,
+    path: String,
+    path_matrix: Matrix,
+    /// Consecutive `LineTo`-style segments outside a path bracket, written
+    /// out as one stroked path by [`Renderer::flush_lines`].
+    lines: String,
+    lines_matrix: Matrix,
+    /// A `MoveTo` since the last segment: the next one starts a new figure.
+    moved: bool,
+}
+
+impl Renderer {
+    fn new(format: Format) -> Self {
+        Renderer {
+            format,
+            dc: Dc::default(),
+            saved: Vec::new(),
+            objects: Vec::new(),
+            px_per_mm: (96.0 / 25.4, 96.0 / 25.4),
+            body: String::new(),
+            in_path: false,
+            path: String::new(),
+            path_matrix: IDENTITY,
+            lines: String::new(),
+            lines_matrix: IDENTITY,
+            moved: true,
+        }
+    }
+
+    /// Logical → SVG user space. EMF output is in device units (the
+    /// header's bounds); WMF has no device, so output is the window with its
+    /// origin at zero and both axes pointing right/down.
+    fn page(&self) -> Matrix {
+        let dc = &self.dc;
+        let (sx, sy) = match self.format {
+            Format::Wmf => {
+                if !dc.window_set {
+                    return IDENTITY;
+                }
+                (dc.window_ext.0.signum(), dc.window_ext.1.signum())
+            }
+            Format::Emf => {
+                let (mx, my) = self.px_per_mm;
+                let ratio = |v: f64, w: f64| if w == 0.0 { 1.0 } else { v / w };
+                match dc.map_mode {
+                    2 => (0.1 * mx, -0.1 * my),
+                    3 => (0.01 * mx, -0.01 * my),
+                    4 => (0.254 * mx, -0.254 * my),
+                    5 => (0.0254 * mx, -0.0254 * my),
+                    6 => (25.4 / 1440.0 * mx, -25.4 / 1440.0 * my),
+                    MM_ISOTROPIC | MM_ANISOTROPIC => {
+                        let sx = ratio(dc.viewport_ext.0, dc.window_ext.0);
+                        let sy = ratio(dc.viewport_ext.1, dc.window_ext.1);
+                        if dc.map_mode == MM_ISOTROPIC {
+                            let s = sx.abs().min(sy.abs());
+                            (s * sx.signum(), s * sy.signum())
+                        } else {
+                            (sx, sy)
+                        }
+                    }
+                    _ => (1.0, 1.0),
+                }
+            }
+        };
+        let (vx, vy) = match self.format {
+            Format::Emf => dc.viewport_org,
+            Format::Wmf => (0.0, 0.0),
+        };
+        [
+            sx,
+            0.0,
+            0.0,
+            sy,
+            vx - dc.window_org.0 * sx,
+            vy - dc.window_org.1 * sy,
+        ]
+    }
+
+    fn matrix(&self) -> Matrix {
+        multiply(&self.dc.world, &self.page())
+    }
+
+    // ── Object table ────────────────────────────────────────────────────
+
+    fn set_object(&mut self, index: usize, object: Object) {
+        if index >= self.objects.len() {
+            // Indices come from the file; don't let a corrupt one allocate
+            // an enormous table.
+            if index > 0xFFFF {
+                return;
+            }
+            self.objects.resize(index + 1, None);
+        }
+        self.objects[index] = Some(object);
+    }
+
+    /// WMF objects take the lowest free slot of the table.
+    fn add_object(&mut self, object: Object) {
+        match self.objects.iter().position(Option::is_none) {
+            Some(index) => self.objects[index] = Some(object),
+            None => self.objects.push(Some(object)),
+        }
+    }
+
+    fn select_object(&mut self, index: u32) {
+        let object = if index & 0x8000_0000 != 0 {
+            stock_object(index & 0x7FFF_FFFF)
+        } else {
+            match self.objects.get(index as usize) {
+                Some(Some(object)) => object.clone(),
+                _ => return,
+            }
+        };
+        match object {
+            Object::Pen(pen) => self.dc.pen = pen,
+            Object::Brush(brush) => self.dc.brush = brush,
+            Object::Font(font) => self.dc.font = font,
+            Object::Other => {}
+        }
+    }
+
+    fn delete_object(&mut self, index: u32) {
+        if let Some(slot) = self.objects.get_mut(index as usize) {
+            *slot = None;
+        }
+    }
+
+    fn restore_dc(&mut self, relative: i32) {
+        // Negative values count back from the top of the stack; positive
+        // ones are absolute levels (1 is the first save).
+        let level = if relative < 0 {
+            self.saved.len() as i64 + relative as i64
+        } else {
+            relative as i64 - 1
+        };
+        if level >= 0 && (level as usize) < self.saved.len() {
+            self.saved.truncate(level as usize + 1);
+            if let Some(dc) = self.saved.pop() {
+                self.dc = dc;
+            }
+        }
+    }
+
+    // ── Drawing ─────────────────────────────────────────────────────────
+
+    /// Where segments go: the open path bracket, or the pending line run.
+    fn segments(&mut self) -> &mut String {
+        if self.in_path {
+            &mut self.path
+        } else {
+            if self.lines.is_empty() {
+                self.lines_matrix = self.matrix();
+            }
+            &mut self.lines
+        }
+    }
+
+    fn begin_segment(&mut self) {
+        let start = self.dc.position;
+        let moved = std::mem::replace(&mut self.moved, false);
+        let buffer = self.segments();
+        if buffer.is_empty() || moved {
+            let _ = write!(buffer, "M{} ", point(start));
+        }
+    }
+
+    fn move_to(&mut self, to: (f64, f64)) {
+        self.dc.position = to;
+        self.moved = true;
+    }
+
+    fn line_to(&mut self, points: &[(f64, f64)]) {
+        let Some(&last) = points.last() else {
+            return;
+        };
+        self.begin_segment();
+        let buffer = self.segments();
+        for &p in points {
+            let _ = write!(buffer, "L{} ", point(p));
+        }
+        self.dc.position = last;
+    }
+
+    fn bezier_to(&mut self, points: &[(f64, f64)]) {
+        let curves = points.len() / 3;
+        if curves == 0 {
+            return;
+        }
+        self.begin_segment();
+        let buffer = self.segments();
+        for c in points.chunks_exact(3) {
+            let _ = write!(buffer, "C{} {} {} ", point(c[0]), point(c[1]), point(c[2]));
+        }
+        self.dc.position = points[curves * 3 - 1];
+    }
+
+    fn arc_to(&mut self, bounds: [f64; 4], start: (f64, f64), end: (f64, f64)) {
+        if let Some((from, arc, to)) = arc_path(bounds, start, end, self.dc.clockwise) {
+            self.line_to(&[from]);
+            self.segments().push_str(&arc);
+            self.dc.position = to;
+        }
+    }
+
+    /// Write out the pending line run with the current pen.
+    fn flush_lines(&mut self) {
+        if !self.lines.is_empty() {
+            let d = std::mem::take(&mut self.lines);
+            let matrix = self.lines_matrix;
+            self.paint(&d, false, true, &matrix);
+        }
+    }
+
+    /// A complete figure: added to an open path bracket, else painted with
+    /// the current pen and (when `filled`) brush.
+    fn shape(&mut self, d: String, filled: bool) {
+        if self.in_path {
+            self.path.push_str(&d);
+            self.moved = true;
+        } else {
+            let matrix = self.matrix();
+            self.paint(&d, filled, true, &matrix);
+        }
+    }
+
+    fn rectangle(&mut self, [l, t, r, b]: [f64; 4]) {
+        self.shape(
+            format!(
+                "M{} {} H{} V{} H{} Z",
+                num(l),
+                num(t),
+                num(r),
+                num(b),
+                num(l)
+            ),
+            true,
+        );
+    }
+
+    fn round_rectangle(&mut self, [l, t, r, b]: [f64; 4], corner: (f64, f64)) {
+        let (l, r) = (l.min(r), l.max(r));
+        let (t, b) = (t.min(b), t.max(b));
+        let rx = (corner.0.abs() / 2.0).min((r - l) / 2.0);
+        let ry = (corner.1.abs() / 2.0).min((b - t) / 2.0);
+        if rx <= 0.0 || ry <= 0.0 {
+            self.rectangle([l, t, r, b]);
+            return;
+        }
+        let a = format!("A{} {} 0 0 1", num(rx), num(ry));
+        let d = format!(
+            "M{} H{} {a} {} V{} {a} {} H{} {a} {} V{} {a} {} Z",
+            point((l + rx, t)),
+            num(r - rx),
+            point((r, t + ry)),
+            num(b - ry),
+            point((r - rx, b)),
+            num(l + rx),
+            point((l, b - ry)),
+            num(t + ry),
+            point((l + rx, t)),
+        );
+        self.shape(d, true);
+    }
+
+    fn ellipse(&mut self, bounds: [f64; 4]) {
+        let right = (bounds[2], (bounds[1] + bounds[3]) / 2.0);
+        if let Some((from, arc, _)) = arc_path(bounds, right, right, false) {
+            self.shape(format!("M{} {arc}Z", point(from)), true);
+        }
+    }
+
+    /// `ARC`, `CHORD` or `PIE`, by `closure`: none, the chord, or through
+    /// the centre.
+    fn arc(&mut self, bounds: [f64; 4], start: (f64, f64), end: (f64, f64), closure: Closure) {
+        let Some((from, arc, _)) = arc_path(bounds, start, end, self.dc.clockwise) else {
+            return;
+        };
+        let d = match closure {
+            Closure::Open => format!("M{} {arc}", point(from)),
+            Closure::Chord => format!("M{} {arc}Z", point(from)),
+            Closure::Pie => {
+                let centre = ((bounds[0] + bounds[2]) / 2.0, (bounds[1] + bounds[3]) / 2.0);
+                format!("M{} L{} {arc}Z", point(centre), point(from))
+            }
+        };
+        self.shape(d, closure != Closure::Open);
+    }
+
+    fn poly(&mut self, points: &[(f64, f64)], closed: bool) {
+        if points.len() < 2 {
+            return;
+        }
+        let mut d = format!("M{} ", point(points[0]));
+        for &p in &points[1..] {
+            let _ = write!(d, "L{} ", point(p));
+        }
+        if closed {
+            d.push('Z');
+        }
+        self.shape(d, closed);
+    }
+
+    fn poly_bezier(&mut self, points: &[(f64, f64)]) {
+        if points.len() < 4 {
+            return;
+        }
+        let mut d = format!("M{} ", point(points[0]));
+        for c in points[1..].chunks_exact(3) {
+            let _ = write!(d, "C{} {} {} ", point(c[0]), point(c[1]), point(c[2]));
+        }
+        self.shape(d, false);
+    }
+
+    /// Several figures painted as one (so `ALTERNATE` fill punches holes).
+    fn poly_poly(&mut self, figures: &[Vec<(f64, f64)>], closed: bool) {
+        let mut d = String::new();
+        for figure in figures.iter().filter(|f| f.len() >= 2) {
+            let _ = write!(d, "M{} ", point(figure[0]));
+            for &p in &figure[1..] {
+                let _ = write!(d, "L{} ", point(p));
+            }
+            if closed {
+                d.push_str("Z ");
+            }
+        }
+        if !d.is_empty() {
+            self.shape(d, closed);
+        }
+    }
+
+    fn fill_rect(&mut self, [l, t, r, b]: [f64; 4]) {
+        let matrix = self.matrix();
+        let d = format!(
+            "M{} {} H{} V{} H{} Z",
+            num(l),
+            num(t),
+            num(r),
+            num(b),
+            num(l)
+        );
+        self.paint(&d, true, false, &matrix);
+    }
+
+    fn end_path(&mut self, fill: bool, stroke: bool) {
+        let d = std::mem::take(&mut self.path);
+        self.in_path = false;
+        if !d.is_empty() {
+            let matrix = self.path_matrix;
+            self.paint(&d, fill, stroke, &matrix);
+        }
+    }
+
+    fn paint(&mut self, d: &str, fill: bool, stroke: bool, matrix: &Matrix) {
+        let fill = if fill { self.dc.brush } else { None };
+        let pen = if stroke { self.dc.pen.clone() } else { None };
+        if fill.is_none() && pen.is_none() {
+            return;
+        }
+        let _ = write!(self.body, "<path d=\"{}\"", d.trim_end());
+        if let Some(color) = fill {
+            let _ = write!(self.body, " fill=\"{}\"", color.hex());
+            if !self.dc.winding {
+                self.body.push_str(" fill-rule=\"evenodd\"");
+            }
+        }
+        if let Some(pen) = pen {
+            self.body.push_str(&stroke_attributes(&pen));
+        }
+        self.body.push_str(&transform_attribute(matrix));
+        self.body.push_str("/>");
+    }
+
+    /// Text at `reference` (or the current position under `TA_UPDATECP`),
+    /// placed in output space so glyphs stay upright under flipped mapping
+    /// modes, as GDI draws them.
+    fn text(&mut self, reference: (f64, f64), text: &str) {
+        if text.trim().is_empty() {
+            return;
+        }
+        let dc = &self.dc;
+        let matrix = self.matrix();
+        let align = dc.text_align;
+        let reference = if align & TA_UPDATECP != 0 {
+            dc.position
+        } else {
+            reference
+        };
+        let (x, y) = apply(&matrix, reference);
+        let scale = (matrix[2] * matrix[2] + matrix[3] * matrix[3]).sqrt();
+        let font = &dc.font;
+        // A positive height includes internal leading; approximate the em.
+        let em = match font.height {
+            h if h < 0.0 => -h,
+            h if h > 0.0 => h * 0.8,
+            _ => 12.0,
+        };
+        let _ = write!(
+            self.body,
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\"",
+            num(x),
+            num(y),
+            num(em * scale)
+        );
+        if !font.face.is_empty() {
+            let _ = write!(self.body, " font-family=\"{}\"", escape_html(&font.face));
+        }
+        if font.weight >= 600 {
+            self.body.push_str(" font-weight=\"bold\"");
+        }
+        if font.italic {
+            self.body.push_str(" font-style=\"italic\"");
+        }
+        match (font.underline, font.strike_out) {
+            (true, true) => self
+                .body
+                .push_str(" text-decoration=\"underline line-through\""),
+            (true, false) => self.body.push_str(" text-decoration=\"underline\""),
+            (false, true) => self.body.push_str(" text-decoration=\"line-through\""),
+            (false, false) => {}
+        }
+        let _ = write!(self.body, " fill=\"{}\"", dc.text_color.hex());
+        match align & TA_CENTER {
+            TA_CENTER => self.body.push_str(" text-anchor=\"middle\""),
+            TA_RIGHT => self.body.push_str(" text-anchor=\"end\""),
+            _ => {}
+        }
+        match align & TA_BASELINE {
+            TA_BASELINE => {}
+            TA_BOTTOM => self.body.push_str(" dominant-baseline=\"text-after-edge\""),
+            _ => self
+                .body
+                .push_str(" dominant-baseline=\"text-before-edge\""),
+        }
+        if font.escapement != 0.0 {
+            let _ = write!(
+                self.body,
+                " transform=\"rotate({} {} {})\"",
+                num(-font.escapement / 10.0),
+                num(x),
+                num(y)
+            );
+        }
+        let _ = write!(
+            self.body,
+            " xml:space=\"preserve\">{}</text>",
+            escape_html(text)
+        );
+    }
+
+    /// An embedded DIB stretched over the logical rectangle at `(x, y)`.
+    fn image(&mut self, x: f64, y: f64, width: f64, height: f64, png: &[u8]) {
+        if width == 0.0 || height == 0.0 {
+            return;
+        }
+        let (x, width) = if width < 0.0 {
+            (x + width, -width)
+        } else {
+            (x, width)
+        };
+        let (y, height) = if height < 0.0 {
+            (y + height, -height)
+        } else {
+            (y, height)
+        };
+        let _ = write!(
+            self.body,
+            "<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\" href=\"data:image/png;base64,{}\"{}/>",
+            num(x),
+            num(y),
+            num(width),
+            num(height),
+            base64::engine::general_purpose::STANDARD.encode(png),
+            transform_attribute(&self.matrix())
+        );
+    }
+
+    /// The finished document, or `None` if nothing was drawn.
+    fn finish(mut self, view: [f64; 4], size: (f64, f64)) -> Option<String> {
+        self.flush_lines();
+        if self.body.is_empty() || view[2] <= 0.0 || view[3] <= 0.0 {
+            return None;
+        }
+        let (width, height) = if size.0 > 0.0 && size.1 > 0.0 {
+            size
+        } else {
+            (view[2], view[3])
+        };
+        Some(format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke-linecap=\"round\" stroke-linejoin=\"round\">{}</svg>",
+            num(view[0]),
+            num(view[1]),
+            num(view[2]),
+            num(view[3]),
+            num(width),
+            num(height),
+            self.body
+        ))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Closure {
+    Open,
+    Chord,
+    Pie,
+}
+
+fn stroke_attributes(pen: &Pen) -> String {
+    let mut attrs = format!(" stroke=\"{}\"", pen.color.hex());
+    // PS_GEOMETRIC pens (from ExtCreatePen) scale with the transform;
+    // everything else with width 0 or 1 is a hairline.
+    let geometric = pen.style & 0x0001_0000 != 0 || pen.width > 1.0;
+    let unit = if geometric {
+        let _ = write!(attrs, " stroke-width=\"{}\"", num(pen.width.max(1.0)));
+        pen.width.max(1.0)
+    } else {
+        attrs.push_str(" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"");
+        1.0
+    };
+    let dashes: &[f64] = match pen.style & 0xF {
+        1 => &[18.0, 6.0],
+        2 => &[3.0, 3.0],
+        3 => &[9.0, 6.0, 3.0, 6.0],
+        4 => &[9.0, 3.0, 3.0, 3.0, 3.0, 3.0],
+        _ => &[],
+    };
+    if !dashes.is_empty() {
+        let pattern: Vec<String> = dashes.iter().map(|d| num(d * unit)).collect();
+        let _ = write!(attrs, " stroke-dasharray=\"{}\"", pattern.join(" "));
+    }
+    match pen.style & 0xF00 {
+        0x100 => attrs.push_str(" stroke-linecap=\"square\""),
+        0x200 => attrs.push_str(" stroke-linecap=\"butt\""),
+        _ => {}
+    }
+    match pen.style & 0xF000 {
+        0x1000 => attrs.push_str(" stroke-linejoin=\"bevel\""),
+        0x2000 => attrs.push_str(" stroke-linejoin=\"miter\""),
+        _ => {}
+    }
+    attrs
+}
+
+fn transform_attribute(matrix: &Matrix) -> String {
+    if *matrix == IDENTITY {
+        return String::new();
+    }
+    let parts: Vec<String> = matrix.iter().map(|v| num(*v)).collect();
+    format!(" transform=\"matrix({})\"", parts.join(" "))
+}
+
+/// SVG path data for the elliptical arc GDI draws inside `bounds` from the
+/// radial through `start` to the one through `end` (counter-clockwise
+/// unless `clockwise`); equal radials give the whole ellipse. Returns the
+/// arc's first point, the `A` commands, and its last point.
+fn arc_path(
+    [l, t, r, b]: [f64; 4],
+    start: (f64, f64),
+    end: (f64, f64),
+    clockwise: bool,
+) -> Option<(Point, String, Point)> {
+    let (cx, cy) = ((l + r) / 2.0, (t + b) / 2.0);
+    let (rx, ry) = ((r - l).abs() / 2.0, (b - t).abs() / 2.0);
+    if rx == 0.0 || ry == 0.0 {
+        return None;
+    }
+    // Angles are measured on the unit circle the ellipse is a scaling of,
+    // counter-clockwise as seen with y pointing down.
+    let angle = |(x, y): (f64, f64)| ((cy - y) / ry).atan2((x - cx) / rx);
+    let at = |a: f64| (cx + rx * a.cos(), cy - ry * a.sin());
+    let (a0, a1) = (angle(start), angle(end));
+    let mut sweep = if clockwise { a0 - a1 } else { a1 - a0 }.rem_euclid(TAU);
+    if sweep < 1e-9 {
+        sweep = TAU;
+    }
+    let flag = if clockwise { 1 } else { 0 };
+    let radii = format!("{} {}", num(rx), num(ry));
+    let from = at(a0);
+    let to = at(if clockwise { a0 - sweep } else { a0 + sweep });
+    let d = if sweep > PI {
+        // Split at the midpoint: a single `A` can't span a full turn, and
+        // halves keep the large-arc flag unambiguous.
+        let mid = at(if clockwise {
+            a0 - sweep / 2.0
+        } else {
+            a0 + sweep / 2.0
+        });
+        format!(
+            "A{radii} 0 0 {flag} {} A{radii} 0 0 {flag} {} ",
+            point(mid),
+            point(to)
+        )
+    } else {
+        format!("A{radii} 0 0 {flag} {} ", point(to))
+    };
+    Some((from, d, to))
+}
+
+/// Shortest form of a coordinate, to two decimals.
+fn num(v: f64) -> String {
+    let s = format!("{:.2}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    match s {
+        "-0" | "" => "0".to_string(),
+        _ => s.to_string(),
+    }
+}
+
+fn point((x, y): (f64, f64)) -> String {
+    format!("{} {}", num(x), num(y))
+}
+
+// ── Byte readers ───────────────────────────────────────────────────────
+
+fn u16_at(b: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        b.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn i16_at(b: &[u8], offset: usize) -> Option<i16> {
+    u16_at(b, offset).map(|v| v as i16)
+}
+
+fn u32_at(b: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        b.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn i32_at(b: &[u8], offset: usize) -> Option<i32> {
+    u32_at(b, offset).map(|v| v as i32)
+}
+
+fn f32_at(b: &[u8], offset: usize) -> Option<f32> {
+    u32_at(b, offset).map(f32::from_bits)
+}
+
+/// Signed 32-bit value as a coordinate, `0` when out of range.
+fn coord32(b: &[u8], offset: usize) -> f64 {
+    i32_at(b, offset).unwrap_or(0) as f64
+}
+
+/// Signed 16-bit value as a coordinate, `0` when out of range.
+fn coord16(b: &[u8], offset: usize) -> f64 {
+    i16_at(b, offset).unwrap_or(0) as f64
+}
+
+/// Up to `count` x/y pairs from `offset`, 16- or 32-bit.
+fn points(b: &[u8], offset: usize, count: usize, short: bool) -> Vec<(f64, f64)> {
+    let step = if short { 4 } else { 8 };
+    let available = b.len().saturating_sub(offset) / step;
+    (0..count.min(available))
+        .map(|i| {
+            let at = offset + i * step;
+            if short {
+                (coord16(b, at), coord16(b, at + 2))
+            } else {
+                (coord32(b, at), coord32(b, at + 4))
+            }
+        })
+        .collect()
+}
+
+/// `l, t, r, b` from four consecutive 32-bit values.
+fn rect32(b: &[u8], offset: usize) -> [f64; 4] {
+    [
+        coord32(b, offset),
+        coord32(b, offset + 4),
+        coord32(b, offset + 8),
+        coord32(b, offset + 12),
+    ]
+}
+
+fn colorref(b: &[u8], offset: usize) -> Color {
+    Color::from_colorref(u32_at(b, offset).unwrap_or(0))
+}
+
+/// Wraps a DIB (a `BITMAPINFO` whose pixel data starts `bits_offset` bytes
+/// in) in a BMP file header and re-encodes it as PNG.
+fn dib_to_png(dib: &[u8], bits_offset: usize) -> Option<Vec<u8>> {
+    let mut bmp = Vec::with_capacity(14 + dib.len());
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&((14 + dib.len()) as u32).to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&((14 + bits_offset) as u32).to_le_bytes());
+    bmp.extend_from_slice(dib);
+    let image = image::load_from_memory_with_format(&bmp, image::ImageFormat::Bmp).ok()?;
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+/// Offset of the pixel data in a packed DIB (header, colour masks and
+/// palette followed directly by the bits), as WMF records store them.
+fn packed_dib_bits_offset(dib: &[u8]) -> Option<usize> {
+    let header = u32_at(dib, 0)? as usize;
+    if header == 12 {
+        // BITMAPCOREHEADER: RGBTRIPLE palette.
+        let bits = u16_at(dib, 10)?;
+        let colors = if bits <= 8 { 1usize << bits } else { 0 };
+        return Some(12 + 3 * colors);
+    }
+    let bits = u16_at(dib, 14)?;
+    let compression = u32_at(dib, 16)?;
+    let used = u32_at(dib, 32)? as usize;
+    let colors = match used {
+        0 if (1..=8).contains(&bits) => 1usize << bits,
+        _ => used,
+    };
+    let masks = match (header, compression) {
+        (40, 3) => 12,
+        (40, 6) => 16,
+        _ => 0,
+    };
+    Some(header + masks + 4 * colors)
+}
+
+/// UTF-16LE text of `chars` code units at `offset`.
+fn utf16_at(b: &[u8], offset: usize, chars: usize) -> String {
+    let units: Vec<u16> = (0..chars)
+        .map_while(|i| u16_at(b, offset + 2 * i))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Single-byte ANSI text, read as Latin-1.
+fn ansi_at(b: &[u8], offset: usize, len: usize) -> String {
+    b.get(offset..(offset + len).min(b.len()))
+        .unwrap_or_default()
+        .iter()
+        .map(|&c| c as char)
+        .collect()
+}
+
+// ── EMF ─────────────────────────────────────────────────────────────────
+
+const EMR_HEADER: u32 = 1;
+const EMR_EOF: u32 = 14;
+/// `ENHMETA_SIGNATURE`, " EMF".
+const EMF_SIGNATURE: u32 = 0x464D_4520;
+
+/// Render an enhanced metafile to SVG, or `None` if it isn't one or draws
+/// nothing the supported records cover.
+pub fn emf_to_svg(bytes: &[u8]) -> Option<String> {
+    if u32_at(bytes, 0)? != EMR_HEADER || u32_at(bytes, 40)? != EMF_SIGNATURE {
+        return None;
+    }
+    let bounds = rect32(bytes, 8);
+    let frame = rect32(bytes, 24);
+    let mut renderer = Renderer::new(Format::Emf);
+    let (device, millimeters) = (
+        (coord32(bytes, 72), coord32(bytes, 76)),
+        (coord32(bytes, 80), coord32(bytes, 84)),
+    );
+    if device.0 > 0.0 && device.1 > 0.0 && millimeters.0 > 0.0 && millimeters.1 > 0.0 {
+        renderer.px_per_mm = (device.0 / millimeters.0, device.1 / millimeters.1);
+    }
+
+    let mut offset = 0;
+    while let (Some(kind), Some(size)) = (u32_at(bytes, offset), u32_at(bytes, offset + 4)) {
+        let size = size as usize;
+        if kind == EMR_EOF || size < 8 || !size.is_multiple_of(4) || offset + size > bytes.len() {
+            break;
+        }
+        renderer.emf_record(kind, &bytes[offset..offset + size]);
+        offset += size;
+    }
+
+    // The header bounds are inclusive device pixels; the frame is the
+    // physical size in hundredths of a millimetre.
+    let view = if bounds[2] >= bounds[0] && bounds[3] >= bounds[1] {
+        [
+            bounds[0],
+            bounds[1],
+            bounds[2] - bounds[0] + 1.0,
+            bounds[3] - bounds[1] + 1.0,
+        ]
+    } else {
+        let (mx, my) = renderer.px_per_mm;
+        [
+            frame[0] / 100.0 * mx,
+            frame[1] / 100.0 * my,
+            (frame[2] - frame[0]) / 100.0 * mx,
+            (frame[3] - frame[1]) / 100.0 * my,
+        ]
+    };
+    let css_px_per_mm = 96.0 / 25.4;
+    let size = (
+        (frame[2] - frame[0]) / 100.0 * css_px_per_mm,
+        (frame[3] - frame[1]) / 100.0 * css_px_per_mm,
+    );
+    renderer.finish(view, size)
+}
+
+impl Renderer {
+    fn emf_record(&mut self, kind: u32, rec: &[u8]) {
+        // Records that extend the current figure keep the pending line run
+        // open; anything else may change the pen or paint over it.
+        if !matches!(kind, 5 | 6 | 27 | 54 | 55 | 88 | 89) {
+            self.flush_lines();
+        }
+        let point_at = |offset| (coord32(rec, offset), coord32(rec, offset + 4));
+        match kind {
+            // EMR_POLYBEZIER(16), EMR_POLYGON(16), EMR_POLYLINE(16),
+            // EMR_POLYBEZIERTO(16), EMR_POLYLINETO(16)
+            2..=6 | 85..=89 => {
+                let short = kind >= 85;
+                let count = u32_at(rec, 24).unwrap_or(0) as usize;
+                let pts = points(rec, 28, count, short);
+                match kind {
+                    2 | 85 => self.poly_bezier(&pts),
+                    3 | 86 => self.poly(&pts, true),
+                    4 | 87 => self.poly(&pts, false),
+                    5 | 88 => self.bezier_to(&pts),
+                    _ => self.line_to(&pts),
+                }
+            }
+            // EMR_POLYPOLYLINE(16), EMR_POLYPOLYGON(16)
+            7 | 8 | 90 | 91 => {
+                let short = kind >= 90;
+                let polys = (u32_at(rec, 24).unwrap_or(0) as usize).min(rec.len() / 4);
+                let mut at = 32 + 4 * polys;
+                let mut figures = Vec::with_capacity(polys);
+                for i in 0..polys {
+                    let count = u32_at(rec, 32 + 4 * i).unwrap_or(0) as usize;
+                    let figure = points(rec, at, count, short);
+                    at += figure.len() * if short { 4 } else { 8 };
+                    figures.push(figure);
+                }
+                self.poly_poly(&figures, kind == 8 || kind == 91);
+            }
+            // EMR_SETWINDOWEXTEX, EMR_SETWINDOWORGEX, EMR_SETVIEWPORTEXTEX,
+            // EMR_SETVIEWPORTORGEX
+            9 => {
+                self.dc.window_ext = point_at(8);
+                self.dc.window_set = true;
+            }
+            10 => self.dc.window_org = point_at(8),
+            11 => self.dc.viewport_ext = point_at(8),
+            12 => self.dc.viewport_org = point_at(8),
+            // EMR_SETMAPMODE
+            17 => self.dc.map_mode = u32_at(rec, 8).unwrap_or(MM_TEXT),
+            // EMR_SETPOLYFILLMODE
+            19 => self.dc.winding = u32_at(rec, 8) == Some(2),
+            // EMR_SETTEXTALIGN
+            22 => self.dc.text_align = u32_at(rec, 8).unwrap_or(0),
+            // EMR_SETTEXTCOLOR
+            24 => self.dc.text_color = colorref(rec, 8),
+            // EMR_MOVETOEX
+            27 => self.move_to(point_at(8)),
+            // EMR_SAVEDC, EMR_RESTOREDC
+            33 => self.saved.push(self.dc.clone()),
+            34 => self.restore_dc(i32_at(rec, 8).unwrap_or(-1)),
+            // EMR_SETWORLDTRANSFORM, EMR_MODIFYWORLDTRANSFORM
+            35 | 36 => {
+                let mut xform = IDENTITY;
+                for (i, v) in xform.iter_mut().enumerate() {
+                    *v = f32_at(rec, 8 + 4 * i).unwrap_or(0.0) as f64;
+                }
+                let mode = if kind == 35 {
+                    4
+                } else {
+                    u32_at(rec, 32).unwrap_or(0)
+                };
+                self.dc.world = match mode {
+                    1 => IDENTITY,
+                    2 => multiply(&xform, &self.dc.world),
+                    3 => multiply(&self.dc.world, &xform),
+                    4 => xform,
+                    _ => self.dc.world,
+                };
+            }
+            // EMR_SELECTOBJECT, EMR_DELETEOBJECT
+            37 => self.select_object(u32_at(rec, 8).unwrap_or(u32::MAX)),
+            40 => self.delete_object(u32_at(rec, 8).unwrap_or(u32::MAX)),
+            // EMR_CREATEPEN
+            38 => {
+                let style = u32_at(rec, 12).unwrap_or(0);
+                let pen = (style & 0xF != 5).then(|| Pen {
+                    color: colorref(rec, 24),
+                    width: coord32(rec, 16),
+                    style,
+                });
+                self.set_object(u32_at(rec, 8).unwrap_or(0) as usize, Object::Pen(pen));
+            }
+            // EMR_EXTCREATEPEN
+            95 => {
+                let style = u32_at(rec, 28).unwrap_or(0);
+                // A null pen style, or a null brush style for the stroke.
+                let null = style & 0xF == 5 || u32_at(rec, 36) == Some(1);
+                let pen = (!null).then(|| Pen {
+                    color: colorref(rec, 40),
+                    width: u32_at(rec, 32).unwrap_or(0) as f64,
+                    style,
+                });
+                self.set_object(u32_at(rec, 8).unwrap_or(0) as usize, Object::Pen(pen));
+            }
+            // EMR_CREATEBRUSHINDIRECT: solid and hatched brushes paint their
+            // colour; BS_NULL and pattern styles paint nothing.
+            39 => {
+                let brush = match u32_at(rec, 12) {
+                    Some(0) | Some(2) => Some(colorref(rec, 16)),
+                    _ => None,
+                };
+                self.set_object(u32_at(rec, 8).unwrap_or(0) as usize, Object::Brush(brush));
+            }
+            // EMR_CREATEMONOBRUSH, EMR_CREATEDIBPATTERNBRUSHPT
+            93 | 94 => self.set_object(u32_at(rec, 8).unwrap_or(0) as usize, Object::Brush(None)),
+            // EMR_CREATEPALETTE
+            49 => self.set_object(u32_at(rec, 8).unwrap_or(0) as usize, Object::Other),
+            // EMR_EXTCREATEFONTINDIRECTW
+            82 => {
+                let font = Font {
+                    height: coord32(rec, 12),
+                    escapement: coord32(rec, 20),
+                    weight: i32_at(rec, 28).unwrap_or(400),
+                    italic: rec.get(32).is_some_and(|&b| b != 0),
+                    underline: rec.get(33).is_some_and(|&b| b != 0),
+                    strike_out: rec.get(34).is_some_and(|&b| b != 0),
+                    face: utf16_at(rec, 40, 32)
+                        .split('\0')
+                        .next()
+                        .unwrap_or("")
+                        .to_string(),
+                };
+                self.set_object(u32_at(rec, 8).unwrap_or(0) as usize, Object::Font(font));
+            }
+            // EMR_ELLIPSE, EMR_RECTANGLE, EMR_ROUNDRECT
+            42 => self.ellipse(rect32(rec, 8)),
+            43 => self.rectangle(rect32(rec, 8)),
+            44 => self.round_rectangle(rect32(rec, 8), point_at(24)),
+            // EMR_ARC, EMR_CHORD, EMR_PIE, EMR_ARCTO
+            45..=47 | 55 => {
+                let (bounds, start, end) = (rect32(rec, 8), point_at(24), point_at(32));
+                match kind {
+                    45 => self.arc(bounds, start, end, Closure::Open),
+                    46 => self.arc(bounds, start, end, Closure::Chord),
+                    47 => self.arc(bounds, start, end, Closure::Pie),
+                    _ => self.arc_to(bounds, start, end),
+                }
+            }
+            // EMR_LINETO
+            54 => self.line_to(&[point_at(8)]),
+            // EMR_SETARCDIRECTION
+            57 => self.dc.clockwise = u32_at(rec, 8) == Some(2),
+            // EMR_BEGINPATH, EMR_ENDPATH, EMR_CLOSEFIGURE, EMR_FILLPATH,
+            // EMR_STROKEANDFILLPATH, EMR_STROKEPATH, EMR_ABORTPATH
+            59 => {
+                self.in_path = true;
+                self.path.clear();
+                self.path_matrix = self.matrix();
+                self.moved = true;
+            }
+            60 => self.in_path = false,
+            61 if !self.path.is_empty() => self.path.push_str("Z "),
+            62 => self.end_path(true, false),
+            63 => self.end_path(true, true),
+            64 => self.end_path(false, true),
+            68 => {
+                self.in_path = false;
+                self.path.clear();
+            }
+            // EMR_BITBLT, EMR_STRETCHBLT: without a bitmap these fill the
+            // destination with the brush.
+            76 | 77 => {
+                let (x, y) = point_at(24);
+                let (width, height) = point_at(32);
+                let (bmi, bmi_len) = (u32_at(rec, 84).unwrap_or(0), u32_at(rec, 88).unwrap_or(0));
+                let (bits, bits_len) = (u32_at(rec, 92).unwrap_or(0), u32_at(rec, 96).unwrap_or(0));
+                if bmi_len == 0 {
+                    self.fill_rect([x, y, x + width, y + height]);
+                } else {
+                    self.emf_bitmap(rec, (bmi, bmi_len), (bits, bits_len), [x, y, width, height]);
+                }
+            }
+            // EMR_SETDIBITSTODEVICE: drawn at source size.
+            80 => {
+                let (x, y) = point_at(24);
+                let (width, height) = point_at(40);
+                let bmi = (u32_at(rec, 48).unwrap_or(0), u32_at(rec, 52).unwrap_or(0));
+                let bits = (u32_at(rec, 56).unwrap_or(0), u32_at(rec, 60).unwrap_or(0));
+                self.emf_bitmap(rec, bmi, bits, [x, y, width, height]);
+            }
+            // EMR_STRETCHDIBITS
+            81 => {
+                let (x, y) = point_at(24);
+                let (width, height) = point_at(72);
+                let bmi = (u32_at(rec, 48).unwrap_or(0), u32_at(rec, 52).unwrap_or(0));
+                let bits = (u32_at(rec, 56).unwrap_or(0), u32_at(rec, 60).unwrap_or(0));
+                self.emf_bitmap(rec, bmi, bits, [x, y, width, height]);
+            }
+            // EMR_EXTTEXTOUTA, EMR_EXTTEXTOUTW
+            83 | 84 => {
+                let chars = u32_at(rec, 44).unwrap_or(0) as usize;
+                let at = u32_at(rec, 48).unwrap_or(0) as usize;
+                // ETO_GLYPH_INDEX: the "string" is font glyph ids.
+                if u32_at(rec, 52).unwrap_or(0) & 0x10 != 0 {
+                    return;
+                }
+                let text = if kind == 84 {
+                    utf16_at(rec, at, chars)
+                } else {
+                    ansi_at(rec, at, chars)
+                };
+                self.text(point_at(36), &text);
+            }
+            _ => {}
+        }
+    }
+
+    /// Draw the DIB whose header and bits sit at the given `(offset, size)`
+    /// ranges of an EMF record.
+    fn emf_bitmap(
+        &mut self,
+        rec: &[u8],
+        bmi: (u32, u32),
+        bits: (u32, u32),
+        [x, y, w, h]: [f64; 4],
+    ) {
+        let range =
+            |(offset, len): (u32, u32)| rec.get(offset as usize..offset as usize + len as usize);
+        let (Some(info), Some(pixels)) = (range(bmi), range(bits)) else {
+            return;
+        };
+        let dib = [info, pixels].concat();
+        if let Some(png) = dib_to_png(&dib, info.len()) {
+            self.image(x, y, w, h, &png);
+        }
+    }
+}
+
+// ── WMF ─────────────────────────────────────────────────────────────────
+
+/// Key of the Aldus placeable header some WMF files start with.
+const PLACEABLE_KEY: u32 = 0x9AC6_CDD7;
+
+/// Render a Windows metafile (with or without a placeable header) to SVG,
+/// or `None` if it isn't one or draws nothing the supported records cover.
+pub fn wmf_to_svg(bytes: &[u8]) -> Option<String> {
+    let mut offset = 0;
+    let mut placeable = None;
+    if u32_at(bytes, 0)? == PLACEABLE_KEY {
+        let bbox = [
+            coord16(bytes, 6),
+            coord16(bytes, 8),
+            coord16(bytes, 10),
+            coord16(bytes, 12),
+        ];
+        let inch = match u16_at(bytes, 14)? {
+            0 => 1440.0,
+            v => v as f64,
+        };
+        placeable = Some((bbox, inch));
+        offset = 22;
+    }
+    // META_HEADER: type (memory or disk) and a nine-word header size.
+    if !matches!(u16_at(bytes, offset)?, 1 | 2) || u16_at(bytes, offset + 2)? != 9 {
+        return None;
+    }
+    offset += 18;
+
+    let mut renderer = Renderer::new(Format::Wmf);
+    while let (Some(words), Some(function)) = (u32_at(bytes, offset), u16_at(bytes, offset + 4)) {
+        let size = words as usize * 2;
+        if function == 0 || size < 6 || offset + size > bytes.len() {
+            break;
+        }
+        renderer.wmf_record(function, &bytes[offset + 6..offset + size]);
+        offset += size;
+    }
+
+    // With a window set, output space is the window (see `Renderer::page`);
+    // otherwise logical coordinates are drawn as-is inside the placeable
+    // bounds.
+    let dc = &renderer.dc;
+    let (view, units_per_inch) = match (dc.window_set, placeable) {
+        (true, placeable) => (
+            [0.0, 0.0, dc.window_ext.0.abs(), dc.window_ext.1.abs()],
+            placeable.map(|(bbox, inch)| {
+                inch * dc.window_ext.0.abs() / (bbox[2] - bbox[0]).abs().max(1.0)
+            }),
+        ),
+        (false, Some(([l, t, r, b], inch))) => (
+            [l.min(r), t.min(b), (r - l).abs(), (b - t).abs()],
+            Some(inch),
+        ),
+        (false, None) => return None,
+    };
+    let size = match units_per_inch {
+        Some(units) => (view[2] * 96.0 / units, view[3] * 96.0 / units),
+        None => (view[2], view[3]),
+    };
+    renderer.finish(view, size)
+}
+
+impl Renderer {
+    fn wmf_record(&mut self, function: u16, p: &[u8]) {
+        // META_MOVETO and META_LINETO extend the pending line run.
+        if !matches!(function, 0x0213 | 0x0214) {
+            self.flush_lines();
+        }
+        // Parameters are 16-bit words, most of them in reverse order (y
+        // before x, bottom-right before top-left).
+        let w = |i: usize| coord16(p, 2 * i);
+        let point_at = |i: usize| (w(i + 1), w(i));
+        let rect_at = |i: usize| [w(i + 3), w(i + 2), w(i + 1), w(i)];
+        match function {
+            // META_SETWINDOWORG, META_SETWINDOWEXT
+            0x020B => self.dc.window_org = point_at(0),
+            0x020C => {
+                self.dc.window_ext = point_at(0);
+                self.dc.window_set = true;
+            }
+            // META_SETMAPMODE
+            0x0103 => self.dc.map_mode = u16_at(p, 0).unwrap_or(1) as u32,
+            // META_SETPOLYFILLMODE, META_SETTEXTALIGN, META_SETTEXTCOLOR
+            0x0106 => self.dc.winding = u16_at(p, 0) == Some(2),
+            0x012E => self.dc.text_align = u16_at(p, 0).unwrap_or(0) as u32,
+            0x0209 => self.dc.text_color = colorref(p, 0),
+            // META_SAVEDC, META_RESTOREDC
+            0x001E => self.saved.push(self.dc.clone()),
+            0x0127 => self.restore_dc(i16_at(p, 0).unwrap_or(-1) as i32),
+            // META_SELECTOBJECT, META_DELETEOBJECT
+            0x012D => self.select_object(u16_at(p, 0).unwrap_or(u16::MAX) as u32),
+            0x01F0 => self.delete_object(u16_at(p, 0).unwrap_or(u16::MAX) as u32),
+            // META_CREATEPENINDIRECT
+            0x02FA => {
+                let style = u16_at(p, 0).unwrap_or(0) as u32;
+                let pen = (style & 0xF != 5).then(|| Pen {
+                    color: colorref(p, 6),
+                    width: w(1),
+                    style,
+                });
+                self.add_object(Object::Pen(pen));
+            }
+            // META_CREATEBRUSHINDIRECT
+            0x02FC => {
+                let brush = match u16_at(p, 0) {
+                    Some(0) | Some(2) => Some(colorref(p, 2)),
+                    _ => None,
+                };
+                self.add_object(Object::Brush(brush));
+            }
+            // META_CREATEFONTINDIRECT
+            0x02FB => {
+                let face = ansi_at(p, 18, 32);
+                let font = Font {
+                    height: w(0),
+                    escapement: w(2),
+                    weight: w(4) as i32,
+                    italic: p.get(10).is_some_and(|&b| b != 0),
+                    underline: p.get(11).is_some_and(|&b| b != 0),
+                    strike_out: p.get(12).is_some_and(|&b| b != 0),
+                    face: face.split('\0').next().unwrap_or("").to_string(),
+                };
+                self.add_object(Object::Font(font));
+            }
+            // META_DIBCREATEPATTERNBRUSH, META_CREATEPATTERNBRUSH
+            0x0142 | 0x01F9 => self.add_object(Object::Brush(None)),
+            // META_CREATEPALETTE, META_CREATEREGION
+            0x00F7 | 0x06FF => self.add_object(Object::Other),
+            // META_MOVETO, META_LINETO
+            0x0214 => self.move_to(point_at(0)),
+            0x0213 => self.line_to(&[point_at(0)]),
+            // META_RECTANGLE, META_ELLIPSE, META_ROUNDRECT
+            0x041B => self.rectangle(rect_at(0)),
+            0x0418 => self.ellipse(rect_at(0)),
+            0x061C => self.round_rectangle(rect_at(2), (w(1), w(0))),
+            // META_ARC, META_PIE, META_CHORD
+            0x0817 | 0x081A | 0x0830 => {
+                let closure = match function {
+                    0x0817 => Closure::Open,
+                    0x081A => Closure::Pie,
+                    _ => Closure::Chord,
+                };
+                self.arc(rect_at(4), point_at(2), point_at(0), closure);
+            }
+            // META_POLYGON, META_POLYLINE
+            0x0324 | 0x0325 => {
+                let count = u16_at(p, 0).unwrap_or(0) as usize;
+                self.poly(&points(p, 2, count, true), function == 0x0324);
+            }
+            // META_POLYPOLYGON
+            0x0538 => {
+                let polys = u16_at(p, 0).unwrap_or(0) as usize;
+                let mut at = 2 + 2 * polys;
+                let mut figures = Vec::with_capacity(polys);
+                for i in 0..polys {
+                    let count = u16_at(p, 2 + 2 * i).unwrap_or(0) as usize;
+                    let figure = points(p, at, count, true);
+                    at += figure.len() * 4;
+                    figures.push(figure);
+                }
+                self.poly_poly(&figures, true);
+            }
+            // META_TEXTOUT: the string is padded to a whole word.
+            0x0521 => {
+                let len = u16_at(p, 0).unwrap_or(0) as usize;
+                let text = ansi_at(p, 2, len);
+                let at = 2 + len.div_ceil(2) * 2;
+                self.text((coord16(p, at + 2), coord16(p, at)), &text);
+            }
+            // META_EXTTEXTOUT: an optional clipping/opaquing rectangle
+            // precedes the string.
+            0x0A32 => {
+                let len = u16_at(p, 4).unwrap_or(0) as usize;
+                let options = u16_at(p, 6).unwrap_or(0);
+                let at = if options & 0x6 != 0 { 16 } else { 8 };
+                self.text(point_at(0), &ansi_at(p, at, len));
+            }
+            // META_PATBLT
+            0x061D => {
+                let (height, width, y, x) = (w(2), w(3), w(4), w(5));
+                self.fill_rect([x, y, x + width, y + height]);
+            }
+            // META_DIBBITBLT, META_DIBSTRETCHBLT, META_STRETCHDIB; the first
+            // two also come without a bitmap (one word longer before the
+            // destination), which is a raster operation we don't draw.
+            0x0940 if p.len() > 18 => {
+                let (height, width, y, x) = (w(4), w(5), w(6), w(7));
+                self.wmf_bitmap(&p[16..], [x, y, width, height]);
+            }
+            0x0B41 if p.len() > 22 => {
+                let (height, width, y, x) = (w(6), w(7), w(8), w(9));
+                self.wmf_bitmap(&p[20..], [x, y, width, height]);
+            }
+            0x0F43 => {
+                let (height, width, y, x) = (w(7), w(8), w(9), w(10));
+                if let Some(dib) = p.get(22..) {
+                    self.wmf_bitmap(dib, [x, y, width, height]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn wmf_bitmap(&mut self, dib: &[u8], [x, y, w, h]: [f64; 4]) {
+        if let Some(png) = packed_dib_bits_offset(dib).and_then(|offset| dib_to_png(dib, offset)) {
+            self.image(x, y, w, h, &png);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An EMF record: type, size, then the given parameter bytes.
+    fn record(kind: u32, params: &[u8]) -> Vec<u8> {
+        let mut rec = kind.to_le_bytes().to_vec();
+        rec.extend_from_slice(&(8 + params.len() as u32).to_le_bytes());
+        rec.extend_from_slice(params);
+        rec
+    }
+
+    fn le32(values: &[i32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn emf(records: &[Vec<u8>]) -> Vec<u8> {
+        let mut header = le32(&[0, 0, 99, 49]); // bounds
+        header.extend(le32(&[0, 0, 2645, 1322])); // frame, 0.01 mm
+        header.extend(EMF_SIGNATURE.to_le_bytes());
+        header.extend(le32(&[0x10000, 0, 0, 0, 0, 0])); // version .. palette
+        header.extend(le32(&[1024, 768, 271, 203])); // device px, mm
+        let mut out = record(EMR_HEADER, &header);
+        for r in records {
+            out.extend_from_slice(r);
+        }
+        out.extend(record(EMR_EOF, &le32(&[0, 0, 0])));
+        out
+    }
+
+    /// A 2×1 bottom-up 24-bit DIB: a BITMAPINFOHEADER and its one row.
+    fn dib() -> (Vec<u8>, Vec<u8>) {
+        let mut info = le32(&[40, 2, 1]);
+        info.extend(1u16.to_le_bytes());
+        info.extend(24u16.to_le_bytes());
+        info.extend(le32(&[0, 8, 0, 0, 0, 0]));
+        let bits = vec![0, 0, 255, 0, 255, 0, 0, 0];
+        (info, bits)
+    }
+
+    #[test]
+    fn test_emf_shapes_fills_and_text() {
+        let mut pen = le32(&[1, 0, 3, 0]);
+        pen.extend(0x0000_00FFu32.to_le_bytes());
+        let mut polygon = le32(&[0, 0, 0, 0, 3]);
+        polygon.extend(
+            [10i16, 10, 40, 10, 25, 30]
+                .iter()
+                .flat_map(|v| v.to_le_bytes()),
+        );
+        let mut text = le32(&[0, 0, 0, 0, 1, 0, 0, 20, 45, 2, 76, 0, 0, 0, 0, 0, 0]);
+        text.extend("Hi".encode_utf16().flat_map(|u| u.to_le_bytes()));
+        let svg = emf_to_svg(&emf(&[
+            record(38, &pen),                        // CREATEPEN #1, red, 3 wide
+            record(37, &le32(&[1])),                 // SELECTOBJECT
+            record(39, &le32(&[2, 0, 0x00FF00, 0])), // CREATEBRUSHINDIRECT #2
+            record(37, &le32(&[2])),
+            record(86, &polygon),        // POLYGON16
+            record(27, &le32(&[50, 5])), // MOVETOEX
+            record(54, &le32(&[90, 5])), // LINETO
+            record(54, &le32(&[90, 40])),
+            record(24, &le32(&[0x00FF0000])),         // SETTEXTCOLOR
+            record(22, &le32(&[TA_BASELINE as i32])), // SETTEXTALIGN
+            record(84, &text),                        // EXTTEXTOUTW
+        ]))
+        .unwrap();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 50\" width=\"99.97\" height=\"49.97\""), "{svg}");
+        assert!(svg.contains("<path d=\"M10 10 L40 10 L25 30 Z\" fill=\"#00ff00\" fill-rule=\"evenodd\" stroke=\"#ff0000\" stroke-width=\"3\"/>"), "{svg}");
+        assert!(
+            svg.contains("<path d=\"M50 5 L90 5 L90 40\" stroke=\"#ff0000\""),
+            "{svg}"
+        );
+        assert!(svg.contains(">Hi</text>"), "{svg}");
+        assert!(svg.contains("x=\"20\" y=\"45\""), "{svg}");
+        assert!(svg.contains("fill=\"#0000ff\""), "{svg}");
+    }
+
+    #[test]
+    fn test_emf_path_bracket_and_mapping() {
+        let mut bezier = le32(&[0, 0, 0, 0, 3]);
+        bezier.extend(le32(&[10, 0, 20, 10, 20, 20]));
+        let svg = emf_to_svg(&emf(&[
+            record(17, &le32(&[MM_ANISOTROPIC as i32])),
+            record(9, &le32(&[200, 100])), // window ext
+            record(11, &le32(&[100, 50])), // viewport ext
+            record(59, &[]),               // BEGINPATH
+            record(27, &le32(&[0, 0])),
+            record(5, &bezier),               // POLYBEZIERTO
+            record(61, &[]),                  // CLOSEFIGURE
+            record(60, &[]),                  // ENDPATH
+            record(62, &le32(&[0, 0, 0, 0])), // FILLPATH
+        ]))
+        .unwrap();
+        assert!(
+            svg.contains("<path d=\"M0 0 C10 0 20 10 20 20 Z\" fill=\"#ffffff\" fill-rule=\"evenodd\" transform=\"matrix(0.5 0 0 0.5 0 0)\"/>"),
+            "{svg}"
+        );
+    }
+
+    #[test]
+    fn test_emf_stretchdibits_embeds_png() {
+        let (info, bits) = dib();
+        let mut params = le32(&[0, 0, 0, 0, 5, 6, 0, 0, 2, 1]);
+        let bmi_at = 8 + params.len() as i32 + 32;
+        params.extend(le32(&[
+            bmi_at,
+            info.len() as i32,
+            bmi_at + info.len() as i32,
+            bits.len() as i32,
+            0,
+            0xCC0020,
+            20,
+            10,
+        ]));
+        params.extend(&info);
+        params.extend(&bits);
+        let svg = emf_to_svg(&emf(&[record(81, &params)])).unwrap();
+        assert!(svg.contains("<image x=\"5\" y=\"6\" width=\"20\" height=\"10\" preserveAspectRatio=\"none\" href=\"data:image/png;base64,iVBOR"), "{svg}");
+    }
+
+    #[test]
+    fn test_emf_without_drawing_is_none() {
+        assert_eq!(emf_to_svg(&emf(&[])), None);
+        assert_eq!(emf_to_svg(b"not a metafile at all, just text"), None);
+    }
+
+    fn wmf_record(function: u16, params: &[i16]) -> Vec<u8> {
+        let mut rec = (3 + params.len() as u32).to_le_bytes().to_vec();
+        rec.extend(function.to_le_bytes());
+        rec.extend(params.iter().flat_map(|v| v.to_le_bytes()));
+        rec
+    }
+
+    fn wmf(records: &[Vec<u8>]) -> Vec<u8> {
+        let mut out = PLACEABLE_KEY.to_le_bytes().to_vec();
+        out.extend(
+            [0i16, 0, 0, 1000, 500, 1000, 0, 0, 0]
+                .iter()
+                .flat_map(|v| v.to_le_bytes()),
+        );
+        out.extend(
+            [1u16, 9, 0x300, 0, 0, 0, 0, 0, 0]
+                .iter()
+                .flat_map(|v| v.to_le_bytes()),
+        );
+        for r in records {
+            out.extend_from_slice(r);
+        }
+        out.extend(wmf_record(0, &[]));
+        out
+    }
+
+    #[test]
+    fn test_wmf_window_shapes_and_text() {
+        let svg = wmf_to_svg(&wmf(&[
+            wmf_record(0x020B, &[0, 0]),            // SETWINDOWORG
+            wmf_record(0x020C, &[500, 1000]),       // SETWINDOWEXT
+            wmf_record(0x02FC, &[0, 0x00FF, 0, 0]), // CREATEBRUSHINDIRECT red → #0
+            wmf_record(0x012D, &[0]),
+            wmf_record(0x041B, &[400, 900, 100, 100]), // RECTANGLE b r t l
+            wmf_record(0x0324, &[3, 0, 0, 50, 0, 25, 40]), // POLYGON
+            wmf_record(0x0521, &[2, i16::from_le_bytes(*b"OK"), 300, 200]), // TEXTOUT
+        ]))
+        .unwrap();
+        assert!(
+            svg.contains("viewBox=\"0 0 1000 500\" width=\"96\" height=\"48\""),
+            "{svg}"
+        );
+        assert!(
+            svg.contains("<path d=\"M100 100 H900 V400 H100 Z\" fill=\"#ff0000\""),
+            "{svg}"
+        );
+        assert!(svg.contains("<path d=\"M0 0 L50 0 L25 40 Z\""), "{svg}");
+        assert!(
+            svg.contains("x=\"200\" y=\"300\"") && svg.contains(">OK</text>"),
+            "{svg}"
+        );
+    }
+
+    #[test]
+    fn test_wmf_flipped_window_and_ellipse() {
+        let svg = wmf_to_svg(&wmf(&[
+            wmf_record(0x020B, &[100, 0]),    // origin y=100
+            wmf_record(0x020C, &[-100, 200]), // y axis points up
+            wmf_record(0x0418, &[100, 100, 0, 0]),
+        ]))
+        .unwrap();
+        assert!(svg.contains("viewBox=\"0 0 200 100\""), "{svg}");
+        assert!(
+            svg.contains("<path d=\"M100 50 A50 50 0 0 0 0 50 A50 50 0 0 0 100 50 Z\""),
+            "{svg}"
+        );
+        assert!(
+            svg.contains("transform=\"matrix(1 0 0 -1 0 100)\""),
+            "{svg}"
+        );
+    }
+
+    #[test]
+    fn test_wmf_stretchdib_packed_bitmap() {
+        let (info, bits) = dib();
+        let mut params: Vec<u8> = 0xCC0020u32.to_le_bytes().to_vec();
+        params.extend(
+            [0i16, 1, 2, 0, 0, 40, 80, 10, 20]
+                .iter()
+                .flat_map(|v| v.to_le_bytes()),
+        );
+        params.extend(&info);
+        params.extend(&bits);
+        let mut rec = (3 + params.len() as u32 / 2).to_le_bytes().to_vec();
+        rec.extend(0x0F43u16.to_le_bytes());
+        rec.extend(params);
+        let svg = wmf_to_svg(&wmf(&[rec])).unwrap();
+        assert!(
+            svg.contains("<image x=\"20\" y=\"10\" width=\"80\" height=\"40\""),
+            "{svg}"
+        );
+    }
+
+    #[test]
+    fn test_arc_path_directions() {
+        let (from, d, to) =
+            arc_path([0.0, 0.0, 20.0, 20.0], (20.0, 10.0), (10.0, 0.0), false).unwrap();
+        assert_eq!((from, to), ((20.0, 10.0), (10.0, 0.0)));
+        assert_eq!(d, "A10 10 0 0 0 10 0 ");
+        let (_, d, _) = arc_path([0.0, 0.0, 20.0, 20.0], (20.0, 10.0), (10.0, 0.0), true).unwrap();
+        assert_eq!(d, "A10 10 0 0 1 2.93 17.07 A10 10 0 0 1 10 0 ");
+    }
+}
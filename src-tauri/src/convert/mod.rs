@@ -1,12 +1,26 @@
 use std::fmt;
+use std::sync::OnceLock;
 
+use serde::{Deserialize, Serialize};
+
+use media::MediaSink;
+use progress::ImportProgress;
+use report::ImportReport;
+
+pub mod chart;
 pub mod docx;
-pub mod xlsx;
-pub mod pdf;
-pub mod pptx;
 pub mod html;
+pub mod inline_fmt;
 pub mod media;
-mod inline_fmt;
+mod metafile;
+mod numfmt;
+mod omml;
+pub mod pdf;
+pub mod pptx;
+pub mod progress;
+pub mod report;
+pub mod sniff;
+pub mod xlsx;
 
 #[derive(Debug)]
 pub struct ConversionError(pub String);
@@ -34,3 +48,297 @@ impl From<&str> for ConversionError {
         ConversionError(s.to_string())
     }
 }
+
+/// Options that tune a single import. Every importer receives one, so new
+/// knobs can be added here without changing the [`Converter`] signature.
+///
+/// Persisted in the user's settings (`UserSettings::import` in `main.rs`), so
+/// a team can tune import per workspace by editing `settings.json`. Missing
+/// fields fall back to the defaults, which reproduce the built-in behavior.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportOptions {
+    /// Longest edge, in pixels, extracted images are downscaled to (see
+    /// [`MediaSink::with_max_dimension`]). `None` writes originals verbatim.
+    pub max_image_dimension: Option<u32>,
+    /// Run formatting shared by the docx and pptx importers.
+    pub inline: inline_fmt::InlineOptions,
+    /// Chart rendering shared by the docx and pptx importers.
+    pub charts: chart::ChartOptions,
+    pub docx: docx::DocxOptions,
+    pub xlsx: xlsx::XlsxOptions,
+    pub pdf: pdf::PdfOptions,
+}
+
+/// A source format that can be imported into Markdown.
+///
+/// Implementations live next to the conversion code in each format module
+/// (e.g. `docx::DocxConverter`) and are registered in [`FormatRegistry`];
+/// nothing in `main.rs` needs to change to add a new format.
+pub trait Converter: Send + Sync {
+    /// Stable format id — the `format` string `import_document` receives.
+    fn id(&self) -> &'static str;
+    /// Human-readable name, used for file-dialog filter labels.
+    fn name(&self) -> &'static str;
+    /// File extensions (lowercase, no dot) this importer accepts.
+    fn extensions(&self) -> &'static [&'static str];
+    /// MIME types this importer accepts.
+    fn mime_types(&self) -> &'static [&'static str];
+    /// Convert the file at `path` to Markdown, writing any extracted images
+    /// via `media` and recording anything dropped along the way in `report`.
+    /// Implementations call `progress.step` between units of work (pages,
+    /// slides, sheets, ...) and stop with its error once cancelled.
+    fn to_markdown(
+        &self,
+        path: &str,
+        media: &mut MediaSink,
+        options: &ImportOptions,
+        report: &mut ImportReport,
+        progress: &ImportProgress,
+    ) -> Result<String, ConversionError>;
+}
+
+/// A target format Markdown can be exported to; the [`Converter`] counterpart.
+pub trait Exporter: Send + Sync {
+    /// Stable format id — the `format` string `export_document` receives.
+    fn id(&self) -> &'static str;
+    /// Human-readable name, used for file-dialog filter labels.
+    fn name(&self) -> &'static str;
+    /// File extensions (lowercase, no dot) this exporter writes.
+    fn extensions(&self) -> &'static [&'static str];
+    /// MIME types this exporter produces.
+    fn mime_types(&self) -> &'static [&'static str];
+    /// Render `markdown` and write the result to `path`.
+    fn export(&self, markdown: &str, path: &str) -> Result<(), ConversionError>;
+}
+
+/// Serializable description of one registered format, as returned by the
+/// `list_formats` command.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FormatInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub extensions: Vec<&'static str>,
+    pub mime_types: Vec<&'static str>,
+}
+
+/// Every registered importer and exporter.
+#[derive(Serialize, Debug, Clone)]
+pub struct FormatList {
+    pub importers: Vec<FormatInfo>,
+    pub exporters: Vec<FormatInfo>,
+}
+
+/// Lookup table of the available importers and exporters, in registration
+/// order (which is also the order `list_formats` reports them in).
+#[derive(Default)]
+pub struct FormatRegistry {
+    importers: Vec<Box<dyn Converter>>,
+    exporters: Vec<Box<dyn Exporter>>,
+}
+
+impl FormatRegistry {
+    /// The registry with every format Pourdown ships: docx/xlsx/pdf/pptx
+    /// import and HTML/PDF export.
+    pub fn with_builtin_formats() -> Self {
+        let mut registry = FormatRegistry::default();
+        registry.register_importer(docx::DocxConverter);
+        registry.register_importer(xlsx::XlsxConverter);
+        registry.register_importer(pdf::PdfConverter);
+        registry.register_importer(pptx::PptxConverter);
+        registry.register_exporter(html::HtmlExporter);
+        registry.register_exporter(pdf::PdfExporter);
+        registry
+    }
+
+    pub fn register_importer(&mut self, converter: impl Converter + 'static) {
+        self.importers.push(Box::new(converter));
+    }
+
+    pub fn register_exporter(&mut self, exporter: impl Exporter + 'static) {
+        self.exporters.push(Box::new(exporter));
+    }
+
+    /// The importer registered under format id `id`.
+    pub fn importer(&self, id: &str) -> Option<&dyn Converter> {
+        self.importers
+            .iter()
+            .find(|c| c.id() == id)
+            .map(|c| c.as_ref())
+    }
+
+    /// The first importer accepting file extension `ext` (case-insensitive,
+    /// no dot).
+    pub fn importer_for_extension(&self, ext: &str) -> Option<&dyn Converter> {
+        let ext = ext.to_lowercase();
+        self.importers
+            .iter()
+            .find(|c| c.extensions().contains(&ext.as_str()))
+            .map(|c| c.as_ref())
+    }
+
+    /// The exporter registered under format id `id`.
+    pub fn exporter(&self, id: &str) -> Option<&dyn Exporter> {
+        self.exporters
+            .iter()
+            .find(|e| e.id() == id)
+            .map(|e| e.as_ref())
+    }
+
+    pub fn formats(&self) -> FormatList {
+        FormatList {
+            importers: self
+                .importers
+                .iter()
+                .map(|c| FormatInfo {
+                    id: c.id(),
+                    name: c.name(),
+                    extensions: c.extensions().to_vec(),
+                    mime_types: c.mime_types().to_vec(),
+                })
+                .collect(),
+            exporters: self
+                .exporters
+                .iter()
+                .map(|e| FormatInfo {
+                    id: e.id(),
+                    name: e.name(),
+                    extensions: e.extensions().to_vec(),
+                    mime_types: e.mime_types().to_vec(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The process-wide registry of built-in formats, built on first use.
+pub fn registry() -> &'static FormatRegistry {
+    static REGISTRY: OnceLock<FormatRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(FormatRegistry::with_builtin_formats)
+}
+
+/// Convert the file at `path` to Markdown, writing any extracted images via
+/// `media`, filling `report` with what was dropped and reporting/cancelling
+/// through `progress`. `format` is the caller's expectation (the requested format id,
+/// or one derived from the file extension); the importer actually used is
+/// decided by the file's content (see [`sniff::resolve_importer`]). Shared by
+/// the `import_document` command and the headless CLI (`cli.rs`).
+pub fn import_to_markdown(
+    format: Option<&str>,
+    path: &str,
+    media: &mut MediaSink,
+    options: &ImportOptions,
+    report: &mut ImportReport,
+    progress: &ImportProgress,
+) -> Result<String, ConversionError> {
+    let markdown = sniff::resolve_importer(format, path)?
+        .to_markdown(path, media, options, report, progress)?;
+    report.stats.images_written = media.written_count();
+    Ok(markdown)
+}
+
+/// Write `markdown` to `path` with the exporter registered for `format`.
+pub fn export_from_markdown(
+    format: &str,
+    markdown: &str,
+    path: &str,
+) -> Result<(), ConversionError> {
+    registry()
+        .exporter(format)
+        .ok_or_else(|| ConversionError(format!("Unsupported export format: {}", format)))?
+        .export(markdown, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CsvConverter;
+
+    impl Converter for CsvConverter {
+        fn id(&self) -> &'static str {
+            "csv"
+        }
+        fn name(&self) -> &'static str {
+            "CSV"
+        }
+        fn extensions(&self) -> &'static [&'static str] {
+            &["csv"]
+        }
+        fn mime_types(&self) -> &'static [&'static str] {
+            &["text/csv"]
+        }
+        fn to_markdown(
+            &self,
+            path: &str,
+            _media: &mut MediaSink,
+            _options: &ImportOptions,
+            _report: &mut ImportReport,
+            _progress: &ImportProgress,
+        ) -> Result<String, ConversionError> {
+            Ok(format!("converted {}", path))
+        }
+    }
+
+    #[test]
+    fn test_builtin_registry_lists_all_formats() {
+        let formats = registry().formats();
+        let importers: Vec<&str> = formats.importers.iter().map(|f| f.id).collect();
+        let exporters: Vec<&str> = formats.exporters.iter().map(|f| f.id).collect();
+        assert_eq!(importers, ["docx", "xlsx", "pdf", "pptx"]);
+        assert_eq!(exporters, ["html", "pdf"]);
+        assert!(formats
+            .importers
+            .iter()
+            .all(|f| !f.extensions.is_empty() && !f.mime_types.is_empty()));
+    }
+
+    #[test]
+    fn test_importer_for_extension_is_case_insensitive() {
+        assert_eq!(
+            registry().importer_for_extension("ODS").map(|c| c.id()),
+            Some("xlsx")
+        );
+        assert_eq!(
+            registry().importer_for_extension("docx").map(|c| c.id()),
+            Some("docx")
+        );
+        assert!(registry().importer_for_extension("txt").is_none());
+        // Legacy .ppt is always rejected by sniffing, so it isn't offered.
+        assert!(registry().importer_for_extension("ppt").is_none());
+    }
+
+    #[test]
+    fn test_registered_importer_is_dispatched() {
+        let mut registry = FormatRegistry::with_builtin_formats();
+        registry.register_importer(CsvConverter);
+        let mut sink = MediaSink::new(std::env::temp_dir());
+        let md = registry
+            .importer_for_extension("csv")
+            .unwrap()
+            .to_markdown(
+                "a.csv",
+                &mut sink,
+                &ImportOptions::default(),
+                &mut ImportReport::default(),
+                &ImportProgress::default(),
+            )
+            .unwrap();
+        assert_eq!(md, "converted a.csv");
+    }
+
+    #[test]
+    fn test_import_options_fill_missing_fields_with_defaults() {
+        let options: ImportOptions =
+            serde_json::from_str(r#"{ "xlsx": { "max_rows_per_sheet": 50 } }"#).unwrap();
+        assert_eq!(options.xlsx.max_rows_per_sheet, 50);
+        assert_eq!(options.max_image_dimension, None);
+        assert_eq!(options.pdf, pdf::PdfOptions::default());
+    }
+
+    #[test]
+    fn test_unknown_export_format_is_an_error() {
+        let err = export_from_markdown("docx", "# hi", "a.docx").unwrap_err();
+        assert_eq!(err.0, "Unsupported export format: docx");
+    }
+}
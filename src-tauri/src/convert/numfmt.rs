@@ -0,0 +1,878 @@
+//! Excel number formats (the `numFmtId` / `formatCode` a cell's style points
+//! at in `xl/styles.xml`) applied to cell values, for the spreadsheet
+//! importer, so a cell reads as Excel shows it: `15.3%` rather than `0.153`,
+//! `$1,234.50` rather than `1234.5`, a date rather than its serial. Covers
+//! digit placeholders (`0`, `#`, `?`), grouping and scaling commas, percent,
+//! scientific notation, simple fractions, quoted and escaped literals,
+//! currency tags, up to four `;` sections with optional `[>n]` conditions,
+//! and date/time codes including elapsed `[h]` and fractional seconds.
+//! Colours, fill (`*`) and locale tags are ignored.
+
+use chrono::{Datelike, Days, NaiveDate};
+
+/// Built-in format codes for the ids `styles.xml` doesn't spell out. The
+/// locale-dependent short date (14) and date-time (22) use ISO order, as
+/// imported dates always have.
+fn builtin_code(id: u32) -> Option<&'static str> {
+    Some(match id {
+        1 => "0",
+        2 => "0.00",
+        3 => "#,##0",
+        4 => "#,##0.00",
+        5 => "$#,##0_);($#,##0)",
+        6 => "$#,##0_);[Red]($#,##0)",
+        7 => "$#,##0.00_);($#,##0.00)",
+        8 => "$#,##0.00_);[Red]($#,##0.00)",
+        9 => "0%",
+        10 => "0.00%",
+        11 => "0.00E+00",
+        12 => "# ?/?",
+        13 => "# ??/??",
+        14 => "yyyy-mm-dd",
+        15 => "d-mmm-yy",
+        16 => "d-mmm",
+        17 => "mmm-yy",
+        18 => "h:mm AM/PM",
+        19 => "h:mm:ss AM/PM",
+        20 => "h:mm",
+        21 => "h:mm:ss",
+        22 => "yyyy-mm-dd h:mm",
+        37 => "#,##0_);(#,##0)",
+        38 => "#,##0_);[Red](#,##0)",
+        39 => "#,##0.00_);(#,##0.00)",
+        40 => "#,##0.00_);[Red](#,##0.00)",
+        45 => "mm:ss",
+        46 => "[h]:mm:ss",
+        47 => "mm:ss.0",
+        48 => "##0.0E+0",
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(String),
+    /// A digit placeholder: `0` (always shown), `#` (dropped when not
+    /// significant) or `?` (a space when not significant).
+    Digit(char),
+    Point,
+    /// A `,` between digit placeholders: group thousands.
+    Thousands,
+    /// A `,` after the last digit placeholder: divide by 1000.
+    Scale,
+    Percent,
+    /// `E+` (always signed) or `E-`.
+    Exponent(bool),
+    /// `@`, or `General` inside a larger code: the value's default text.
+    General,
+    Year(usize),
+    Month(usize),
+    Day(usize),
+    Hour(usize),
+    Minute(usize),
+    Second(usize),
+    /// Digits of fractional seconds (`ss.00`).
+    SubSecond(usize),
+    /// `AM/PM` (`true`) or `A/P`: 12-hour clock.
+    AmPm(bool),
+    /// `[h]`, `[mm]`, `[ss]`: total elapsed units, not wrapped.
+    Elapsed(char, usize),
+}
+
+impl Token {
+    fn is_date(&self) -> bool {
+        matches!(
+            self,
+            Token::Year(_)
+                | Token::Month(_)
+                | Token::Day(_)
+                | Token::Hour(_)
+                | Token::Minute(_)
+                | Token::Second(_)
+                | Token::AmPm(_)
+                | Token::Elapsed(..)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Section {
+    tokens: Vec<Token>,
+    condition: Option<(Comparison, f64)>,
+    date: bool,
+}
+
+impl Section {
+    fn matches(&self, value: f64) -> bool {
+        match self.condition {
+            Some((Comparison::Lt, n)) => value < n,
+            Some((Comparison::Le, n)) => value <= n,
+            Some((Comparison::Gt, n)) => value > n,
+            Some((Comparison::Ge, n)) => value >= n,
+            Some((Comparison::Eq, n)) => value == n,
+            Some((Comparison::Ne, n)) => value != n,
+            None => true,
+        }
+    }
+}
+
+/// A parsed format code, ready to apply to numeric cell values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormat {
+    sections: Vec<Section>,
+}
+
+impl NumberFormat {
+    /// The format for a `numFmtId`, using `custom` (the workbook's own
+    /// `formatCode`) when present; `None` for General and unknown ids.
+    pub fn for_id(id: u32, custom: Option<&str>) -> Option<Self> {
+        Self::parse(custom.or_else(|| builtin_code(id))?)
+    }
+
+    /// Parse a format code; `None` for General (or an empty code), which
+    /// leaves the value's default rendering alone.
+    pub fn parse(code: &str) -> Option<Self> {
+        if code.trim().is_empty() || code.trim().eq_ignore_ascii_case("general") {
+            return None;
+        }
+        Some(NumberFormat {
+            sections: split_sections(code)
+                .iter()
+                .map(|s| parse_section(s))
+                .collect(),
+        })
+    }
+
+    /// Whether the code formats numbers as dates or times.
+    #[cfg(test)]
+    pub fn is_date(&self) -> bool {
+        self.sections.first().is_some_and(|s| s.date)
+    }
+
+    /// `value` as the format shows it. Dates count days from 1899-12-30, or
+    /// from 1904-01-01 when `date1904` (the workbook's `date1904` setting).
+    pub fn format(&self, value: f64, date1904: bool) -> String {
+        let (section, value, minus) = self.pick(value);
+        // Excel has no negative dates; those show as the plain number.
+        let text = if section.date {
+            (!minus)
+                .then(|| format_date(&section.tokens, value, date1904))
+                .flatten()
+        } else {
+            Some(format_number(&section.tokens, value))
+        };
+        match text {
+            // A negative that rounds away to nothing shows unsigned.
+            Some(text) if minus && text.chars().any(|c| c.is_ascii_digit() && c != '0') => {
+                format!("-{}", text)
+            }
+            Some(text) => text,
+            None => general(if minus { -value } else { value }),
+        }
+    }
+
+    /// The section for `value`, the magnitude to render with it, and whether
+    /// a minus sign goes in front (only when the section doesn't already
+    /// express the sign).
+    fn pick(&self, value: f64) -> (&Section, f64, bool) {
+        let sections = &self.sections;
+        if sections.iter().any(|s| s.condition.is_some()) {
+            let section = sections
+                .iter()
+                .find(|s| s.condition.is_some() && s.matches(value))
+                .or_else(|| sections.iter().find(|s| s.condition.is_none()))
+                .unwrap_or(&sections[0]);
+            return (section, value.abs(), value < 0.0);
+        }
+        match sections.len() {
+            1 => (&sections[0], value.abs(), value < 0.0),
+            2 if value < 0.0 => (&sections[1], -value, false),
+            n if n >= 3 && value < 0.0 => (&sections[1], -value, false),
+            n if n >= 3 && value == 0.0 => (&sections[2], 0.0, false),
+            _ => (&sections[0], value, false),
+        }
+    }
+}
+
+/// A number's default text: integers without a fraction, anything else in
+/// Rust's shortest round-trip form.
+fn general(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// Split a code on `;` outside quotes, brackets and escapes.
+fn split_sections(code: &str) -> Vec<String> {
+    let mut sections = vec![String::new()];
+    let mut chars = code.chars();
+    let (mut quoted, mut bracketed) = (false, false);
+    while let Some(c) = chars.next() {
+        let current = sections.last_mut().unwrap();
+        match c {
+            '"' if !bracketed => quoted = !quoted,
+            '[' if !quoted => bracketed = true,
+            ']' if !quoted => bracketed = false,
+            '\\' if !quoted => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+                continue;
+            }
+            ';' if !quoted && !bracketed => {
+                sections.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    sections
+}
+
+fn starts_with_ignore_case(chars: &[char], word: &str) -> bool {
+    chars.len() >= word.len()
+        && chars
+            .iter()
+            .zip(word.chars())
+            .all(|(a, b)| a.eq_ignore_ascii_case(&b))
+}
+
+fn parse_section(code: &str) -> Section {
+    let chars: Vec<char> = code.chars().collect();
+    let mut tokens = Vec::new();
+    let mut condition = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&q| q == '"')
+                    .map_or(chars.len(), |p| i + 1 + p);
+                tokens.push(Token::Literal(chars[i + 1..end].iter().collect()));
+                i = end + 1;
+            }
+            '\\' => {
+                if let Some(&next) = chars.get(i + 1) {
+                    tokens.push(Token::Literal(next.to_string()));
+                }
+                i += 2;
+            }
+            // `_x` pads with the width of `x`; `*x` repeats `x` to fill.
+            '_' => {
+                tokens.push(Token::Literal(" ".to_string()));
+                i += 2;
+            }
+            '*' => i += 2,
+            '[' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&b| b == ']')
+                    .map_or(chars.len(), |p| i + 1 + p);
+                let inner: String = chars[i + 1..end].iter().collect();
+                parse_bracket(&inner, &mut tokens, &mut condition);
+                i = end + 1;
+            }
+            '0' | '#' | '?' => {
+                tokens.push(Token::Digit(c));
+                i += 1;
+            }
+            '.' => {
+                tokens.push(Token::Point);
+                i += 1;
+            }
+            ',' => {
+                // Resolved to `Thousands` or `Scale` below.
+                tokens.push(Token::Scale);
+                i += 1;
+            }
+            '%' => {
+                tokens.push(Token::Percent);
+                i += 1;
+            }
+            '@' => {
+                tokens.push(Token::General);
+                i += 1;
+            }
+            'E' | 'e' if matches!(chars.get(i + 1), Some('+' | '-')) => {
+                tokens.push(Token::Exponent(chars[i + 1] == '+'));
+                i += 2;
+            }
+            _ if starts_with_ignore_case(&chars[i..], "am/pm") => {
+                tokens.push(Token::AmPm(true));
+                i += 5;
+            }
+            _ if starts_with_ignore_case(&chars[i..], "a/p") => {
+                tokens.push(Token::AmPm(false));
+                i += 3;
+            }
+            _ if starts_with_ignore_case(&chars[i..], "general") => {
+                tokens.push(Token::General);
+                i += 7;
+            }
+            'y' | 'Y' | 'm' | 'M' | 'd' | 'D' | 'h' | 'H' | 's' | 'S' => {
+                let lower = c.to_ascii_lowercase();
+                let run = chars[i..]
+                    .iter()
+                    .take_while(|x| x.to_ascii_lowercase() == lower)
+                    .count();
+                tokens.push(match lower {
+                    'y' => Token::Year(run),
+                    'm' => Token::Month(run),
+                    'd' => Token::Day(run),
+                    'h' => Token::Hour(run),
+                    _ => Token::Second(run),
+                });
+                i += run;
+            }
+            _ => {
+                tokens.push(Token::Literal(c.to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    let date = tokens.iter().any(Token::is_date);
+    if date {
+        resolve_date_tokens(&mut tokens);
+    } else {
+        resolve_number_tokens(&mut tokens);
+    }
+    Section {
+        tokens,
+        condition,
+        date,
+    }
+}
+
+/// `[$€-407]` currency tags, `[h]`-style elapsed time and `[>=100]`
+/// conditions; colours and other tags are dropped.
+fn parse_bracket(inner: &str, tokens: &mut Vec<Token>, condition: &mut Option<(Comparison, f64)>) {
+    if let Some(currency) = inner.strip_prefix('$') {
+        let symbol = currency.split('-').next().unwrap_or("");
+        if !symbol.is_empty() {
+            tokens.push(Token::Literal(symbol.to_string()));
+        }
+        return;
+    }
+    let lower = inner.to_ascii_lowercase();
+    if let Some(unit) = lower
+        .chars()
+        .next()
+        .filter(|u| matches!(u, 'h' | 'm' | 's'))
+    {
+        if lower.chars().all(|c| c == unit) {
+            tokens.push(Token::Elapsed(unit, lower.len()));
+            return;
+        }
+    }
+    let (op, rest) = match inner {
+        s if s.starts_with("<=") => (Comparison::Le, &s[2..]),
+        s if s.starts_with(">=") => (Comparison::Ge, &s[2..]),
+        s if s.starts_with("<>") => (Comparison::Ne, &s[2..]),
+        s if s.starts_with('<') => (Comparison::Lt, &s[1..]),
+        s if s.starts_with('>') => (Comparison::Gt, &s[1..]),
+        s if s.starts_with('=') => (Comparison::Eq, &s[1..]),
+        _ => return,
+    };
+    if let Ok(n) = rest.trim().parse() {
+        *condition = Some((op, n));
+    }
+}
+
+/// A comma followed by another digit placeholder groups thousands; one
+/// after the last placeholder scales by 1000. Only the first `.` is the
+/// decimal point.
+fn resolve_number_tokens(tokens: &mut [Token]) {
+    let last_digit = tokens.iter().rposition(|t| matches!(t, Token::Digit(_)));
+    let mut seen_point = false;
+    for (i, token) in tokens.iter_mut().enumerate() {
+        match token {
+            Token::Scale if last_digit.is_some_and(|d| i < d) => *token = Token::Thousands,
+            Token::Point if seen_point => *token = Token::Literal(".".to_string()),
+            Token::Point => seen_point = true,
+            _ => {}
+        }
+    }
+}
+
+/// `m` after an hour or before a second means minutes; a `.` and zeros
+/// after seconds are fractional seconds. Other placeholders and commas in a
+/// date code are plain text or dropped.
+fn resolve_date_tokens(tokens: &mut Vec<Token>) {
+    let time_neighbour = |tokens: &[Token], i: usize| {
+        let before = tokens[..i].iter().rev().find(|t| t.is_date());
+        let after = tokens[i + 1..].iter().find(|t| t.is_date());
+        matches!(before, Some(Token::Hour(_) | Token::Elapsed('h', _)))
+            || matches!(after, Some(Token::Second(_) | Token::Elapsed('s', _)))
+    };
+    for i in 0..tokens.len() {
+        if let Token::Month(n) = tokens[i] {
+            if n <= 2 && time_neighbour(tokens, i) {
+                tokens[i] = Token::Minute(n);
+            }
+        }
+    }
+    let mut resolved = Vec::with_capacity(tokens.len());
+    let mut iter = std::mem::take(tokens).into_iter().peekable();
+    while let Some(token) = iter.next() {
+        match token {
+            Token::Point => {
+                let after_seconds = matches!(
+                    resolved.last(),
+                    Some(Token::Second(_) | Token::Elapsed('s', _))
+                );
+                let mut digits = 0;
+                while after_seconds && iter.peek() == Some(&Token::Digit('0')) {
+                    iter.next();
+                    digits += 1;
+                }
+                resolved.push(if digits > 0 {
+                    Token::SubSecond(digits)
+                } else {
+                    Token::Literal(".".to_string())
+                });
+            }
+            Token::Scale => resolved.push(Token::Literal(",".to_string())),
+            Token::Percent => resolved.push(Token::Literal("%".to_string())),
+            Token::Digit(_) | Token::Exponent(_) => {}
+            other => resolved.push(other),
+        }
+    }
+    *tokens = resolved;
+}
+
+/// Render a non-negative `value` with a number section's tokens.
+fn format_number(tokens: &[Token], value: f64) -> String {
+    if let Some(text) = format_fraction(tokens, value) {
+        return text;
+    }
+    let count = |wanted: &Token| tokens.iter().filter(|t| *t == wanted).count() as i32;
+    let mut v = value * 100f64.powi(count(&Token::Percent)) / 1000f64.powi(count(&Token::Scale));
+    let thousands = tokens.contains(&Token::Thousands);
+    let point = tokens.iter().position(|t| *t == Token::Point);
+    let exponent_at = tokens.iter().position(|t| matches!(t, Token::Exponent(_)));
+    let int_end = point.or(exponent_at).unwrap_or(tokens.len());
+    let digit_slots = |range: std::ops::Range<usize>| -> Vec<usize> {
+        range
+            .filter(|&i| matches!(tokens[i], Token::Digit(_)))
+            .collect()
+    };
+    let int_slots = digit_slots(0..int_end);
+    let frac_slots = match point {
+        Some(p) => digit_slots(p + 1..exponent_at.unwrap_or(tokens.len())),
+        None => Vec::new(),
+    };
+    let exp_slots = match exponent_at {
+        Some(e) => digit_slots(e + 1..tokens.len()),
+        None => Vec::new(),
+    };
+
+    // Scientific: the mantissa keeps as many integer digits as there are
+    // integer placeholders (`##0.0E+0` steps the exponent by three).
+    let mut exponent = 0i32;
+    if exponent_at.is_some() && v != 0.0 {
+        let step = int_slots.len().max(1) as i32;
+        exponent = v.log10().floor() as i32;
+        exponent -= exponent.rem_euclid(step);
+        v /= 10f64.powi(exponent);
+        let rounded: f64 = format!("{:.*}", frac_slots.len(), v).parse().unwrap_or(v);
+        if rounded >= 10f64.powi(step) {
+            exponent += step;
+            v /= 10f64.powi(step);
+        }
+    }
+
+    let fixed = format!("{:.*}", frac_slots.len(), v);
+    let (int_part, frac_part) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let int_digits: Vec<char> = int_part.trim_start_matches('0').chars().collect();
+    // Trailing fraction zeros under `#` disappear, under `?` become spaces.
+    let mut frac: Vec<Option<char>> = frac_part.chars().map(Some).collect();
+    for (k, &slot) in frac_slots.iter().enumerate().rev() {
+        if frac[k] != Some('0') {
+            break;
+        }
+        match tokens[slot] {
+            Token::Digit('#') => frac[k] = None,
+            Token::Digit('?') => frac[k] = Some(' '),
+            _ => break,
+        }
+    }
+
+    let mut out = String::new();
+    let push_digit = |out: &mut String, digit: char, position: usize| {
+        out.push(digit);
+        if thousands && position > 0 && position.is_multiple_of(3) {
+            out.push(',');
+        }
+    };
+    let slots = int_slots.len();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Digit(kind) if i < int_end => {
+                // Digits fill the integer placeholders from the right; the
+                // leftmost placeholder takes any that remain.
+                let slot = int_slots.iter().position(|&s| s == i).unwrap_or(0);
+                let from_right = slots - 1 - slot;
+                let len = int_digits.len();
+                let assigned = if slot == 0 {
+                    0..len.saturating_sub(from_right)
+                } else if from_right < len {
+                    len - 1 - from_right..len - from_right
+                } else {
+                    0..0
+                };
+                if assigned.is_empty() {
+                    match kind {
+                        '0' => push_digit(&mut out, '0', from_right),
+                        '?' => out.push(' '),
+                        _ => {}
+                    }
+                }
+                for j in assigned {
+                    push_digit(&mut out, int_digits[j], len - 1 - j);
+                }
+            }
+            Token::Digit(_) => {
+                if let Some(k) = frac_slots.iter().position(|&s| s == i) {
+                    if let Some(d) = frac[k] {
+                        out.push(d);
+                    }
+                }
+            }
+            Token::Point => out.push('.'),
+            Token::Percent => out.push('%'),
+            Token::Exponent(plus) => {
+                out.push('E');
+                if exponent < 0 {
+                    out.push('-');
+                } else if *plus {
+                    out.push('+');
+                }
+                let width = exp_slots
+                    .iter()
+                    .filter(|&&s| tokens[s] == Token::Digit('0'))
+                    .count()
+                    .max(1);
+                out.push_str(&format!("{:0width$}", exponent.abs(), width = width));
+            }
+            Token::Literal(text) => out.push_str(text),
+            Token::General => out.push_str(&general(value)),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Most `?`/`#`/`0` denominator digits a fraction is searched with.
+const MAX_DENOMINATOR_DIGITS: usize = 4;
+
+/// `# ?/?`-style fractions: a whole part (when a placeholder precedes the
+/// fraction) and the closest fraction whose denominator has as many digits
+/// as its placeholders, or the fixed denominator written in the code.
+fn format_fraction(tokens: &[Token], value: f64) -> Option<String> {
+    let slash = tokens
+        .iter()
+        .position(|t| *t == Token::Literal("/".to_string()))?;
+    let before = &tokens[..slash];
+    let numerator_start = before
+        .iter()
+        .rposition(|t| !matches!(t, Token::Digit(_)))
+        .map_or(0, |p| p + 1);
+    if numerator_start == slash {
+        return None;
+    }
+    let whole = before[..numerator_start]
+        .iter()
+        .any(|t| matches!(t, Token::Digit(_)));
+    let after = &tokens[slash + 1..];
+    let den_digits = after
+        .iter()
+        .take_while(|t| matches!(t, Token::Digit(_)))
+        .count();
+    let fixed_den: Option<u64> = match after.first() {
+        Some(Token::Literal(text)) => text.parse().ok(),
+        _ => None,
+    };
+    if den_digits == 0 && fixed_den.is_none() {
+        return None;
+    }
+    let (int, frac) = if whole {
+        (value.trunc(), value.fract())
+    } else {
+        (0.0, value)
+    };
+    let (mut num, den) = match fixed_den {
+        Some(den) => (((frac * den as f64).round()) as u64, den.max(1)),
+        None => {
+            // The best denominator is searched for; past four digits that
+            // search gets slow, and the extra precision isn't visible anyway.
+            let max = 10u64.pow(den_digits.min(MAX_DENOMINATOR_DIGITS) as u32) - 1;
+            (1..=max)
+                .map(|d| ((frac * d as f64).round() as u64, d))
+                .min_by(|a, b| {
+                    let err = |(n, d): (u64, u64)| (frac - n as f64 / d as f64).abs();
+                    err(*a).total_cmp(&err(*b))
+                })
+                .unwrap_or((0, 1))
+        }
+    };
+    let mut int = int as u64;
+    if whole && num == den {
+        int += 1;
+        num = 0;
+    }
+    Some(match (whole, num) {
+        (true, 0) => int.to_string(),
+        (true, _) if int == 0 => format!("{}/{}", num, den),
+        (true, _) => format!("{} {}/{}", int, num, den),
+        (false, _) => format!("{}/{}", num, den),
+    })
+}
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Render a date/time serial with a date section's tokens; `None` when the
+/// serial isn't a representable date.
+fn format_date(tokens: &[Token], value: f64, date1904: bool) -> Option<String> {
+    if !(0.0..2_958_466.0).contains(&value) {
+        return None;
+    }
+    let sub_digits = tokens
+        .iter()
+        .filter_map(|t| match t {
+            Token::SubSecond(n) => Some(*n),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+        .min(3);
+    let unit = 10i64.pow(sub_digits as u32);
+    let total = (value * 86_400.0 * unit as f64).round() as i64;
+    let days = total / (86_400 * unit);
+    let seconds = (total / unit) % 86_400;
+    let fraction = total % unit;
+    let epoch = if date1904 {
+        NaiveDate::from_ymd_opt(1904, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(1899, 12, 30)?
+    };
+    let date = epoch.checked_add_days(Days::new(days as u64))?;
+    let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let twelve_hour = tokens.iter().any(|t| matches!(t, Token::AmPm(_)));
+
+    let mut out = String::new();
+    for token in tokens {
+        match token {
+            Token::Year(n) if *n <= 2 => out.push_str(&format!("{:02}", date.year() % 100)),
+            Token::Year(_) => out.push_str(&format!("{:04}", date.year())),
+            Token::Month(n) => {
+                let month = date.month0() as usize;
+                match n {
+                    1 => out.push_str(&(month + 1).to_string()),
+                    2 => out.push_str(&format!("{:02}", month + 1)),
+                    3 => out.push_str(&MONTHS[month][..3]),
+                    5 => out.push_str(&MONTHS[month][..1]),
+                    _ => out.push_str(MONTHS[month]),
+                }
+            }
+            Token::Day(n) => {
+                let weekday = WEEKDAYS[date.weekday().num_days_from_monday() as usize];
+                match n {
+                    1 => out.push_str(&date.day().to_string()),
+                    2 => out.push_str(&format!("{:02}", date.day())),
+                    3 => out.push_str(&weekday[..3]),
+                    _ => out.push_str(weekday),
+                }
+            }
+            Token::Hour(n) => {
+                let h = if twelve_hour {
+                    (hour + 11) % 12 + 1
+                } else {
+                    hour
+                };
+                out.push_str(&if *n >= 2 {
+                    format!("{:02}", h)
+                } else {
+                    h.to_string()
+                });
+            }
+            Token::Minute(n) => out.push_str(&if *n >= 2 {
+                format!("{:02}", minute)
+            } else {
+                minute.to_string()
+            }),
+            Token::Second(n) => out.push_str(&if *n >= 2 {
+                format!("{:02}", second)
+            } else {
+                second.to_string()
+            }),
+            Token::SubSecond(n) => {
+                let digits = format!("{:0width$}", fraction, width = sub_digits);
+                out.push('.');
+                out.push_str(&digits[..(*n).min(digits.len())]);
+            }
+            Token::AmPm(full) => out.push_str(match (hour < 12, full) {
+                (true, true) => "AM",
+                (false, true) => "PM",
+                (true, false) => "A",
+                (false, false) => "P",
+            }),
+            Token::Elapsed(unit, width) => {
+                let whole_seconds = days * 86_400 + seconds;
+                let amount = match unit {
+                    'h' => whole_seconds / 3600,
+                    'm' => whole_seconds / 60,
+                    _ => whole_seconds,
+                };
+                out.push_str(&format!("{:0width$}", amount, width = *width));
+            }
+            Token::Literal(text) => out.push_str(text),
+            Token::General => out.push_str(&general(value)),
+            _ => {}
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(code: &str, value: f64) -> String {
+        NumberFormat::parse(code).unwrap().format(value, false)
+    }
+
+    #[test]
+    fn test_general_is_none() {
+        assert_eq!(NumberFormat::parse("General"), None);
+        assert_eq!(NumberFormat::for_id(0, None), None);
+        assert_eq!(NumberFormat::for_id(164, None), None);
+    }
+
+    #[test]
+    fn test_decimals_grouping_and_percent() {
+        assert_eq!(fmt("0.0%", 0.153), "15.3%");
+        assert_eq!(fmt("0%", 0.5), "50%");
+        assert_eq!(fmt("#,##0.00", 1234567.891), "1,234,567.89");
+        assert_eq!(fmt("#,##0", 999.5), "1,000");
+        assert_eq!(fmt("0.00", 3.0), "3.00");
+        assert_eq!(fmt("#.##", 0.5), ".5");
+        assert_eq!(fmt("0.0#", 2.5), "2.5");
+        assert_eq!(fmt("000-0000", 5551234.0), "555-1234");
+        assert_eq!(fmt("#,##0,\"K\"", 1_234_000.0), "1,234K");
+        assert_eq!(fmt("0.00", -0.001), "0.00");
+    }
+
+    #[test]
+    fn test_currency_sections_and_literals() {
+        let id7 = NumberFormat::for_id(7, None).unwrap();
+        assert_eq!(id7.format(1234.5, false), "$1,234.50 ");
+        assert_eq!(id7.format(-1234.5, false), "($1,234.50)");
+        assert_eq!(fmt("[$€-407] #,##0.00", 12.0), "€ 12.00");
+        assert_eq!(fmt("#,##0.00 [$€-1]", 12.0), "12.00 €");
+        assert_eq!(fmt("0.00;[Red]-0.00;\"zero\"", 0.0), "zero");
+        assert_eq!(fmt("0.00", -2.0), "-2.00");
+        assert_eq!(fmt("[>=1000]#,##0,\"k\";0", 25_000.0), "25k");
+        assert_eq!(fmt("[>=1000]#,##0,\"k\";0", 250.0), "250");
+        assert_eq!(fmt("\"Total: \"@", 12.0), "Total: 12");
+    }
+
+    #[test]
+    fn test_scientific_and_fractions() {
+        assert_eq!(fmt("0.00E+00", 12345.0), "1.23E+04");
+        assert_eq!(fmt("0.00E+00", 0.00012), "1.20E-04");
+        assert_eq!(fmt("##0.0E+0", 12345.0), "12.3E+3");
+        assert_eq!(fmt("# ?/?", 1.5), "1 1/2");
+        assert_eq!(fmt("# ??/??", 2.3), "2 3/10");
+        // Long denominators are searched with four digits at most.
+        assert_eq!(
+            fmt("# ????????????????????/????????????????????", 0.5),
+            "1/2"
+        );
+        assert_eq!(fmt("# ?/8", 0.25), "2/8");
+        assert_eq!(fmt("?/?", 0.75), "3/4");
+    }
+
+    #[test]
+    fn test_dates_and_times() {
+        // 45000 is 2023-03-15 (a Wednesday); .75 is 18:00.
+        assert!(NumberFormat::parse("yyyy-mm-dd").unwrap().is_date());
+        assert!(!NumberFormat::parse("0.00").unwrap().is_date());
+        assert_eq!(fmt("yyyy-mm-dd", 45000.0), "2023-03-15");
+        assert_eq!(fmt("d-mmm-yy", 45000.0), "15-Mar-23");
+        assert_eq!(
+            fmt("dddd, mmmm d, yyyy", 45000.0),
+            "Wednesday, March 15, 2023"
+        );
+        assert_eq!(fmt("m/d/yyyy h:mm AM/PM", 45000.75), "3/15/2023 6:00 PM");
+        assert_eq!(fmt("hh:mm:ss", 0.5 + 1.0 / 86_400.0), "12:00:01");
+        assert_eq!(fmt("[h]:mm", 1.5), "36:00");
+        assert_eq!(fmt("mm:ss.0", 1.25 / 1440.0), "01:15.0");
+        assert_eq!(fmt("yyyy\\-mm", 45000.0), "2023-03");
+        assert_eq!(
+            NumberFormat::parse("yyyy-mm-dd")
+                .unwrap()
+                .format(45000.0 - 1462.0, true),
+            "2023-03-15"
+        );
+        // Out-of-range serials fall back to the plain number.
+        assert_eq!(fmt("yyyy-mm-dd", -5.0), "-5");
+    }
+
+    #[test]
+    fn test_builtin_ids() {
+        let format = |id| {
+            NumberFormat::for_id(id, None)
+                .unwrap()
+                .format(45000.0, false)
+        };
+        assert_eq!(format(14), "2023-03-15");
+        assert_eq!(format(3), "45,000");
+        assert_eq!(format(10), "4500000.00%");
+        // A custom code overrides the built-in meaning of the id.
+        assert_eq!(
+            NumberFormat::for_id(14, Some("0.0"))
+                .unwrap()
+                .format(2.0, false),
+            "2.0"
+        );
+    }
+}
@@ -0,0 +1,802 @@
+//! Office Math (OMML, the `m:` namespace Word's equation editor writes) to
+//! LaTeX, for the DOCX importer. Covers the constructs Word's equation
+//! gallery produces: fractions, sub/superscripts, radicals, n-ary operators,
+//! matrices, delimiters, accents, bars, group characters, function names,
+//! limits and equation arrays. Anything else falls back to its children's
+//! LaTeX, so unknown wrappers lose structure but not content.
+
+use docx_rs::{EventReader, XmlEvent};
+
+/// A parsed OMML element, keyed by local name (`m:f` → `f`): OMML reuses
+/// WordprocessingML's run/text names (`w:r`, `w:t`) and both are handled
+/// the same way, so the prefix doesn't matter.
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|el| el.name == name)
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(el) => Some(el),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// `m:val` of property `prop` inside the `pr` properties element, e.g.
+    /// `prop_val("naryPr", "chr")`.
+    fn prop_val(&self, pr: &str, prop: &str) -> Option<&str> {
+        self.child(pr)?.child(prop)?.attr("val")
+    }
+
+    /// An OMML on/off property: on when present, unless `m:val` says off.
+    fn prop_on(&self, pr: &str, prop: &str) -> bool {
+        match self.child(pr).and_then(|p| p.child(prop)) {
+            Some(el) => !matches!(el.attr("val"), Some("0" | "off" | "false")),
+            None => false,
+        }
+    }
+
+    fn text(&self) -> String {
+        let mut out = String::new();
+        for node in &self.children {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Element(el) => out.push_str(&el.text()),
+            }
+        }
+        out
+    }
+}
+
+fn parse(xml: &str) -> Option<Element> {
+    let mut parser = EventReader::new(xml.as_bytes());
+    let mut stack = vec![Element {
+        name: String::new(),
+        attrs: Vec::new(),
+        children: Vec::new(),
+    }];
+    loop {
+        match parser.next() {
+            Ok(XmlEvent::StartElement {
+                name, attributes, ..
+            }) => stack.push(Element {
+                name: name.local_name,
+                attrs: attributes
+                    .into_iter()
+                    .map(|a| (a.name.local_name, a.value))
+                    .collect(),
+                children: Vec::new(),
+            }),
+            Ok(XmlEvent::EndElement { .. }) => {
+                let el = stack.pop()?;
+                stack.last_mut()?.children.push(Node::Element(el));
+            }
+            Ok(XmlEvent::Characters(text)) => {
+                stack.last_mut()?.children.push(Node::Text(text));
+            }
+            // Whitespace only matters inside a text element (`xml:space`).
+            Ok(XmlEvent::Whitespace(text)) => {
+                let parent = stack.last_mut()?;
+                if parent.name == "t" {
+                    parent.children.push(Node::Text(text));
+                }
+            }
+            Ok(XmlEvent::EndDocument) => break,
+            Err(_) => return None,
+        }
+    }
+    let mut root = stack.pop()?;
+    match root.children.pop()? {
+        Node::Element(el) => Some(el),
+        Node::Text(_) => None,
+    }
+}
+
+/// Translate one `m:oMath` or `m:oMathPara` element (its full XML, as cut
+/// from `word/document.xml`) to LaTeX, without `$` delimiters. The
+/// equations of a multi-line `m:oMathPara` are joined with `\\`.
+pub fn omml_to_latex(xml: &str) -> Option<String> {
+    let root = parse(xml)?;
+    let latex = Translator::default().convert(&root);
+    Some(latex.trim().to_string())
+}
+
+#[derive(Default)]
+struct Translator {
+    /// Inside an `m:eqArr`, where `&` in the text marks alignment points
+    /// rather than a literal ampersand.
+    in_eq_arr: bool,
+}
+
+impl Translator {
+    fn convert(&mut self, el: &Element) -> String {
+        match el.name.as_str() {
+            "oMathPara" => el
+                .elements()
+                .filter(|e| e.name == "oMath")
+                .map(|e| self.convert(e).trim().to_string())
+                .collect::<Vec<_>>()
+                .join(" \\\\ "),
+            "r" => self.run(el),
+            "f" => self.fraction(el),
+            "sSub" => format!("{}_{{{}}}", self.base(el), self.part(el, "sub")),
+            "sSup" => format!("{}^{{{}}}", self.base(el), self.part(el, "sup")),
+            "sSubSup" => format!(
+                "{}_{{{}}}^{{{}}}",
+                self.base(el),
+                self.part(el, "sub"),
+                self.part(el, "sup")
+            ),
+            "sPre" => format!(
+                "{{}}_{{{}}}^{{{}}}{}",
+                self.part(el, "sub"),
+                self.part(el, "sup"),
+                self.base(el)
+            ),
+            "rad" => self.radical(el),
+            "nary" => self.nary(el),
+            "d" => self.delimiter(el),
+            "m" => format!("\\begin{{matrix}}{}\\end{{matrix}}", self.matrix_rows(el)),
+            "eqArr" => self.eq_arr(el),
+            "acc" => self.accent(el),
+            "bar" => {
+                let command = match el.prop_val("barPr", "pos") {
+                    Some("top") => "\\overline",
+                    _ => "\\underline",
+                };
+                format!("{}{{{}}}", command, self.part(el, "e"))
+            }
+            "groupChr" => self.group_chr(el),
+            "borderBox" => format!("\\boxed{{{}}}", self.part(el, "e")),
+            "func" => self.func(el),
+            "limLow" => self.limit(el, "_", "\\underset"),
+            "limUpp" => self.limit(el, "^", "\\overset"),
+            "phant" if el.prop_on("phantPr", "show") || el.child("phantPr").is_none() => {
+                self.part(el, "e")
+            }
+            "phant" => String::new(),
+            // Formatting/properties carry no content.
+            name if name.ends_with("Pr") => String::new(),
+            // `m:oMath`, `m:e`, `m:num`, `m:box`, ... and anything unknown.
+            _ => self.concat(el),
+        }
+    }
+
+    /// The children's LaTeX run together, with a space wherever a control
+    /// word (`\alpha`) would otherwise run into a following letter.
+    fn concat(&mut self, el: &Element) -> String {
+        let mut out = String::new();
+        for child in el.elements() {
+            let piece = self.convert(child);
+            push_latex(&mut out, &piece);
+        }
+        out
+    }
+
+    /// The LaTeX of child `name`, or nothing if it's absent.
+    fn part(&mut self, el: &Element, name: &str) -> String {
+        el.child(name).map(|c| self.concat(c)).unwrap_or_default()
+    }
+
+    /// The base (`m:e`) of a script, braced unless it's a single symbol so
+    /// `{ab}_{i}` subscripts the whole base.
+    fn base(&mut self, el: &Element) -> String {
+        let base = self.part(el, "e");
+        if is_atom(&base) {
+            base
+        } else {
+            format!("{{{}}}", base)
+        }
+    }
+
+    fn run(&mut self, el: &Element) -> String {
+        let text: String = el
+            .elements()
+            .filter(|c| c.name == "t")
+            .map(|c| c.text())
+            .collect();
+        if text.is_empty() {
+            return String::new();
+        }
+        // `m:nor` is normal (non-math) text; `m:sty="p"` is upright math,
+        // which Word uses for multi-letter words like function names.
+        if el.prop_on("rPr", "nor") {
+            return format!("\\text{{{}}}", escape_text(&text));
+        }
+        let upright = el.prop_val("rPr", "sty") == Some("p");
+        if upright && text.chars().count() > 1 && text.chars().all(|c| c.is_ascii_alphabetic()) {
+            return if FUNCTION_NAMES.contains(&text.as_str()) {
+                format!("\\{}", text)
+            } else {
+                format!("\\mathrm{{{}}}", text)
+            };
+        }
+        let mut out = String::new();
+        for c in text.chars() {
+            match c {
+                '&' if self.in_eq_arr => out.push('&'),
+                _ => push_latex(&mut out, &latex_char(c)),
+            }
+        }
+        out
+    }
+
+    fn fraction(&mut self, el: &Element) -> String {
+        let num = self.part(el, "num");
+        let den = self.part(el, "den");
+        match el.prop_val("fPr", "type") {
+            Some("lin") | Some("skw") => format!("{{{}}}/{{{}}}", num, den),
+            Some("noBar") => format!("\\genfrac{{}}{{}}{{0pt}}{{}}{{{}}}{{{}}}", num, den),
+            _ => format!("\\frac{{{}}}{{{}}}", num, den),
+        }
+    }
+
+    fn radical(&mut self, el: &Element) -> String {
+        let deg = self.part(el, "deg");
+        let body = self.part(el, "e");
+        if el.prop_on("radPr", "degHide") || deg.is_empty() {
+            format!("\\sqrt{{{}}}", body)
+        } else {
+            format!("\\sqrt[{}]{{{}}}", deg, body)
+        }
+    }
+
+    fn nary(&mut self, el: &Element) -> String {
+        // An n-ary without `m:chr` is an integral.
+        let chr = el
+            .prop_val("naryPr", "chr")
+            .and_then(|v| v.chars().next())
+            .unwrap_or('∫');
+        let mut out = nary_command(chr)
+            .map(str::to_string)
+            .unwrap_or_else(|| latex_char(chr));
+        if !el.prop_on("naryPr", "subHide") {
+            let sub = self.part(el, "sub");
+            if !sub.is_empty() {
+                out.push_str(&format!("_{{{}}}", sub));
+            }
+        }
+        if !el.prop_on("naryPr", "supHide") {
+            let sup = self.part(el, "sup");
+            if !sup.is_empty() {
+                out.push_str(&format!("^{{{}}}", sup));
+            }
+        }
+        let body = self.part(el, "e");
+        push_operand(&mut out, &body);
+        out
+    }
+
+    fn delimiter(&mut self, el: &Element) -> String {
+        // `m:begChr`/`m:endChr` default to parentheses and `m:sepChr` to a
+        // bar; an empty value means no delimiter on that side.
+        let beg = el.prop_val("dPr", "begChr").unwrap_or("(");
+        let end = el.prop_val("dPr", "endChr").unwrap_or(")");
+        let sep = el.prop_val("dPr", "sepChr").unwrap_or("|");
+
+        let items: Vec<&Element> = el.elements().filter(|c| c.name == "e").collect();
+
+        // A matrix alone in brackets is one of LaTeX's bracketed matrices.
+        if let [item] = items.as_slice() {
+            let mut inner = item.elements().filter(|c| !c.name.ends_with("Pr"));
+            if let (Some(matrix), None) = (inner.next(), inner.next()) {
+                if matrix.name == "m" {
+                    if let Some(env) = matrix_environment(beg, end) {
+                        let rows = self.matrix_rows(matrix);
+                        return format!("\\begin{{{env}}}{rows}\\end{{{env}}}");
+                    }
+                }
+            }
+        }
+
+        let separator = format!(" {} ", delimiter_latex(sep, "|"));
+        let body = items
+            .into_iter()
+            .map(|item| self.concat(item))
+            .collect::<Vec<_>>()
+            .join(separator.as_str());
+        format!(
+            "\\left{}{}\\right{}",
+            delimiter_latex(beg, "."),
+            body,
+            delimiter_latex(end, ".")
+        )
+    }
+
+    fn matrix_rows(&mut self, el: &Element) -> String {
+        el.elements()
+            .filter(|row| row.name == "mr")
+            .map(|row| {
+                row.elements()
+                    .filter(|cell| cell.name == "e")
+                    .map(|cell| self.concat(cell))
+                    .collect::<Vec<_>>()
+                    .join(" & ")
+            })
+            .collect::<Vec<_>>()
+            .join(" \\\\ ")
+    }
+
+    fn eq_arr(&mut self, el: &Element) -> String {
+        let outer = std::mem::replace(&mut self.in_eq_arr, true);
+        let rows = el
+            .elements()
+            .filter(|row| row.name == "e")
+            .map(|row| self.concat(row))
+            .collect::<Vec<_>>()
+            .join(" \\\\ ");
+        self.in_eq_arr = outer;
+        format!("\\begin{{aligned}}{}\\end{{aligned}}", rows)
+    }
+
+    fn accent(&mut self, el: &Element) -> String {
+        // `m:chr` defaults to a circumflex (hat).
+        let chr = el
+            .prop_val("accPr", "chr")
+            .and_then(|v| v.chars().next())
+            .unwrap_or('\u{0302}');
+        let command = match chr {
+            '\u{0300}' => "\\grave",
+            '\u{0301}' => "\\acute",
+            '\u{0303}' | '~' => "\\tilde",
+            '\u{0304}' | '\u{0305}' | '¯' => "\\bar",
+            '\u{0306}' => "\\breve",
+            '\u{0307}' | '˙' => "\\dot",
+            '\u{0308}' | '¨' => "\\ddot",
+            '\u{030C}' => "\\check",
+            '\u{20D6}' => "\\overleftarrow",
+            '\u{20D7}' | '→' => "\\vec",
+            '\u{20E1}' => "\\overleftrightarrow",
+            _ => "\\hat",
+        };
+        format!("{}{{{}}}", command, self.part(el, "e"))
+    }
+
+    fn group_chr(&mut self, el: &Element) -> String {
+        // Defaults to an under-brace.
+        let chr = el
+            .prop_val("groupChrPr", "chr")
+            .and_then(|v| v.chars().next())
+            .unwrap_or('⏟');
+        let top = el.prop_val("groupChrPr", "pos") == Some("top");
+        let body = self.part(el, "e");
+        match chr {
+            '⏞' => format!("\\overbrace{{{}}}", body),
+            '⏟' => format!("\\underbrace{{{}}}", body),
+            _ => {
+                let symbol = latex_char(chr);
+                if top {
+                    format!("\\overset{{{}}}{{{}}}", symbol, body)
+                } else {
+                    format!("\\underset{{{}}}{{{}}}", symbol, body)
+                }
+            }
+        }
+    }
+
+    fn func(&mut self, el: &Element) -> String {
+        let name = self.part(el, "fName");
+        let mut out = if name.starts_with('\\') || name.is_empty() {
+            name
+        } else {
+            format!("\\operatorname{{{}}}", name)
+        };
+        let arg = self.part(el, "e");
+        push_operand(&mut out, &arg);
+        out
+    }
+
+    /// `m:limLow`/`m:limUpp`: a subscript/superscript limit on an operator
+    /// name (`\lim_{n \to \infty}`), or a stacked expression otherwise.
+    fn limit(&mut self, el: &Element, script: &str, stack: &str) -> String {
+        let base = self.part(el, "e");
+        let lim = self.part(el, "lim");
+        let operator = base
+            .strip_prefix('\\')
+            .is_some_and(|name| FUNCTION_NAMES.contains(&name));
+        if operator {
+            format!("{}{}{{{}}}", base, script, lim)
+        } else {
+            format!("{}{{{}}}{{{}}}", stack, lim, base)
+        }
+    }
+}
+
+/// Operator names LaTeX has a command for (`\sin`, `\lim`, ...).
+const FUNCTION_NAMES: &[&str] = &[
+    "arccos", "arcsin", "arctan", "arg", "cos", "cosh", "cot", "coth", "csc", "deg", "det", "dim",
+    "exp", "gcd", "hom", "inf", "ker", "lg", "lim", "liminf", "limsup", "ln", "log", "max", "min",
+    "Pr", "sec", "sin", "sinh", "sup", "tan", "tanh",
+];
+
+/// Append `piece` to `out`, separating a trailing control word from a
+/// leading letter (`\alpha` + `x` → `\alpha x`).
+fn push_latex(out: &mut String, piece: &str) {
+    let starts_with_letter = piece
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic());
+    if starts_with_letter {
+        let word_start = out
+            .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+            .len();
+        if word_start < out.len() && out[..word_start].ends_with('\\') {
+            out.push(' ');
+        }
+    }
+    out.push_str(piece);
+}
+
+/// Append the operand of an operator (`\sum_{i}`, `\sin`) set off by a
+/// space, which keeps the source readable and ends any control word.
+fn push_operand(out: &mut String, operand: &str) {
+    if !out.is_empty() && !operand.is_empty() {
+        out.push(' ');
+    }
+    out.push_str(operand);
+}
+
+/// A single symbol or control word, which needs no braces as a script base.
+fn is_atom(latex: &str) -> bool {
+    let mut chars = latex.chars();
+    match chars.next() {
+        Some('\\') => {
+            let rest = &latex[1..];
+            !rest.is_empty() && rest.chars().all(|c| c.is_ascii_alphabetic())
+        }
+        Some(_) => chars.next().is_none(),
+        None => true,
+    }
+}
+
+fn escape_text(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '{' | '}' | '#' | '$' | '%' | '&' | '_' => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// LaTeX for one character of math text: Greek letters and operators
+/// become commands, LaTeX's special characters are escaped, everything
+/// else is kept as-is.
+fn latex_char(c: char) -> String {
+    let command = match c {
+        '{' => "\\{",
+        '}' => "\\}",
+        '#' => "\\#",
+        '$' => "\\$",
+        '%' => "\\%",
+        '&' => "\\&",
+        '_' => "\\_",
+        '\\' => "\\backslash",
+        '~' => "\\sim",
+        '^' => "\\hat{}",
+        '−' => "-",
+        'α' => "\\alpha",
+        'β' => "\\beta",
+        'γ' => "\\gamma",
+        'δ' => "\\delta",
+        'ε' => "\\varepsilon",
+        'ϵ' => "\\epsilon",
+        'ζ' => "\\zeta",
+        'η' => "\\eta",
+        'θ' => "\\theta",
+        'ϑ' => "\\vartheta",
+        'ι' => "\\iota",
+        'κ' => "\\kappa",
+        'λ' => "\\lambda",
+        'μ' => "\\mu",
+        'ν' => "\\nu",
+        'ξ' => "\\xi",
+        'π' => "\\pi",
+        'ϖ' => "\\varpi",
+        'ρ' => "\\rho",
+        'ϱ' => "\\varrho",
+        'σ' => "\\sigma",
+        'ς' => "\\varsigma",
+        'τ' => "\\tau",
+        'υ' => "\\upsilon",
+        'φ' => "\\varphi",
+        'ϕ' => "\\phi",
+        'χ' => "\\chi",
+        'ψ' => "\\psi",
+        'ω' => "\\omega",
+        'Γ' => "\\Gamma",
+        'Δ' => "\\Delta",
+        'Θ' => "\\Theta",
+        'Λ' => "\\Lambda",
+        'Ξ' => "\\Xi",
+        'Π' => "\\Pi",
+        'Σ' => "\\Sigma",
+        'Υ' => "\\Upsilon",
+        'Φ' => "\\Phi",
+        'Ψ' => "\\Psi",
+        'Ω' => "\\Omega",
+        '±' => "\\pm",
+        '∓' => "\\mp",
+        '×' => "\\times",
+        '÷' => "\\div",
+        '·' | '⋅' => "\\cdot",
+        '∗' => "\\ast",
+        '∘' => "\\circ",
+        '≤' => "\\leq",
+        '≥' => "\\geq",
+        '≪' => "\\ll",
+        '≫' => "\\gg",
+        '≠' => "\\neq",
+        '≈' => "\\approx",
+        '≡' => "\\equiv",
+        '∼' => "\\sim",
+        '≃' => "\\simeq",
+        '≅' => "\\cong",
+        '∝' => "\\propto",
+        '∞' => "\\infty",
+        '∂' => "\\partial",
+        '∇' => "\\nabla",
+        '∈' => "\\in",
+        '∉' => "\\notin",
+        '∋' => "\\ni",
+        '⊂' => "\\subset",
+        '⊃' => "\\supset",
+        '⊆' => "\\subseteq",
+        '⊇' => "\\supseteq",
+        '∪' => "\\cup",
+        '∩' => "\\cap",
+        '∅' => "\\emptyset",
+        '∀' => "\\forall",
+        '∃' => "\\exists",
+        '¬' => "\\neg",
+        '∧' => "\\wedge",
+        '∨' => "\\vee",
+        '⊕' => "\\oplus",
+        '⊗' => "\\otimes",
+        '⊥' => "\\perp",
+        '∥' => "\\parallel",
+        '∠' => "\\angle",
+        '→' => "\\to",
+        '←' => "\\leftarrow",
+        '↔' => "\\leftrightarrow",
+        '⇒' => "\\Rightarrow",
+        '⇐' => "\\Leftarrow",
+        '⇔' => "\\Leftrightarrow",
+        '↦' => "\\mapsto",
+        '…' => "\\ldots",
+        '⋯' => "\\cdots",
+        '⋮' => "\\vdots",
+        '⋱' => "\\ddots",
+        '′' => "'",
+        '″' => "''",
+        '°' => "^{\\circ}",
+        'ℏ' => "\\hbar",
+        'ℓ' => "\\ell",
+        'ℝ' => "\\mathbb{R}",
+        'ℕ' => "\\mathbb{N}",
+        'ℤ' => "\\mathbb{Z}",
+        'ℚ' => "\\mathbb{Q}",
+        'ℂ' => "\\mathbb{C}",
+        '√' => "\\surd",
+        _ => return c.to_string(),
+    };
+    command.to_string()
+}
+
+fn nary_command(chr: char) -> Option<&'static str> {
+    Some(match chr {
+        '∑' => "\\sum",
+        '∏' => "\\prod",
+        '∐' => "\\coprod",
+        '∫' => "\\int",
+        '∬' => "\\iint",
+        '∭' => "\\iiint",
+        '∮' => "\\oint",
+        '⋃' => "\\bigcup",
+        '⋂' => "\\bigcap",
+        '⋁' => "\\bigvee",
+        '⋀' => "\\bigwedge",
+        '⨁' => "\\bigoplus",
+        '⨂' => "\\bigotimes",
+        _ => return None,
+    })
+}
+
+/// `\left`/`\right` form of a delimiter character; `empty` (`.` for an
+/// invisible delimiter) when the value is empty.
+fn delimiter_latex(chr: &str, empty: &str) -> String {
+    match chr {
+        "" => empty.to_string(),
+        "{" => "\\{".to_string(),
+        "}" => "\\}".to_string(),
+        "⟨" | "〈" => "\\langle".to_string(),
+        "⟩" | "〉" => "\\rangle".to_string(),
+        "‖" => "\\|".to_string(),
+        "⌊" => "\\lfloor".to_string(),
+        "⌋" => "\\rfloor".to_string(),
+        "⌈" => "\\lceil".to_string(),
+        "⌉" => "\\rceil".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn matrix_environment(beg: &str, end: &str) -> Option<&'static str> {
+    Some(match (beg, end) {
+        ("(", ")") => "pmatrix",
+        ("[", "]") => "bmatrix",
+        ("{", "}") => "Bmatrix",
+        ("|", "|") => "vmatrix",
+        ("‖", "‖") => "Vmatrix",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn latex(inner: &str) -> String {
+        omml_to_latex(&format!(
+            r#"<m:oMath xmlns:m="http://schemas.openxmlformats.org/officeDocument/2006/math">{}</m:oMath>"#,
+            inner
+        ))
+        .expect("valid OMML")
+    }
+
+    fn r(text: &str) -> String {
+        format!("<m:r><m:t>{}</m:t></m:r>", text)
+    }
+
+    #[test]
+    fn test_fraction_and_scripts() {
+        let xml = format!(
+            "<m:f><m:num>{}</m:num><m:den><m:sSup><m:e>{}</m:e><m:sup>{}</m:sup></m:sSup></m:den></m:f>",
+            r("a+b"),
+            r("x"),
+            r("2")
+        );
+        assert_eq!(latex(&xml), "\\frac{a+b}{x^{2}}");
+
+        let xml = format!(
+            "<m:sSubSup><m:e>{}</m:e><m:sub>{}</m:sub><m:sup>{}</m:sup></m:sSubSup>",
+            r("ab"),
+            r("i"),
+            r("2")
+        );
+        assert_eq!(latex(&xml), "{ab}_{i}^{2}");
+    }
+
+    #[test]
+    fn test_radical_with_and_without_degree() {
+        let xml = format!(
+            "<m:rad><m:radPr><m:degHide m:val=\"1\"/></m:radPr><m:deg/><m:e>{}</m:e></m:rad>",
+            r("x")
+        );
+        assert_eq!(latex(&xml), "\\sqrt{x}");
+        let xml = format!(
+            "<m:rad><m:deg>{}</m:deg><m:e>{}</m:e></m:rad>",
+            r("3"),
+            r("y")
+        );
+        assert_eq!(latex(&xml), "\\sqrt[3]{y}");
+    }
+
+    #[test]
+    fn test_nary_sum_and_default_integral() {
+        let xml = format!(
+            "<m:nary><m:naryPr><m:chr m:val=\"∑\"/></m:naryPr><m:sub>{}</m:sub><m:sup>{}</m:sup><m:e>{}</m:e></m:nary>",
+            r("i=1"),
+            r("n"),
+            r("x")
+        );
+        assert_eq!(latex(&xml), "\\sum_{i=1}^{n} x");
+        let xml = format!(
+            "<m:nary><m:naryPr><m:subHide m:val=\"1\"/><m:supHide m:val=\"1\"/></m:naryPr><m:sub/><m:sup/><m:e>{}</m:e></m:nary>",
+            r("f dx")
+        );
+        assert_eq!(latex(&xml), "\\int f dx");
+    }
+
+    #[test]
+    fn test_delimiters_and_matrices() {
+        let xml = format!("<m:d><m:e>{}</m:e><m:e>{}</m:e></m:d>", r("a"), r("b"));
+        assert_eq!(latex(&xml), "\\left(a | b\\right)");
+
+        let xml = format!(
+            "<m:d><m:dPr><m:begChr m:val=\"{{\"/><m:endChr m:val=\"\"/></m:dPr><m:e>{}</m:e></m:d>",
+            r("x")
+        );
+        assert_eq!(latex(&xml), "\\left\\{x\\right.");
+
+        let matrix = format!(
+            "<m:m><m:mr><m:e>{}</m:e><m:e>{}</m:e></m:mr><m:mr><m:e>{}</m:e><m:e>{}</m:e></m:mr></m:m>",
+            r("1"),
+            r("0"),
+            r("0"),
+            r("1")
+        );
+        let xml = format!(
+            "<m:d><m:dPr><m:begChr m:val=\"[\"/><m:endChr m:val=\"]\"/></m:dPr><m:e>{}</m:e></m:d>",
+            matrix
+        );
+        assert_eq!(
+            latex(&xml),
+            "\\begin{bmatrix}1 & 0 \\\\ 0 & 1\\end{bmatrix}"
+        );
+        assert_eq!(
+            latex(&matrix),
+            "\\begin{matrix}1 & 0 \\\\ 0 & 1\\end{matrix}"
+        );
+    }
+
+    #[test]
+    fn test_accents_and_bars() {
+        let xml = format!(
+            "<m:acc><m:accPr><m:chr m:val=\"\u{20D7}\"/></m:accPr><m:e>{}</m:e></m:acc>",
+            r("v")
+        );
+        assert_eq!(latex(&xml), "\\vec{v}");
+        let xml = format!("<m:acc><m:e>{}</m:e></m:acc>", r("x"));
+        assert_eq!(latex(&xml), "\\hat{x}");
+        let xml = format!(
+            "<m:bar><m:barPr><m:pos m:val=\"top\"/></m:barPr><m:e>{}</m:e></m:bar>",
+            r("z")
+        );
+        assert_eq!(latex(&xml), "\\overline{z}");
+    }
+
+    #[test]
+    fn test_functions_limits_and_symbols() {
+        let sin = "<m:r><m:rPr><m:sty m:val=\"p\"/></m:rPr><m:t>sin</m:t></m:r>";
+        let xml = format!(
+            "<m:func><m:fName>{}</m:fName><m:e>{}</m:e></m:func>",
+            sin,
+            r("θ")
+        );
+        assert_eq!(latex(&xml), "\\sin \\theta");
+
+        let lim = "<m:r><m:rPr><m:sty m:val=\"p\"/></m:rPr><m:t>lim</m:t></m:r>";
+        let xml = format!(
+            "<m:func><m:fName><m:limLow><m:e>{}</m:e><m:lim>{}</m:lim></m:limLow></m:fName><m:e>{}</m:e></m:func>",
+            lim,
+            r("n→∞"),
+            r("a")
+        );
+        assert_eq!(latex(&xml), "\\lim_{n\\to\\infty} a");
+
+        assert_eq!(latex(&r("α≤2%")), "\\alpha\\leq2\\%");
+    }
+
+    #[test]
+    fn test_math_paragraph_joins_equations() {
+        let xml = format!(
+            r#"<m:oMathPara xmlns:m="http://schemas.openxmlformats.org/officeDocument/2006/math"><m:oMathParaPr/><m:oMath>{}</m:oMath><m:oMath>{}</m:oMath></m:oMathPara>"#,
+            r("a=1"),
+            r("b=2")
+        );
+        assert_eq!(omml_to_latex(&xml).unwrap(), "a=1 \\\\ b=2");
+    }
+}
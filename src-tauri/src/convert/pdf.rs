@@ -1,11 +1,14 @@
 use markdown2pdf::config::ConfigSource;
 use pdfium_render::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 
 use super::media::MediaSink;
-use super::ConversionError;
+use super::progress::ImportProgress;
+use super::report::{ImportReport, Location, WarningKind};
+use super::{ConversionError, Converter, Exporter, ImportOptions};
 
 // Guards the one-time initialization of the global pdfium bindings.
 static PDFIUM_INIT: Mutex<bool> = Mutex::new(false);
@@ -30,6 +33,86 @@ pub fn set_pdfium_lib_path(path: PathBuf) {
     let _ = PDFIUM_RESOLVED_PATH.set(path);
 }
 
+/// PDF importer registered in [`super::FormatRegistry`].
+pub struct PdfConverter;
+
+impl Converter for PdfConverter {
+    fn id(&self) -> &'static str {
+        "pdf"
+    }
+    fn name(&self) -> &'static str {
+        "PDF Document"
+    }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pdf"]
+    }
+    fn mime_types(&self) -> &'static [&'static str] {
+        &["application/pdf"]
+    }
+    fn to_markdown(
+        &self,
+        path: &str,
+        media: &mut MediaSink,
+        options: &ImportOptions,
+        report: &mut ImportReport,
+        progress: &ImportProgress,
+    ) -> Result<String, ConversionError> {
+        pdf_to_markdown(path, media, &options.pdf, report, progress)
+    }
+}
+
+/// PDF knobs in [`ImportOptions`]. The defaults are the constants the layout
+/// heuristics below were tuned against; see each constant's doc comment for
+/// what loosening or tightening it trades off.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfOptions {
+    /// Minimum line font size, as a multiple of the document body size, for
+    /// a `#` / `##` / `###` heading (see [`HEADING_FONT_RATIOS`]).
+    pub heading_font_ratios: [f32; 3],
+    /// Consecutive column-aligned rows needed to accept a borderless table
+    /// (see [`MIN_CORE_ROWS`]).
+    pub min_table_rows: usize,
+    /// Fraction of page height, top and bottom, scanned for running
+    /// headers/footers (see [`HF_BAND_FRACTION`]).
+    pub header_footer_band: f32,
+    /// Pages a margin line or image must recur on before it's removed as a
+    /// running header/footer (see [`HF_MIN_PAGES`]).
+    pub header_footer_min_pages: usize,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        PdfOptions {
+            heading_font_ratios: HEADING_FONT_RATIOS,
+            min_table_rows: MIN_CORE_ROWS,
+            header_footer_band: HF_BAND_FRACTION,
+            header_footer_min_pages: HF_MIN_PAGES,
+        }
+    }
+}
+
+/// PDF exporter registered in [`super::FormatRegistry`].
+pub struct PdfExporter;
+
+impl Exporter for PdfExporter {
+    fn id(&self) -> &'static str {
+        "pdf"
+    }
+    fn name(&self) -> &'static str {
+        "PDF Document"
+    }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pdf"]
+    }
+    fn mime_types(&self) -> &'static [&'static str] {
+        &["application/pdf"]
+    }
+    fn export(&self, markdown: &str, path: &str) -> Result<(), ConversionError> {
+        markdown_to_pdf(markdown, path)
+    }
+}
+
 /// Convert Markdown to a PDF file.
 pub fn markdown_to_pdf(markdown: &str, path: &str) -> Result<(), ConversionError> {
     markdown2pdf::parse_into_file(markdown.to_string(), path, ConfigSource::Default, None)
@@ -37,7 +120,13 @@ pub fn markdown_to_pdf(markdown: &str, path: &str) -> Result<(), ConversionError
 }
 
 /// Convert a PDF file to Markdown using layout-aware extraction.
-pub fn pdf_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, ConversionError> {
+pub fn pdf_to_markdown(
+    path: &str,
+    media: &mut MediaSink,
+    options: &PdfOptions,
+    report: &mut ImportReport,
+    progress: &ImportProgress,
+) -> Result<String, ConversionError> {
     // Initialize pdfium bindings exactly once per process
     {
         let mut initialized = PDFIUM_INIT
@@ -59,21 +148,48 @@ pub fn pdf_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, Conv
         .load_pdf_from_file(path, None)
         .map_err(|e| ConversionError(format!("Failed to open PDF: {}", e)))?;
 
+    // Progress counts each page twice, once per pass below.
+    let total = doc.pages().len() as usize * 2;
+
     // Pass 1: extract every page's blocks up front, with no rendering yet, so
     // running headers/footers can be detected by looking across all pages
     // before any single page is rendered.
     let mut pages: Vec<PageContent> = Vec::new();
-    for page in doc.pages().iter() {
+    for (index, page) in doc.pages().iter().enumerate() {
+        progress.step(index, total)?;
+        let location = Location::Page { number: index + 1 };
+        let blocks = extract_page_blocks(&page, media, report, &location)?;
+        // Image-only pages are almost always scans: pdfium can't OCR, so
+        // their text is silently missing from the Markdown.
+        if blocks.iter().any(|b| b.is_image) && blocks.iter().all(|b| b.is_image) {
+            report.warn(
+                WarningKind::NoTextLayer,
+                Some(location),
+                "Page has images but no extractable text (scanned?)",
+            );
+        }
         pages.push(PageContent {
-            blocks: extract_page_blocks(&page, media)?,
+            blocks,
             h_rules: collect_horizontal_rules(&page),
             v_rules: collect_vertical_rules(&page),
             height: page.height().value,
         });
     }
-
-    let hf_keys = detect_running_headers_footers(&pages);
-    let repeated_images = detect_repeated_images(&pages);
+    report.stats.pages = Some(pages.len());
+
+    let hf_keys = detect_running_headers_footers(&pages, options);
+    let repeated_images = detect_repeated_images(&pages, options.header_footer_min_pages);
+    if !hf_keys.is_empty() || !repeated_images.is_empty() {
+        report.warn(
+            WarningKind::HeadersFootersRemoved,
+            None,
+            format!(
+                "Removed {} repeated header/footer lines and {} repeated images",
+                hf_keys.len(),
+                repeated_images.len()
+            ),
+        );
+    }
     // A document-wide, length-weighted body font size, used only for heading
     // classification (see `document_body_size`) — more robust than any single
     // page's median, which a reference-/equation-/caption-heavy page can drag
@@ -84,12 +200,26 @@ pub fn pdf_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, Conv
     // Pass 2: render each page, dropping any blocks identified as a repeated
     // running header/footer (text or image).
     let mut md = String::new();
-    for page in &pages {
-        let kept = filter_header_footer_blocks(&page.blocks, page.height, &hf_keys);
+    for (index, page) in pages.iter().enumerate() {
+        progress.step(pages.len() + index, total)?;
+        let kept = filter_header_footer_blocks(
+            &page.blocks,
+            page.height,
+            &hf_keys,
+            options.header_footer_band,
+        );
         let kept = filter_repeated_images(&kept, &repeated_images);
-        md.push_str(&render_page_blocks(&kept, &page.h_rules, &page.v_rules, heading_body_size));
+        md.push_str(&render_page_blocks(
+            &kept,
+            &page.h_rules,
+            &page.v_rules,
+            heading_body_size,
+            options,
+            report,
+        ));
         md.push('\n');
     }
+    progress.step(total, total)?;
 
     Ok(md)
 }
@@ -350,9 +480,11 @@ fn is_duplicated_halves(text: &str) -> bool {
 /// subsection heading, whose own letter-dot label sits at index 0
 /// (`"A. RUZE LENS"`, `"I. INTRODUCTION"`) — those are left alone.
 fn looks_like_author_byline(text: &str) -> bool {
-    text.split_whitespace()
-        .skip(1)
-        .any(|tok| tok.len() == 2 && tok.ends_with('.') && tok.chars().next().is_some_and(|c| c.is_ascii_uppercase()))
+    text.split_whitespace().skip(1).any(|tok| {
+        tok.len() == 2
+            && tok.ends_with('.')
+            && tok.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+    })
 }
 
 #[derive(Clone)]
@@ -431,7 +563,10 @@ fn segment_line_into_cells(blocks: &[TextBlock], line: &[usize], gap_thresh: f32
 /// left edge lines up with the corresponding column within `tol`.
 fn columns_match(row: &[Cell], columns: &[f32], tol: f32) -> bool {
     row.len() == columns.len()
-        && row.iter().zip(columns).all(|(c, &x)| (c.x_start - x).abs() <= tol)
+        && row
+            .iter()
+            .zip(columns)
+            .all(|(c, &x)| (c.x_start - x).abs() <= tol)
 }
 
 /// Attempts to interpret `row` as a wrapped continuation of the previous
@@ -530,11 +665,16 @@ const CAPTION_LABEL_PREFIXES: &[&str] = &["Figure", "Fig.", "Fig", "Table", "表
 /// Case-insensitive: confirmed against a real IEEE-style paper, which
 /// typesets caption labels in ALL CAPS ("FIGURE 1.", "TABLE 1.") rather than
 /// the Title Case used elsewhere ("Figure 1:").
-fn is_caption_label(text: &str) -> bool {
+pub(crate) fn is_caption_label(text: &str) -> bool {
     let upper = text.trim_start().to_ascii_uppercase();
     for &prefix in CAPTION_LABEL_PREFIXES {
         if let Some(rest) = upper.strip_prefix(&prefix.to_ascii_uppercase()) {
-            if rest.trim_start().chars().next().is_some_and(|c| c.is_ascii_digit()) {
+            if rest
+                .trim_start()
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_digit())
+            {
                 return true;
             }
         }
@@ -546,7 +686,7 @@ fn is_caption_label(text: &str) -> bool {
 /// otherwise prematurely close the `![…]` span, so they're swapped for
 /// parens rather than backslash-escaped (simpler, and captions containing a
 /// literal bracket are rare enough that the fidelity loss is negligible).
-fn escape_alt_text(text: &str) -> String {
+pub(crate) fn escape_alt_text(text: &str) -> String {
     text.replace('[', "(").replace(']', ")")
 }
 
@@ -568,7 +708,11 @@ fn with_caption_alt(image_md: &str, line_texts: &[String], i: usize) -> String {
         return image_md.to_string();
     }
     let next = line_texts.get(i + 1).map(|s| s.trim());
-    let prev = if i > 0 { line_texts.get(i - 1).map(|s| s.trim()) } else { None };
+    let prev = if i > 0 {
+        line_texts.get(i - 1).map(|s| s.trim())
+    } else {
+        None
+    };
     let caption = next
         .filter(|s| is_caption_label(s))
         .or_else(|| prev.filter(|s| is_caption_label(s)));
@@ -717,7 +861,12 @@ fn line_math_ratio(blocks: &[TextBlock], line: &[usize]) -> f32 {
         if block.is_image {
             continue;
         }
-        let n = block.text.trim().chars().filter(|c| !c.is_whitespace()).count();
+        let n = block
+            .text
+            .trim()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .count();
         total_chars += n;
         if block_is_math(block) {
             math_chars += n;
@@ -837,7 +986,11 @@ fn numbered_heading_level(text: &str) -> Option<&'static str> {
     if !title.chars().next().is_some_and(|c| c.is_ascii_uppercase()) {
         return None;
     }
-    if title.chars().any(|c| HEADING_TITLE_REJECT_SYMBOLS.contains(&c)) || contains_greek(title) {
+    if title
+        .chars()
+        .any(|c| HEADING_TITLE_REJECT_SYMBOLS.contains(&c))
+        || contains_greek(title)
+    {
         return None;
     }
     if has_interior_sentence_boundary(title) {
@@ -849,7 +1002,9 @@ fn numbered_heading_level(text: &str) -> Option<&'static str> {
         return None;
     }
     let single_char_tokens = tokens.iter().filter(|t| t.chars().count() == 1).count();
-    if (single_char_tokens as f32 / tokens.len() as f32) > HEADING_TITLE_MAX_SINGLE_CHAR_TOKEN_FRACTION {
+    if (single_char_tokens as f32 / tokens.len() as f32)
+        > HEADING_TITLE_MAX_SINGLE_CHAR_TOKEN_FRACTION
+    {
         return None;
     }
 
@@ -905,7 +1060,10 @@ fn is_bare_page_ref(text: &str) -> bool {
 /// True if `text` already ends with a page number (last non-space char is a
 /// digit) — i.e. a TOC entry that doesn't need a detached page ref merged in.
 fn ends_with_page_number(text: &str) -> bool {
-    text.trim_end().chars().next_back().is_some_and(|c| c.is_ascii_digit())
+    text.trim_end()
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_ascii_digit())
 }
 
 /// Returns the trailing run of ASCII digits in `text` (e.g. "… 65" -> "65"),
@@ -998,7 +1156,8 @@ fn has_rule_between(h_rules: &[f32], y_upper: f32, y_lower: f32) -> bool {
 /// "Name: Alice" over "Role: Engineer" happening to line up); requiring a
 /// third confirms it's a genuine repeating column structure. [`is_bordered_grid`]
 /// is the exception: a real ruled box around just 2 aligned rows is
-/// independent structural evidence standing in for the third row.
+/// independent structural evidence standing in for the third row. Default
+/// for [`PdfOptions::min_table_rows`].
 const MIN_CORE_ROWS: usize = 3;
 
 /// True if the row range `[y_top, y_bottom]` — whose cells align to
@@ -1030,7 +1189,9 @@ fn is_bordered_grid(
     // exact match.
     let margin = tol * 2.0;
     let has_top_rule = h_rules.iter().any(|&y| y > y_top && y <= y_top + margin);
-    let has_bottom_rule = h_rules.iter().any(|&y| y < y_bottom && y >= y_bottom - margin);
+    let has_bottom_rule = h_rules
+        .iter()
+        .any(|&y| y < y_bottom && y >= y_bottom - margin);
     let min_x = columns.iter().cloned().fold(f32::MAX, f32::min);
     let max_x = columns.iter().cloned().fold(f32::MIN, f32::max);
     let has_interior_v_rule = v_rules.iter().any(|&x| x > min_x + tol && x < max_x - tol);
@@ -1038,17 +1199,18 @@ fn is_bordered_grid(
 }
 
 /// Detects table regions across a page's visual lines using conservative
-/// geometry clustering: a region only starts where at least `MIN_CORE_ROWS`
-/// consecutive lines share the same ≥2 column positions (the "core" rows) —
-/// or, if the range is confirmed enclosed by a ruled box ([`is_bordered_grid`]),
-/// just 2 — then extends with further aligned rows or wrapped continuation
-/// lines until neither applies.
+/// geometry clustering: a region only starts where at least `min_rows`
+/// (see [`MIN_CORE_ROWS`]) consecutive lines share the same ≥2 column
+/// positions (the "core" rows) — or, if the range is confirmed enclosed by a
+/// ruled box ([`is_bordered_grid`]), just 2 — then extends with further
+/// aligned rows or wrapped continuation lines until neither applies.
 fn detect_table_regions(
     rows: &[Vec<Cell>],
     line_ys: &[f32],
     h_rules: &[f32],
     v_rules: &[f32],
     body_size: f32,
+    min_rows: usize,
 ) -> Vec<TableRegion> {
     let tol = body_size;
     let mut regions = Vec::new();
@@ -1063,7 +1225,7 @@ fn detect_table_regions(
             continue;
         }
 
-        // A candidate table must have at least MIN_CORE_ROWS - 1 more rows
+        // A candidate table must have at least `min_rows` - 1 more rows
         // that align exactly with this one's columns — this is the
         // conservative gate that keeps ordinary multi-column text from
         // becoming a table.
@@ -1077,9 +1239,16 @@ fn detect_table_regions(
             core_end = j;
             j += 1;
         }
-        if core_end - i + 1 < MIN_CORE_ROWS {
+        if core_end - i + 1 < min_rows {
             let bordered = core_end > i
-                && is_bordered_grid(line_ys[i], line_ys[core_end], &columns, h_rules, v_rules, tol);
+                && is_bordered_grid(
+                    line_ys[i],
+                    line_ys[core_end],
+                    &columns,
+                    h_rules,
+                    v_rules,
+                    tol,
+                );
             if !bordered {
                 i += 1;
                 continue;
@@ -1335,14 +1504,20 @@ fn detect_gutter(blocks: &[TextBlock]) -> Option<f32> {
         .map(|line| {
             let mut sorted = line.clone();
             sorted.sort_by(|&a, &b| {
-                blocks[a].x.partial_cmp(&blocks[b].x).unwrap_or(std::cmp::Ordering::Equal)
+                blocks[a]
+                    .x
+                    .partial_cmp(&blocks[b].x)
+                    .unwrap_or(std::cmp::Ordering::Equal)
             });
             let joined: String = sorted.iter().map(|&i| blocks[i].text.trim()).collect();
             contains_dot_leader(&joined)
         })
         .collect();
 
-    let x_min = text_indices.iter().map(|&i| blocks[i].x).fold(f32::MAX, f32::min);
+    let x_min = text_indices
+        .iter()
+        .map(|&i| blocks[i].x)
+        .fold(f32::MAX, f32::min);
     let x_max = text_indices
         .iter()
         .map(|&i| blocks[i].x_end)
@@ -1576,6 +1751,7 @@ fn segment_page(blocks: &[TextBlock], gutter: f32, body_size: f32) -> Vec<Region
 /// generous — the band alone doesn't decide removal, it only defines the
 /// candidate pool; the real guard against stripping body text is the
 /// cross-page repeat requirement in [`detect_running_headers_footers`].
+/// Default for [`PdfOptions::header_footer_band`].
 const HF_BAND_FRACTION: f32 = 0.12;
 
 /// Minimum number of distinct pages a band line must recur on (with the same
@@ -1583,7 +1759,7 @@ const HF_BAND_FRACTION: f32 = 0.12;
 /// header/footer. Mirrors the "require repeated structural evidence" gate
 /// used elsewhere in this file (`MIN_CORE_ROWS`, `MIN_TWO_COLUMN_LINES`) so a
 /// one-off heading or title that happens to sit in the margin band isn't
-/// removed.
+/// removed. Default for [`PdfOptions::header_footer_min_pages`].
 const HF_MIN_PAGES: usize = 3;
 
 /// Normalizes a candidate header/footer line for cross-page comparison: runs
@@ -1608,18 +1784,18 @@ fn normalize_hf(text: &str) -> String {
     out.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
-/// Groups the non-image blocks lying in the top or bottom `HF_BAND_FRACTION`
-/// of a page of the given `height` into visual lines (by y-proximity, same
+/// Groups the non-image blocks lying in the top or bottom `band_fraction`
+/// (see [`HF_BAND_FRACTION`]) of a page of the given `height` into visual lines (by y-proximity, same
 /// idiom as `render_region`), and returns each line's normalized text
 /// alongside the indices (into `blocks`) of the blocks that make it up. This
 /// is the shared unit used by both cross-page detection and per-page
 /// filtering, so the two always agree on what counts as a "band line".
-fn band_lines(blocks: &[TextBlock], height: f32) -> Vec<(String, Vec<usize>)> {
+fn band_lines(blocks: &[TextBlock], height: f32, band_fraction: f32) -> Vec<(String, Vec<usize>)> {
     if height <= 0.0 {
         return Vec::new();
     }
-    let top_thresh = height * (1.0 - HF_BAND_FRACTION);
-    let bottom_thresh = height * HF_BAND_FRACTION;
+    let top_thresh = height * (1.0 - band_fraction);
+    let bottom_thresh = height * band_fraction;
 
     let mut candidates: Vec<usize> = (0..blocks.len())
         .filter(|&i| {
@@ -1673,15 +1849,16 @@ fn band_lines(blocks: &[TextBlock], height: f32) -> Vec<(String, Vec<usize>)> {
 }
 
 /// Scans every page's margin bands and returns the normalized text of every
-/// line that recurs on at least `HF_MIN_PAGES` distinct pages — the running
-/// headers/footers to strip. Each page contributes its band lines as a
-/// *set* (deduped) before tallying, so a line repeated multiple times within
-/// a single page can't satisfy the cross-page threshold on its own.
-fn detect_running_headers_footers(pages: &[PageContent]) -> HashSet<String> {
+/// line that recurs on at least `header_footer_min_pages` (see
+/// [`HF_MIN_PAGES`]) distinct pages — the running headers/footers to strip.
+/// Each page contributes its band lines as a *set* (deduped) before
+/// tallying, so a line repeated multiple times within a single page can't
+/// satisfy the cross-page threshold on its own.
+fn detect_running_headers_footers(pages: &[PageContent], options: &PdfOptions) -> HashSet<String> {
     let mut page_counts: HashMap<String, usize> = HashMap::new();
     for page in pages {
         let mut seen_this_page: HashSet<String> = HashSet::new();
-        for (text, _) in band_lines(&page.blocks, page.height) {
+        for (text, _) in band_lines(&page.blocks, page.height, options.header_footer_band) {
             if !text.is_empty() {
                 seen_this_page.insert(text);
             }
@@ -1692,7 +1869,7 @@ fn detect_running_headers_footers(pages: &[PageContent]) -> HashSet<String> {
     }
     page_counts
         .into_iter()
-        .filter(|&(_, count)| count >= HF_MIN_PAGES)
+        .filter(|&(_, count)| count >= options.header_footer_min_pages)
         .map(|(text, _)| text)
         .collect()
 }
@@ -1705,12 +1882,13 @@ fn filter_header_footer_blocks(
     blocks: &[TextBlock],
     height: f32,
     hf_keys: &HashSet<String>,
+    band_fraction: f32,
 ) -> Vec<TextBlock> {
     if hf_keys.is_empty() {
         return blocks.to_vec();
     }
     let mut drop: HashSet<usize> = HashSet::new();
-    for (text, idxs) in band_lines(blocks, height) {
+    for (text, idxs) in band_lines(blocks, height, band_fraction) {
         if hf_keys.contains(&text) {
             drop.extend(idxs);
         }
@@ -1726,13 +1904,14 @@ fn filter_header_footer_blocks(
 /// Scans every page's image blocks and returns the `text` (the rendered
 /// `![](...)` link, which — thanks to [`content_image_key`] — is identical
 /// across pages for byte-identical source images) of every image that
-/// recurs on at least `HF_MIN_PAGES` distinct pages: a repeated running
-/// header/footer logo or watermark, not incidental content-image reuse.
+/// recurs on at least `min_pages` (see [`HF_MIN_PAGES`]) distinct pages: a
+/// repeated running header/footer logo or watermark, not incidental
+/// content-image reuse.
 /// Mirrors [`detect_running_headers_footers`]'s dedupe-per-page-then-tally
 /// shape, but is deliberately position-independent (no margin-band
 /// restriction) so a centered watermark is caught too, not just a logo
 /// confined to the header/footer band.
-fn detect_repeated_images(pages: &[PageContent]) -> HashSet<String> {
+fn detect_repeated_images(pages: &[PageContent], min_pages: usize) -> HashSet<String> {
     let mut page_counts: HashMap<String, usize> = HashMap::new();
     for page in pages {
         let seen_this_page: HashSet<&str> = page
@@ -1747,7 +1926,7 @@ fn detect_repeated_images(pages: &[PageContent]) -> HashSet<String> {
     }
     page_counts
         .into_iter()
-        .filter(|&(_, count)| count >= HF_MIN_PAGES)
+        .filter(|&(_, count)| count >= min_pages)
         .map(|(text, _)| text)
         .collect()
 }
@@ -1755,7 +1934,10 @@ fn detect_repeated_images(pages: &[PageContent]) -> HashSet<String> {
 /// Returns `blocks` with any image block whose link text is in
 /// `repeated_images` removed. A no-op (returns a full copy) when
 /// `repeated_images` is empty.
-fn filter_repeated_images(blocks: &[TextBlock], repeated_images: &HashSet<String>) -> Vec<TextBlock> {
+fn filter_repeated_images(
+    blocks: &[TextBlock],
+    repeated_images: &HashSet<String>,
+) -> Vec<TextBlock> {
     if repeated_images.is_empty() {
         return blocks.to_vec();
     }
@@ -1801,6 +1983,8 @@ fn strip_undecodable(text: &str) -> String {
 fn extract_page_blocks(
     page: &PdfPage,
     media: &mut MediaSink,
+    report: &mut ImportReport,
+    location: &Location,
 ) -> Result<Vec<TextBlock>, ConversionError> {
     let mut blocks: Vec<TextBlock> = Vec::new();
 
@@ -1860,7 +2044,7 @@ fn extract_page_blocks(
                             image::ImageFormat::Png,
                         )
                         .is_ok();
-                    if encoded {
+                    let link = if encoded {
                         // Content-addressed key (not a per-page name): two
                         // pages embedding byte-identical images (e.g. a
                         // repeated header/footer logo) collapse to the same
@@ -1868,15 +2052,29 @@ fn extract_page_blocks(
                         // blocks get identical `text`, which is what lets
                         // `detect_repeated_images` recognize the recurrence.
                         let key = content_image_key(&png_bytes);
-                        match media.add(&key, &png_bytes) {
-                            Some(rel_path) => format!("![]({})", rel_path),
-                            None => "*(unsupported image)*".to_string(),
-                        }
+                        media
+                            .add(&key, &png_bytes)
+                            .map(|rel_path| format!("![]({})", rel_path))
                     } else {
+                        None
+                    };
+                    link.unwrap_or_else(|| {
+                        report.skip_image(
+                            WarningKind::UnsupportedImage,
+                            Some(location.clone()),
+                            "Image could not be extracted as PNG",
+                        );
                         "*(unsupported image)*".to_string()
-                    }
+                    })
+                }
+                Err(_) => {
+                    report.skip_image(
+                        WarningKind::UnsupportedImage,
+                        Some(location.clone()),
+                        "Image could not be decoded",
+                    );
+                    continue;
                 }
-                Err(_) => continue,
             };
             blocks.push(TextBlock {
                 x,
@@ -1951,6 +2149,8 @@ fn render_page_blocks(
     h_rules: &[f32],
     v_rules: &[f32],
     heading_body_size: f32,
+    options: &PdfOptions,
+    report: &mut ImportReport,
 ) -> String {
     if blocks.is_empty() {
         return String::new();
@@ -1989,17 +2189,53 @@ fn render_page_blocks(
     // a detected gutter splits the page into full-width dividers and
     // two-column bands, each rendered as its own region.
     match detect_gutter(blocks) {
-        None => render_region(blocks, &all_indices, body_size, heading_body_size, h_rules, v_rules),
+        None => render_region(
+            blocks,
+            &all_indices,
+            body_size,
+            heading_body_size,
+            h_rules,
+            v_rules,
+            options,
+            report,
+        ),
         Some(gutter) => {
             let mut out = String::new();
             for region in segment_page(blocks, gutter, body_size) {
                 match region {
                     Region::Full(indices) => {
-                        out.push_str(&render_region(blocks, &indices, body_size, heading_body_size, h_rules, v_rules));
+                        out.push_str(&render_region(
+                            blocks,
+                            &indices,
+                            body_size,
+                            heading_body_size,
+                            h_rules,
+                            v_rules,
+                            options,
+                            report,
+                        ));
                     }
                     Region::TwoCol { left, right } => {
-                        out.push_str(&render_region(blocks, &left, body_size, heading_body_size, h_rules, v_rules));
-                        out.push_str(&render_region(blocks, &right, body_size, heading_body_size, h_rules, v_rules));
+                        out.push_str(&render_region(
+                            blocks,
+                            &left,
+                            body_size,
+                            heading_body_size,
+                            h_rules,
+                            v_rules,
+                            options,
+                            report,
+                        ));
+                        out.push_str(&render_region(
+                            blocks,
+                            &right,
+                            body_size,
+                            heading_body_size,
+                            h_rules,
+                            v_rules,
+                            options,
+                            report,
+                        ));
                     }
                 }
             }
@@ -2095,7 +2331,12 @@ fn detect_toc_regions(line_texts: &[String], is_image_line: &[bool]) -> Vec<(usi
 ///   opens a new entry.
 /// - Anything else is a wrapped continuation of the current entry's title,
 ///   joined with [`append_wrapped`] (hyphen-aware, same as body reflow).
-fn render_toc_region(line_texts: &[String], is_image_line: &[bool], start: usize, end: usize) -> String {
+fn render_toc_region(
+    line_texts: &[String],
+    is_image_line: &[bool],
+    start: usize,
+    end: usize,
+) -> String {
     let mut out = String::new();
     let mut entries: Vec<String> = Vec::new();
     let mut needy_cursor = 0usize;
@@ -2178,7 +2419,12 @@ fn char_weighted_median_font_size(blocks: &[TextBlock], line: &[usize]) -> f32 {
     let mut sized: Vec<(f32, usize)> = line
         .iter()
         .filter(|&&idx| !blocks[idx].is_image)
-        .map(|&idx| (blocks[idx].font_size, blocks[idx].text.chars().count().max(1)))
+        .map(|&idx| {
+            (
+                blocks[idx].font_size,
+                blocks[idx].text.chars().count().max(1),
+            )
+        })
         .collect();
     if sized.is_empty() {
         return 0.0;
@@ -2196,6 +2442,12 @@ fn char_weighted_median_font_size(blocks: &[TextBlock], line: &[usize]) -> f32 {
     sized.last().map(|&(size, _)| size).unwrap_or(0.0)
 }
 
+/// Minimum line font size, as a multiple of the document-wide body size (see
+/// [`document_body_size`]), for a line of heading shape to become a `#`,
+/// `##` or `###` heading in [`render_region`]. Default for
+/// [`PdfOptions::heading_font_ratios`].
+const HEADING_FONT_RATIOS: [f32; 3] = [1.8, 1.4, 1.15];
+
 /// Renders one reading-order region of a page as Markdown — either the
 /// whole page (no multi-column layout detected) or a single column of a
 /// two-column band. Groups the region's blocks into visual lines top to
@@ -2204,6 +2456,10 @@ fn char_weighted_median_font_size(blocks: &[TextBlock], line: &[usize]) -> f32 {
 /// consecutive plain body lines are reflowed into single paragraphs —
 /// de-hyphenating words that wrapped across the PDF's line break (see
 /// [`append_wrapped`]).
+// The page geometry, the two body-size baselines and the per-import
+// options/report are each threaded unchanged from `render_page_blocks`;
+// bundling them into a struct just to satisfy the lint would obscure that.
+#[allow(clippy::too_many_arguments)]
 fn render_region(
     blocks: &[TextBlock],
     indices: &[usize],
@@ -2211,6 +2467,8 @@ fn render_region(
     heading_body_size: f32,
     h_rules: &[f32],
     v_rules: &[f32],
+    options: &PdfOptions,
+    report: &mut ImportReport,
 ) -> String {
     if indices.is_empty() {
         return String::new();
@@ -2303,7 +2561,14 @@ fn render_region(
         })
         .collect();
     let line_ys: Vec<f32> = lines.iter().map(|line| blocks[line[0]].y).collect();
-    let regions = detect_table_regions(&rows, &line_ys, h_rules, v_rules, body_size);
+    let regions = detect_table_regions(
+        &rows,
+        &line_ys,
+        h_rules,
+        v_rules,
+        body_size,
+        options.min_table_rows,
+    );
 
     let mut out = String::new();
     let mut prev_y = f32::MAX;
@@ -2328,7 +2593,12 @@ fn render_region(
             if !prev_was_toc {
                 ensure_blank_line(&mut out);
             }
-            out.push_str(&render_toc_region(&line_texts, &image_line_flags, start, end));
+            out.push_str(&render_toc_region(
+                &line_texts,
+                &image_line_flags,
+                start,
+                end,
+            ));
             prev_y = line_ys[end];
             prev_was_toc = true;
             i = end + 1;
@@ -2355,6 +2625,7 @@ fn render_region(
             }
             out.push_str(&render_gfm_table(region));
             out.push('\n');
+            report.stats.tables += 1;
 
             prev_y = line_ys[region.end_line];
             i = region.end_line + 1;
@@ -2440,17 +2711,19 @@ fn render_region(
         // ratio (against the document-wide baseline, not this page's own
         // median — see `document_body_size`), then the ALL-CAPS heuristic.
         // Image lines are never headings — they have no meaningful font size.
+        let [h1_ratio, h2_ratio, h3_ratio] = options.heading_font_ratios;
         let heading = if is_image_line {
             ""
-        } else if let Some(level) =
-            heading_shape_ok.then(|| numbered_heading_level(line_text)).flatten()
+        } else if let Some(level) = heading_shape_ok
+            .then(|| numbered_heading_level(line_text))
+            .flatten()
         {
             level
-        } else if heading_shape_ok && max_font >= heading_body_size * 1.8 {
+        } else if heading_shape_ok && max_font >= heading_body_size * h1_ratio {
             "# "
-        } else if heading_shape_ok && max_font >= heading_body_size * 1.4 {
+        } else if heading_shape_ok && max_font >= heading_body_size * h2_ratio {
             "## "
-        } else if heading_shape_ok && max_font >= heading_body_size * 1.15 {
+        } else if heading_shape_ok && max_font >= heading_body_size * h3_ratio {
             "### "
         } else if is_all_caps_heading(line_text) {
             "## "
@@ -2598,7 +2871,9 @@ mod tests {
     fn test_is_duplicated_halves() {
         assert!(is_duplicated_halves("AIS AIS"));
         assert!(is_duplicated_halves("EN-DE EN-FR EN-DE EN-FR"));
-        assert!(is_duplicated_halves("Intractable, may be Intractable, may be"));
+        assert!(is_duplicated_halves(
+            "Intractable, may be Intractable, may be"
+        ));
         assert!(!is_duplicated_halves("INDEX TERMS"));
         assert!(!is_duplicated_halves("ABSTRACT"));
         assert!(!is_duplicated_halves("ONE"));
@@ -2622,7 +2897,12 @@ mod tests {
         // ~50 body-sized characters around it should dominate.
         let blocks = vec![
             text_block_sized(0.0, 0.0, "T", 40.0),
-            text_block_sized(20.0, 0.0, "systems are posing challenges to the network", 10.0),
+            text_block_sized(
+                20.0,
+                0.0,
+                "systems are posing challenges to the network",
+                10.0,
+            ),
         ];
         let line = vec![0, 1];
         assert_eq!(char_weighted_median_font_size(&blocks, &line), 10.0);
@@ -2702,10 +2982,16 @@ mod tests {
 
     #[test]
     fn test_has_interior_sentence_boundary() {
-        assert!(has_interior_sentence_boundary("GHz. Consequently, it realized"));
+        assert!(has_interior_sentence_boundary(
+            "GHz. Consequently, it realized"
+        ));
         assert!(has_interior_sentence_boundary("GPUs. Even our base model"));
-        assert!(!has_interior_sentence_boundary("Convergence of Algorithm 1"));
-        assert!(!has_interior_sentence_boundary("Scaled Dot-Product Attention"));
+        assert!(!has_interior_sentence_boundary(
+            "Convergence of Algorithm 1"
+        ));
+        assert!(!has_interior_sentence_boundary(
+            "Scaled Dot-Product Attention"
+        ));
         // Uppercase-before-dot abbreviation isn't a sentence boundary.
         assert!(!has_interior_sentence_boundary("U.S. Policy"));
     }
@@ -2773,7 +3059,10 @@ mod tests {
 
     #[test]
     fn test_render_line_with_inline_math_matches_plain_join_when_no_math() {
-        let blocks = vec![text_block(0.0, 0.0, "Hello"), text_block(60.0, 0.0, "World")];
+        let blocks = vec![
+            text_block(0.0, 0.0, "Hello"),
+            text_block(60.0, 0.0, "World"),
+        ];
         let line = vec![0usize, 1];
         assert_eq!(render_line_with_inline_math(&blocks, &line), "Hello World");
     }
@@ -2885,8 +3174,8 @@ mod tests {
         // "Hello" and "World" are close together (one cell); "Version" is far
         // to the right of "World" (a second cell).
         let blocks = vec![
-            text_block(0.0, 0.0, "Hello"),   // x_end = 50
-            text_block(55.0, 0.0, "World"),  // gap 5, x_end = 105
+            text_block(0.0, 0.0, "Hello"),     // x_end = 50
+            text_block(55.0, 0.0, "World"),    // gap 5, x_end = 105
             text_block(300.0, 0.0, "Version"), // gap 195, far
         ];
         let line = vec![0, 1, 2];
@@ -2916,7 +3205,7 @@ mod tests {
             aligned_row(&[(0.0, "0.2"), (100.0, "2024-08-07"), (200.0, "ITD")]),
         ];
         let line_ys = vec![300.0, 280.0, 260.0];
-        let regions = detect_table_regions(&rows, &line_ys, &[], &[], 12.0);
+        let regions = detect_table_regions(&rows, &line_ys, &[], &[], 12.0, MIN_CORE_ROWS);
         assert_eq!(regions.len(), 1);
         let region = &regions[0];
         assert_eq!(region.start_line, 0);
@@ -2939,7 +3228,7 @@ mod tests {
             aligned_row(&[(0.0, "Third bullet")]),
         ];
         let ys = vec![300.0, 280.0, 260.0];
-        assert!(detect_table_regions(&single_col, &ys, &[], &[], 12.0).is_empty());
+        assert!(detect_table_regions(&single_col, &ys, &[], &[], 12.0, MIN_CORE_ROWS).is_empty());
 
         // A 2-line key:value block (2 aligned columns, but only 2 rows) is
         // exactly the incidental-alignment case MIN_CORE_ROWS guards against.
@@ -2948,7 +3237,7 @@ mod tests {
             aligned_row(&[(0.0, "Role:"), (100.0, "Engineer")]),
         ];
         let ys2 = vec![300.0, 280.0];
-        assert!(detect_table_regions(&key_value, &ys2, &[], &[], 12.0).is_empty());
+        assert!(detect_table_regions(&key_value, &ys2, &[], &[], 12.0, MIN_CORE_ROWS).is_empty());
     }
 
     #[test]
@@ -2968,7 +3257,7 @@ mod tests {
         let ys = vec![300.0, 280.0];
         let h_rules = vec![305.0, 275.0]; // just above row 0, just below row 1
         let v_rules = vec![50.0]; // interior divider between columns at 0.0 and 100.0
-        let regions = detect_table_regions(&rows, &ys, &h_rules, &v_rules, 12.0);
+        let regions = detect_table_regions(&rows, &ys, &h_rules, &v_rules, 12.0, MIN_CORE_ROWS);
         assert_eq!(regions.len(), 1);
         assert_eq!(regions[0].logical_rows.len(), 2);
     }
@@ -2984,7 +3273,7 @@ mod tests {
         ];
         let ys = vec![300.0, 280.0];
         let h_rules = vec![305.0, 275.0];
-        assert!(detect_table_regions(&rows, &ys, &h_rules, &[], 12.0).is_empty());
+        assert!(detect_table_regions(&rows, &ys, &h_rules, &[], 12.0, MIN_CORE_ROWS).is_empty());
     }
 
     #[test]
@@ -2992,15 +3281,50 @@ mod tests {
         let columns = vec![0.0, 100.0];
         let tol = 12.0;
         // All three present: accepted.
-        assert!(is_bordered_grid(300.0, 280.0, &columns, &[305.0, 275.0], &[50.0], tol));
+        assert!(is_bordered_grid(
+            300.0,
+            280.0,
+            &columns,
+            &[305.0, 275.0],
+            &[50.0],
+            tol
+        ));
         // Missing top rule.
-        assert!(!is_bordered_grid(300.0, 280.0, &columns, &[275.0], &[50.0], tol));
+        assert!(!is_bordered_grid(
+            300.0,
+            280.0,
+            &columns,
+            &[275.0],
+            &[50.0],
+            tol
+        ));
         // Missing bottom rule.
-        assert!(!is_bordered_grid(300.0, 280.0, &columns, &[305.0], &[50.0], tol));
+        assert!(!is_bordered_grid(
+            300.0,
+            280.0,
+            &columns,
+            &[305.0],
+            &[50.0],
+            tol
+        ));
         // Missing interior vertical rule.
-        assert!(!is_bordered_grid(300.0, 280.0, &columns, &[305.0, 275.0], &[], tol));
+        assert!(!is_bordered_grid(
+            300.0,
+            280.0,
+            &columns,
+            &[305.0, 275.0],
+            &[],
+            tol
+        ));
         // Fewer than 2 columns: never a grid.
-        assert!(!is_bordered_grid(300.0, 280.0, &[0.0], &[305.0, 275.0], &[50.0], tol));
+        assert!(!is_bordered_grid(
+            300.0,
+            280.0,
+            &[0.0],
+            &[305.0, 275.0],
+            &[50.0],
+            tol
+        ));
     }
 
     #[test]
@@ -3009,13 +3333,25 @@ mod tests {
         // page-number cell, roughly aligned across entries — exactly the
         // shape that would otherwise satisfy the column-alignment gates.
         let toc = vec![
-            aligned_row(&[(0.0, "TABLE OF CONTENTS ...................."), (400.0, "1")]),
-            aligned_row(&[(0.0, "DOCUMENT CONTROL ....................."), (400.0, "3")]),
-            aligned_row(&[(0.0, "1. ABOUT THIS DOCUMENT ..............."), (400.0, "4")]),
-            aligned_row(&[(0.0, "2. INTRODUCTION ......................"), (400.0, "5")]),
+            aligned_row(&[
+                (0.0, "TABLE OF CONTENTS ...................."),
+                (400.0, "1"),
+            ]),
+            aligned_row(&[
+                (0.0, "DOCUMENT CONTROL ....................."),
+                (400.0, "3"),
+            ]),
+            aligned_row(&[
+                (0.0, "1. ABOUT THIS DOCUMENT ..............."),
+                (400.0, "4"),
+            ]),
+            aligned_row(&[
+                (0.0, "2. INTRODUCTION ......................"),
+                (400.0, "5"),
+            ]),
         ];
         let ys = vec![400.0, 380.0, 360.0, 340.0];
-        assert!(detect_table_regions(&toc, &ys, &[], &[], 12.0).is_empty());
+        assert!(detect_table_regions(&toc, &ys, &[], &[], 12.0, MIN_CORE_ROWS).is_empty());
     }
 
     #[test]
@@ -3027,12 +3363,16 @@ mod tests {
         let rows = vec![
             aligned_row(&[(0.0, "Version"), (100.0, "Date"), (200.0, "Notes")]),
             aligned_row(&[(0.0, "0.1"), (100.0, "2024-07-26"), (200.0, "Initial")]),
-            aligned_row(&[(0.0, "0.8"), (100.0, "2024-10-28"), (200.0, "Re-draft of MNMS")]),
+            aligned_row(&[
+                (0.0, "0.8"),
+                (100.0, "2024-10-28"),
+                (200.0, "Re-draft of MNMS"),
+            ]),
             aligned_row(&[(200.0, "Phase 1 only")]), // continuation, col 2 only
             aligned_row(&[(0.0, "0.81"), (100.0, "2024-10-30"), (200.0, "IT feedback")]),
         ];
         let line_ys = vec![420.0, 400.0, 380.0, 360.0, 340.0];
-        let regions = detect_table_regions(&rows, &line_ys, &[], &[], 12.0);
+        let regions = detect_table_regions(&rows, &line_ys, &[], &[], 12.0, MIN_CORE_ROWS);
         assert_eq!(regions.len(), 1);
         let region = &regions[0];
         assert_eq!(region.end_line, 4);
@@ -3087,10 +3427,8 @@ mod tests {
 
     #[test]
     fn test_diagnose_load_error_hint_wrong_architecture() {
-        let hint = diagnose_load_error_hint(
-            "LoadLibraryError(LoadLibraryExW { source: 193 })",
-            true,
-        );
+        let hint =
+            diagnose_load_error_hint("LoadLibraryError(LoadLibraryExW { source: 193 })", true);
         assert!(hint.contains("wrong architecture"));
     }
 
@@ -3099,10 +3437,8 @@ mod tests {
         // The real-world Windows text this regression is about: GetProcAddress
         // failing with error 127 (ERROR_PROC_NOT_FOUND) — a missing *export*,
         // not a missing dependency — must NOT get the VCRUNTIME140 hint.
-        let hint = diagnose_load_error_hint(
-            "LoadLibraryError(GetProcAddress { source: 127 })",
-            true,
-        );
+        let hint =
+            diagnose_load_error_hint("LoadLibraryError(GetProcAddress { source: 127 })", true);
         assert!(hint.contains("bundled PDFium is an older build"));
         assert!(!hint.contains("VCRUNTIME"));
     }
@@ -3122,10 +3458,8 @@ mod tests {
         // "the specified module could not be found") still gets the
         // VCRUNTIME140 hint — this must stay reachable, not just the new
         // missing-symbol branch.
-        let hint = diagnose_load_error_hint(
-            "LoadLibraryError(LoadLibraryExW { source: 126 })",
-            true,
-        );
+        let hint =
+            diagnose_load_error_hint("LoadLibraryError(LoadLibraryExW { source: 126 })", true);
         assert!(hint.contains("VCRUNTIME140"));
     }
 
@@ -3137,19 +3471,32 @@ mod tests {
     /// unconditionally.
     #[test]
     fn test_pdf_to_markdown_fixture() {
-        let lib = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("frameworks/pdfium.framework/pdfium");
+        let lib =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("frameworks/pdfium.framework/pdfium");
         if !lib.exists() {
-            eprintln!("skipping test_pdf_to_markdown_fixture: pdfium not found at {:?}", lib);
+            eprintln!(
+                "skipping test_pdf_to_markdown_fixture: pdfium not found at {:?}",
+                lib
+            );
             return;
         }
         std::env::set_var("PDFIUM_LIBRARY_PATH", &lib);
 
         let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.pdf");
         let mut sink = MediaSink::new(std::env::temp_dir());
-        let md = match pdf_to_markdown(path, &mut sink) {
+        let md = match pdf_to_markdown(
+            path,
+            &mut sink,
+            &PdfOptions::default(),
+            &mut ImportReport::default(),
+            &ImportProgress::default(),
+        ) {
             Ok(md) => md,
             Err(e) => {
-                eprintln!("skipping test_pdf_to_markdown_fixture: pdfium load failed: {}", e);
+                eprintln!(
+                    "skipping test_pdf_to_markdown_fixture: pdfium load failed: {}",
+                    e
+                );
                 return;
             }
         };
@@ -3442,15 +3789,43 @@ mod tests {
         let gutter = detect_gutter(&blocks).expect("should detect two columns");
         let h_rules: Vec<f32> = Vec::new();
         let v_rules: Vec<f32> = Vec::new();
+        let mut report = ImportReport::default();
         let mut out = String::new();
         for region in segment_page(&blocks, gutter, body_size) {
             match region {
                 Region::Full(indices) => {
-                    out.push_str(&render_region(&blocks, &indices, body_size, body_size, &h_rules, &v_rules));
+                    out.push_str(&render_region(
+                        &blocks,
+                        &indices,
+                        body_size,
+                        body_size,
+                        &h_rules,
+                        &v_rules,
+                        &PdfOptions::default(),
+                        &mut report,
+                    ));
                 }
                 Region::TwoCol { left, right } => {
-                    out.push_str(&render_region(&blocks, &left, body_size, body_size, &h_rules, &v_rules));
-                    out.push_str(&render_region(&blocks, &right, body_size, body_size, &h_rules, &v_rules));
+                    out.push_str(&render_region(
+                        &blocks,
+                        &left,
+                        body_size,
+                        body_size,
+                        &h_rules,
+                        &v_rules,
+                        &PdfOptions::default(),
+                        &mut report,
+                    ));
+                    out.push_str(&render_region(
+                        &blocks,
+                        &right,
+                        body_size,
+                        body_size,
+                        &h_rules,
+                        &v_rules,
+                        &PdfOptions::default(),
+                        &mut report,
+                    ));
                 }
             }
         }
@@ -3506,7 +3881,7 @@ mod tests {
             page_with_header_footer("D. B. Acharya et al.: Survey", "18915", "Body text three"),
             page_with_header_footer("D. B. Acharya et al.: Survey", "18916", "Body text four"),
         ];
-        let keys = detect_running_headers_footers(&pages);
+        let keys = detect_running_headers_footers(&pages, &PdfOptions::default());
 
         assert!(
             keys.contains(&normalize_hf("D. B. Acharya et al.: Survey")),
@@ -3530,7 +3905,7 @@ mod tests {
             page_with_header_footer("Rare Header", "1", "Body A"),
             page_with_header_footer("Rare Header", "2", "Body B"),
         ];
-        let keys = detect_running_headers_footers(&pages);
+        let keys = detect_running_headers_footers(&pages, &PdfOptions::default());
         assert!(
             !keys.contains(&normalize_hf("Rare Header")),
             "a header repeated on fewer than HF_MIN_PAGES pages should not be flagged"
@@ -3544,7 +3919,8 @@ mod tests {
         hf_keys.insert(normalize_hf("D. B. Acharya et al.: Survey"));
         hf_keys.insert(normalize_hf("18913"));
 
-        let kept = filter_header_footer_blocks(&page.blocks, page.height, &hf_keys);
+        let kept =
+            filter_header_footer_blocks(&page.blocks, page.height, &hf_keys, HF_BAND_FRACTION);
         assert_eq!(kept.len(), 1);
         assert_eq!(kept[0].text, "Body text");
     }
@@ -3552,7 +3928,12 @@ mod tests {
     #[test]
     fn test_filter_header_footer_blocks_noop_when_no_keys() {
         let page = page_with_header_footer("D. B. Acharya et al.: Survey", "18913", "Body text");
-        let kept = filter_header_footer_blocks(&page.blocks, page.height, &HashSet::new());
+        let kept = filter_header_footer_blocks(
+            &page.blocks,
+            page.height,
+            &HashSet::new(),
+            HF_BAND_FRACTION,
+        );
         assert_eq!(kept.len(), page.blocks.len());
     }
 
@@ -3648,9 +4029,14 @@ mod tests {
         let is_image = vec![false; lines.len()];
         let md = render_toc_region(&lines, &is_image, 0, lines.len() - 1);
 
-        assert!(!md.contains('#'), "TOC region must never emit a heading:\n{md}");
         assert!(
-            md.contains("- 14.2 H02 – APPROVAL LABEL (WITH SERVICE) ON NEW CS CODE / CS CODE VERSION … 82"),
+            !md.contains('#'),
+            "TOC region must never emit a heading:\n{md}"
+        );
+        assert!(
+            md.contains(
+                "- 14.2 H02 – APPROVAL LABEL (WITH SERVICE) ON NEW CS CODE / CS CODE VERSION … 82"
+            ),
             "expected wrapped title tail merged into its entry:\n{md}"
         );
     }
@@ -3667,8 +4053,14 @@ mod tests {
         let md = render_toc_region(&lines, &is_image, 0, lines.len() - 1);
         let bullets: Vec<&str> = md.lines().filter(|l| l.starts_with("- ")).collect();
         assert_eq!(bullets.len(), 2);
-        assert!(bullets[0].ends_with("65"), "first entry should get 65:\n{md}");
-        assert!(bullets[1].ends_with("68"), "second entry should get 68:\n{md}");
+        assert!(
+            bullets[0].ends_with("65"),
+            "first entry should get 65:\n{md}"
+        );
+        assert!(
+            bullets[1].ends_with("68"),
+            "second entry should get 68:\n{md}"
+        );
     }
 
     // --- Figure/table caption association ---
@@ -3688,7 +4080,9 @@ mod tests {
         assert!(is_caption_label(
             "FIGURE 1. Schematic representation of a Ruze lens with constant thickness."
         ));
-        assert!(is_caption_label("TABLE 1. Comparison of traditional AI and agentic AI."));
+        assert!(is_caption_label(
+            "TABLE 1. Comparison of traditional AI and agentic AI."
+        ));
     }
 
     #[test]
@@ -3782,7 +4176,7 @@ mod tests {
             page_with_logo("![](assets/image1.png)"),
             page_with_logo("![](assets/image1.png)"),
         ];
-        let repeated = detect_repeated_images(&pages);
+        let repeated = detect_repeated_images(&pages, HF_MIN_PAGES);
         assert!(repeated.contains("![](assets/image1.png)"));
     }
 
@@ -3792,7 +4186,7 @@ mod tests {
             page_with_logo("![](assets/image1.png)"),
             page_with_logo("![](assets/image1.png)"),
         ];
-        let repeated = detect_repeated_images(&pages);
+        let repeated = detect_repeated_images(&pages, HF_MIN_PAGES);
         assert!(
             repeated.is_empty(),
             "an image on fewer than HF_MIN_PAGES pages should not be flagged"
@@ -3854,7 +4248,10 @@ mod tests {
 
         let pages = vec![dense_page, prose_page];
         let size = document_body_size(&pages);
-        assert_eq!(size, 12.0, "should anchor on the dominant body text, not the dense page's small runs");
+        assert_eq!(
+            size, 12.0,
+            "should anchor on the dominant body text, not the dense page's small runs"
+        );
     }
 
     #[test]
@@ -3879,7 +4276,16 @@ mod tests {
         let indices = vec![0];
         let body_size = 12.0;
         let heading_body_size = 12.0; // ratio 14/12 ~= 1.17, clears the 1.15 "###" gate
-        let out = render_region(&blocks, &indices, body_size, heading_body_size, &[], &[]);
+        let out = render_region(
+            &blocks,
+            &indices,
+            body_size,
+            heading_body_size,
+            &[],
+            &[],
+            &PdfOptions::default(),
+            &mut ImportReport::default(),
+        );
         assert!(
             !out.trim_start().starts_with('#'),
             "an over-length line should not become a heading even if font ratio clears the gate:\n{out}"
@@ -3888,11 +4294,25 @@ mod tests {
 
     #[test]
     fn test_render_region_large_font_bullet_is_list_not_heading() {
-        let blocks = vec![text_block_sized(0.0, 500.0, "• Goal Alignment with Human Values", 16.0)];
+        let blocks = vec![text_block_sized(
+            0.0,
+            500.0,
+            "• Goal Alignment with Human Values",
+            16.0,
+        )];
         let indices = vec![0];
         let body_size = 12.0;
         let heading_body_size = 12.0;
-        let out = render_region(&blocks, &indices, body_size, heading_body_size, &[], &[]);
+        let out = render_region(
+            &blocks,
+            &indices,
+            body_size,
+            heading_body_size,
+            &[],
+            &[],
+            &PdfOptions::default(),
+            &mut ImportReport::default(),
+        );
         assert!(
             out.trim_start().starts_with("• "),
             "a bulleted line should render as a list item, not a heading, regardless of font size:\n{out}"
@@ -3905,11 +4325,19 @@ mod tests {
         let indices = vec![0];
         let body_size = 12.0;
         let heading_body_size = 12.0; // same font size as body: ratio path won't fire
-        let out = render_region(&blocks, &indices, body_size, heading_body_size, &[], &[]);
+        let out = render_region(
+            &blocks,
+            &indices,
+            body_size,
+            heading_body_size,
+            &[],
+            &[],
+            &PdfOptions::default(),
+            &mut ImportReport::default(),
+        );
         assert!(
             out.starts_with("## J. ROADMAP FOR FUTURE RESEARCH"),
             "a genuine ALL-CAPS heading should still be promoted:\n{out}"
         );
     }
-
 }
@@ -1,13 +1,51 @@
-use std::collections::HashMap;
-use super::inline_fmt::{apply_inline_fmt, escape_markdown};
+use super::chart;
+use super::inline_fmt::{escape_markdown, format_segments, InlineOptions, RunFormat, VertAlign};
 use super::media::MediaSink;
-use super::ConversionError;
+use super::progress::ImportProgress;
+use super::report::{ImportReport, Location, WarningKind};
+use super::{ConversionError, Converter, ImportOptions};
+use std::collections::HashMap;
+
+/// PowerPoint importer registered in [`super::FormatRegistry`].
+pub struct PptxConverter;
+
+impl Converter for PptxConverter {
+    fn id(&self) -> &'static str {
+        "pptx"
+    }
+    fn name(&self) -> &'static str {
+        "PowerPoint Presentation"
+    }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pptx"]
+    }
+    fn mime_types(&self) -> &'static [&'static str] {
+        &["application/vnd.openxmlformats-officedocument.presentationml.presentation"]
+    }
+    fn to_markdown(
+        &self,
+        path: &str,
+        media: &mut MediaSink,
+        options: &ImportOptions,
+        report: &mut ImportReport,
+        progress: &ImportProgress,
+    ) -> Result<String, ConversionError> {
+        pptx_to_markdown(path, media, options, report, progress)
+    }
+}
 
 /// Convert a PPTX file to Markdown.
 /// Each slide becomes a section separated by `---`. Embedded images are
 /// extracted via `media` and rendered as real `![]()` links (falling back to
-/// a text note for non-renderable formats like EMF/WMF).
-pub fn pptx_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, ConversionError> {
+/// a text note for formats that can't be rendered or converted, recorded in
+/// `report`).
+pub fn pptx_to_markdown(
+    path: &str,
+    media: &mut MediaSink,
+    options: &ImportOptions,
+    report: &mut ImportReport,
+    progress: &ImportProgress,
+) -> Result<String, ConversionError> {
     use std::io::Read;
 
     let file = std::fs::File::open(path)
@@ -16,10 +54,10 @@ pub fn pptx_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, Con
     let mut archive = zip::ZipArchive::new(file)
         .map_err(|e| ConversionError(format!("Failed to read PPTX archive: {}", e)))?;
 
-    // Collect rels, slide XML, and media bytes in a single pass
+    // Collect rels, slide XML, and media and chart parts in a single pass
     let mut rels_map: HashMap<usize, String> = HashMap::new();
     let mut slides_raw: Vec<(usize, String)> = Vec::new();
-    let mut media_bytes: HashMap<String, Vec<u8>> = HashMap::new();
+    let mut part_bytes: HashMap<String, Vec<u8>> = HashMap::new();
 
     for i in 0..archive.len() {
         let mut entry = archive
@@ -53,42 +91,50 @@ pub fn pptx_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, Con
                     .map_err(|e| ConversionError(format!("Failed to read slide XML: {}", e)))?;
                 slides_raw.push((num, content));
             }
-        } else if name.starts_with("ppt/media/") {
+        } else if name.starts_with("ppt/media/") || name.starts_with("ppt/charts/chart") {
             let mut buf = Vec::new();
             entry
                 .read_to_end(&mut buf)
                 .map_err(|e| ConversionError(format!("Failed to read media entry: {}", e)))?;
-            media_bytes.insert(name, buf);
+            part_bytes.insert(name, buf);
         }
     }
 
     slides_raw.sort_by_key(|(n, _)| *n);
+    report.stats.pages = Some(slides_raw.len());
 
     let mut parts: Vec<String> = Vec::new();
-    for (num, xml) in &slides_raw {
+    let total = slides_raw.len();
+    for (done, (num, xml)) in slides_raw.iter().enumerate() {
+        progress.step(done, total)?;
         let rels = rels_map
             .get(num)
             .map(|r| parse_slide_rels(r))
             .unwrap_or_default();
-        let text = extract_slide_content(xml, &rels, &media_bytes, media);
+        let location = Location::Slide { number: *num };
+        let text =
+            extract_slide_content(xml, &rels, &part_bytes, media, options, report, &location);
         if !text.is_empty() {
             parts.push(text);
         }
     }
 
+    progress.step(total, total)?;
+
     Ok(parts.join("\n\n---\n\n"))
 }
 
 /// Parse a slide rels XML and return a map of rId → resolved `ppt/media/...`
-/// path. `Target` is relative to `ppt/slides/` (e.g. `../media/image1.png`),
+/// (or `ppt/charts/...`) path. `Target` is relative to `ppt/slides/` (e.g. `../media/image1.png`),
 /// so it's resolved against that base to get the archive entry name.
 fn parse_slide_rels(rels_xml: &str) -> HashMap<String, String> {
     let mut map = HashMap::new();
     for chunk in rels_xml.split("<Relationship ") {
-        if !chunk.contains("/image") {
+        if !chunk.contains("/image") && !chunk.contains("/chart\"") {
             continue;
         }
-        if let (Some(id), Some(target)) = (get_xml_attr(chunk, "Id"), get_xml_attr(chunk, "Target")) {
+        if let (Some(id), Some(target)) = (get_xml_attr(chunk, "Id"), get_xml_attr(chunk, "Target"))
+        {
             map.insert(id, resolve_slide_relative_path(&target));
         }
     }
@@ -123,12 +169,16 @@ fn get_xml_attr(s: &str, attr: &str) -> Option<String> {
     None
 }
 
-/// Extract slide content: title (as `# heading`), images, and body paragraphs.
+/// Extract slide content: title (as `# heading`), images, and body
+/// paragraphs with each chart placed where its graphic frame sits.
 fn extract_slide_content(
     xml: &str,
     rels: &HashMap<String, String>,
-    media_bytes: &HashMap<String, Vec<u8>>,
+    parts: &HashMap<String, Vec<u8>>,
     media: &mut MediaSink,
+    options: &ImportOptions,
+    report: &mut ImportReport,
+    location: &Location,
 ) -> String {
     // Strip the slide background block so its image embeds aren't treated as content images
     let stripped: String;
@@ -148,8 +198,8 @@ fn extract_slide_content(
     };
 
     // Collect content images from r:embed references (deduplicated), extracting
-    // bytes via `media` into real `![]()` links; non-renderable formats
-    // (e.g. EMF/WMF) fall back to a text note instead of a broken image.
+    // bytes via `media` into real `![]()` links; formats that can't be
+    // rendered or converted fall back to a text note instead of a broken image.
     let mut image_placeholders: Vec<String> = Vec::new();
     let mut search_from = 0;
     while let Some(pos) = xml[search_from..].find("r:embed=\"") {
@@ -157,21 +207,31 @@ fn extract_slide_content(
         if let Some(end) = xml[abs..].find('"') {
             let rid = &xml[abs..abs + end];
             if let Some(media_path) = rels.get(rid) {
-                let placeholder = if let Some(bytes) = media_bytes.get(media_path) {
+                let file_name = media_path.rsplit('/').next().unwrap_or(media_path);
+                let (placeholder, skipped) = if let Some(bytes) = parts.get(media_path) {
                     match media.add(media_path, bytes) {
-                        Some(rel_path) => format!("![]({})", rel_path),
-                        None => format!(
-                            "*(unsupported image: {})*",
-                            media_path.rsplit('/').next().unwrap_or(media_path)
+                        Some(rel_path) => (format!("![]({})", rel_path), None),
+                        None => (
+                            format!("*(unsupported image: {})*", file_name),
+                            Some((
+                                WarningKind::UnsupportedImage,
+                                format!("Unsupported image format: {}", file_name),
+                            )),
                         ),
                     }
                 } else {
-                    format!(
-                        "*(unsupported image: {})*",
-                        media_path.rsplit('/').next().unwrap_or(media_path)
+                    (
+                        format!("*(unsupported image: {})*", file_name),
+                        Some((
+                            WarningKind::MissingImage,
+                            format!("Image part missing from the presentation: {}", media_path),
+                        )),
                     )
                 };
                 if !image_placeholders.contains(&placeholder) {
+                    if let Some((kind, message)) = skipped {
+                        report.skip_image(kind, Some(location.clone()), message);
+                    }
                     image_placeholders.push(placeholder);
                 }
             }
@@ -179,11 +239,28 @@ fn extract_slide_content(
         search_from = abs;
     }
 
+    // Charts (`<c:chart r:id="…"/>` in a graphic frame) become their title
+    // and a table of their cached data, kept at the frame's offset so the
+    // body places them where they sit in the shape tree.
+    let mut charts: Vec<(usize, String)> = Vec::new();
+    for (pos, _) in xml.match_indices("<c:chart ") {
+        let chunk = &xml[pos..];
+        let chunk = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+        let part = get_xml_attr(chunk, "r:id")
+            .and_then(|rid| rels.get(&rid))
+            .and_then(|path| parts.get(path))
+            .and_then(|bytes| chart::parse_chart(&String::from_utf8_lossy(bytes)));
+        if let Some(parsed) = part {
+            charts.push((pos, chart::chart_to_markdown(&parsed, &options.charts)));
+        }
+    }
+
     // Extract title from a placeholder shape (<p:ph type="title"> or "ctrTitle") if present
     let ph_title = find_placeholder_title(xml);
 
-    // Extract all paragraphs from the slide, building the body
-    let (fallback_title, body) = extract_paragraphs(xml, ph_title.as_deref());
+    // Extract all paragraphs from the slide, building the body around the charts
+    let (fallback_title, body) =
+        extract_paragraphs(xml, ph_title.as_deref(), &charts, &options.inline);
 
     let title = ph_title.as_deref().or(fallback_title.as_deref());
 
@@ -261,12 +338,25 @@ fn extract_run_text_plain(para: &str) -> String {
 
 /// Extract paragraphs from ALL shapes on the slide.
 /// The `known_title` text is excluded from the body (to avoid duplication).
+/// `blocks` are pre-rendered blocks (chart tables) keyed by their byte offset
+/// in `xml`, placed between the paragraphs around them.
 /// Returns (first_para_as_fallback_title, body_text).
-fn extract_paragraphs(xml: &str, known_title: Option<&str>) -> (Option<String>, String) {
+fn extract_paragraphs(
+    xml: &str,
+    known_title: Option<&str>,
+    blocks: &[(usize, String)],
+    inline: &InlineOptions,
+) -> (Option<String>, String) {
     let mut first_para: Option<String> = None;
     let mut body_parts: Vec<(bool, usize, String)> = Vec::new(); // (is_bullet, level, text)
+    let mut blocks = blocks.iter().peekable();
 
-    for para_chunk in xml.split("<a:p>").skip(1) {
+    for (para_start, _) in xml.match_indices("<a:p>") {
+        while let Some((_, block)) = blocks.next_if(|(pos, _)| *pos < para_start) {
+            body_parts.push((false, 0, block.clone()));
+        }
+
+        let para_chunk = &xml[para_start + "<a:p>".len()..];
         let para_end = para_chunk.find("</a:p>").unwrap_or(para_chunk.len());
         let para = &para_chunk[..para_end];
 
@@ -280,7 +370,7 @@ fn extract_paragraphs(xml: &str, known_title: Option<&str>) -> (Option<String>,
             .unwrap_or(0);
 
         // Build run text with inline formatting
-        let text = extract_run_text_formatted(para);
+        let text = extract_run_text_formatted(para, inline);
         let trimmed = text.trim().to_string();
 
         if trimmed.is_empty() {
@@ -301,6 +391,8 @@ fn extract_paragraphs(xml: &str, known_title: Option<&str>) -> (Option<String>,
         }
     }
 
+    body_parts.extend(blocks.map(|(_, block)| (false, 0, block.clone())));
+
     // Build body string with proper Markdown paragraph/bullet separators
     let mut body = String::new();
     let mut last_was_bullet = false;
@@ -308,7 +400,13 @@ fn extract_paragraphs(xml: &str, known_title: Option<&str>) -> (Option<String>,
     for (is_bullet, level, text) in &body_parts {
         if *is_bullet {
             let indent = "  ".repeat(*level);
-            let sep = if last_was_bullet { "\n" } else if body.is_empty() { "" } else { "\n\n" };
+            let sep = if last_was_bullet {
+                "\n"
+            } else if body.is_empty() {
+                ""
+            } else {
+                "\n\n"
+            };
             body.push_str(&format!("{}{}- {}", sep, indent, text));
             last_was_bullet = true;
         } else {
@@ -321,31 +419,26 @@ fn extract_paragraphs(xml: &str, known_title: Option<&str>) -> (Option<String>,
     (first_para, body)
 }
 
-/// Extract text from a paragraph's runs with basic bold/italic Markdown formatting.
+/// Extract text from a paragraph's runs with inline formatting.
 ///
-/// Runs are first collected into (text, bold, italic) segments, adjacent
-/// segments with identical formatting are merged, and only then wrapped in
-/// emphasis markers. Wrapping each run independently would produce artifacts
-/// like `**專案**` + `**範疇**` = `**專案****範疇**` (invalid/ambiguous
+/// Runs are first collected into (text, format) segments, then handed to
+/// `format_segments`, which merges adjacent segments with identical
+/// formatting before wrapping them. Wrapping each run independently would
+/// produce artifacts like `**專案**` + `**範疇**` = `**專案****範疇**` (invalid/ambiguous
 /// CommonMark) whenever a formatting run happens to be split across two
 /// `<a:r>` elements. `apply_inline_fmt` additionally keeps leading/trailing
 /// whitespace outside the markers (`**Frontline **` is not a valid closer).
-fn extract_run_text_formatted(para: &str) -> String {
-    let mut segments: Vec<(String, bool, bool)> = Vec::new();
+fn extract_run_text_formatted(para: &str, inline: &InlineOptions) -> String {
+    let mut segments: Vec<(String, RunFormat)> = Vec::new();
     for run_chunk in para.split("<a:r>").skip(1) {
         let run_end = run_chunk.find("</a:r>").unwrap_or(run_chunk.len());
         let run = &run_chunk[..run_end];
 
-        // Detect bold/italic from <a:rPr> tag
-        let (is_bold, is_italic) = if let Some(rpr_start) = run.find("<a:rPr") {
-            let rpr_end = run[rpr_start..].find('>').unwrap_or(run.len() - rpr_start);
-            let rpr = &run[rpr_start..rpr_start + rpr_end];
-            let bold = rpr.contains(" b=\"1\"") || rpr.contains("\tb=\"1\"");
-            let italic = rpr.contains(" i=\"1\"") || rpr.contains("\ti=\"1\"");
-            (bold, italic)
-        } else {
-            (false, false)
-        };
+        // Detect formatting from the <a:rPr> tag and its fill children
+        let format = run
+            .find("<a:rPr")
+            .map(|rpr_start| run_format(&run[rpr_start..]))
+            .unwrap_or_default();
 
         // Extract text content
         if let Some(t_start) = run.find("<a:t>") {
@@ -353,33 +446,56 @@ fn extract_run_text_formatted(para: &str) -> String {
             if let Some(t_end) = after.find("</a:t>") {
                 let text = escape_markdown(&xml_decode(&after[..t_end]));
                 if !text.is_empty() {
-                    segments.push((text, is_bold, is_italic));
+                    segments.push((text, format));
                 }
             }
         }
     }
+    format_segments(segments, inline)
+}
 
-    // Merge adjacent segments with identical formatting to prevent `****` artifacts.
-    let mut merged: Vec<(String, bool, bool)> = Vec::new();
-    for seg in segments {
-        if let Some(last) = merged.last_mut() {
-            if last.1 == seg.1 && last.2 == seg.2 {
-                last.0.push_str(&seg.0);
-                continue;
-            }
-        }
-        merged.push(seg);
-    }
-
-    let mut result = String::new();
-    for (text, bold, italic) in merged {
-        // pptx runs don't carry strikethrough detection today, so pass false.
-        result.push_str(&apply_inline_fmt(&text, bold, italic, false));
+/// The formatting of an `<a:rPr>` element, given the XML starting at it:
+/// `b`/`i`/`u`/`strike`/`baseline` attributes, an `<a:highlight>` child
+/// and an `<a:solidFill>` RGB color.
+fn run_format(rpr: &str) -> RunFormat {
+    let tag_end = rpr.find('>').unwrap_or(rpr.len());
+    let tag = &rpr[..tag_end];
+    let attr = |name: &str| {
+        get_xml_attr(tag, &format!(" {}", name))
+            .or_else(|| get_xml_attr(tag, &format!("\t{}", name)))
+    };
+    let flag = |name: &str| attr(name).is_some_and(|v| v == "1" || v == "true");
+    // Children only when the element isn't self-closing.
+    let children = if tag.ends_with('/') {
+        ""
+    } else {
+        let body = &rpr[tag_end..];
+        &body[..body.find("</a:rPr>").unwrap_or(body.len())]
+    };
+    let fill = children
+        .find("<a:solidFill>")
+        .map(|i| &children[i..])
+        .map(|fill| &fill[..fill.find("</a:solidFill>").unwrap_or(fill.len())]);
+    let fill_color = fill.and_then(|fill| get_xml_attr(fill, "<a:srgbClr val"));
+    let baseline = attr("baseline")
+        .and_then(|v| v.parse::<i32>().ok())
+        .unwrap_or(0);
+    RunFormat {
+        bold: flag("b"),
+        italic: flag("i"),
+        strike: attr("strike").is_some_and(|v| v != "noStrike"),
+        underline: attr("u").is_some_and(|v| v != "none"),
+        highlight: children.contains("<a:highlight>"),
+        vert_align: match baseline {
+            b if b > 0 => VertAlign::Superscript,
+            b if b < 0 => VertAlign::Subscript,
+            _ => VertAlign::Baseline,
+        },
+        color: fill_color.as_deref().and_then(RunFormat::hex_color),
     }
-    result
 }
 
-fn xml_decode(s: &str) -> String {
+pub(crate) fn xml_decode(s: &str) -> String {
     s.replace("&amp;", "&")
         .replace("&lt;", "<")
         .replace("&gt;", ">")
@@ -401,7 +517,10 @@ mod tests {
 
     #[test]
     fn test_resolve_slide_relative_path() {
-        assert_eq!(resolve_slide_relative_path("../media/image1.png"), "ppt/media/image1.png");
+        assert_eq!(
+            resolve_slide_relative_path("../media/image1.png"),
+            "ppt/media/image1.png"
+        );
     }
 
     #[test]
@@ -420,7 +539,7 @@ mod tests {
     #[test]
     fn test_extract_paragraphs_first_para_then_bullet() {
         let xml = "<a:p><a:r><a:t>First</a:t></a:r></a:p><a:p><a:r><a:t>Second</a:t></a:r></a:p>";
-        let (first, body) = extract_paragraphs(xml, None);
+        let (first, body) = extract_paragraphs(xml, None, &[], &InlineOptions::default());
         assert_eq!(first, Some("First".to_string()));
         assert_eq!(body, "- Second");
     }
@@ -434,7 +553,10 @@ mod tests {
             r#"<a:r><a:rPr b="1"/><a:t>專案</a:t></a:r>"#,
             r#"<a:r><a:rPr b="1"/><a:t>範疇</a:t></a:r>"#,
         );
-        assert_eq!(extract_run_text_formatted(para), "**專案範疇**");
+        assert_eq!(
+            extract_run_text_formatted(para, &InlineOptions::default()),
+            "**專案範疇**"
+        );
     }
 
     #[test]
@@ -445,39 +567,161 @@ mod tests {
             r#"<a:r><a:rPr b="1"/><a:t>Frontline </a:t></a:r>"#,
             r#"<a:r><a:t>能提供</a:t></a:r>"#,
         );
-        assert_eq!(extract_run_text_formatted(para), "**Frontline** 能提供");
+        assert_eq!(
+            extract_run_text_formatted(para, &InlineOptions::default()),
+            "**Frontline** 能提供"
+        );
     }
 
     #[test]
     fn test_extract_run_text_formatted_escapes_literal_markdown_chars() {
         let para = r#"<a:r><a:t>* not a bullet</a:t></a:r>"#;
-        assert_eq!(extract_run_text_formatted(para), "\\* not a bullet");
+        assert_eq!(
+            extract_run_text_formatted(para, &InlineOptions::default()),
+            "\\* not a bullet"
+        );
     }
 
     #[test]
     fn test_extract_run_text_formatted_italic() {
         let para = r#"<a:r><a:rPr i="1"/><a:t>hi</a:t></a:r>"#;
-        assert_eq!(extract_run_text_formatted(para), "*hi*");
+        assert_eq!(
+            extract_run_text_formatted(para, &InlineOptions::default()),
+            "*hi*"
+        );
+    }
+
+    #[test]
+    fn test_extract_run_text_formatted_underline_highlight_scripts() {
+        let para = concat!(
+            r#"<a:r><a:rPr lang="en-US"/><a:t>H</a:t></a:r>"#,
+            r#"<a:r><a:rPr lang="en-US" baseline="-25000"/><a:t>2</a:t></a:r>"#,
+            r#"<a:r><a:rPr lang="en-US"/><a:t>O </a:t></a:r>"#,
+            r#"<a:r><a:rPr lang="en-US" u="sng" strike="noStrike"/><a:t>key</a:t></a:r>"#,
+            r#"<a:r><a:rPr lang="en-US"/><a:t> x</a:t></a:r>"#,
+            r#"<a:r><a:rPr lang="en-US" baseline="30000"/><a:t>2</a:t></a:r>"#,
+            r#"<a:r><a:rPr lang="en-US"><a:solidFill><a:srgbClr val="C00000"/></a:solidFill>"#,
+            r#"<a:highlight><a:srgbClr val="FFFF00"/></a:highlight></a:rPr><a:t> new</a:t></a:r>"#,
+        );
+        assert_eq!(
+            extract_run_text_formatted(para, &InlineOptions::default()),
+            "H<sub>2</sub>O <u>key</u> x<sup>2</sup> <mark>new</mark>"
+        );
+        let options = InlineOptions {
+            color: true,
+            ..InlineOptions::default()
+        };
+        assert!(extract_run_text_formatted(para, &options)
+            .ends_with(" <span style=\"color: #C00000\"><mark>new</mark></span>"));
+    }
+
+    #[test]
+    fn test_extract_slide_content_places_chart_between_shapes() {
+        let sp = |text: &str| {
+            format!(
+                r#"<p:sp><p:txBody><a:p><a:pPr><a:buNone/></a:pPr><a:r><a:t>{}</a:t></a:r></a:p></p:txBody></p:sp>"#,
+                text
+            )
+        };
+        let xml = format!(
+            r#"<p:spTree><p:sp><p:nvSpPr><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>Results</a:t></a:r></a:p></p:txBody></p:sp>{}<p:graphicFrame><a:graphic><a:graphicData><c:chart r:id="rId2"/></a:graphicData></a:graphic></p:graphicFrame>{}</p:spTree>"#,
+            sp("Before the chart"),
+            sp("After the chart")
+        );
+        let chart_xml = r#"<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart">
+<c:chart><c:title/><c:plotArea><c:pieChart>
+<c:ser><c:tx><c:v>Share</c:v></c:tx>
+<c:cat><c:strLit><c:pt idx="0"><c:v>Us</c:v></c:pt><c:pt idx="1"><c:v>Them</c:v></c:pt></c:strLit></c:cat>
+<c:val><c:numLit><c:pt idx="0"><c:v>60</c:v></c:pt><c:pt idx="1"><c:v>40</c:v></c:pt></c:numLit></c:val>
+</c:ser></c:pieChart></c:plotArea></c:chart></c:chartSpace>"#;
+        let rels = HashMap::from([("rId2".to_string(), "ppt/charts/chart1.xml".to_string())]);
+        let parts = HashMap::from([(
+            "ppt/charts/chart1.xml".to_string(),
+            chart_xml.as_bytes().to_vec(),
+        )]);
+        let mut sink = MediaSink::new(std::env::temp_dir());
+
+        let md = extract_slide_content(
+            &xml,
+            &rels,
+            &parts,
+            &mut sink,
+            &ImportOptions::default(),
+            &mut ImportReport::default(),
+            &Location::Slide { number: 1 },
+        );
+
+        assert_eq!(
+            md,
+            "# Results\n\nBefore the chart\n\n**Share**\n\n| Category | Share |\n| --- | --- |\n| Us | 60 |\n| Them | 40 |\n\nAfter the chart"
+        );
     }
 
     /// End-to-end regression test against `tests/fixtures/sample.pptx`
     /// (see `src/fixture_gen.rs`). Covers the slide-title/`---`-separator
-    /// structure, bold formatting, bullets, and embedded images together.
+    /// structure, bold formatting, bullets, embedded images, and charts
+    /// together.
     #[test]
     fn test_pptx_to_markdown_fixture() {
         let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.pptx");
-        let dir = std::env::temp_dir().join(format!("pourdown-pptx-fixture-{}", std::process::id()));
+        let dir =
+            std::env::temp_dir().join(format!("pourdown-pptx-fixture-{}", std::process::id()));
         let mut sink = MediaSink::new(dir.clone());
+        let mut report = ImportReport::default();
 
-        let md = pptx_to_markdown(path, &mut sink).expect("pptx_to_markdown should succeed");
+        let md = pptx_to_markdown(
+            path,
+            &mut sink,
+            &ImportOptions::default(),
+            &mut report,
+            &ImportProgress::default(),
+        )
+        .expect("pptx_to_markdown should succeed");
 
         assert!(md.contains("# Slide One"), "slide 1 title missing:\n{md}");
-        assert!(md.contains("**Bold intro**"), "bold body text missing:\n{md}");
+        assert!(
+            md.contains("**Bold intro**"),
+            "bold body text missing:\n{md}"
+        );
         assert!(md.contains("---"), "slide separator missing:\n{md}");
         assert!(md.contains("# Slide Two"), "slide 2 title missing:\n{md}");
         assert!(md.contains("- First bullet"), "bullet not detected:\n{md}");
-        assert!(md.contains("![](assets/image1.png)"), "image link missing:\n{md}");
+        assert!(
+            md.contains("![](assets/image1.png)"),
+            "image link missing:\n{md}"
+        );
+        assert!(
+            md.contains("**Market Share**\n\n| Category | Share |\n| --- | --- |\n| Us | 60 |\n| Them | 40 |"),
+            "chart table missing:\n{md}"
+        );
+        assert_eq!(report.stats.pages, Some(2));
+        assert_eq!(report.stats.images_skipped, 0);
 
         let _ = std::fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn test_pptx_to_markdown_stops_when_cancelled() {
+        use crate::convert::progress::{CancelToken, CANCELLED};
+
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.pptx");
+        let token = CancelToken::default();
+        token.cancel();
+        let mut sink = MediaSink::new(std::env::temp_dir());
+
+        let err = pptx_to_markdown(
+            path,
+            &mut sink,
+            &ImportOptions::default(),
+            &mut ImportReport::default(),
+            &ImportProgress::new(token),
+        )
+        .unwrap_err();
+
+        assert_eq!(err.0, CANCELLED);
+        assert!(
+            sink.is_empty(),
+            "no slide should be converted after cancellation"
+        );
+    }
 }
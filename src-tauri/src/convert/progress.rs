@@ -0,0 +1,112 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::ConversionError;
+
+/// Shared flag a caller flips to stop an import in flight. Cloning shares
+/// the flag, so the `cancel_import` command can hold one clone while the
+/// converter polls another.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Per-import progress reporting and cancellation, handed to every
+/// converter. Converters call [`ImportProgress::step`] between units of work
+/// (PDF pages, slides, sheets, DOCX body blocks); that's both where progress
+/// is reported and where a cancelled import stops.
+///
+/// `Default` gives a no-op instance (never cancelled, reports nowhere), used
+/// by the headless CLI and tests.
+pub struct ImportProgress {
+    cancel: CancelToken,
+    on_progress: Option<Box<dyn Fn(usize, usize) + Send + Sync>>,
+    /// Last whole percentage passed to `on_progress`, so a converter can call
+    /// `step` per paragraph without flooding the frontend with events.
+    last_percent: AtomicUsize,
+}
+
+/// The message a cancelled import fails with.
+pub const CANCELLED: &str = "Import cancelled";
+
+impl Default for ImportProgress {
+    fn default() -> Self {
+        ImportProgress::new(CancelToken::default())
+    }
+}
+
+impl ImportProgress {
+    pub fn new(cancel: CancelToken) -> Self {
+        ImportProgress {
+            cancel,
+            on_progress: None,
+            last_percent: AtomicUsize::new(usize::MAX),
+        }
+    }
+
+    /// Call `callback(done, total)` as work completes; invoked at most once
+    /// per whole percent.
+    pub fn with_callback(
+        mut self,
+        callback: impl Fn(usize, usize) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Record that `done` of `total` units are finished. Fails with
+    /// [`CANCELLED`] once the import's [`CancelToken`] has been cancelled, so
+    /// converters can simply `?` it at each unit boundary.
+    pub fn step(&self, done: usize, total: usize) -> Result<(), ConversionError> {
+        if self.cancel.is_cancelled() {
+            return Err(ConversionError(CANCELLED.to_string()));
+        }
+        if let Some(callback) = &self.on_progress {
+            let percent = (done.min(total) * 100).checked_div(total).unwrap_or(100);
+            if self.last_percent.swap(percent, Ordering::Relaxed) != percent {
+                callback(done.min(total), total);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_step_fails_once_cancelled() {
+        let token = CancelToken::default();
+        let progress = ImportProgress::new(token.clone());
+        assert!(progress.step(0, 3).is_ok());
+        token.cancel();
+        assert_eq!(progress.step(1, 3).unwrap_err().0, CANCELLED);
+    }
+
+    #[test]
+    fn test_callback_fires_once_per_percent() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let sink = calls.clone();
+        let progress = ImportProgress::new(CancelToken::default())
+            .with_callback(move |done, total| sink.lock().unwrap().push((done, total)));
+
+        for done in 0..=400 {
+            progress.step(done, 400).unwrap();
+        }
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 101);
+        assert_eq!(calls.first(), Some(&(0, 400)));
+        assert_eq!(calls.last(), Some(&(400, 400)));
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// What an importer dropped or changed while converting, returned to the
+/// frontend alongside the Markdown (see `ImportResult` in `main.rs`) so the UI
+/// can show what was lost instead of the loss going unnoticed.
+///
+/// Converters receive one as `&mut ImportReport` and record into it as they
+/// go; `images_written` is filled in by [`super::import_to_markdown`] from the
+/// [`super::media::MediaSink`], since that's where writes are de-duplicated.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub warnings: Vec<ImportWarning>,
+    pub stats: ImportStats,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ImportStats {
+    /// Pages (PDF, or DOCX when `docProps/app.xml` records it), slides
+    /// (PPTX) or sheets (spreadsheets). `None` when the format has no
+    /// meaningful page count.
+    pub pages: Option<usize>,
+    /// Tables emitted into the Markdown.
+    pub tables: usize,
+    /// Distinct image files written to the assets directory.
+    pub images_written: usize,
+    /// Images left out of the Markdown (unsupported format or missing part);
+    /// each also has a warning.
+    pub images_skipped: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportWarning {
+    pub kind: WarningKind,
+    /// Where in the source the loss happened; `None` for the whole document.
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// An image in a format the webview can't render and `MediaSink` couldn't
+    /// convert (e.g. an EMF+-only metafile).
+    UnsupportedImage,
+    /// An image reference whose media part is absent from the archive.
+    MissingImage,
+    /// Sheet rows beyond the per-sheet cap were left out.
+    RowsTruncated,
+    /// DOCX tracked insertions/deletions were accepted rather than kept as
+    /// CriticMarkup.
+    TrackedChanges,
+    /// DOCX or spreadsheet comments were dropped.
+    Comments,
+    /// A DOCX footnote/endnote is referenced but its text is missing.
+    Footnotes,
+    /// A PDF page has images but no extractable text (likely a scan).
+    NoTextLayer,
+    /// Repeated running header/footer lines were removed from PDF pages, or
+    /// DOCX page headers/footers were left out.
+    HeadersFootersRemoved,
+    /// Hidden spreadsheet sheets, rows or columns were left out.
+    HiddenContent,
+}
+
+/// Where a warning applies. Serialized as e.g.
+/// `{ "type": "page", "number": 3 }` or `{ "type": "sheet", "name": "Q1" }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Location {
+    /// 1-based PDF page.
+    Page {
+        number: usize,
+    },
+    /// 1-based PPTX slide.
+    Slide {
+        number: usize,
+    },
+    Sheet {
+        name: String,
+    },
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Location::Page { number } => write!(f, "page {}", number),
+            Location::Slide { number } => write!(f, "slide {}", number),
+            Location::Sheet { name } => write!(f, "sheet '{}'", name),
+        }
+    }
+}
+
+impl fmt::Display for ImportWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}: {}", location, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl ImportReport {
+    pub fn warn(
+        &mut self,
+        kind: WarningKind,
+        location: Option<Location>,
+        message: impl Into<String>,
+    ) {
+        self.warnings.push(ImportWarning {
+            kind,
+            location,
+            message: message.into(),
+        });
+    }
+
+    /// Record an image left out of the Markdown: counts it in
+    /// `images_skipped` and adds a matching warning.
+    pub fn skip_image(
+        &mut self,
+        kind: WarningKind,
+        location: Option<Location>,
+        message: impl Into<String>,
+    ) {
+        self.stats.images_skipped += 1;
+        self.warn(kind, location, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_image_counts_and_warns() {
+        let mut report = ImportReport::default();
+        report.skip_image(
+            WarningKind::UnsupportedImage,
+            Some(Location::Slide { number: 2 }),
+            "Unsupported image format: image3.emf",
+        );
+        assert_eq!(report.stats.images_skipped, 1);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(
+            report.warnings[0].to_string(),
+            "slide 2: Unsupported image format: image3.emf"
+        );
+    }
+
+    #[test]
+    fn test_report_serializes_typed_warnings() {
+        let mut report = ImportReport::default();
+        report.warn(
+            WarningKind::RowsTruncated,
+            Some(Location::Sheet {
+                name: "Q1".to_string(),
+            }),
+            "12 rows were omitted",
+        );
+        report.warn(WarningKind::Comments, None, "3 comments were dropped");
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["warnings"][0]["kind"], "rows_truncated");
+        assert_eq!(json["warnings"][0]["location"]["type"], "sheet");
+        assert_eq!(json["warnings"][0]["location"]["name"], "Q1");
+        assert!(json["warnings"][1]["location"].is_null());
+        assert_eq!(json["stats"]["tables"], 0);
+        assert!(json["stats"]["pages"].is_null());
+    }
+}
@@ -0,0 +1,688 @@
+//! Content sniffing: identify what a file actually is from its bytes rather
+//! than trusting its extension or the `format` string the caller passed.
+//!
+//! Mislabeled files are common in practice — a legacy `.doc` renamed to
+//! `.docx`, a PDF saved without an extension, a CSV export named `.xlsx` —
+//! and feeding one to the wrong parser produces a confusing low-level error
+//! ("invalid Zip archive", "Failed to parse DOCX: ..."). [`resolve_importer`]
+//! redirects a file to the importer that can actually read it, or rejects it
+//! with an error naming the detected type.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use super::{registry, ConversionError, Converter};
+
+/// How many leading bytes are inspected for magic numbers and text heuristics.
+const SNIFF_LEN: usize = 8192;
+
+/// The format a file's content was identified as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectedFormat {
+    Docx,
+    Xlsx,
+    Pptx,
+    Pdf,
+    Ods,
+    /// Word 97-2003 binary document (OLE compound file).
+    LegacyWord,
+    /// Excel 97-2003 binary workbook (OLE compound file).
+    LegacyExcel,
+    /// PowerPoint 97-2003 binary presentation (OLE compound file).
+    LegacyPowerPoint,
+    /// A password-protected Office file: OOXML wrapped in an encrypted OLE
+    /// container, unreadable without the password.
+    EncryptedOffice,
+    /// An OpenDocument file other than a spreadsheet (e.g. `.odt`, `.odp`).
+    OtherOpenDocument,
+    /// A ZIP archive that isn't a recognized Office/OpenDocument package.
+    OtherZip,
+    Rtf,
+    Html,
+    /// Delimited text (comma/semicolon/tab separated values).
+    Csv,
+    PlainText,
+    Unknown,
+}
+
+impl DetectedFormat {
+    /// Id of the registered importer that reads this content, if any.
+    pub fn importer_id(self) -> Option<&'static str> {
+        match self {
+            DetectedFormat::Docx => Some("docx"),
+            DetectedFormat::Xlsx | DetectedFormat::Ods | DetectedFormat::LegacyExcel => {
+                Some("xlsx")
+            }
+            DetectedFormat::Pptx => Some("pptx"),
+            DetectedFormat::Pdf => Some("pdf"),
+            _ => None,
+        }
+    }
+
+    /// Human-readable description, used in error messages.
+    pub fn description(self) -> &'static str {
+        match self {
+            DetectedFormat::Docx => "a Word document (.docx)",
+            DetectedFormat::Xlsx => "an Excel workbook (.xlsx)",
+            DetectedFormat::Pptx => "a PowerPoint presentation (.pptx)",
+            DetectedFormat::Pdf => "a PDF document",
+            DetectedFormat::Ods => "an OpenDocument spreadsheet (.ods)",
+            DetectedFormat::LegacyWord => "a legacy Word 97-2003 document (.doc)",
+            DetectedFormat::LegacyExcel => "a legacy Excel 97-2003 workbook (.xls)",
+            DetectedFormat::LegacyPowerPoint => "a legacy PowerPoint 97-2003 presentation (.ppt)",
+            DetectedFormat::EncryptedOffice => "a password-protected Office document",
+            DetectedFormat::OtherOpenDocument => "an OpenDocument file that isn't a spreadsheet",
+            DetectedFormat::OtherZip => "a ZIP archive that isn't an Office document",
+            DetectedFormat::Rtf => "an RTF document",
+            DetectedFormat::Html => "an HTML page",
+            DetectedFormat::Csv => "CSV text",
+            DetectedFormat::PlainText => "plain text",
+            DetectedFormat::Unknown => "an unrecognized file type",
+        }
+    }
+
+    /// What the user can do about a file no importer reads, appended to the
+    /// rejection error.
+    fn remedy(self) -> &'static str {
+        match self {
+            DetectedFormat::LegacyWord => {
+                " Open it in Word and save it as .docx, then import that."
+            }
+            DetectedFormat::LegacyPowerPoint => {
+                " Open it in PowerPoint and save it as .pptx, then import that."
+            }
+            DetectedFormat::EncryptedOffice => " Remove the password protection and try again.",
+            DetectedFormat::Csv => {
+                " Open it in a spreadsheet app and save it as .xlsx, then import that."
+            }
+            DetectedFormat::Html | DetectedFormat::PlainText => {
+                " Open it directly as a document instead of importing it."
+            }
+            _ => "",
+        }
+    }
+}
+
+/// Identify the format of the file at `path` from its content. Only the
+/// parts detection looks at are read: the leading bytes, and for ZIP and
+/// OLE containers their directories.
+pub fn detect_file_format(path: &str) -> Result<DetectedFormat, ConversionError> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| ConversionError(format!("Failed to read file: {}", e)))?;
+    detect_reader(file).map_err(|e| ConversionError(format!("Failed to read file: {}", e)))
+}
+
+/// Identify a format from the file's content: magic numbers first, then
+/// (for ZIP packages) the `[Content_Types].xml` / ODF `mimetype` part, then
+/// (for OLE compound files) the names of the streams inside, then text
+/// heuristics.
+fn detect_reader<R: Read + Seek>(mut reader: R) -> std::io::Result<DetectedFormat> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    (&mut reader)
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)?;
+
+    // PDF readers tolerate junk before the header (the spec allows it within
+    // the first 1024 bytes), so search rather than require offset 0.
+    if find_bytes(&head[..head.len().min(1024)], b"%PDF-").is_some() {
+        return Ok(DetectedFormat::Pdf);
+    }
+    if head.starts_with(b"PK\x03\x04") {
+        return Ok(detect_zip_package(reader));
+    }
+    if head.starts_with(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]) {
+        return Ok(detect_ole_document(&mut reader));
+    }
+    if head.starts_with(b"{\\rtf") {
+        return Ok(DetectedFormat::Rtf);
+    }
+    Ok(detect_text(&head))
+}
+
+/// Classify an OOXML/ODF package by its declared content types. OOXML names
+/// the main part's type in `[Content_Types].xml`; ODF stores its MIME type
+/// in a `mimetype` entry.
+fn detect_zip_package<R: Read + Seek>(reader: R) -> DetectedFormat {
+    let mut archive = match zip::ZipArchive::new(reader) {
+        Ok(a) => a,
+        Err(_) => return DetectedFormat::Unknown,
+    };
+
+    if let Some(content_types) = read_zip_text(&mut archive, "[Content_Types].xml") {
+        return classify_content_types(&content_types);
+    }
+    // A package missing `[Content_Types].xml` (some generators skip it) is
+    // still identifiable by its main part's conventional location.
+    let names: Vec<&str> = archive.file_names().collect();
+    if names.contains(&"word/document.xml") {
+        return DetectedFormat::Docx;
+    }
+    if names.contains(&"xl/workbook.xml") || names.contains(&"xl/workbook.bin") {
+        return DetectedFormat::Xlsx;
+    }
+    if names.iter().any(|n| n.starts_with("ppt/slides/")) {
+        return DetectedFormat::Pptx;
+    }
+    if let Some(mimetype) = read_zip_text(&mut archive, "mimetype") {
+        return match mimetype.trim() {
+            "application/vnd.oasis.opendocument.spreadsheet"
+            | "application/vnd.oasis.opendocument.spreadsheet-template" => DetectedFormat::Ods,
+            m if m.starts_with("application/vnd.oasis.opendocument.") => {
+                DetectedFormat::OtherOpenDocument
+            }
+            _ => DetectedFormat::OtherZip,
+        };
+    }
+    DetectedFormat::OtherZip
+}
+
+/// Classify an OOXML package from its `[Content_Types].xml`. Matches on the
+/// main part's content-type stem so document, template and macro-enabled
+/// variants (`.docm`, `.xltx`, `.pptm`, ...) are all recognized.
+fn classify_content_types(xml: &str) -> DetectedFormat {
+    if xml.contains("wordprocessingml.document.main+xml")
+        || xml.contains("wordprocessingml.template.main+xml")
+        || xml.contains("application/vnd.ms-word.document.macroEnabled.main+xml")
+        || xml.contains("application/vnd.ms-word.template.macroEnabledTemplate.main+xml")
+    {
+        DetectedFormat::Docx
+    } else if xml.contains("spreadsheetml.sheet.main+xml")
+        || xml.contains("spreadsheetml.template.main+xml")
+        || xml.contains("application/vnd.ms-excel.sheet.macroEnabled.main+xml")
+        || xml.contains("application/vnd.ms-excel.sheet.binary.macroEnabled.main")
+        || xml.contains("application/vnd.ms-excel.template.macroEnabled.main+xml")
+    {
+        DetectedFormat::Xlsx
+    } else if xml.contains("presentationml.presentation.main+xml")
+        || xml.contains("presentationml.slideshow.main+xml")
+        || xml.contains("presentationml.template.main+xml")
+        || xml.contains("application/vnd.ms-powerpoint.presentation.macroEnabled.main+xml")
+        || xml.contains("application/vnd.ms-powerpoint.slideshow.macroEnabled.main+xml")
+    {
+        DetectedFormat::Pptx
+    } else {
+        DetectedFormat::OtherZip
+    }
+}
+
+fn read_zip_text<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, name: &str) -> Option<String> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+/// Classify an OLE compound file (the pre-2007 Office container) by the
+/// names of the streams in its root storage. Only the root counts: an
+/// embedded object (say, an Excel chart in a slide deck) keeps its own
+/// streams in a storage of its own.
+fn detect_ole_document<R: Read + Seek>(reader: &mut R) -> DetectedFormat {
+    let names = cfb_root_streams(reader).unwrap_or_default();
+    let has_stream = |name: &str| names.iter().any(|n| n.eq_ignore_ascii_case(name));
+    if has_stream("EncryptedPackage") {
+        DetectedFormat::EncryptedOffice
+    } else if has_stream("WordDocument") {
+        DetectedFormat::LegacyWord
+    } else if has_stream("PowerPoint Document") {
+        DetectedFormat::LegacyPowerPoint
+    } else if has_stream("Workbook") || has_stream("Book") {
+        // `Book` is the Excel 5.0/95 name for the workbook stream.
+        DetectedFormat::LegacyExcel
+    } else {
+        DetectedFormat::Unknown
+    }
+}
+
+/// CFB sector ids at or above this are chain markers (end of chain, free,
+/// FAT or DIFAT sector), not sectors.
+const CFB_MAX_SECTOR: u32 = 0xFFFF_FFFA;
+/// Sectors of the directory chain read before giving up on a looping chain.
+const CFB_MAX_DIRECTORY_SECTORS: usize = 4096;
+
+/// Names of the streams in a compound file's root storage. Reads just the
+/// header, the FAT sectors the directory chain runs through and the
+/// directory itself; `None` if the structure doesn't hold together.
+fn cfb_root_streams<R: Read + Seek>(reader: &mut R) -> Option<Vec<String>> {
+    let u32_at = |buf: &[u8], at: usize| {
+        u32::from_le_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]])
+    };
+    let mut header = [0u8; 512];
+    reader.seek(SeekFrom::Start(0)).ok()?;
+    reader.read_exact(&mut header).ok()?;
+    // 512-byte sectors in version 3 files, 4096 in version 4.
+    let shift = u16::from_le_bytes([header[0x1E], header[0x1F]]);
+    if shift != 9 && shift != 12 {
+        return None;
+    }
+    let sector_size = 1usize << shift;
+    let read_sector = |reader: &mut R, id: u32| -> Option<Vec<u8>> {
+        let mut sector = vec![0; sector_size];
+        reader
+            .seek(SeekFrom::Start((u64::from(id) + 1) * sector_size as u64))
+            .ok()?;
+        reader.read_exact(&mut sector).ok()?;
+        Some(sector)
+    };
+
+    // The header lists the first 109 FAT sectors; a chain of DIFAT sectors
+    // lists the rest. Neither can name more sectors than the file holds.
+    let file_sectors = (reader.seek(SeekFrom::End(0)).ok()? >> shift) as usize;
+    let fat_count = (u32_at(&header, 0x2C) as usize).min(file_sectors);
+    let mut fat_sectors: Vec<u32> = (0..109).map(|i| u32_at(&header, 0x4C + i * 4)).collect();
+    let mut difat = u32_at(&header, 0x44);
+    for _ in 0..file_sectors {
+        if fat_sectors.len() >= fat_count || difat >= CFB_MAX_SECTOR {
+            break;
+        }
+        let sector = read_sector(reader, difat)?;
+        let per_sector = sector_size / 4 - 1;
+        fat_sectors.extend((0..per_sector).map(|i| u32_at(&sector, i * 4)));
+        difat = u32_at(&sector, per_sector * 4);
+    }
+    fat_sectors.truncate(fat_count);
+
+    let next_sector = |reader: &mut R, id: u32| -> Option<u32> {
+        let per_sector = sector_size / 4;
+        let fat_sector = *fat_sectors.get(id as usize / per_sector)?;
+        let sector = read_sector(reader, fat_sector)?;
+        Some(u32_at(&sector, (id as usize % per_sector) * 4))
+    };
+    let mut directory = Vec::new();
+    let mut id = u32_at(&header, 0x30);
+    for _ in 0..CFB_MAX_DIRECTORY_SECTORS {
+        if id >= CFB_MAX_SECTOR {
+            break;
+        }
+        directory.extend(read_sector(reader, id)?);
+        id = next_sector(reader, id)?;
+    }
+
+    // Each 128-byte entry: a UTF-16LE name, its byte length, the object
+    // type and the left/right sibling and child ids of a red-black tree.
+    // The root storage is entry 0; its children hang off its child id.
+    let entries: Vec<&[u8]> = directory.chunks_exact(128).collect();
+    let mut names = Vec::new();
+    let mut pending = vec![u32_at(entries.first()?, 76)];
+    let mut visited = 0;
+    while let Some(id) = pending.pop() {
+        let Some(entry) = entries.get(id as usize) else {
+            continue;
+        };
+        visited += 1;
+        if visited > entries.len() {
+            break;
+        }
+        pending.push(u32_at(entry, 68));
+        pending.push(u32_at(entry, 72));
+        // Type 2 is a stream; storages (1) hold embedded objects.
+        if entry[66] == 2 {
+            let len = (u16::from_le_bytes([entry[64], entry[65]]) as usize / 2).clamp(1, 32) - 1;
+            let units: Vec<u16> = entry[..len * 2]
+                .chunks_exact(2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .collect();
+            names.push(String::from_utf16_lossy(&units));
+        }
+    }
+    Some(names)
+}
+
+/// Classify a file with no binary signature: HTML, delimited text, plain
+/// text, or (if it contains NUL bytes / isn't UTF-8) unknown binary.
+fn detect_text(head: &[u8]) -> DetectedFormat {
+    if head.is_empty() || head.contains(&0) {
+        return DetectedFormat::Unknown;
+    }
+    // `head` may end mid-character; only the valid prefix matters here.
+    let text = match std::str::from_utf8(head) {
+        Ok(t) => t,
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&head[..e.valid_up_to()]).unwrap_or("")
+        }
+        Err(_) => return DetectedFormat::Unknown,
+    };
+    let text = text.trim_start_matches('\u{FEFF}');
+
+    let lower_start = text
+        .trim_start()
+        .chars()
+        .take(64)
+        .collect::<String>()
+        .to_lowercase();
+    if lower_start.starts_with("<!doctype html") || lower_start.starts_with("<html") {
+        return DetectedFormat::Html;
+    }
+    if looks_like_delimited(text) {
+        return DetectedFormat::Csv;
+    }
+    DetectedFormat::PlainText
+}
+
+/// True if the first few non-empty lines all contain the same, non-zero
+/// number of one delimiter (`,`, `;` or tab) — the shape of a CSV/TSV export.
+/// Requires at least two lines so a single sentence with a comma isn't CSV.
+fn looks_like_delimited(text: &str) -> bool {
+    let mut lines: Vec<&str> = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .take(10)
+        .collect();
+    // The last line may have been cut off by the sniff window.
+    if lines.len() > 2 {
+        lines.pop();
+    }
+    if lines.len() < 2 {
+        return false;
+    }
+    [',', ';', '\t'].iter().any(|&delim| {
+        let first = lines[0].matches(delim).count();
+        first > 0 && lines.iter().all(|l| l.matches(delim).count() == first)
+    })
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || haystack.len() < needle.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Pick the importer for the file at `path`. The file's detected content
+/// wins over `requested` (the caller's format id or the file extension's):
+/// a file whose content an importer can read is redirected to it, and one no
+/// importer can read is rejected with an error naming what it actually is.
+/// Content that can't be identified at all falls back to `requested`, so
+/// that parser reports its own error.
+pub fn resolve_importer(
+    requested: Option<&str>,
+    path: &str,
+) -> Result<&'static dyn Converter, ConversionError> {
+    let detected = detect_file_format(path)?;
+    resolve_detected(requested, detected)
+}
+
+fn resolve_detected(
+    requested: Option<&str>,
+    detected: DetectedFormat,
+) -> Result<&'static dyn Converter, ConversionError> {
+    if let Some(id) = detected.importer_id() {
+        return registry()
+            .importer(id)
+            .ok_or_else(|| ConversionError(format!("Unsupported import format: {}", id)));
+    }
+
+    match (detected, requested) {
+        (DetectedFormat::Unknown, Some(id)) => registry()
+            .importer(id)
+            .ok_or_else(|| ConversionError(format!("Unsupported import format: {}", id))),
+        (DetectedFormat::Unknown, None) => Err(ConversionError(
+            "Unsupported file type: the file's content doesn't match any importable format"
+                .to_string(),
+        )),
+        (other, Some(id)) => {
+            let expected = registry().importer(id).map(|c| c.name()).unwrap_or(id);
+            Err(ConversionError(format!(
+                "Can't import this file as {}: it is {}.{}",
+                expected,
+                other.description(),
+                other.remedy()
+            )))
+        }
+        (other, None) => Err(ConversionError(format!(
+            "Can't import this file: it is {}.{}",
+            other.description(),
+            other.remedy()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    fn zip_with(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            for (name, content) in entries {
+                zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                    .unwrap();
+                zip.write_all(content.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+        buf
+    }
+
+    fn detect_format(bytes: &[u8]) -> DetectedFormat {
+        detect_reader(Cursor::new(bytes)).unwrap()
+    }
+
+    const NONE: u32 = 0xFFFF_FFFF;
+
+    /// A version 3 compound file: sector 0 is the FAT, sector 1 the
+    /// directory (up to four `(name, type, left, right, child)` entries,
+    /// the first being the root), then `data`.
+    fn ole_with_entries(entries: &[(&str, u8, u32, u32, u32)], data: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+        bytes.resize(512, 0);
+        bytes[0x18..0x1C].copy_from_slice(&[0x3E, 0x00, 0x03, 0x00]);
+        bytes[0x1C..0x20].copy_from_slice(&[0xFE, 0xFF, 0x09, 0x00]);
+        bytes[0x2C..0x30].copy_from_slice(&1u32.to_le_bytes());
+        bytes[0x30..0x34].copy_from_slice(&1u32.to_le_bytes());
+        bytes[0x44..0x48].copy_from_slice(&0xFFFF_FFFEu32.to_le_bytes());
+        bytes[0x4C..0x50].copy_from_slice(&0u32.to_le_bytes());
+        for i in 1..109 {
+            bytes[0x4C + i * 4..0x50 + i * 4].copy_from_slice(&NONE.to_le_bytes());
+        }
+        let mut fat = vec![0xFFFF_FFFDu32, 0xFFFF_FFFE];
+        fat.resize(128, NONE);
+        bytes.extend(fat.iter().flat_map(|id| id.to_le_bytes()));
+        for &(name, kind, left, right, child) in entries {
+            let mut entry = [0u8; 128];
+            let utf16: Vec<u8> = name
+                .encode_utf16()
+                .chain([0])
+                .flat_map(|u| u.to_le_bytes())
+                .collect();
+            entry[..utf16.len()].copy_from_slice(&utf16);
+            entry[64..66].copy_from_slice(&(utf16.len() as u16).to_le_bytes());
+            entry[66] = kind;
+            entry[68..72].copy_from_slice(&left.to_le_bytes());
+            entry[72..76].copy_from_slice(&right.to_le_bytes());
+            entry[76..80].copy_from_slice(&child.to_le_bytes());
+            bytes.extend(entry);
+        }
+        bytes.resize(1536, 0);
+        bytes.extend(data);
+        bytes
+    }
+
+    fn ole_with_stream(name: &str) -> Vec<u8> {
+        ole_with_entries(
+            &[
+                ("Root Entry", 5, NONE, NONE, 1),
+                (name, 2, NONE, NONE, NONE),
+            ],
+            &[],
+        )
+    }
+
+    #[test]
+    fn test_detects_fixture_formats() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+        let detect = |name: &str| detect_file_format(&format!("{}/{}", dir, name)).unwrap();
+        assert_eq!(detect("sample.docx"), DetectedFormat::Docx);
+        assert_eq!(detect("sample.xlsx"), DetectedFormat::Xlsx);
+        assert_eq!(detect("sample.pdf"), DetectedFormat::Pdf);
+        // The pptx fixture deliberately omits `[Content_Types].xml` (see
+        // fixture_gen.rs), so this also covers the part-name fallback.
+        assert_eq!(detect("sample.pptx"), DetectedFormat::Pptx);
+    }
+
+    #[test]
+    fn test_detect_pdf_with_leading_junk() {
+        assert_eq!(
+            detect_format(b"\xEF\xBB\xBF\r\n%PDF-1.7\n..."),
+            DetectedFormat::Pdf
+        );
+    }
+
+    #[test]
+    fn test_detect_ooxml_by_content_types() {
+        let xlsm = zip_with(&[(
+            "[Content_Types].xml",
+            r#"<Types><Override PartName="/xl/workbook.xml" ContentType="application/vnd.ms-excel.sheet.macroEnabled.main+xml"/></Types>"#,
+        )]);
+        assert_eq!(detect_format(&xlsm), DetectedFormat::Xlsx);
+        let pptx = zip_with(&[(
+            "[Content_Types].xml",
+            r#"<Types><Override PartName="/ppt/presentation.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml"/></Types>"#,
+        )]);
+        assert_eq!(detect_format(&pptx), DetectedFormat::Pptx);
+        assert_eq!(
+            detect_format(&zip_with(&[("readme.txt", "hi")])),
+            DetectedFormat::OtherZip
+        );
+    }
+
+    #[test]
+    fn test_detect_odf_by_mimetype() {
+        let ods = zip_with(&[("mimetype", "application/vnd.oasis.opendocument.spreadsheet")]);
+        assert_eq!(detect_format(&ods), DetectedFormat::Ods);
+        let odt = zip_with(&[("mimetype", "application/vnd.oasis.opendocument.text")]);
+        assert_eq!(detect_format(&odt), DetectedFormat::OtherOpenDocument);
+    }
+
+    #[test]
+    fn test_detect_ole_documents_by_stream_name() {
+        assert_eq!(
+            detect_format(&ole_with_stream("WordDocument")),
+            DetectedFormat::LegacyWord
+        );
+        assert_eq!(
+            detect_format(&ole_with_stream("Workbook")),
+            DetectedFormat::LegacyExcel
+        );
+        assert_eq!(
+            detect_format(&ole_with_stream("PowerPoint Document")),
+            DetectedFormat::LegacyPowerPoint
+        );
+        assert_eq!(
+            detect_format(&ole_with_stream("EncryptedPackage")),
+            DetectedFormat::EncryptedOffice
+        );
+        assert_eq!(
+            detect_format(&ole_with_stream("Book")),
+            DetectedFormat::LegacyExcel
+        );
+        assert_eq!(
+            detect_format(&ole_with_stream("Contents")),
+            DetectedFormat::Unknown
+        );
+    }
+
+    #[test]
+    fn test_detect_ole_reads_only_root_stream_names() {
+        // A deck with an embedded workbook (its own storage) and "Order Book"
+        // in its slide text is still a presentation.
+        let slide_text: Vec<u8> = "Order Book"
+            .encode_utf16()
+            .flat_map(|u| u.to_le_bytes())
+            .collect();
+        let ppt = ole_with_entries(
+            &[
+                ("Root Entry", 5, NONE, NONE, 1),
+                ("PowerPoint Document", 2, NONE, 2, NONE),
+                ("MBD0001", 1, NONE, NONE, 3),
+                ("Workbook", 2, NONE, NONE, NONE),
+            ],
+            &slide_text,
+        );
+        assert_eq!(detect_format(&ppt), DetectedFormat::LegacyPowerPoint);
+        // A truncated directory isn't guessed at.
+        assert_eq!(detect_format(&ppt[..1024]), DetectedFormat::Unknown);
+    }
+
+    #[test]
+    fn test_detect_ole_with_looping_difat() {
+        // A header claiming four billion FAT sectors whose DIFAT chain
+        // points back at itself must not be walked forever.
+        let mut doc = ole_with_stream("WordDocument");
+        doc[0x2C..0x30].copy_from_slice(&u32::MAX.to_le_bytes());
+        doc[0x44..0x48].copy_from_slice(&2u32.to_le_bytes());
+        let mut difat = vec![NONE; 127];
+        difat.push(2);
+        doc.extend(difat.iter().flat_map(|id| id.to_le_bytes()));
+        assert_eq!(detect_format(&doc), DetectedFormat::LegacyWord);
+    }
+
+    #[test]
+    fn test_detect_text_formats() {
+        assert_eq!(
+            detect_format(b"Name,Date,Amount\nAda,2024-01-02,3\nBob,2024-02-03,4\n"),
+            DetectedFormat::Csv
+        );
+        assert_eq!(detect_format(b"a\tb\n1\t2\n"), DetectedFormat::Csv);
+        assert_eq!(
+            detect_format(b"<!DOCTYPE html><html></html>"),
+            DetectedFormat::Html
+        );
+        assert_eq!(
+            detect_format(b"Hello, world.\nSecond line here.\n"),
+            DetectedFormat::PlainText
+        );
+        assert_eq!(detect_format(b"{\\rtf1\\ansi hello}"), DetectedFormat::Rtf);
+        assert_eq!(detect_format(&[0x00, 0x01, 0x02]), DetectedFormat::Unknown);
+    }
+
+    #[test]
+    fn test_resolve_redirects_to_detected_importer() {
+        // A PDF saved with a .docx name (or no extension) goes to the PDF importer.
+        assert_eq!(
+            resolve_detected(Some("docx"), DetectedFormat::Pdf)
+                .unwrap()
+                .id(),
+            "pdf"
+        );
+        assert_eq!(
+            resolve_detected(None, DetectedFormat::Pdf).unwrap().id(),
+            "pdf"
+        );
+        // Legacy .xls is read by the spreadsheet importer (calamine).
+        assert_eq!(
+            resolve_detected(Some("xlsx"), DetectedFormat::LegacyExcel)
+                .unwrap()
+                .id(),
+            "xlsx"
+        );
+    }
+
+    #[test]
+    fn test_resolve_rejects_unsupported_content_naming_detected_type() {
+        let err = resolve_detected(Some("docx"), DetectedFormat::LegacyWord)
+            .err()
+            .unwrap();
+        assert!(
+            err.0.contains("legacy Word 97-2003 document (.doc)"),
+            "{}",
+            err.0
+        );
+        assert!(err.0.contains("Word Document"), "{}", err.0);
+        let err = resolve_detected(Some("xlsx"), DetectedFormat::Csv)
+            .err()
+            .unwrap();
+        assert!(err.0.contains("CSV text"), "{}", err.0);
+    }
+
+    #[test]
+    fn test_resolve_unknown_content_falls_back_to_requested() {
+        assert_eq!(
+            resolve_detected(Some("pptx"), DetectedFormat::Unknown)
+                .unwrap()
+                .id(),
+            "pptx"
+        );
+        assert!(resolve_detected(None, DetectedFormat::Unknown).is_err());
+    }
+}
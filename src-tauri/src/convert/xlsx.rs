@@ -1,12 +1,121 @@
-use calamine::{open_workbook_auto, Data, Reader};
+use calamine::{open_workbook_auto_from_rs, Data, Dimensions, Range, Reader, SheetVisible, Sheets};
 use chrono::{Days, NaiveDate};
-use std::io::Read;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read, Seek};
 
+use super::html::{markdown_inline_to_html, spanned_table_html, SpannedCell};
 use super::media::MediaSink;
-use super::ConversionError;
+use super::numfmt::NumberFormat;
+use super::pdf::escape_alt_text;
+use super::pptx::xml_decode;
+use super::progress::ImportProgress;
+use super::report::{ImportReport, Location, WarningKind};
+use super::{ConversionError, Converter, ImportOptions};
 
+/// Default for [`XlsxOptions::max_rows_per_sheet`].
 const MAX_ROWS_PER_SHEET: usize = 500;
 
+/// Spreadsheet knobs in [`ImportOptions`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct XlsxOptions {
+    /// Data rows (after the header) emitted per sheet; the rest are omitted
+    /// with a note.
+    pub max_rows_per_sheet: usize,
+    pub merged_cells: MergedCells,
+    /// Keep hidden and "very hidden" sheets, and hidden rows and columns,
+    /// instead of leaving them out.
+    pub include_hidden: bool,
+    pub formulas: Formulas,
+    /// Turn cells with a hyperlink into Markdown links.
+    pub hyperlinks: bool,
+    pub comments: CellComments,
+}
+
+impl Default for XlsxOptions {
+    fn default() -> Self {
+        XlsxOptions {
+            max_rows_per_sheet: MAX_ROWS_PER_SHEET,
+            merged_cells: MergedCells::default(),
+            include_hidden: false,
+            formulas: Formulas::default(),
+            hyperlinks: false,
+            comments: CellComments::default(),
+        }
+    }
+}
+
+/// What a merged cell range becomes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergedCells {
+    /// The sheet becomes an HTML `<table>` with `colspan`/`rowspan`, as DOCX
+    /// tables with merged cells do.
+    #[default]
+    Span,
+    /// The range's value is repeated in every cell it covers, keeping a GFM
+    /// table.
+    Fill,
+}
+
+/// What a formula cell shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Formulas {
+    /// The value Excel last calculated, as for any other cell.
+    #[default]
+    Values,
+    /// The value followed by the formula as code: ``42 `=SUM(B2:B4)` ``.
+    Beside,
+    /// Only the formula, as code.
+    Instead,
+}
+
+/// How cell comments (threaded comments and legacy notes) are imported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CellComments {
+    #[default]
+    Drop,
+    /// A `[^n]` footnote on the cell whose definition is `author: text`,
+    /// with each reply as a further paragraph.
+    Footnotes,
+}
+
+/// Spreadsheet importer registered in [`super::FormatRegistry`]. calamine
+/// reads legacy `.xls` and OpenDocument `.ods` as well as `.xlsx`.
+pub struct XlsxConverter;
+
+impl Converter for XlsxConverter {
+    fn id(&self) -> &'static str {
+        "xlsx"
+    }
+    fn name(&self) -> &'static str {
+        "Spreadsheet"
+    }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xlsx", "xlsm", "xlsb", "xls", "ods"]
+    }
+    fn mime_types(&self) -> &'static [&'static str] {
+        &[
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            "application/vnd.ms-excel",
+            "application/vnd.oasis.opendocument.spreadsheet",
+        ]
+    }
+    fn to_markdown(
+        &self,
+        path: &str,
+        media: &mut MediaSink,
+        options: &ImportOptions,
+        report: &mut ImportReport,
+        progress: &ImportProgress,
+    ) -> Result<String, ConversionError> {
+        xlsx_to_markdown(path, media, options, report, progress)
+    }
+}
+
 /// Convert an Excel serial date number to an ISO date string (YYYY-MM-DD).
 /// Excel's epoch is 1899-12-30 (accounting for the Lotus 1-2-3 leap-year bug).
 fn excel_serial_to_date(serial: i64) -> String {
@@ -27,7 +136,6 @@ fn looks_like_excel_date(f: f64) -> bool {
     f.fract() == 0.0 && (25_569.0..=73_050.0).contains(&f)
 }
 
-
 /// Convert a cell value to a Markdown-safe string.
 /// - Newlines are collapsed to a space (GFM tables must be single-line).
 /// - Pipe characters are escaped to avoid breaking table structure.
@@ -55,7 +163,12 @@ pub fn cell_to_string(cell: &Data) -> String {
         Data::DateTimeIso(s) => s.clone(),
         Data::DurationIso(s) => s.clone(),
     };
-    // Collapse cell-internal newlines; escape pipes so table structure is intact.
+    escape_cell(&s)
+}
+
+/// Collapse cell-internal newlines and escape pipes so table structure is
+/// intact.
+fn escape_cell(s: &str) -> String {
     s.replace("\r\n", " ")
         .replace(['\r', '\n'], " ")
         .replace('|', "\\|")
@@ -81,24 +194,104 @@ fn cell_to_string_ctx(cell: &Data, date_col: bool) -> String {
     cell_to_string(cell)
 }
 
-/// Convert an Excel file (xlsx/xls/ods/csv) to Markdown.
-/// Each sheet becomes a ## heading followed by a GFM table.
-/// Rows are capped at MAX_ROWS_PER_SHEET with an inline note if truncated.
-pub fn xlsx_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, ConversionError> {
-    let mut workbook = open_workbook_auto(path)
+/// Render a cell as Excel displays it when its number `format` is known
+/// (xlsx styles), otherwise as [`cell_to_string_ctx`] does.
+fn formatted_cell(
+    cell: &Data,
+    format: Option<&NumberFormat>,
+    date_col: bool,
+    date1904: bool,
+) -> String {
+    let value = match cell {
+        Data::Float(f) => *f,
+        Data::Int(i) => *i as f64,
+        Data::DateTime(dt) => dt.as_f64(),
+        _ => return cell_to_string_ctx(cell, date_col),
+    };
+    match format {
+        Some(format) => escape_cell(&format.format(value, date1904)),
+        None => cell_to_string_ctx(cell, date_col),
+    }
+}
+
+/// Convert a spreadsheet (xlsx/xlsm/xlsb/xls/ods) to Markdown.
+/// Each sheet becomes a ## heading followed by a GFM table per Excel Table
+/// or island of data (titled with ### when there are several).
+/// Rows are capped at `options.xlsx.max_rows_per_sheet` with an inline note
+/// (and a `report` warning) if truncated.
+pub fn xlsx_to_markdown(
+    path: &str,
+    media: &mut MediaSink,
+    options: &ImportOptions,
+    report: &mut ImportReport,
+    progress: &ImportProgress,
+) -> Result<String, ConversionError> {
+    let max_rows = options.xlsx.max_rows_per_sheet;
+    // Opened by content rather than by extension (calamine's
+    // `open_workbook_auto` trusts the extension), so a legacy `.xls` saved
+    // under an `.xlsx` name still reads — see `sniff::resolve_importer`.
+    let bytes =
+        std::fs::read(path).map_err(|e| ConversionError(format!("Failed to read file: {}", e)))?;
+    // Hidden rows and columns and number formats live only in the package
+    // XML; read them before calamine takes the bytes.
+    let mut info = read_workbook_info(&bytes);
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(bytes))
         .map_err(|e| ConversionError(format!("Failed to open spreadsheet: {}", e)))?;
 
-    let sheet_names: Vec<String> = workbook.sheet_names().to_vec();
+    let sheets = workbook.sheets_metadata().to_vec();
+    report.stats.pages = Some(sheets.len());
     let mut output = String::new();
+    // Footnote definitions for cell comments, numbered across the workbook
+    // along with the notes listed under HTML tables.
+    let mut notes: Vec<(usize, String)> = Vec::new();
+    let mut note_count = 0;
 
-    for sheet_name in sheet_names {
-        let range = workbook
-            .worksheet_range(&sheet_name)
-            .map_err(|e| ConversionError(format!("Failed to read sheet '{}': {}", sheet_name, e)))?;
+    let total = sheets.len();
+    for (done, sheet) in sheets.into_iter().enumerate() {
+        progress.step(done, total)?;
+        let sheet_name = sheet.name;
+        let location = Some(Location::Sheet {
+            name: sheet_name.clone(),
+        });
+        if sheet.visible != SheetVisible::Visible && !options.xlsx.include_hidden {
+            report.warn(
+                WarningKind::HiddenContent,
+                location,
+                "Hidden sheet was left out",
+            );
+            continue;
+        }
+        let range = workbook.worksheet_range(&sheet_name).map_err(|e| {
+            ConversionError(format!("Failed to read sheet '{}': {}", sheet_name, e))
+        })?;
 
-        let (total_rows, col_count) = range.get_size();
+        let (range_rows, range_cols) = range.get_size();
+        let (first_row, first_col) = range.start().unwrap_or((0, 0));
+        let mut layout = info.sheets.remove(&sheet_name).unwrap_or_default();
+        if options.xlsx.include_hidden {
+            layout.hidden_rows.clear();
+            layout.hidden_cols.clear();
+        }
+        // Absolute (0-based) indices of the rows and columns that are shown.
+        let cols: Vec<u32> = (first_col..first_col + range_cols as u32)
+            .filter(|c| !layout.hidden_cols.contains(c))
+            .collect();
+        let rows: Vec<u32> = (first_row..first_row + range_rows as u32)
+            .filter(|r| !layout.hidden_rows.contains(r))
+            .collect();
+        let (hidden_rows, hidden_cols) = (range_rows - rows.len(), range_cols - cols.len());
+        if hidden_rows + hidden_cols > 0 {
+            report.warn(
+                WarningKind::HiddenContent,
+                location.clone(),
+                format!(
+                    "{} hidden rows and {} hidden columns were left out",
+                    hidden_rows, hidden_cols
+                ),
+            );
+        }
 
-        if col_count == 0 {
+        if cols.is_empty() {
             continue;
         }
 
@@ -107,91 +300,311 @@ pub fn xlsx_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, Con
         }
         output.push_str(&format!("## {}\n\n", sheet_name));
 
-        if total_rows == 0 {
-            output.push_str("*(empty sheet)*\n");
-            continue;
+        let regions = merge_regions(&mut workbook, &sheet_name);
+        let blocks = sheet_blocks(&range, &rows, &cols, &layout.tables, &regions);
+
+        // Pictures in cells take the cell's place in the table; floating ones
+        // follow the table holding their anchor cell (or the last one starting
+        // above it), since a GFM table can't carry a paragraph between rows.
+        // `placed[0]` goes before the first table, `placed[i + 1]` after
+        // table `i`.
+        let mut in_cell: HashMap<(u32, u32), String> = HashMap::new();
+        let mut placed: Vec<Vec<String>> = vec![Vec::new(); blocks.len() + 1];
+        let mut pictures: Vec<&SheetPicture> = layout
+            .pictures
+            .iter()
+            .filter(|p| info.media.contains_key(&p.part))
+            .collect();
+        pictures.sort_by_key(|p| p.anchor.unwrap_or((u32::MAX, u32::MAX)));
+        for picture in pictures {
+            let path = media.add(&picture.part, &info.media[&picture.part]);
+            let link = media_link(&picture.part, path, &picture.alt, report, location.clone());
+            match picture.anchor {
+                Some(position) if picture.in_cell => {
+                    in_cell.insert(position, escape_cell(&link));
+                }
+                Some((row, col)) => {
+                    let slot = blocks
+                        .iter()
+                        .position(|b| b.contains(row, col))
+                        .or_else(|| blocks.iter().rposition(|b| b.rows[0] <= row))
+                        .map_or(0, |i| i + 1);
+                    placed[slot].push(link);
+                }
+                None => placed[blocks.len()].push(link),
+            }
         }
 
-        let rows_to_read = total_rows.min(MAX_ROWS_PER_SHEET + 1); // +1 for header
-        let mut rows_iter = range.rows().take(rows_to_read);
+        if blocks.is_empty() {
+            if placed[0].is_empty() {
+                output.push_str("*(empty sheet)*\n");
+            }
+            for link in &placed[0] {
+                output.push_str(&format!("{}\n", link));
+            }
+            continue;
+        }
+        for link in &placed[0] {
+            output.push_str(&format!("{}\n\n", link));
+        }
+        // A lone island is the whole sheet and needs no title of its own.
+        let titled = blocks.len() > 1 || blocks[0].name.is_some();
 
-        // --- Header row ---
-        let header_raw = match rows_iter.next() {
-            Some(row) => row,
-            None => continue,
+        let cell = |row: u32, col: u32| -> Data {
+            range.get_value((row, col)).cloned().unwrap_or(Data::Empty)
+        };
+        let format = |row: u32, col: u32| {
+            layout
+                .cell_styles
+                .get(&(row, col))
+                .and_then(|&s| info.formats.get(s)?.as_ref())
+        };
+        let formulas = match options.xlsx.formulas {
+            Formulas::Values => None,
+            _ => workbook.worksheet_formula(&sheet_name).ok(),
+        };
+        if options.xlsx.comments == CellComments::Drop && !layout.comments.is_empty() {
+            report.warn(
+                WarningKind::Comments,
+                location.clone(),
+                format!("{} comments were dropped", layout.comments.len()),
+            );
+        }
+        // A cell's Markdown: its in-cell picture or formatted value, plus
+        // whatever the hyperlink, formula and comment options add. A comment
+        // goes into `cell_notes`; inside an HTML table, where a `[^n]`
+        // footnote reference isn't read, it's marked `<sup>n</sup>`.
+        let mut cell_text = |row: u32,
+                             col: u32,
+                             date_col: bool,
+                             cell_notes: &mut Vec<(usize, String)>,
+                             html: bool|
+         -> String {
+            let mut text = match in_cell.get(&(row, col)) {
+                Some(link) => link.clone(),
+                None => formatted_cell(&cell(row, col), format(row, col), date_col, info.date1904),
+            };
+            if let Some(url) = layout
+                .hyperlinks
+                .get(&(row, col))
+                .filter(|_| options.xlsx.hyperlinks && !text.is_empty())
+            {
+                text = format!(
+                    "[{}]({})",
+                    text,
+                    url.replace(' ', "%20").replace('|', "%7C")
+                );
+            }
+            if let Some(formula) = formulas
+                .as_ref()
+                .and_then(|f| f.get_value((row, col)))
+                .filter(|f| !f.is_empty())
+            {
+                let code = escape_cell(&format!("`={}`", formula));
+                text = if options.xlsx.formulas == Formulas::Instead || text.is_empty() {
+                    code
+                } else {
+                    format!("{} {}", text, code)
+                };
+            }
+            if let Some(thread) = layout
+                .comments
+                .get(&(row, col))
+                .filter(|_| options.xlsx.comments == CellComments::Footnotes)
+            {
+                let entries: Vec<String> = thread
+                    .iter()
+                    .map(|c| {
+                        if c.author.is_empty() {
+                            c.text.clone()
+                        } else {
+                            format!("{}: {}", c.author, c.text)
+                        }
+                    })
+                    .collect();
+                note_count += 1;
+                if html {
+                    cell_notes.push((note_count, entries.join("<br>\n")));
+                    text.push_str(&format!("<sup>{}</sup>", note_count));
+                } else {
+                    cell_notes.push((note_count, entries.join("\n\n    ")));
+                    text.push_str(&format!("[^{}]", note_count));
+                }
+            }
+            text
         };
+        // The row cap covers the whole sheet: once it's spent, later tables
+        // are left out along with the rest of the current one.
+        let mut budget = max_rows;
+        let mut omitted = 0;
+        for (index, block) in blocks.iter().enumerate() {
+            let header_rows = usize::from(block.header.is_none());
+            let data_rows = block.rows.len() - header_rows;
+            let shown_data = data_rows.min(budget);
+            omitted += data_rows - shown_data;
+            budget -= shown_data;
+            if shown_data == 0 && data_rows > 0 {
+                for link in &placed[index + 1] {
+                    output.push_str(&format!("\n{}\n", link));
+                }
+                continue;
+            }
+            let shown_rows = &block.rows[..header_rows + shown_data];
+            report.stats.tables += 1;
 
-        // Detect which columns are date columns based on header text.
-        let date_cols: Vec<bool> = header_raw
-            .iter()
-            .map(|c| is_date_header(&cell_to_string(c)))
-            .collect();
+            if index > 0 {
+                output.push('\n');
+            }
+            if titled {
+                output.push_str(&format!("### {}\n\n", block.title()));
+            }
 
-        let header_strs: Vec<String> = header_raw
-            .iter()
-            .map(cell_to_string)
-            .collect();
+            // Excel Tables can't hold merged cells, and a synthesized header
+            // row has no sheet row to line merges up with.
+            let merges = match block.header {
+                Some(_) => GridMerges::default(),
+                None => grid_merges(&regions, shown_rows, &block.cols),
+            };
+            let html = !merges.spans.is_empty() && options.xlsx.merged_cells == MergedCells::Span;
+            // Comments in an HTML table are listed right after it.
+            let mut table_notes: Vec<(usize, String)> = Vec::new();
+            let cell_notes = if html { &mut table_notes } else { &mut notes };
 
-        output.push('|');
-        for h in &header_strs {
-            output.push_str(&format!(" {} |", h));
-        }
-        output.push('\n');
+            // Cells show as their number format says; without one, columns
+            // whose header mentions a date still get their serials read as
+            // dates.
+            let mut grid: Vec<Vec<String>> = Vec::with_capacity(shown_rows.len() + 1);
+            let date_cols: Vec<bool> = match &block.header {
+                Some(names) => {
+                    grid.push(names.iter().map(|name| escape_cell(name)).collect());
+                    names.iter().map(|name| is_date_header(name)).collect()
+                }
+                None => {
+                    let header_row = shown_rows[0];
+                    let header_raw: Vec<Data> =
+                        block.cols.iter().map(|&c| cell(header_row, c)).collect();
+                    grid.push(
+                        block
+                            .cols
+                            .iter()
+                            .map(|&c| cell_text(header_row, c, false, cell_notes, html))
+                            .collect(),
+                    );
+                    header_raw
+                        .iter()
+                        .map(|c| is_date_header(&cell_to_string(c)))
+                        .collect()
+                }
+            };
+            for &r in &shown_rows[header_rows..] {
+                grid.push(
+                    block
+                        .cols
+                        .iter()
+                        .enumerate()
+                        .map(|(ci, &c)| cell_text(r, c, date_cols[ci], cell_notes, html))
+                        .collect(),
+                );
+            }
 
-        // Separator
-        output.push('|');
-        for _ in 0..col_count {
-            output.push_str(" --- |");
-        }
-        output.push('\n');
+            if !html {
+                for (&(r, c), &(rowspan, colspan)) in &merges.spans {
+                    let value = grid[r][c].clone();
+                    for row in &mut grid[r..r + rowspan] {
+                        row[c..c + colspan].fill(value.clone());
+                    }
+                }
+                let mut rows = grid.into_iter();
+                let header_strs = rows.next().unwrap_or_default();
 
-        // --- Data rows: collect first, then merge continuation rows ---
-        let mut all_rows: Vec<Vec<String>> = Vec::new();
+                output.push('|');
+                for h in &header_strs {
+                    output.push_str(&format!(" {} |", h));
+                }
+                output.push('\n');
 
-        for row in rows_iter.take(MAX_ROWS_PER_SHEET) {
-            let cells: Vec<String> = row
-                .iter()
-                .enumerate()
-                .map(|(ci, c)| cell_to_string_ctx(c, *date_cols.get(ci).unwrap_or(&false)))
-                .collect();
-            all_rows.push(cells);
-        }
+                // Separator
+                output.push('|');
+                for _ in 0..block.cols.len() {
+                    output.push_str(" --- |");
+                }
+                output.push('\n');
 
-        // Merge continuation rows (Option A heuristic):
-        // A continuation row is one where:
-        //   - its first cell is non-empty (carries a note or partial data)
-        //   - all cells past the "split point" are empty in both this row and the prior row
-        //     OR: this row's early cells are empty and its later cells have values that fill
-        //         gaps left empty in the prior row.
-        //
-        // Concretely: if this row's cells slot exactly into the empty cells of the prior row
-        // (no column has a value in both rows), merge them.
-        let merged_rows = merge_continuation_rows(all_rows);
+                // Merge continuation rows (Option A heuristic):
+                // A continuation row is one where:
+                //   - its first cell is non-empty (carries a note or partial data)
+                //   - all cells past the "split point" are empty in both this row and the prior row
+                //     OR: this row's early cells are empty and its later cells have values that fill
+                //         gaps left empty in the prior row.
+                //
+                // Concretely: if this row's cells slot exactly into the empty cells of the prior row
+                // (no column has a value in both rows), merge them.
+                let merged_rows = merge_continuation_rows(rows.collect());
 
-        for row in &merged_rows {
-            output.push('|');
-            for cell in row {
-                output.push_str(&format!(" {} |", cell));
+                for row in &merged_rows {
+                    output.push('|');
+                    for cell in row {
+                        output.push_str(&format!(" {} |", cell));
+                    }
+                    output.push('\n');
+                }
+            } else {
+                // The merged ranges already say which cells belong together,
+                // so the continuation-row heuristic doesn't apply.
+                let html_rows: Vec<Vec<SpannedCell>> = grid
+                    .into_iter()
+                    .enumerate()
+                    .map(|(r, row)| {
+                        row.into_iter()
+                            .enumerate()
+                            .filter(|(c, _)| !merges.covered.contains(&(r, *c)))
+                            .map(|(c, text)| {
+                                let (rowspan, colspan) =
+                                    merges.spans.get(&(r, c)).copied().unwrap_or((1, 1));
+                                SpannedCell {
+                                    html: markdown_inline_to_html(&text),
+                                    colspan,
+                                    rowspan,
+                                }
+                            })
+                            .collect()
+                    })
+                    .collect();
+                output.push_str(&spanned_table_html(&html_rows));
+                for (n, note) in &table_notes {
+                    output.push_str(&format!("\n<sup>{}</sup> {}\n", n, note));
+                }
+            }
+            for link in &placed[index + 1] {
+                output.push_str(&format!("\n{}\n", link));
             }
-            output.push('\n');
         }
 
         // Truncation notice
-        if total_rows > MAX_ROWS_PER_SHEET + 1 {
-            let omitted = total_rows - MAX_ROWS_PER_SHEET - 1;
+        if omitted > 0 {
             output.push_str(&format!(
                 "\n> **Note**: {} rows were omitted (showing first {} data rows).\n",
-                omitted, MAX_ROWS_PER_SHEET
+                omitted, max_rows
             ));
+            report.warn(
+                WarningKind::RowsTruncated,
+                location,
+                format!(
+                    "{} rows were omitted (showing first {} data rows)",
+                    omitted, max_rows
+                ),
+            );
         }
     }
 
+    progress.step(total, total)?;
+
     // calamine doesn't read embedded pictures (xlsx only; xls/ods/csv have no
-    // `xl/media/`, so this is a no-op there). Extraction is best-effort:
-    // images are pulled from the archive but can't be reliably mapped back to
-    // a specific sheet/cell from `xl/media/*` alone, so they're appended in a
-    // dedicated section rather than placed inline.
-    let images = extract_xlsx_media(path, media);
+    // `xl/media/`, so this is a no-op there). Pictures a sheet's drawing or
+    // cells place were handled with that sheet (or left out with a hidden
+    // one); any other `xl/media/*` part has no sheet or cell to go with, so
+    // it's appended in a dedicated section.
+    let images = extract_xlsx_media(path, media, report, &info.media);
     if !images.is_empty() {
         if !output.is_empty() {
             output.push('\n');
@@ -206,14 +619,27 @@ pub fn xlsx_to_markdown(path: &str, media: &mut MediaSink) -> Result<String, Con
         }
     }
 
+    if !notes.is_empty() {
+        output.push('\n');
+        for (n, note) in &notes {
+            output.push_str(&format!("[^{}]: {}\n", n, note));
+        }
+    }
+
     Ok(output)
 }
 
-/// Best-effort extraction of `xl/media/*` pictures from an xlsx archive.
-/// Returns a list of Markdown image links (or "(unsupported image)" notes for
-/// non-renderable formats). Silently returns empty for non-ZIP formats
-/// (xls/ods/csv) or files with no embedded media.
-fn extract_xlsx_media(path: &str, media: &mut MediaSink) -> Vec<String> {
+/// Best-effort extraction of the `xl/media/*` pictures of an xlsx archive
+/// that no sheet places (`placed`). Returns a list of Markdown image links
+/// (or "(unsupported image)" notes for non-renderable formats, recorded in
+/// `report`). Silently returns empty for non-ZIP formats (xls/ods/csv) or
+/// files with no embedded media.
+fn extract_xlsx_media(
+    path: &str,
+    media: &mut MediaSink,
+    report: &mut ImportReport,
+    placed: &HashMap<String, Vec<u8>>,
+) -> Vec<String> {
     let mut links = Vec::new();
 
     let file = match std::fs::File::open(path) {
@@ -228,7 +654,7 @@ fn extract_xlsx_media(path: &str, media: &mut MediaSink) -> Vec<String> {
     let mut media_names: Vec<String> = Vec::new();
     for i in 0..archive.len() {
         if let Ok(entry) = archive.by_index(i) {
-            if entry.name().starts_with("xl/media/") {
+            if entry.name().starts_with("xl/media/") && !placed.contains_key(entry.name()) {
                 media_names.push(entry.name().to_string());
             }
         }
@@ -239,21 +665,936 @@ fn extract_xlsx_media(path: &str, media: &mut MediaSink) -> Vec<String> {
         if let Ok(mut entry) = archive.by_name(&name) {
             let mut buf = Vec::new();
             if entry.read_to_end(&mut buf).is_ok() {
-                let link = match media.add(&name, &buf) {
-                    Some(rel_path) => format!("![]({})", rel_path),
-                    None => format!(
-                        "*(unsupported image: {})*",
-                        name.rsplit('/').next().unwrap_or(&name)
-                    ),
+                let path = media.add(&name, &buf);
+                links.push(media_link(&name, path, "", report, None));
+            }
+        }
+    }
+
+    links
+}
+
+/// The Markdown for image part `name` given where [`MediaSink::add`] wrote
+/// it, or an "(unsupported image)" note, recorded in `report`, when it
+/// couldn't.
+fn media_link(
+    name: &str,
+    path: Option<String>,
+    alt: &str,
+    report: &mut ImportReport,
+    location: Option<Location>,
+) -> String {
+    match path {
+        Some(rel_path) => format!("![{}]({})", escape_alt_text(alt), rel_path),
+        None => {
+            let file_name = name.rsplit('/').next().unwrap_or(name);
+            report.skip_image(
+                WarningKind::UnsupportedImage,
+                location,
+                format!("Unsupported image format: {}", file_name),
+            );
+            format!("*(unsupported image: {})*", file_name)
+        }
+    }
+}
+
+/// Merged cell ranges of a sheet, from calamine's xlsx and xls readers
+/// (xlsb and ods don't expose them, so their ranges come out unmerged).
+fn merge_regions<RS: Read + Seek>(workbook: &mut Sheets<RS>, sheet: &str) -> Vec<Dimensions> {
+    match workbook {
+        Sheets::Xlsx(xlsx) => xlsx
+            .worksheet_merge_cells(sheet)
+            .and_then(Result::ok)
+            .unwrap_or_default(),
+        Sheets::Xls(xls) => xls.worksheet_merge_cells(sheet).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Merged ranges placed on the emitted grid, whose rows and columns are
+/// the absolute sheet positions in `rows` and `cols` (hidden ones already
+/// removed).
+#[derive(Debug, Default, PartialEq)]
+struct GridMerges {
+    /// Top-left grid cell → (rowspan, colspan), counting shown cells only.
+    spans: HashMap<(usize, usize), (usize, usize)>,
+    /// The other grid cells a range covers.
+    covered: HashSet<(usize, usize)>,
+}
+
+fn grid_merges(regions: &[Dimensions], rows: &[u32], cols: &[u32]) -> GridMerges {
+    let mut merges = GridMerges::default();
+    let shown = |positions: &[u32], start: u32, end: u32| -> Option<(usize, usize)> {
+        let first = positions.iter().position(|&p| p >= start && p <= end)?;
+        let count = positions[first..].iter().take_while(|&&p| p <= end).count();
+        Some((first, count))
+    };
+    for region in regions {
+        let (Some((r, rowspan)), Some((c, colspan))) = (
+            shown(rows, region.start.0, region.end.0),
+            shown(cols, region.start.1, region.end.1),
+        ) else {
+            continue;
+        };
+        // A range whose top-left cell is hidden has its value hidden with it.
+        if rows[r] != region.start.0 || cols[c] != region.start.1 || rowspan * colspan == 1 {
+            continue;
+        }
+        merges.spans.insert((r, c), (rowspan, colspan));
+        for dr in 0..rowspan {
+            for dc in 0..colspan {
+                if (dr, dc) != (0, 0) {
+                    merges.covered.insert((r + dr, c + dc));
+                }
+            }
+        }
+    }
+    merges
+}
+
+/// What a worksheet's XML says that calamine doesn't: hidden rows and
+/// columns, and the number format of each cell that has one. Positions are
+/// 0-based and absolute, like calamine's.
+#[derive(Debug, Default, PartialEq)]
+struct SheetInfo {
+    hidden_rows: HashSet<u32>,
+    hidden_cols: HashSet<u32>,
+    /// `cellXfs` style index by `(row, col)`, for cells whose style has a
+    /// number format other than General.
+    cell_styles: HashMap<(u32, u32), usize>,
+    tables: Vec<TableDef>,
+    pictures: Vec<SheetPicture>,
+    /// Hyperlink targets by cell.
+    hyperlinks: HashMap<(u32, u32), String>,
+    /// Comments by cell: a threaded comment and its replies, or a legacy
+    /// note.
+    comments: HashMap<(u32, u32), Vec<CellComment>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CellComment {
+    author: String,
+    text: String,
+}
+
+/// A picture on a worksheet: floating over it on the drawing layer, or
+/// placed in a cell.
+#[derive(Debug, Clone, PartialEq)]
+struct SheetPicture {
+    /// The 0-based cell the picture is anchored to (`xdr:from`) or sits in;
+    /// `None` for an absolutely positioned one.
+    anchor: Option<(u32, u32)>,
+    in_cell: bool,
+    /// Archive path of the image (`xl/media/image1.png`).
+    part: String,
+    alt: String,
+}
+
+/// An Excel Table (`xl/tables/tableN.xml`) on a worksheet.
+#[derive(Debug, PartialEq)]
+struct TableDef {
+    name: String,
+    /// First and last cell, 0-based and inclusive.
+    start: (u32, u32),
+    end: (u32, u32),
+    /// Whether the first row holds the column names; without one they come
+    /// from `columns`.
+    header_row: bool,
+    columns: Vec<String>,
+}
+
+/// Sheet and style details read straight from an xlsx/xlsm archive.
+#[derive(Debug, Default)]
+struct WorkbookInfo {
+    sheets: HashMap<String, SheetInfo>,
+    /// Number format by `cellXfs` style index; `None` for General.
+    formats: Vec<Option<NumberFormat>>,
+    /// Dates count from 1904-01-01 instead of 1899-12-30.
+    date1904: bool,
+    /// Bytes of every image a sheet places, by archive path.
+    media: HashMap<String, Vec<u8>>,
+}
+
+/// The [`WorkbookInfo`] of an xlsx/xlsm archive; empty for other formats,
+/// whose hidden rows and columns are kept and numbers shown unformatted.
+fn read_workbook_info(bytes: &[u8]) -> WorkbookInfo {
+    let Ok(mut archive) = zip::ZipArchive::new(Cursor::new(bytes)) else {
+        return WorkbookInfo::default();
+    };
+    let formats = read_zip_text(&mut archive, "xl/styles.xml")
+        .map(|xml| parse_cell_formats(&xml))
+        .unwrap_or_default();
+    let date1904 = read_zip_text(&mut archive, "xl/workbook.xml").is_some_and(|xml| {
+        xml.split("<workbookPr").nth(1).is_some_and(|chunk| {
+            let tag = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+            matches!(xml_attr(tag, "date1904").as_deref(), Some("1" | "true"))
+        })
+    });
+    let cell_images = read_cell_images(&mut archive);
+    // Threaded comments name their authors by id from `xl/persons/`.
+    let mut persons = HashMap::new();
+    for rel in read_rels(&mut archive, "xl/workbook.xml")
+        .into_iter()
+        .filter(|r| r.kind == "person")
+    {
+        for chunk in read_zip_text(&mut archive, &rel.target)
+            .unwrap_or_default()
+            .split("<person ")
+            .skip(1)
+        {
+            if let (Some(id), Some(name)) = (xml_attr(chunk, "id"), xml_attr(chunk, "displayName"))
+            {
+                persons.insert(id, xml_decode(&name));
+            }
+        }
+    }
+    let sheets: HashMap<String, SheetInfo> = sheet_parts(&mut archive)
+        .into_iter()
+        .filter_map(|(name, part)| {
+            let xml = read_zip_text(&mut archive, &part)?;
+            let mut info = parse_sheet_info(&xml, &formats);
+            let sheet_rels = read_rels(&mut archive, &part);
+            let rels: HashMap<String, String> = sheet_rels
+                .iter()
+                .map(|rel| (rel.id.clone(), rel.target.clone()))
+                .collect();
+            info.hyperlinks = parse_hyperlinks(&xml, &rels);
+            // A threaded comment also leaves a legacy note on its cell for
+            // older Excel versions; the thread wins.
+            for rel in sheet_rels.iter().filter(|r| r.kind == "threadedComment") {
+                let xml = read_zip_text(&mut archive, &rel.target).unwrap_or_default();
+                for (cell, comment) in parse_threaded_comments(&xml, &persons) {
+                    info.comments.entry(cell).or_default().push(comment);
+                }
+            }
+            let threaded: HashSet<(u32, u32)> = info.comments.keys().copied().collect();
+            for rel in sheet_rels.iter().filter(|r| r.kind == "comments") {
+                let xml = read_zip_text(&mut archive, &rel.target).unwrap_or_default();
+                for (cell, comment) in parse_comments(&xml) {
+                    if !threaded.contains(&cell) {
+                        info.comments.entry(cell).or_default().push(comment);
+                    }
+                }
+            }
+            info.tables = xml
+                .split("<tablePart ")
+                .skip(1)
+                .filter_map(|chunk| {
+                    let target = rels.get(&xml_attr(chunk, "r:id")?)?;
+                    parse_table(&read_zip_text(&mut archive, target)?)
+                })
+                .collect();
+            for chunk in xml.split("<drawing ").skip(1) {
+                let Some(drawing) = xml_attr(chunk, "r:id").and_then(|id| rels.get(&id)) else {
+                    continue;
+                };
+                let Some(drawing_xml) = read_zip_text(&mut archive, drawing) else {
+                    continue;
                 };
-                links.push(link);
+                let images = part_rels(&mut archive, drawing);
+                info.pictures.extend(parse_drawing(&drawing_xml, &images));
+            }
+            info.pictures
+                .extend(
+                    value_metadata_cells(&xml)
+                        .into_iter()
+                        .filter_map(|(cell, vm)| {
+                            let (part, alt) = cell_images.get(&vm)?.clone();
+                            Some(SheetPicture {
+                                anchor: Some(cell),
+                                in_cell: true,
+                                part,
+                                alt,
+                            })
+                        }),
+                );
+            Some((name, info))
+        })
+        .collect();
+    let mut media = HashMap::new();
+    for picture in sheets.values().flat_map(|info| &info.pictures) {
+        if !media.contains_key(&picture.part) {
+            if let Some(bytes) = read_zip_bytes(&mut archive, &picture.part) {
+                media.insert(picture.part.clone(), bytes);
             }
         }
     }
+    WorkbookInfo {
+        sheets,
+        formats,
+        date1904,
+        media,
+    }
+}
 
+/// Pictures on a drawing part (`xl/drawings/drawingN.xml`): each one's
+/// anchor cell (`xdr:from`, none for an absolute anchor), image part
+/// (through the drawing's relationships, `images`) and description
+/// (`descr`, else `title`). Shapes and charts without a picture are
+/// skipped.
+fn parse_drawing(xml: &str, images: &HashMap<String, String>) -> Vec<SheetPicture> {
+    let mut starts: Vec<usize> = [
+        "<xdr:twoCellAnchor",
+        "<xdr:oneCellAnchor",
+        "<xdr:absoluteAnchor",
+    ]
+    .iter()
+    .flat_map(|tag| xml.match_indices(tag).map(|(i, _)| i))
+    .collect();
+    starts.sort_unstable();
+    let mut pictures = Vec::new();
+    for (n, &start) in starts.iter().enumerate() {
+        let anchor = &xml[start..starts.get(n + 1).copied().unwrap_or(xml.len())];
+        let from = anchor.split_once("<xdr:from>").map(|(_, rest)| {
+            let rest = &rest[..rest.find("</xdr:from>").unwrap_or(rest.len())];
+            let number = |tag: &str| -> Option<u32> {
+                let open = format!("<xdr:{}>", tag);
+                let value = &rest[rest.find(&open)? + open.len()..];
+                value[..value.find('<')?].trim().parse().ok()
+            };
+            (number("row"), number("col"))
+        });
+        let cell = match from {
+            Some((Some(row), Some(col))) => Some((row, col)),
+            _ => None,
+        };
+        // A group shape can hold several pictures under one anchor.
+        for pic in anchor.split("<xdr:pic>").skip(1) {
+            let Some(part) = xml_attr(pic, "r:embed").and_then(|id| images.get(&id)) else {
+                continue;
+            };
+            let alt = pic
+                .split_once("<xdr:cNvPr ")
+                .map(|(_, rest)| &rest[..rest.find('>').unwrap_or(rest.len())])
+                .and_then(|tag| {
+                    let text = |name: &str| {
+                        xml_attr(tag, name)
+                            .map(|v| xml_decode(&v.replace("&#xA;", " ").replace("&#10;", " ")))
+                            .map(|v| v.split_whitespace().collect::<Vec<_>>().join(" "))
+                            .filter(|v| !v.is_empty())
+                    };
+                    text("descr").or_else(|| text("title"))
+                })
+                .unwrap_or_default();
+            pictures.push(SheetPicture {
+                anchor: cell,
+                in_cell: false,
+                part: part.clone(),
+                alt,
+            });
+        }
+    }
+    pictures
+}
+
+/// Hyperlink targets by cell from a sheet's `<hyperlinks>`: the external
+/// URL its relationship (`rels`) points at, plus a `#location` when it
+/// names a place in the target. Links only to a place inside the workbook
+/// are left out, as there's nothing in the Markdown for them to point at.
+/// A link over a range applies to each of its cells.
+fn parse_hyperlinks(xml: &str, rels: &HashMap<String, String>) -> HashMap<(u32, u32), String> {
+    let mut links = HashMap::new();
+    for chunk in xml.split("<hyperlink ").skip(1) {
+        let tag = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+        let (Some(reference), Some(url)) = (
+            xml_attr(tag, "ref"),
+            xml_attr(tag, "r:id").and_then(|id| rels.get(&id)),
+        ) else {
+            continue;
+        };
+        let url = match xml_attr(tag, "location") {
+            Some(location) => format!("{}#{}", url, xml_decode(&location)),
+            None => url.clone(),
+        };
+        let (first, last) = reference
+            .split_once(':')
+            .unwrap_or((&reference, &reference));
+        let (Some(start), Some(end)) = (parse_cell_ref(first), parse_cell_ref(last)) else {
+            continue;
+        };
+        for row in start.0..=end.0 {
+            for col in start.1..=end.1 {
+                links.insert((row, col), url.clone());
+            }
+        }
+    }
     links
 }
 
+/// The text of every `<t>` run in a rich-text fragment, joined.
+fn rich_text(xml: &str) -> String {
+    let mut text = String::new();
+    for chunk in xml.split("<t").skip(1) {
+        // `<t>` or `<t xml:space=...>`, not `<text>`.
+        if !chunk.starts_with(['>', ' ']) {
+            continue;
+        }
+        let body = &chunk[chunk.find('>').map_or(chunk.len(), |i| i + 1)..];
+        text.push_str(&xml_decode(
+            &body[..body.find("</t>").unwrap_or(body.len())],
+        ));
+    }
+    text
+}
+
+/// Collapse line breaks and runs of whitespace into single spaces.
+fn fold_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Legacy notes (`xl/commentsN.xml`) by cell. Excel starts each note's text
+/// with its author's name (`Ada:`), which is dropped as it's kept apart.
+fn parse_comments(xml: &str) -> Vec<((u32, u32), CellComment)> {
+    let authors: Vec<String> = xml
+        .split("<author>")
+        .skip(1)
+        .map(|chunk| xml_decode(&chunk[..chunk.find("</author>").unwrap_or(chunk.len())]))
+        .collect();
+    xml.split("<comment ")
+        .skip(1)
+        .filter_map(|chunk| {
+            let tag = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+            let cell = parse_cell_ref(&xml_attr(tag, "ref")?)?;
+            let author = xml_attr(tag, "authorId")
+                .and_then(|id| authors.get(id.parse::<usize>().ok()?).cloned())
+                .unwrap_or_default();
+            let body = &chunk[..chunk.find("</comment>").unwrap_or(chunk.len())];
+            let text = fold_whitespace(&rich_text(body));
+            let text = match text.strip_prefix(&format!("{}:", author)) {
+                Some(rest) if !author.is_empty() => rest.trim_start().to_string(),
+                _ => text,
+            };
+            Some((cell, CellComment { author, text }))
+        })
+        .collect()
+}
+
+/// Threaded comments (`xl/threadedComments/threadedCommentN.xml`) by cell,
+/// replies following the comment they answer, with authors looked up in
+/// `persons`.
+fn parse_threaded_comments(
+    xml: &str,
+    persons: &HashMap<String, String>,
+) -> Vec<((u32, u32), CellComment)> {
+    xml.split("<threadedComment ")
+        .skip(1)
+        .filter_map(|chunk| {
+            let tag = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+            let cell = parse_cell_ref(&xml_attr(tag, "ref")?)?;
+            let author = xml_attr(tag, "personId")
+                .and_then(|id| persons.get(&id).cloned())
+                .unwrap_or_default();
+            let text = chunk.split_once("<text>").map_or("", |(_, rest)| {
+                &rest[..rest.find("</text>").unwrap_or(rest.len())]
+            });
+            Some((
+                cell,
+                CellComment {
+                    author,
+                    text: fold_whitespace(&xml_decode(text)),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Cells carrying a value-metadata index (`vm`), the hook for pictures
+/// placed in cells.
+fn value_metadata_cells(xml: &str) -> Vec<((u32, u32), u32)> {
+    xml.split("<c ")
+        .skip(1)
+        .filter_map(|chunk| {
+            let tag = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+            let vm = xml_attr(tag, "vm")?.parse().ok()?;
+            Some((parse_cell_ref(&xml_attr(tag, "r")?)?, vm))
+        })
+        .collect()
+}
+
+/// Images placed in cells ("Place in Cell"), with their alt text, by the
+/// cells' 1-based `vm` index: `xl/metadata.xml` maps it to a rich value in
+/// `xl/richData/rdrichvalue.xml`, whose `_rvRel:LocalImageIdentifier` picks
+/// an entry of `xl/richData/richValueRel.xml`.
+fn read_cell_images(
+    archive: &mut zip::ZipArchive<Cursor<&[u8]>>,
+) -> HashMap<u32, (String, String)> {
+    let (Some(metadata), Some(values), Some(structures), Some(rel_list)) = (
+        read_zip_text(archive, "xl/metadata.xml"),
+        read_zip_text(archive, "xl/richData/rdrichvalue.xml"),
+        read_zip_text(archive, "xl/richData/rdrichvaluestructure.xml"),
+        read_zip_text(archive, "xl/richData/richValueRel.xml"),
+    ) else {
+        return HashMap::new();
+    };
+    let rels = part_rels(archive, "xl/richData/richValueRel.xml");
+    let targets: Vec<Option<&String>> = rel_list
+        .split("<rel ")
+        .skip(1)
+        .map(|chunk| rels.get(&xml_attr(chunk, "r:id")?))
+        .collect();
+    let structures: Vec<Vec<String>> = structures
+        .split("<s ")
+        .skip(1)
+        .map(|s| {
+            s.split("<k ")
+                .skip(1)
+                .filter_map(|k| xml_attr(k, "n"))
+                .collect()
+        })
+        .collect();
+    let values: Vec<(usize, Vec<String>)> = values
+        .split("<rv ")
+        .skip(1)
+        .map(|rv| {
+            let rv = &rv[..rv.find("</rv>").unwrap_or(rv.len())];
+            let structure = xml_attr(rv, "s").and_then(|s| s.parse().ok()).unwrap_or(0);
+            let fields = rv
+                .split("<v")
+                .skip(1)
+                .map(|v| {
+                    let v = &v[v.find('>').map_or(v.len(), |i| i + 1)..];
+                    xml_decode(&v[..v.find("</v>").unwrap_or(v.len())])
+                })
+                .collect();
+            (structure, fields)
+        })
+        .collect();
+    let section = |open: &str, close: &str| -> String {
+        metadata
+            .split_once(open)
+            .map(|(_, rest)| rest[..rest.find(close).unwrap_or(rest.len())].to_string())
+            .unwrap_or_default()
+    };
+    let rich_values: Vec<Option<usize>> =
+        section("<futureMetadata name=\"XLRICHVALUE\"", "</futureMetadata>")
+            .split("<bk>")
+            .skip(1)
+            .map(|bk| {
+                bk.split_once("rvb ")
+                    .and_then(|(_, rest)| xml_attr(rest, "i")?.parse().ok())
+            })
+            .collect();
+
+    let mut images = HashMap::new();
+    for (n, bk) in section("<valueMetadata", "</valueMetadata>")
+        .split("<bk>")
+        .skip(1)
+        .enumerate()
+    {
+        let image = (|| {
+            let future: usize = bk
+                .split_once("<rc ")
+                .and_then(|(_, rc)| xml_attr(rc, "v")?.parse().ok())?;
+            let (structure, fields) = values.get((*rich_values.get(future)?)?)?;
+            let keys = structures.get(*structure)?;
+            let field = |key: &str| fields.get(keys.iter().position(|k| k == key)?);
+            let rel: usize = field("_rvRel:LocalImageIdentifier")?.parse().ok()?;
+            let part = (*targets.get(rel)?)?.clone();
+            Some((part, field("Text").cloned().unwrap_or_default()))
+        })();
+        if let Some(image) = image {
+            images.insert(n as u32 + 1, image);
+        }
+    }
+    images
+}
+
+/// Relationship targets by id from the `_rels` part that belongs to
+/// `part` (see [`read_rels`]).
+fn part_rels(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, part: &str) -> HashMap<String, String> {
+    read_rels(archive, part)
+        .into_iter()
+        .map(|rel| (rel.id, rel.target))
+        .collect()
+}
+
+/// One entry of a `_rels` part.
+struct Relationship {
+    id: String,
+    /// The last segment of the relationship type (`table`, `drawing`,
+    /// `comments`, `hyperlink`, ...).
+    kind: String,
+    /// An archive path, or the URL as written for an external target.
+    target: String,
+}
+
+/// The relationships of `part`, with internal targets resolved to archive
+/// paths.
+fn read_rels(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, part: &str) -> Vec<Relationship> {
+    let (dir, file) = part.rsplit_once('/').unwrap_or(("", part));
+    let Some(rels) = read_zip_text(archive, &format!("{}/_rels/{}.rels", dir, file)) else {
+        return Vec::new();
+    };
+    rels.split("<Relationship ")
+        .skip(1)
+        .filter_map(|chunk| {
+            let tag = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+            let target = xml_decode(&xml_attr(tag, "Target")?);
+            let external = xml_attr(tag, "TargetMode").as_deref() == Some("External");
+            Some(Relationship {
+                id: xml_attr(tag, "Id")?,
+                kind: xml_attr(tag, "Type")
+                    .and_then(|t| t.rsplit('/').next().map(str::to_string))
+                    .unwrap_or_default(),
+                target: if external {
+                    target
+                } else {
+                    resolve_part_path(dir, &target)
+                },
+            })
+        })
+        .collect()
+}
+
+/// Resolve a relationship target (e.g. `../tables/table1.xml`) against the
+/// directory of the part it belongs to (e.g. `xl/worksheets`).
+fn resolve_part_path(dir: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+    let mut path: Vec<&str> = dir.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in target.split('/') {
+        match segment {
+            "." | "" => {}
+            ".." => {
+                path.pop();
+            }
+            other => path.push(other),
+        }
+    }
+    path.join("/")
+}
+
+/// A table part's name, cell range and column names.
+fn parse_table(xml: &str) -> Option<TableDef> {
+    let chunk = xml.split("<table ").nth(1)?;
+    let tag = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+    let name = xml_attr(tag, "displayName").or_else(|| xml_attr(tag, "name"))?;
+    let reference = xml_attr(tag, "ref")?;
+    let (first, last) = reference
+        .split_once(':')
+        .unwrap_or((&reference, &reference));
+    Some(TableDef {
+        name: xml_decode(&name),
+        start: parse_cell_ref(first)?,
+        end: parse_cell_ref(last)?,
+        header_row: xml_attr(tag, "headerRowCount").as_deref() != Some("0"),
+        columns: xml
+            .split("<tableColumn ")
+            .skip(1)
+            .map(|chunk| {
+                xml_attr(chunk, "name")
+                    .map(|n| xml_decode(&n))
+                    .unwrap_or_default()
+            })
+            .collect(),
+    })
+}
+
+/// One table's worth of a sheet: an Excel Table or an island of data.
+#[derive(Debug, PartialEq)]
+struct Block {
+    /// The Excel Table's name; `None` for an island.
+    name: Option<String>,
+    /// Absolute indices of the shown rows and columns it covers.
+    rows: Vec<u32>,
+    cols: Vec<u32>,
+    /// Column names for a table without a header row of its own.
+    header: Option<Vec<String>>,
+}
+
+impl Block {
+    fn contains(&self, row: u32, col: u32) -> bool {
+        (self.rows[0]..=self.rows[self.rows.len() - 1]).contains(&row)
+            && (self.cols[0]..=self.cols[self.cols.len() - 1]).contains(&col)
+    }
+
+    /// The table's name, or the island's cell range (`B2:D9`).
+    fn title(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        let (first, last) = (self.rows[0], self.rows[self.rows.len() - 1]);
+        let (left, right) = (self.cols[0], self.cols[self.cols.len() - 1]);
+        format!("{}:{}", cell_name(first, left), cell_name(last, right))
+    }
+}
+
+/// The `A1`-style name of a 0-based cell position.
+fn cell_name(row: u32, col: u32) -> String {
+    let mut letters = Vec::new();
+    let mut n = col + 1;
+    while n > 0 {
+        letters.push((b'A' + ((n - 1) % 26) as u8) as char);
+        n = (n - 1) / 26;
+    }
+    letters.iter().rev().collect::<String>() + &(row + 1).to_string()
+}
+
+/// Split a sheet's shown cells into the tables to render: each Excel Table,
+/// then islands of the remaining cells separated by blank rows or columns,
+/// in reading order. Cells covered by a merged range count as filled, so a
+/// merged title stays with the table under it.
+fn sheet_blocks(
+    range: &Range<Data>,
+    rows: &[u32],
+    cols: &[u32],
+    tables: &[TableDef],
+    regions: &[Dimensions],
+) -> Vec<Block> {
+    let in_table = |r: u32, c: u32| {
+        tables
+            .iter()
+            .any(|t| (t.start.0..=t.end.0).contains(&r) && (t.start.1..=t.end.1).contains(&c))
+    };
+    let mut blocks: Vec<Block> = tables
+        .iter()
+        .filter_map(|table| {
+            let rows: Vec<u32> = rows
+                .iter()
+                .copied()
+                .filter(|r| (table.start.0..=table.end.0).contains(r))
+                .collect();
+            let cols: Vec<u32> = cols
+                .iter()
+                .copied()
+                .filter(|c| (table.start.1..=table.end.1).contains(c))
+                .collect();
+            if rows.is_empty() || cols.is_empty() {
+                return None;
+            }
+            let header = (!table.header_row).then(|| {
+                cols.iter()
+                    .map(|&c| {
+                        table
+                            .columns
+                            .get((c - table.start.1) as usize)
+                            .cloned()
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            });
+            Some(Block {
+                name: Some(table.name.clone()),
+                rows,
+                cols,
+                header,
+            })
+        })
+        .collect();
+
+    let covered: HashSet<(u32, u32)> = regions
+        .iter()
+        .flat_map(|d| {
+            (d.start.0..=d.end.0).flat_map(move |r| (d.start.1..=d.end.1).map(move |c| (r, c)))
+        })
+        .collect();
+    let filled = |r: u32, c: u32| {
+        !in_table(r, c)
+            && (covered.contains(&(r, c))
+                || match range.get_value((r, c)) {
+                    None | Some(Data::Empty) => false,
+                    Some(Data::String(s)) => !s.trim().is_empty(),
+                    Some(_) => true,
+                })
+    };
+    blocks.extend(
+        islands(rows, cols, &filled)
+            .into_iter()
+            .map(|(rows, cols)| Block {
+                name: None,
+                rows,
+                cols,
+                header: None,
+            }),
+    );
+    blocks.sort_by_key(|b| (b.rows[0], b.cols[0]));
+    blocks
+}
+
+/// Rectangles of filled cells separated by wholly blank rows or columns,
+/// found by splitting on blank rows, then on blank columns within each band,
+/// and again inside each piece until nothing splits further.
+fn islands(
+    rows: &[u32],
+    cols: &[u32],
+    filled: &dyn Fn(u32, u32) -> bool,
+) -> Vec<(Vec<u32>, Vec<u32>)> {
+    let mut found = Vec::new();
+    for band in runs(rows, |r| cols.iter().any(|&c| filled(r, c))) {
+        let pieces = runs(cols, |c| band.iter().any(|&r| filled(r, c)));
+        if pieces.len() == 1 && band.len() == rows.len() && pieces[0].len() == cols.len() {
+            found.push((band, pieces.into_iter().next().unwrap_or_default()));
+            continue;
+        }
+        for piece in pieces {
+            found.extend(islands(&band, &piece, filled));
+        }
+    }
+    found
+}
+
+/// Maximal runs of consecutive `items` for which `keep` holds.
+fn runs(items: &[u32], keep: impl Fn(u32) -> bool) -> Vec<Vec<u32>> {
+    let mut runs: Vec<Vec<u32>> = Vec::new();
+    let mut open = false;
+    for &item in items {
+        if keep(item) {
+            if !open {
+                runs.push(Vec::new());
+            }
+            runs.last_mut().unwrap().push(item);
+            open = true;
+        } else {
+            open = false;
+        }
+    }
+    runs
+}
+
+/// Number formats by `cellXfs` index from `xl/styles.xml`: each `<xf>`'s
+/// `numFmtId`, resolved through the workbook's `<numFmt>` codes or the
+/// built-in ones.
+fn parse_cell_formats(xml: &str) -> Vec<Option<NumberFormat>> {
+    let custom: HashMap<u32, String> = xml
+        .split("<numFmt ")
+        .skip(1)
+        .filter_map(|chunk| {
+            let id = xml_attr(chunk, "numFmtId")?.parse().ok()?;
+            Some((id, xml_decode(&xml_attr(chunk, "formatCode")?)))
+        })
+        .collect();
+    // `<cellStyleXfs>` holds `<xf>`s too; only `<cellXfs>` ones are indexed
+    // by the cells' `s` attribute.
+    let Some(start) = xml.find("<cellXfs") else {
+        return Vec::new();
+    };
+    let end = xml[start..]
+        .find("</cellXfs>")
+        .map_or(xml.len(), |e| start + e);
+    xml[start..end]
+        .split("<xf ")
+        .skip(1)
+        .map(|chunk| {
+            let tag = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+            let id = xml_attr(tag, "numFmtId")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0);
+            NumberFormat::for_id(id, custom.get(&id).map(String::as_str))
+        })
+        .collect()
+}
+
+/// 0-based `(row, col)` of an `A1`-style reference.
+fn parse_cell_ref(reference: &str) -> Option<(u32, u32)> {
+    let split = reference.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = reference.split_at(split);
+    if letters.is_empty() {
+        return None;
+    }
+    let col = letters.chars().try_fold(0u32, |acc, c| {
+        c.is_ascii_uppercase()
+            .then(|| acc * 26 + (c as u32 - 'A' as u32 + 1))
+    })?;
+    let row: u32 = digits.parse().ok()?;
+    Some((row.checked_sub(1)?, col - 1))
+}
+
+/// Worksheet part names (`xl/worksheets/sheet1.xml`) by sheet name, from
+/// `xl/workbook.xml` and its relationships.
+fn sheet_parts(archive: &mut zip::ZipArchive<Cursor<&[u8]>>) -> HashMap<String, String> {
+    let (Some(workbook), Some(rels)) = (
+        read_zip_text(archive, "xl/workbook.xml"),
+        read_zip_text(archive, "xl/_rels/workbook.xml.rels"),
+    ) else {
+        return HashMap::new();
+    };
+    let targets: HashMap<String, String> = rels
+        .split("<Relationship ")
+        .skip(1)
+        .filter_map(|chunk| Some((xml_attr(chunk, "Id")?, xml_attr(chunk, "Target")?)))
+        .collect();
+    workbook
+        .split("<sheet ")
+        .skip(1)
+        .filter_map(|chunk| {
+            let target = targets.get(&xml_attr(chunk, "r:id")?)?;
+            let part = match target.strip_prefix('/') {
+                Some(absolute) => absolute.to_string(),
+                None => format!("xl/{}", target),
+            };
+            Some((xml_decode(&xml_attr(chunk, "name")?), part))
+        })
+        .collect()
+}
+
+/// `formats` tells which style indices are worth recording for cells.
+fn parse_sheet_info(xml: &str, formats: &[Option<NumberFormat>]) -> SheetInfo {
+    let mut info = SheetInfo::default();
+    let is_hidden = |tag: &str| matches!(xml_attr(tag, "hidden").as_deref(), Some("1" | "true"));
+    // `<col min max>` ranges are 1-based and inclusive.
+    for chunk in xml.split("<col ").skip(1) {
+        let tag = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+        if is_hidden(tag) {
+            let bound = |name| xml_attr(tag, name).and_then(|v| v.parse::<u32>().ok());
+            if let (Some(min), Some(max)) = (bound("min"), bound("max")) {
+                // A trailing hidden range often runs to the last column
+                // (16384); only the columns a table could reach matter.
+                info.hidden_cols
+                    .extend(min.saturating_sub(1)..max.min(16_384));
+            }
+        }
+    }
+    for chunk in xml.split("<row ").skip(1) {
+        let tag = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+        if is_hidden(tag) {
+            if let Some(r) = xml_attr(tag, "r").and_then(|v| v.parse::<u32>().ok()) {
+                info.hidden_rows.insert(r.saturating_sub(1));
+            }
+        }
+    }
+    for chunk in xml.split("<c ").skip(1) {
+        let tag = &chunk[..chunk.find('>').unwrap_or(chunk.len())];
+        let style = xml_attr(tag, "s").and_then(|v| v.parse::<usize>().ok());
+        if let Some(style) = style.filter(|&s| formats.get(s).is_some_and(Option::is_some)) {
+            if let Some(position) = xml_attr(tag, "r").as_deref().and_then(parse_cell_ref) {
+                info.cell_styles.insert(position, style);
+            }
+        }
+    }
+    info
+}
+
+/// The value of attribute `name` in an XML tag fragment, matched as a whole
+/// attribute name (so `r` doesn't match `ref`).
+fn xml_attr(tag: &str, name: &str) -> Option<String> {
+    let search = format!("{}=\"", name);
+    let mut from = 0;
+    while let Some(pos) = tag[from..].find(&search) {
+        let at = from + pos;
+        if at == 0 || tag[..at].ends_with(char::is_whitespace) {
+            let after = &tag[at + search.len()..];
+            return after.find('"').map(|end| after[..end].to_string());
+        }
+        from = at + search.len();
+    }
+    None
+}
+
+fn read_zip_bytes(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, name: &str) -> Option<Vec<u8>> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = Vec::new();
+    entry.read_to_end(&mut content).ok()?;
+    Some(content)
+}
+
+fn read_zip_text(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, name: &str) -> Option<String> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
 /// Merge "continuation rows" into their preceding row where possible.
 ///
 /// A row is merged upward when every non-empty cell in the current row corresponds
@@ -355,7 +1696,10 @@ mod tests {
     #[test]
     fn test_cell_to_string_ctx_date() {
         // Float in a date column → ISO date
-        assert_eq!(cell_to_string_ctx(&Data::Float(46078.0), true), "2026-02-25");
+        assert_eq!(
+            cell_to_string_ctx(&Data::Float(46078.0), true),
+            "2026-02-25"
+        );
         // Int in a date column → ISO date (calamine may return either type)
         assert_eq!(cell_to_string_ctx(&Data::Int(46078), true), "2026-02-25");
         // Same value in a non-date column → raw number
@@ -392,19 +1736,630 @@ mod tests {
     fn test_xlsx_to_markdown_fixture() {
         let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.xlsx");
         let mut sink = MediaSink::new(std::env::temp_dir());
+        let mut report = ImportReport::default();
 
-        let md = xlsx_to_markdown(path, &mut sink).expect("xlsx_to_markdown should succeed");
+        let md = xlsx_to_markdown(
+            path,
+            &mut sink,
+            &ImportOptions::default(),
+            &mut report,
+            &ImportProgress::default(),
+        )
+        .expect("xlsx_to_markdown should succeed");
 
         assert!(md.contains("## Data"), "first sheet section missing:\n{md}");
-        assert!(md.contains("## Notes"), "second sheet section missing:\n{md}");
+        assert!(
+            md.contains("## Notes"),
+            "second sheet section missing:\n{md}"
+        );
         assert!(
             md.contains(&excel_serial_to_date(45000)),
             "date serial not reformatted:\n{md}"
         );
-        assert!(md.contains("Line one Line two"), "newline in cell not collapsed:\n{md}");
+        assert!(
+            md.contains("Line one Line two"),
+            "newline in cell not collapsed:\n{md}"
+        );
+        assert!(
+            md.contains("Line one Line two"),
+            "newline in cell not collapsed:\n{md}"
+        );
+        assert!(
+            md.contains(
+                "| Alice | 2023-03-15 | Line one Line two | $1,234.50 | 15.3% | 2023-04-15 |"
+            ),
+            "number formats not applied:\n{md}"
+        );
+        assert!(
+            md.contains("| Bob | 2023-06-23 | Done | -$80.00 | 100.0% | 2023-06-23 |"),
+            "{md}"
+        );
         assert!(
             !md.contains("rows were omitted"),
             "small sheet should not trigger the 500-row cap note:\n{md}"
         );
+        assert_eq!(report.stats.pages, Some(6));
+        assert_eq!(report.stats.tables, 7);
+        let warnings: Vec<(WarningKind, String)> = report
+            .warnings
+            .iter()
+            .map(|w| (w.kind, w.to_string()))
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                (
+                    WarningKind::HiddenContent,
+                    "sheet 'Summary': 1 hidden rows and 1 hidden columns were left out".to_string()
+                ),
+                (
+                    WarningKind::Comments,
+                    "sheet 'Summary': 1 comments were dropped".to_string()
+                ),
+                (
+                    WarningKind::HiddenContent,
+                    "sheet 'Lookup': Hidden sheet was left out".to_string()
+                ),
+                (
+                    WarningKind::Comments,
+                    "sheet 'Calc': 2 comments were dropped".to_string()
+                ),
+            ]
+        );
+    }
+
+    /// The fixture's "Summary" sheet: a merged title across A1:D1, a label
+    /// merged down A3:A4, hidden column C and hidden row 5.
+    #[test]
+    fn test_xlsx_merged_cells_span_and_hidden_skipped() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.xlsx");
+        let mut sink = MediaSink::new(std::env::temp_dir());
+        let mut report = ImportReport::default();
+
+        let md = xlsx_to_markdown(
+            path,
+            &mut sink,
+            &ImportOptions::default(),
+            &mut report,
+            &ImportProgress::default(),
+        )
+        .expect("xlsx_to_markdown should succeed");
+
+        assert!(
+            md.contains(
+                "## Summary\n\n<table>\n\
+                 <tr><th colspan=\"3\">Quarterly summary</th></tr>\n\
+                 <tr><td>Region</td><td>Q1</td><td>Q2</td></tr>\n\
+                 <tr><td rowspan=\"2\">North</td><td>10</td><td>20</td></tr>\n\
+                 <tr><td>11</td><td>21</td></tr>\n\
+                 <tr><td>South</td><td>30</td><td>40</td></tr>\n\
+                 </table>\n"
+            ),
+            "{md}"
+        );
+        assert!(!md.contains("Helper") && !md.contains("Scratch"), "{md}");
+        assert!(
+            !md.contains("Lookup") && !md.contains("internal lookup value"),
+            "{md}"
+        );
+    }
+
+    #[test]
+    fn test_xlsx_merged_cells_fill_and_include_hidden() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.xlsx");
+        let mut sink = MediaSink::new(std::env::temp_dir());
+        let mut report = ImportReport::default();
+        let mut options = ImportOptions::default();
+        options.xlsx.merged_cells = MergedCells::Fill;
+        options.xlsx.include_hidden = true;
+
+        let md = xlsx_to_markdown(
+            path,
+            &mut sink,
+            &options,
+            &mut report,
+            &ImportProgress::default(),
+        )
+        .expect("xlsx_to_markdown should succeed");
+
+        assert!(
+            md.contains(
+                "| Quarterly summary | Quarterly summary | Quarterly summary | Quarterly summary |\n\
+                 | --- | --- | --- | --- |\n\
+                 | Region | Q1 | Helper | Q2 |\n\
+                 | North | 10 | 1 | 20 |\n\
+                 | North | 11 | 2 | 21 |\n\
+                 | Scratch | 99 |  |  |\n"
+            ),
+            "{md}"
+        );
+        assert!(
+            md.contains("## Lookup\n\n| Code |\n| --- |\n| internal lookup value |\n"),
+            "{md}"
+        );
+        assert!(
+            report
+                .warnings
+                .iter()
+                .all(|w| w.kind == WarningKind::Comments),
+            "{:?}",
+            report.warnings
+        );
+    }
+
+    /// The fixture's "Report" sheet: the Excel Table "Sales" at A1:B3, with
+    /// islands of data at E1:F2 and A5:A6, and a picture anchored at A3.
+    #[test]
+    fn test_xlsx_tables_and_islands_rendered_separately() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.xlsx");
+        let dir = std::env::temp_dir().join(format!("pourdown-xlsx-report-{}", std::process::id()));
+        let mut sink = MediaSink::new(dir.join("assets"));
+        let mut report = ImportReport::default();
+        let md = xlsx_to_markdown(
+            path,
+            &mut sink,
+            &ImportOptions::default(),
+            &mut report,
+            &ImportProgress::default(),
+        )
+        .expect("xlsx_to_markdown should succeed");
+
+        let report_sheet = &md
+            [md.find("## Report").expect("Report sheet")..md.find("## Calc").expect("Calc sheet")];
+        assert_eq!(
+            report_sheet,
+            "## Report\n\n### Sales\n\n| Product | Units |\n| --- | --- |\n| Apples | 10 |\n| Pears | 4 |\n\
+             \n![Pear crate](assets/image1.png)\n\n\
+             ### E1:F2\n\n| Owner | Team |\n| --- | --- |\n| Kim | Ops |\n\n\
+             ### A5:A6\n\n| Notes |\n| --- |\n| Shipped monthly |\n\n"
+        );
+        // A lone island keeps the untitled single-table layout.
+        assert!(md.contains("## Notes\n\n| Comment |"), "{md}");
+        // The placed picture isn't repeated in the leftover section.
+        assert!(!md.contains("Embedded Images"), "{md}");
+        assert!(dir.join("assets/image1.png").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// The fixture's "Calc" sheet: a formula in B3, a hyperlink on C2, a
+    /// legacy note on A2 and a threaded comment with a reply on A3.
+    #[test]
+    fn test_xlsx_formulas_hyperlinks_and_comments() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.xlsx");
+        let convert = |options: &ImportOptions| {
+            let mut sink = MediaSink::new(std::env::temp_dir());
+            let mut report = ImportReport::default();
+            let md = xlsx_to_markdown(
+                path,
+                &mut sink,
+                options,
+                &mut report,
+                &ImportProgress::default(),
+            )
+            .expect("xlsx_to_markdown should succeed");
+            md[md.find("## Calc").expect("Calc sheet")..].to_string()
+        };
+
+        assert_eq!(
+            convert(&ImportOptions::default()),
+            "## Calc\n\n| Item | Price | Link |\n| --- | --- | --- |\n| Widget | 2.5 | Docs |\n| Total | 5 |  |\n"
+        );
+
+        let mut options = ImportOptions::default();
+        options.xlsx.formulas = Formulas::Beside;
+        options.xlsx.hyperlinks = true;
+        options.xlsx.comments = CellComments::Footnotes;
+        assert_eq!(
+            convert(&options),
+            "## Calc\n\n| Item | Price | Link |\n| --- | --- | --- |\n\
+             | Widget[^2] | 2.5 | [Docs](https://example.com/docs?a=1&b=2) |\n\
+             | Total[^3] | 5 `=B2*2` |  |\n\
+             \n[^2]: Ada: Discontinued in May\n\
+             [^3]: Bob: Check the sum\n\n    Kim: Looks right\n"
+        );
+
+        options.xlsx.formulas = Formulas::Instead;
+        assert!(convert(&options).contains("| Total[^3] | `=B2*2` |  |\n"));
+    }
+
+    /// A comment on a merged cell: "Summary" renders as an HTML table, which
+    /// can't hold a `[^n]` reference, so its note follows the table.
+    #[test]
+    fn test_xlsx_comment_in_spanned_table() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.xlsx");
+        let mut sink = MediaSink::new(std::env::temp_dir());
+        let mut report = ImportReport::default();
+        let mut options = ImportOptions::default();
+        options.xlsx.comments = CellComments::Footnotes;
+
+        let md = xlsx_to_markdown(
+            path,
+            &mut sink,
+            &options,
+            &mut report,
+            &ImportProgress::default(),
+        )
+        .expect("xlsx_to_markdown should succeed");
+
+        assert!(
+            md.contains(
+                "<tr><td rowspan=\"2\">North<sup>1</sup></td><td>10</td><td>20</td></tr>\n\
+                 <tr><td>11</td><td>21</td></tr>\n\
+                 <tr><td>South</td><td>30</td><td>40</td></tr>\n\
+                 </table>\n\
+                 \n<sup>1</sup> Lee: Includes the North-East office\n"
+            ),
+            "{md}"
+        );
+        assert!(!md.contains("[^1]"), "{md}");
+        assert!(md.contains("[^2]: Ada: Discontinued in May\n"), "{md}");
+    }
+
+    /// An in-cell picture in "Summary", which renders as an HTML table,
+    /// becomes an `<img>` with an `assets/` source, which saving and the
+    /// CLI relocate along with the Markdown links.
+    #[test]
+    fn test_xlsx_in_cell_picture_in_spanned_table() {
+        let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.xlsx");
+        let parts = [
+            (
+                "xl/metadata.xml",
+                r#"<metadata><futureMetadata name="XLRICHVALUE" count="1"><bk><extLst><ext><xlrd:rvb i="0"/></ext></extLst></bk></futureMetadata><valueMetadata count="1"><bk><rc t="1" v="0"/></bk></valueMetadata></metadata>"#,
+            ),
+            (
+                "xl/richData/rdrichvalue.xml",
+                r#"<rvData count="1"><rv s="0"><v>0</v><v>5</v><v>Crate</v></rv></rvData>"#,
+            ),
+            (
+                "xl/richData/rdrichvaluestructure.xml",
+                r#"<rvStructures count="1"><s t="_localImage"><k n="_rvRel:LocalImageIdentifier" t="i"/><k n="CalcOrigin" t="i"/><k n="Text" t="s"/></s></rvStructures>"#,
+            ),
+            (
+                "xl/richData/richValueRel.xml",
+                r#"<richValueRels><rel r:id="rId1"/></richValueRels>"#,
+            ),
+            (
+                "xl/richData/_rels/richValueRel.xml.rels",
+                r#"<Relationships><Relationship Id="rId1" Target="../media/image1.png"/></Relationships>"#,
+            ),
+        ];
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(fixture).unwrap()).unwrap();
+        let mut sheet = String::new();
+        archive
+            .by_name("xl/worksheets/sheet3.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+        let sheet = sheet.replace(
+            r#"<c r="D6"><v>40</v></c>"#,
+            r#"<c r="D6" t="e" vm="1"><v>#VALUE!</v></c>"#,
+        );
+        let dir =
+            std::env::temp_dir().join(format!("pourdown-xlsx-cell-img-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("summary.xlsx");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        for i in 0..archive.len() {
+            let entry = archive.by_index_raw(i).unwrap();
+            if entry.name() != "xl/worksheets/sheet3.xml" {
+                zip.raw_copy_file(entry).unwrap();
+            }
+        }
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in [("xl/worksheets/sheet3.xml", sheet.as_str())]
+            .into_iter()
+            .chain(parts)
+        {
+            zip.start_file(name, options).unwrap();
+            std::io::Write::write_all(&mut zip, content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let mut sink = MediaSink::new(dir.join("assets"));
+        let md = xlsx_to_markdown(
+            path.to_str().unwrap(),
+            &mut sink,
+            &ImportOptions::default(),
+            &mut ImportReport::default(),
+            &ImportProgress::default(),
+        )
+        .expect("xlsx_to_markdown should succeed");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(
+            md.contains(
+                "<tr><td>South</td><td>30</td><td><img src=\"assets/image1.png\" alt=\"Crate\" /></td></tr>"
+            ),
+            "{md}"
+        );
+    }
+
+    #[test]
+    fn test_parse_hyperlinks_and_comments() {
+        let rels = HashMap::from([("rId1".to_string(), "https://example.com/".to_string())]);
+        let xml = r#"<hyperlinks><hyperlink ref="A1:A2" r:id="rId1" location="top"/><hyperlink ref="B1" location="Sheet2!A1"/></hyperlinks>"#;
+        let links = parse_hyperlinks(xml, &rels);
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[&(1, 0)], "https://example.com/#top");
+
+        let xml = r#"<comments><authors><author>Ada</author></authors><commentList><comment ref="B2" authorId="0"><text><r><t>Ada:</t></r><r><t xml:space="preserve">
+Check &amp; fix</t></r></text></comment></commentList></comments>"#;
+        assert_eq!(
+            parse_comments(xml),
+            [(
+                (1, 1),
+                CellComment {
+                    author: "Ada".to_string(),
+                    text: "Check & fix".to_string()
+                }
+            )]
+        );
+
+        let persons = HashMap::from([("{P1}".to_string(), "Bob".to_string())]);
+        let xml = r#"<ThreadedComments><threadedComment ref="C3" personId="{P1}" id="{1}"><text>Why?</text></threadedComment><threadedComment ref="C3" personId="{P9}" id="{2}" parentId="{1}"><text>See   below</text></threadedComment></ThreadedComments>"#;
+        assert_eq!(
+            parse_threaded_comments(xml, &persons),
+            [
+                (
+                    (2, 2),
+                    CellComment {
+                        author: "Bob".to_string(),
+                        text: "Why?".to_string()
+                    }
+                ),
+                (
+                    (2, 2),
+                    CellComment {
+                        author: String::new(),
+                        text: "See below".to_string()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_drawing_anchors_and_alt_text() {
+        let xml = r#"<xdr:wsDr><xdr:oneCellAnchor><xdr:from><xdr:col>3</xdr:col><xdr:colOff>10</xdr:colOff><xdr:row>7</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:from><xdr:ext cx="1" cy="1"/>
+<xdr:pic><xdr:nvPicPr><xdr:cNvPr id="2" name="Picture 1" title="Logo"/></xdr:nvPicPr><xdr:blipFill><a:blip r:embed="rId1"/></xdr:blipFill></xdr:pic></xdr:oneCellAnchor>
+<xdr:twoCellAnchor><xdr:from><xdr:col>0</xdr:col><xdr:row>0</xdr:row></xdr:from><xdr:graphicFrame><a:graphic><c:chart r:id="rId9"/></a:graphic></xdr:graphicFrame></xdr:twoCellAnchor>
+<xdr:absoluteAnchor><xdr:pos x="0" y="0"/><xdr:pic><xdr:nvPicPr><xdr:cNvPr id="3" name="Picture 2" descr="Line one&#xA;line two"/></xdr:nvPicPr><xdr:blipFill><a:blip r:embed="rId2"/></xdr:blipFill></xdr:pic></xdr:absoluteAnchor></xdr:wsDr>"#;
+        let images = HashMap::from([
+            ("rId1".to_string(), "xl/media/image1.png".to_string()),
+            ("rId2".to_string(), "xl/media/image2.jpeg".to_string()),
+        ]);
+        let picture = |anchor, part: &str, alt: &str| SheetPicture {
+            anchor,
+            in_cell: false,
+            part: part.to_string(),
+            alt: alt.to_string(),
+        };
+        assert_eq!(
+            parse_drawing(xml, &images),
+            [
+                picture(Some((7, 3)), "xl/media/image1.png", "Logo"),
+                picture(None, "xl/media/image2.jpeg", "Line one line two"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_cell_images_follows_rich_value_chain() {
+        let parts = [
+            (
+                "xl/metadata.xml",
+                r#"<metadata><futureMetadata name="XLRICHVALUE" count="2"><bk><extLst><ext><xlrd:rvb i="1"/></ext></extLst></bk><bk><extLst><ext><xlrd:rvb i="0"/></ext></extLst></bk></futureMetadata>
+<valueMetadata count="2"><bk><rc t="1" v="0"/></bk><bk><rc t="1" v="1"/></bk></valueMetadata></metadata>"#,
+            ),
+            (
+                "xl/richData/rdrichvalue.xml",
+                r#"<rvData count="2"><rv s="0"><v>0</v><v>5</v></rv><rv s="1"><v>1</v><v>5</v><v>A &amp; B</v></rv></rvData>"#,
+            ),
+            (
+                "xl/richData/rdrichvaluestructure.xml",
+                r#"<rvStructures count="2"><s t="_localImage"><k n="_rvRel:LocalImageIdentifier" t="i"/><k n="CalcOrigin" t="i"/></s><s t="_localImage"><k n="_rvRel:LocalImageIdentifier" t="i"/><k n="CalcOrigin" t="i"/><k n="Text" t="s"/></s></rvStructures>"#,
+            ),
+            (
+                "xl/richData/richValueRel.xml",
+                r#"<richValueRels><rel r:id="rId1"/><rel r:id="rId2"/></richValueRels>"#,
+            ),
+            (
+                "xl/richData/_rels/richValueRel.xml.rels",
+                r#"<Relationships><Relationship Id="rId1" Target="../media/image1.png"/><Relationship Id="rId2" Target="../media/image2.png"/></Relationships>"#,
+            ),
+        ];
+        let mut bytes = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut bytes));
+            for (name, content) in parts {
+                zip.start_file(name, zip::write::SimpleFileOptions::default())
+                    .unwrap();
+                std::io::Write::write_all(&mut zip, content.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes.as_slice())).unwrap();
+        // vm 1 -> future 0 -> rich value 1 -> rel 1; vm 2 -> rich value 0 -> rel 0.
+        assert_eq!(
+            read_cell_images(&mut archive),
+            HashMap::from([
+                (1, ("xl/media/image2.png".to_string(), "A & B".to_string())),
+                (2, ("xl/media/image1.png".to_string(), String::new())),
+            ])
+        );
+        let sheet = r#"<sheetData><row r="2"><c r="B2" t="e" vm="2"><v>#VALUE!</v></c><c r="C2"><v>1</v></c></row></sheetData>"#;
+        assert_eq!(value_metadata_cells(sheet), [((1, 1), 2)]);
+    }
+
+    #[test]
+    fn test_xlsx_row_cap_spans_tables_on_a_sheet() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.xlsx");
+        let mut sink = MediaSink::new(std::env::temp_dir());
+        let mut report = ImportReport::default();
+        let mut options = ImportOptions::default();
+        options.xlsx.max_rows_per_sheet = 2;
+        let md = xlsx_to_markdown(
+            path,
+            &mut sink,
+            &options,
+            &mut report,
+            &ImportProgress::default(),
+        )
+        .expect("xlsx_to_markdown should succeed");
+
+        // Sales uses the two rows; the islands after it are left out.
+        let report_sheet = &md[md.find("## Report").expect("Report sheet")..];
+        assert!(report_sheet.contains("| Pears | 4 |"), "{report_sheet}");
+        assert!(!report_sheet.contains("### E1:F2"), "{report_sheet}");
+        assert!(
+            report_sheet.contains("2 rows were omitted (showing first 2 data rows)"),
+            "{report_sheet}"
+        );
+    }
+
+    #[test]
+    fn test_sheet_blocks_islands_and_headerless_table() {
+        let mut range = Range::new((0, 0), (6, 5));
+        for (pos, text) in [
+            ((0, 0), "a"),
+            ((1, 1), "b"),
+            ((0, 3), "c"),
+            ((3, 3), "d"),
+            ((5, 0), "t1"),
+            ((6, 1), "t2"),
+        ] {
+            range.set_value(pos, Data::String(text.to_string()));
+        }
+        let table = TableDef {
+            name: "Codes".to_string(),
+            start: (5, 0),
+            end: (6, 1),
+            header_row: false,
+            columns: vec!["Key".to_string(), "Value".to_string()],
+        };
+        // B3:B4 is merged, joining (1, 1) to the row below the blank one.
+        let regions = [Dimensions::new((2, 1), (3, 1))];
+        let rows: Vec<u32> = (0..7).collect();
+        let cols: Vec<u32> = (0..6).collect();
+        let blocks = sheet_blocks(&range, &rows, &cols, &[table], &regions);
+        let shape: Vec<(String, Option<Vec<String>>)> = blocks
+            .iter()
+            .map(|b| (b.title(), b.header.clone()))
+            .collect();
+        assert_eq!(
+            shape,
+            [
+                ("A1:B4".to_string(), None),
+                ("D1:D1".to_string(), None),
+                ("D4:D4".to_string(), None),
+                (
+                    "Codes".to_string(),
+                    Some(vec!["Key".to_string(), "Value".to_string()])
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_table_and_part_paths() {
+        let xml = r#"<table id="2" name="Table2" displayName="Q&amp;A" ref="B2:D9" headerRowCount="0"><tableColumns count="3"><tableColumn id="1" name="Ask"/><tableColumn id="2" name="Answer"/><tableColumn id="3" name="By"/></tableColumns></table>"#;
+        let table = parse_table(xml).unwrap();
+        assert_eq!(table.name, "Q&A");
+        assert_eq!((table.start, table.end), ((1, 1), (8, 3)));
+        assert!(!table.header_row);
+        assert_eq!(table.columns, ["Ask", "Answer", "By"]);
+        assert_eq!(
+            resolve_part_path("xl/worksheets", "../tables/table1.xml"),
+            "xl/tables/table1.xml"
+        );
+        assert_eq!(
+            resolve_part_path("xl/worksheets", "/xl/tables/t.xml"),
+            "xl/tables/t.xml"
+        );
+        assert_eq!(cell_name(0, 27), "AB1");
+        assert_eq!(cell_name(9, 0), "A10");
+    }
+
+    #[test]
+    fn test_parse_sheet_info() {
+        let xml = r#"<worksheet><cols><col min="2" max="3" hidden="1"/><col min="5" max="5" width="9"/></cols>
+<sheetData><row r="1" spans="1:4"><c r="A1" s="1"/><c r="AB1" s="2" t="n"><v>1</v></c></row><row r="4" hidden="true" ht="0"><c r="A4"/></row></sheetData></worksheet>"#;
+        let formats = [None, None, NumberFormat::parse("0%")];
+        let info = parse_sheet_info(xml, &formats);
+        assert_eq!(info.hidden_cols, HashSet::from([1, 2]));
+        assert_eq!(info.hidden_rows, HashSet::from([3]));
+        // Only the cell whose style carries a format is recorded.
+        assert_eq!(info.cell_styles, HashMap::from([((0, 27), 2)]));
+    }
+
+    #[test]
+    fn test_parse_cell_formats() {
+        let xml = r#"<styleSheet><numFmts count="1"><numFmt numFmtId="164" formatCode="&quot;$&quot;#,##0.00"/></numFmts>
+<cellStyleXfs count="1"><xf numFmtId="10"/></cellStyleXfs>
+<cellXfs count="4"><xf numFmtId="0" fontId="0"/><xf numFmtId="164" applyNumberFormat="1"/><xf numFmtId="10"/><xf numFmtId="14"></xf></cellXfs></styleSheet>"#;
+        let formats = parse_cell_formats(xml);
+        assert_eq!(formats.len(), 4);
+        assert!(formats[0].is_none());
+        assert_eq!(
+            formats[1].as_ref().unwrap().format(1234.5, false),
+            "$1,234.50"
+        );
+        assert_eq!(formats[2].as_ref().unwrap().format(0.153, false), "15.30%");
+        assert!(formats[3].as_ref().unwrap().is_date());
+    }
+
+    #[test]
+    fn test_parse_cell_ref() {
+        assert_eq!(parse_cell_ref("A1"), Some((0, 0)));
+        assert_eq!(parse_cell_ref("AB12"), Some((11, 27)));
+        assert_eq!(parse_cell_ref("12"), None);
+        assert_eq!(parse_cell_ref("a1"), None);
+    }
+
+    #[test]
+    fn test_grid_merges_count_shown_cells_only() {
+        // Rows 0, 2, 3 and columns 0, 1, 3 are shown.
+        let regions = [
+            Dimensions::new((0, 0), (0, 3)), // spans hidden column 2
+            Dimensions::new((1, 1), (3, 1)), // top-left in hidden row 1
+            Dimensions::new((2, 0), (3, 0)),
+        ];
+        let merges = grid_merges(&regions, &[0, 2, 3], &[0, 1, 3]);
+        assert_eq!(
+            merges.spans,
+            HashMap::from([((0, 0), (1, 3)), ((1, 0), (2, 1))])
+        );
+        assert_eq!(merges.covered, HashSet::from([(0, 1), (0, 2), (2, 0)]));
+    }
+
+    #[test]
+    fn test_xlsx_to_markdown_respects_max_rows_option() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.xlsx");
+        let mut sink = MediaSink::new(std::env::temp_dir());
+        let mut report = ImportReport::default();
+        let mut options = ImportOptions::default();
+        options.xlsx.max_rows_per_sheet = 1;
+
+        let md = xlsx_to_markdown(
+            path,
+            &mut sink,
+            &options,
+            &mut report,
+            &ImportProgress::default(),
+        )
+        .expect("xlsx_to_markdown should succeed");
+
+        assert!(
+            md.contains("rows were omitted (showing first 1 data rows)"),
+            "{md}"
+        );
+        assert!(report
+            .warnings
+            .iter()
+            .any(|w| w.kind == WarningKind::RowsTruncated
+                && w.location
+                    == Some(Location::Sheet {
+                        name: "Data".to_string()
+                    })));
     }
 }
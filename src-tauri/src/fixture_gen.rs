@@ -18,8 +18,8 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use docx_rs::{
-    AbstractNumbering, Docx, IndentLevel, Level, LevelJc, LevelText, NumberFormat, Numbering,
-    NumberingId, Paragraph, Pic, Run, Start, Table, TableCell, TableRow,
+    AbstractNumbering, Docx, IndentLevel, Level, LevelJc, LevelOverride, LevelText, NumberFormat,
+    Numbering, NumberingId, Paragraph, Pic, Run, Start, Table, TableCell, TableRow,
 };
 use zip::write::SimpleFileOptions;
 use zip::ZipWriter;
@@ -50,9 +50,11 @@ fn regenerate_fixtures() {
     write_sample_pdf(&dir.join("sample.pdf"));
 }
 
-/// An H1 heading, bold/italic/strike runs, a bullet list, a numbered list, a
-/// two-column table, and one embedded image — covers docx_to_markdown's
-/// documented per-format behaviors.
+/// An H1 heading, bold/italic/strike runs, a bullet list, a numbered list
+/// (with a nested bullet, a restarted copy via `w:lvlOverride`, and a
+/// letter-formatted list starting at 5), a two-column table, and one
+/// embedded image — covers docx_to_markdown's documented per-format
+/// behaviors.
 fn write_sample_docx(path: &Path) {
     let bullet_abstract = AbstractNumbering::new(1).add_level(Level::new(
         0,
@@ -61,19 +63,42 @@ fn write_sample_docx(path: &Path) {
         LevelText::new("\u{2022}"),
         LevelJc::new("left"),
     ));
-    let decimal_abstract = AbstractNumbering::new(2).add_level(Level::new(
+    let decimal_abstract = AbstractNumbering::new(2)
+        .add_level(Level::new(
+            0,
+            Start::new(1),
+            NumberFormat::new("decimal"),
+            LevelText::new("%1."),
+            LevelJc::new("left"),
+        ))
+        .add_level(Level::new(
+            1,
+            Start::new(1),
+            NumberFormat::new("bullet"),
+            LevelText::new("\u{25E6}"),
+            LevelJc::new("left"),
+        ));
+    let letter_abstract = AbstractNumbering::new(3).add_level(Level::new(
         0,
-        Start::new(1),
-        NumberFormat::new("decimal"),
-        LevelText::new("%1."),
+        Start::new(5),
+        NumberFormat::new("lowerLetter"),
+        LevelText::new("%1)"),
         LevelJc::new("left"),
     ));
+    let list_item = |text: &str, num_id: usize, level: usize| {
+        Paragraph::new()
+            .add_run(Run::new().add_text(text))
+            .numbering(NumberingId::new(num_id), IndentLevel::new(level))
+    };
 
     let docx = Docx::new()
         .add_abstract_numbering(bullet_abstract)
         .add_abstract_numbering(decimal_abstract)
+        .add_abstract_numbering(letter_abstract)
         .add_numbering(Numbering::new(1, 1))
         .add_numbering(Numbering::new(2, 2))
+        .add_numbering(Numbering::new(3, 2).add_override(LevelOverride::new(0).start(1)))
+        .add_numbering(Numbering::new(4, 3))
         .add_paragraph(
             Paragraph::new()
                 .add_run(Run::new().add_text("Sample Heading"))
@@ -99,11 +124,11 @@ fn write_sample_docx(path: &Path) {
                 .add_run(Run::new().add_text("Second bullet"))
                 .numbering(NumberingId::new(1), IndentLevel::new(0)),
         )
-        .add_paragraph(
-            Paragraph::new()
-                .add_run(Run::new().add_text("First step"))
-                .numbering(NumberingId::new(2), IndentLevel::new(0)),
-        )
+        .add_paragraph(list_item("First step", 2, 0))
+        .add_paragraph(list_item("Step detail", 2, 1))
+        .add_paragraph(list_item("Second step", 2, 0))
+        .add_paragraph(list_item("Restarted step", 3, 0))
+        .add_paragraph(list_item("Fifth item", 4, 0))
         .add_table(Table::new(vec![
             TableRow::new(vec![
                 TableCell::new()
@@ -124,19 +149,36 @@ fn write_sample_docx(path: &Path) {
     docx.build().pack(file).expect("pack sample.docx");
 }
 
-/// Two sheets: "Data" has a header-detected date column (Excel serial) and a
-/// multi-line cell (collapsed to one line by `cell_to_string`); "Notes" is a
-/// minimal second sheet. Assembled as raw OOXML (calamine has no writer
-/// counterpart) using inline strings, so no `sharedStrings.xml` part is
-/// needed.
+/// Six sheets: "Data" has a header-detected date column (Excel serial), a
+/// multi-line cell (collapsed to one line by `cell_to_string`) and columns
+/// with currency, percent and date number formats; "Notes" is
+/// a minimal second sheet; "Summary" has a merged title row, a vertically
+/// merged label, a hidden column and a hidden row; "Lookup" is a very hidden
+/// helper sheet; "Report" holds an Excel Table, two islands of data and a
+/// picture anchored in the table; "Calc" has a formula, a hyperlink, a note
+/// and a threaded comment. Assembled as raw OOXML (calamine has no
+/// writer counterpart) using inline strings, so no `sharedStrings.xml` part
+/// is needed.
 fn write_sample_xlsx(path: &Path) {
     const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
 <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
 <Default Extension="xml" ContentType="application/xml"/>
+<Default Extension="png" ContentType="image/png"/>
 <Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>
 <Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
 <Override PartName="/xl/worksheets/sheet2.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+<Override PartName="/xl/worksheets/sheet3.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+<Override PartName="/xl/worksheets/sheet4.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+<Override PartName="/xl/worksheets/sheet5.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+<Override PartName="/xl/tables/table1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.table+xml"/>
+<Override PartName="/xl/worksheets/sheet6.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+<Override PartName="/xl/comments2.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.comments+xml"/>
+<Override PartName="/xl/comments1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.comments+xml"/>
+<Override PartName="/xl/threadedComments/threadedComment1.xml" ContentType="application/vnd.ms-excel.threadedcomments+xml"/>
+<Override PartName="/xl/persons/person.xml" ContentType="application/vnd.ms-excel.person+xml"/>
+<Override PartName="/xl/drawings/drawing1.xml" ContentType="application/vnd.openxmlformats-officedocument.drawing+xml"/>
+<Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>
 </Types>"#;
 
     const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
@@ -149,6 +191,10 @@ fn write_sample_xlsx(path: &Path) {
 <sheets>
 <sheet name="Data" sheetId="1" r:id="rId1"/>
 <sheet name="Notes" sheetId="2" r:id="rId2"/>
+<sheet name="Summary" sheetId="3" r:id="rId3"/>
+<sheet name="Lookup" sheetId="4" state="veryHidden" r:id="rId4"/>
+<sheet name="Report" sheetId="5" r:id="rId6"/>
+<sheet name="Calc" sheetId="6" r:id="rId7"/>
 </sheets>
 </workbook>"#;
 
@@ -156,11 +202,33 @@ fn write_sample_xlsx(path: &Path) {
 <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
 <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>
 <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/>
+<Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet3.xml"/>
+<Relationship Id="rId4" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet4.xml"/>
+<Relationship Id="rId5" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>
+<Relationship Id="rId6" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet5.xml"/>
+<Relationship Id="rId7" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet6.xml"/>
+<Relationship Id="rId8" Type="http://schemas.microsoft.com/office/2017/10/relationships/person" Target="persons/person.xml"/>
 </Relationships>"#;
 
+    // Cell styles 1-3: a custom currency code, a custom percent code and
+    // the built-in short date (id 14).
+    const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<numFmts count="2"><numFmt numFmtId="164" formatCode="&quot;$&quot;#,##0.00"/><numFmt numFmtId="165" formatCode="0.0%"/></numFmts>
+<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>
+<cellXfs count="4">
+<xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/>
+<xf numFmtId="164" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>
+<xf numFmtId="165" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>
+<xf numFmtId="14" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>
+</cellXfs>
+</styleSheet>"#;
+
     // B2/B3 hold the Excel serial 45000/45100 — the "Date" header makes
     // xlsx_to_markdown reinterpret them as ISO dates. C2 has an embedded
     // newline to exercise the single-line collapse in cell_to_string.
+    // Amount, Share and Due carry currency, percent and date number
+    // formats (Due's header doesn't mention a date).
     const SHEET1: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
 <sheetData>
@@ -168,17 +236,26 @@ fn write_sample_xlsx(path: &Path) {
 <c r="A1" t="inlineStr"><is><t>Name</t></is></c>
 <c r="B1" t="inlineStr"><is><t>Date</t></is></c>
 <c r="C1" t="inlineStr"><is><t>Notes</t></is></c>
+<c r="D1" t="inlineStr"><is><t>Amount</t></is></c>
+<c r="E1" t="inlineStr"><is><t>Share</t></is></c>
+<c r="F1" t="inlineStr"><is><t>Due</t></is></c>
 </row>
 <row r="2">
 <c r="A2" t="inlineStr"><is><t>Alice</t></is></c>
 <c r="B2"><v>45000</v></c>
 <c r="C2" t="inlineStr"><is><t>Line one
 Line two</t></is></c>
+<c r="D2" s="1"><v>1234.5</v></c>
+<c r="E2" s="2"><v>0.153</v></c>
+<c r="F2" s="3"><v>45031</v></c>
 </row>
 <row r="3">
 <c r="A3" t="inlineStr"><is><t>Bob</t></is></c>
 <c r="B3"><v>45100</v></c>
 <c r="C3" t="inlineStr"><is><t>Done</t></is></c>
+<c r="D3" s="1"><v>-80</v></c>
+<c r="E3" s="2"><v>1</v></c>
+<c r="F3" s="3"><v>45100</v></c>
 </row>
 </sheetData>
 </worksheet>"#;
@@ -195,6 +272,138 @@ Line two</t></is></c>
 </sheetData>
 </worksheet>"#;
 
+    // A1:D1 is a merged title and A3:A4 a merged row label, with a note;
+    // column C and row 5 are hidden.
+    const SHEET3: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<cols><col min="3" max="3" width="0" hidden="1" customWidth="1"/></cols>
+<sheetData>
+<row r="1"><c r="A1" t="inlineStr"><is><t>Quarterly summary</t></is></c></row>
+<row r="2">
+<c r="A2" t="inlineStr"><is><t>Region</t></is></c>
+<c r="B2" t="inlineStr"><is><t>Q1</t></is></c>
+<c r="C2" t="inlineStr"><is><t>Helper</t></is></c>
+<c r="D2" t="inlineStr"><is><t>Q2</t></is></c>
+</row>
+<row r="3"><c r="A3" t="inlineStr"><is><t>North</t></is></c><c r="B3"><v>10</v></c><c r="C3"><v>1</v></c><c r="D3"><v>20</v></c></row>
+<row r="4"><c r="B4"><v>11</v></c><c r="C4"><v>2</v></c><c r="D4"><v>21</v></c></row>
+<row r="5" hidden="1"><c r="A5" t="inlineStr"><is><t>Scratch</t></is></c><c r="B5"><v>99</v></c></row>
+<row r="6"><c r="A6" t="inlineStr"><is><t>South</t></is></c><c r="B6"><v>30</v></c><c r="C6"><v>3</v></c><c r="D6"><v>40</v></c></row>
+</sheetData>
+<mergeCells count="2"><mergeCell ref="A1:D1"/><mergeCell ref="A3:A4"/></mergeCells>
+</worksheet>"#;
+
+    const SHEET3_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments2.xml"/>
+</Relationships>"#;
+
+    const COMMENTS2: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<authors><author>Lee</author></authors>
+<commentList>
+<comment ref="A3" authorId="0"><text><r><t xml:space="preserve">Lee:
+Includes the North-East office</t></r></text></comment>
+</commentList>
+</comments>"#;
+
+    const SHEET4: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<sheetData>
+<row r="1"><c r="A1" t="inlineStr"><is><t>Code</t></is></c></row>
+<row r="2"><c r="A2" t="inlineStr"><is><t>internal lookup value</t></is></c></row>
+</sheetData>
+</worksheet>"#;
+
+    // A1:B3 is the Excel Table "Sales"; E1:F2 and A5:A6 are separate
+    // islands of data beside and below it. A picture is anchored at A3.
+    const SHEET5: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<sheetData>
+<row r="1"><c r="A1" t="inlineStr"><is><t>Product</t></is></c><c r="B1" t="inlineStr"><is><t>Units</t></is></c><c r="E1" t="inlineStr"><is><t>Owner</t></is></c><c r="F1" t="inlineStr"><is><t>Team</t></is></c></row>
+<row r="2"><c r="A2" t="inlineStr"><is><t>Apples</t></is></c><c r="B2"><v>10</v></c><c r="E2" t="inlineStr"><is><t>Kim</t></is></c><c r="F2" t="inlineStr"><is><t>Ops</t></is></c></row>
+<row r="3"><c r="A3" t="inlineStr"><is><t>Pears</t></is></c><c r="B3"><v>4</v></c></row>
+<row r="5"><c r="A5" t="inlineStr"><is><t>Notes</t></is></c></row>
+<row r="6"><c r="A6" t="inlineStr"><is><t>Shipped monthly</t></is></c></row>
+</sheetData>
+<drawing r:id="rId2"/>
+<tableParts count="1"><tablePart r:id="rId1"/></tableParts>
+</worksheet>"#;
+
+    const SHEET5_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/table" Target="../tables/table1.xml"/>
+<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing" Target="../drawings/drawing1.xml"/>
+</Relationships>"#;
+
+    const DRAWING1: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<xdr:wsDr xmlns:xdr="http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<xdr:twoCellAnchor editAs="oneCell">
+<xdr:from><xdr:col>0</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>2</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:from>
+<xdr:to><xdr:col>1</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>4</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:to>
+<xdr:pic>
+<xdr:nvPicPr><xdr:cNvPr id="2" name="Picture 1" descr="Pear crate"/><xdr:cNvPicPr/></xdr:nvPicPr>
+<xdr:blipFill><a:blip r:embed="rId1"/><a:stretch><a:fillRect/></a:stretch></xdr:blipFill>
+<xdr:spPr><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></xdr:spPr>
+</xdr:pic>
+<xdr:clientData/>
+</xdr:twoCellAnchor>
+</xdr:wsDr>"#;
+
+    const DRAWING1_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image1.png"/>
+</Relationships>"#;
+
+    const TABLE1: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="1" name="Table1" displayName="Sales" ref="A1:B3">
+<autoFilter ref="A1:B3"/>
+<tableColumns count="2"><tableColumn id="1" name="Product"/><tableColumn id="2" name="Units"/></tableColumns>
+<tableStyleInfo name="TableStyleMedium2" showRowStripes="1"/>
+</table>"#;
+
+    // B3 holds a formula, C2 a hyperlink; A2 has a legacy note and A3 a
+    // threaded comment with a reply (plus the legacy note Excel writes
+    // alongside it for older versions).
+    const SHEET6: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<sheetData>
+<row r="1"><c r="A1" t="inlineStr"><is><t>Item</t></is></c><c r="B1" t="inlineStr"><is><t>Price</t></is></c><c r="C1" t="inlineStr"><is><t>Link</t></is></c></row>
+<row r="2"><c r="A2" t="inlineStr"><is><t>Widget</t></is></c><c r="B2"><v>2.5</v></c><c r="C2" t="inlineStr"><is><t>Docs</t></is></c></row>
+<row r="3"><c r="A3" t="inlineStr"><is><t>Total</t></is></c><c r="B3"><f>B2*2</f><v>5</v></c></row>
+</sheetData>
+<hyperlinks><hyperlink ref="C2" r:id="rId3"/></hyperlinks>
+</worksheet>"#;
+
+    const SHEET6_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments1.xml"/>
+<Relationship Id="rId2" Type="http://schemas.microsoft.com/office/2017/10/relationships/threadedComment" Target="../threadedComments/threadedComment1.xml"/>
+<Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com/docs?a=1&amp;b=2" TargetMode="External"/>
+</Relationships>"#;
+
+    const COMMENTS1: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<authors><author>Ada</author><author>tc={00000000-0000-0000-0000-000000000001}</author></authors>
+<commentList>
+<comment ref="A2" authorId="0"><text><r><rPr><b/></rPr><t>Ada:</t></r><r><t xml:space="preserve">
+Discontinued in May</t></r></text></comment>
+<comment ref="A3" authorId="1"><text><t>[Threaded comment] Your version of Excel allows you to read this threaded comment.</t></text></comment>
+</commentList>
+</comments>"#;
+
+    const THREADED_COMMENTS1: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<ThreadedComments xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments">
+<threadedComment ref="A3" dT="2024-05-01T10:00:00.00" personId="{P1}" id="{00000000-0000-0000-0000-000000000001}"><text>Check the sum</text></threadedComment>
+<threadedComment ref="A3" dT="2024-05-01T11:00:00.00" personId="{P2}" id="{00000000-0000-0000-0000-000000000002}" parentId="{00000000-0000-0000-0000-000000000001}"><text>Looks right</text></threadedComment>
+</ThreadedComments>"#;
+
+    const PERSONS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<personList xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments">
+<person displayName="Bob" id="{P1}" userId="bob" providerId="None"/>
+<person displayName="Kim" id="{P2}" userId="kim" providerId="None"/>
+</personList>"#;
+
     let file = std::fs::File::create(path).expect("create sample.xlsx");
     let mut zip = ZipWriter::new(file);
     let opts = SimpleFileOptions::default();
@@ -203,18 +412,41 @@ Line two</t></is></c>
         ("_rels/.rels", ROOT_RELS),
         ("xl/workbook.xml", WORKBOOK),
         ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS),
+        ("xl/styles.xml", STYLES),
         ("xl/worksheets/sheet1.xml", SHEET1),
         ("xl/worksheets/sheet2.xml", SHEET2),
+        ("xl/worksheets/sheet3.xml", SHEET3),
+        ("xl/worksheets/_rels/sheet3.xml.rels", SHEET3_RELS),
+        ("xl/comments2.xml", COMMENTS2),
+        ("xl/worksheets/sheet4.xml", SHEET4),
+        ("xl/worksheets/sheet5.xml", SHEET5),
+        ("xl/worksheets/_rels/sheet5.xml.rels", SHEET5_RELS),
+        ("xl/tables/table1.xml", TABLE1),
+        ("xl/drawings/drawing1.xml", DRAWING1),
+        ("xl/drawings/_rels/drawing1.xml.rels", DRAWING1_RELS),
+        ("xl/worksheets/sheet6.xml", SHEET6),
+        ("xl/worksheets/_rels/sheet6.xml.rels", SHEET6_RELS),
+        ("xl/comments1.xml", COMMENTS1),
+        (
+            "xl/threadedComments/threadedComment1.xml",
+            THREADED_COMMENTS1,
+        ),
+        ("xl/persons/person.xml", PERSONS),
     ] {
-        zip.start_file(name, opts).unwrap_or_else(|e| panic!("start_file {name}: {e}"));
-        zip.write_all(content.as_bytes()).unwrap_or_else(|e| panic!("write {name}: {e}"));
+        zip.start_file(name, opts)
+            .unwrap_or_else(|e| panic!("start_file {name}: {e}"));
+        zip.write_all(content.as_bytes())
+            .unwrap_or_else(|e| panic!("write {name}: {e}"));
     }
+    zip.start_file("xl/media/image1.png", opts)
+        .expect("start_file image1.png");
+    zip.write_all(&tiny_png()).expect("write image1.png");
     zip.finish().expect("finish sample.xlsx");
 }
 
 /// Two slides separated by `---`: slide 1 has a title placeholder and a
 /// non-bulleted bold paragraph; slide 2 has a title, a bulleted paragraph,
-/// and an embedded image. Only the archive parts pptx_to_markdown actually
+/// an embedded image, and a pie chart. Only the archive parts pptx_to_markdown actually
 /// reads are included (no `presentation.xml` — Pourdown's reader never opens
 /// it, so a real PowerPoint-openable file isn't required for this fixture).
 fn write_sample_pptx(path: &Path) {
@@ -238,27 +470,44 @@ fn write_sample_pptx(path: &Path) {
 <a:p><a:r><a:t>First bullet</a:t></a:r></a:p>
 </p:txBody></p:sp>
 <p:pic><p:blipFill><a:blip r:embed="rId1"/></p:blipFill></p:pic>
+<p:graphicFrame><a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/chart">
+<c:chart xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" r:id="rId2"/>
+</a:graphicData></a:graphic></p:graphicFrame>
 </p:spTree></p:cSld>
 </p:sld>"#;
 
     const SLIDE2_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
 <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image1.png"/>
+<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart" Target="../charts/chart1.xml"/>
 </Relationships>"#;
 
+    const CHART1: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+<c:chart><c:title><c:tx><c:rich><a:p><a:r><a:t>Market Share</a:t></a:r></a:p></c:rich></c:tx></c:title>
+<c:plotArea><c:pieChart><c:ser>
+<c:tx><c:strRef><c:strCache><c:pt idx="0"><c:v>Share</c:v></c:pt></c:strCache></c:strRef></c:tx>
+<c:cat><c:strRef><c:strCache><c:ptCount val="2"/><c:pt idx="0"><c:v>Us</c:v></c:pt><c:pt idx="1"><c:v>Them</c:v></c:pt></c:strCache></c:strRef></c:cat>
+<c:val><c:numRef><c:numCache><c:ptCount val="2"/><c:pt idx="0"><c:v>60</c:v></c:pt><c:pt idx="1"><c:v>40</c:v></c:pt></c:numCache></c:numRef></c:val>
+</c:ser></c:pieChart></c:plotArea></c:chart>
+</c:chartSpace>"#;
+
     let file = std::fs::File::create(path).expect("create sample.pptx");
     let mut zip = ZipWriter::new(file);
     let opts = SimpleFileOptions::default();
 
-    let entries: [(&str, &[u8]); 4] = [
+    let entries: [(&str, &[u8]); 5] = [
         ("ppt/slides/slide1.xml", SLIDE1.as_bytes()),
         ("ppt/slides/slide2.xml", SLIDE2.as_bytes()),
         ("ppt/slides/_rels/slide2.xml.rels", SLIDE2_RELS.as_bytes()),
         ("ppt/media/image1.png", &tiny_png()),
+        ("ppt/charts/chart1.xml", CHART1.as_bytes()),
     ];
     for (name, bytes) in entries {
-        zip.start_file(name, opts).unwrap_or_else(|e| panic!("start_file {name}: {e}"));
-        zip.write_all(bytes).unwrap_or_else(|e| panic!("write {name}: {e}"));
+        zip.start_file(name, opts)
+            .unwrap_or_else(|e| panic!("start_file {name}: {e}"));
+        zip.write_all(bytes)
+            .unwrap_or_else(|e| panic!("write {name}: {e}"));
     }
     zip.finish().expect("finish sample.pptx");
 }
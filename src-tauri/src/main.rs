@@ -1,20 +1,23 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod cli;
 mod convert;
 #[cfg(test)]
 mod fixture_gen;
 
-use std::collections::VecDeque;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Mutex;
-use serde::{Deserialize, Serialize};
-use tauri::menu::{AboutMetadata, AboutMetadataBuilder, CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::image::Image;
+use tauri::menu::{
+    AboutMetadata, AboutMetadataBuilder, CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu,
+};
 use tauri::{AppHandle, Emitter, Manager, State};
 use walkdir::WalkDir;
-use regex::RegexBuilder;
 
 // Dev-only diagnostic logging: compiles to a no-op in release builds so
 // nothing but real errors reach stdout in a shipped binary. Behaves like
@@ -32,6 +35,9 @@ struct AppState {
     pending_open_files: Mutex<VecDeque<String>>,
     language: Mutex<String>,
     source_mode: Mutex<bool>,
+    // Cancellation tokens of in-flight imports, keyed by the frontend's
+    // import id (see `import_document` / `cancel_import`).
+    active_imports: Mutex<HashMap<String, convert::progress::CancelToken>>,
 }
 
 impl AppState {
@@ -41,6 +47,7 @@ impl AppState {
             pending_open_files: Mutex::new(VecDeque::new()),
             language: Mutex::new(language),
             source_mode: Mutex::new(source_mode),
+            active_imports: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -55,8 +62,7 @@ impl AppState {
 fn app_config_dir() -> Result<PathBuf, String> {
     let config_dir = if cfg!(target_os = "macos") {
         // macOS: ~/Library/Application Support
-        let home =
-            std::env::var("HOME").map_err(|_| "Failed to get HOME directory".to_string())?;
+        let home = std::env::var("HOME").map_err(|_| "Failed to get HOME directory".to_string())?;
         PathBuf::from(home).join("Library/Application Support")
     } else if cfg!(target_os = "windows") {
         // Windows: %LOCALAPPDATA%
@@ -65,8 +71,7 @@ fn app_config_dir() -> Result<PathBuf, String> {
         PathBuf::from(local_app_data)
     } else {
         // Linux: ~/.config
-        let home =
-            std::env::var("HOME").map_err(|_| "Failed to get HOME directory".to_string())?;
+        let home = std::env::var("HOME").map_err(|_| "Failed to get HOME directory".to_string())?;
         PathBuf::from(home).join(".config")
     };
 
@@ -102,6 +107,19 @@ fn imports_root_dir() -> Result<PathBuf, String> {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 struct UserSettings {
     language: String,
+    // Import tuning (image size cap, sheet row cap, PDF heuristics). Absent
+    // in settings files written before it existed, hence the default.
+    #[serde(default)]
+    import: convert::ImportOptions,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        UserSettings {
+            language: "en".to_string(),
+            import: convert::ImportOptions::default(),
+        }
+    }
 }
 
 impl UserSettings {
@@ -151,10 +169,9 @@ impl UserSettings {
         let path = Self::config_path()?;
         let content = serde_json::to_string_pretty(self)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
-        
-        fs::write(&path, content)
-            .map_err(|e| format!("Failed to write settings file: {}", e))?;
-        
+
+        fs::write(&path, content).map_err(|e| format!("Failed to write settings file: {}", e))?;
+
         debug_log!("💾 Settings saved to: {}", path.display());
         Ok(())
     }
@@ -210,11 +227,11 @@ fn get_label(lang: &str, key: &str) -> String {
             "file_import" => "匯入".to_string(),
             "file_import_docx" => "從 Word (.docx)".to_string(),
             "file_import_xlsx" => "從試算表 (.xlsx)".to_string(),
-            "file_import_pdf"  => "從 PDF".to_string(),
+            "file_import_pdf" => "從 PDF".to_string(),
             "file_import_pptx" => "從 PowerPoint (.pptx)".to_string(),
             "file_export" => "匯出".to_string(),
             "file_export_html" => "匯出為 HTML...".to_string(),
-            "file_export_pdf"  => "匯出為 PDF...".to_string(),
+            "file_export_pdf" => "匯出為 PDF...".to_string(),
             "app_about" => "關於 Pourdown".to_string(),
             "app_preferences" => "偏好設定…".to_string(),
             "app_services" => "服務".to_string(),
@@ -276,11 +293,11 @@ fn get_label(lang: &str, key: &str) -> String {
             "file_import" => "Import".to_string(),
             "file_import_docx" => "From Word (.docx)".to_string(),
             "file_import_xlsx" => "From Spreadsheet (.xlsx)".to_string(),
-            "file_import_pdf"  => "From PDF".to_string(),
+            "file_import_pdf" => "From PDF".to_string(),
             "file_import_pptx" => "From PowerPoint (.pptx)".to_string(),
             "file_export" => "Export".to_string(),
             "file_export_html" => "Export as HTML...".to_string(),
-            "file_export_pdf"  => "Export as PDF...".to_string(),
+            "file_export_pdf" => "Export as PDF...".to_string(),
             "app_about" => "About Pourdown".to_string(),
             "app_preferences" => "Preferences…".to_string(),
             "app_services" => "Services".to_string(),
@@ -308,8 +325,7 @@ struct FileEntry {
 // Read a markdown file
 #[tauri::command]
 async fn read_markdown_file(path: String) -> Result<String, String> {
-    fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read file: {}", e))
+    fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))
 }
 
 // Save a markdown file
@@ -317,36 +333,33 @@ async fn read_markdown_file(path: String) -> Result<String, String> {
 async fn save_markdown_file(path: String, content: String) -> Result<(), String> {
     // Create parent directory if it doesn't exist
     if let Some(parent) = PathBuf::from(&path).parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
     }
-    
-    fs::write(&path, content)
-        .map_err(|e| format!("Failed to write file: {}", e))
+
+    fs::write(&path, content).map_err(|e| format!("Failed to write file: {}", e))
 }
 
 // List directory contents
 #[tauri::command]
 async fn list_directory(path: String) -> Result<Vec<FileEntry>, String> {
-    let entries = fs::read_dir(&path)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
-    
+    let entries = fs::read_dir(&path).map_err(|e| format!("Failed to read directory: {}", e))?;
+
     let mut file_entries = Vec::new();
-    
+
     for entry in entries {
         match entry {
             Ok(entry) => {
                 let path = entry.path();
                 let name = entry.file_name().to_string_lossy().to_string();
-                
+
                 // Skip hidden files
                 if name.starts_with('.') {
                     continue;
                 }
-                
+
                 let is_directory = path.is_dir();
                 let path_str = path.to_string_lossy().to_string();
-                
+
                 file_entries.push(FileEntry {
                     name,
                     path: path_str,
@@ -356,23 +369,23 @@ async fn list_directory(path: String) -> Result<Vec<FileEntry>, String> {
             Err(_) => continue,
         }
     }
-    
+
     // Sort: directories first, then files, both alphabetically
-    file_entries.sort_by(|a, b| {
-        match (a.is_directory, b.is_directory) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        }
+    file_entries.sort_by(|a, b| match (a.is_directory, b.is_directory) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
     });
-    
+
     Ok(file_entries)
 }
 
 // Get recent files
 #[tauri::command]
 fn get_recent_files(state: State<AppState>) -> Result<Vec<String>, String> {
-    let recent = state.recent_files.lock()
+    let recent = state
+        .recent_files
+        .lock()
         .map_err(|_| "Failed to lock state".to_string())?;
     Ok(recent.iter().cloned().collect())
 }
@@ -380,18 +393,20 @@ fn get_recent_files(state: State<AppState>) -> Result<Vec<String>, String> {
 // Add a file to recent files
 #[tauri::command]
 fn add_recent_file(path: String, state: State<AppState>) -> Result<(), String> {
-    let mut recent = state.recent_files.lock()
+    let mut recent = state
+        .recent_files
+        .lock()
         .map_err(|_| "Failed to lock state".to_string())?;
-    
+
     // Remove if already exists
     recent.retain(|p| p != &path);
-    
+
     // Add to front
     recent.push_front(path);
-    
+
     // Keep only 10 most recent
     recent.truncate(10);
-    
+
     Ok(())
 }
 
@@ -400,27 +415,23 @@ fn add_recent_file(path: String, state: State<AppState>) -> Result<(), String> {
 async fn create_file(path: String) -> Result<(), String> {
     // Create parent directory if it doesn't exist
     if let Some(parent) = PathBuf::from(&path).parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
     }
-    
+
     // Create empty file
-    fs::write(&path, "")
-        .map_err(|e| format!("Failed to create file: {}", e))
+    fs::write(&path, "").map_err(|e| format!("Failed to create file: {}", e))
 }
 
 // Delete a file
 #[tauri::command]
 async fn delete_file(path: String) -> Result<(), String> {
-    fs::remove_file(&path)
-        .map_err(|e| format!("Failed to delete file: {}", e))
+    fs::remove_file(&path).map_err(|e| format!("Failed to delete file: {}", e))
 }
 
 // Rename a file
 #[tauri::command]
 async fn rename_file(old_path: String, new_path: String) -> Result<(), String> {
-    fs::rename(&old_path, &new_path)
-        .map_err(|e| format!("Failed to rename file: {}", e))
+    fs::rename(&old_path, &new_path).map_err(|e| format!("Failed to rename file: {}", e))
 }
 
 // Check if file exists
@@ -479,7 +490,8 @@ async fn search_in_files(
             continue;
         }
 
-        let ext = path.extension()
+        let ext = path
+            .extension()
             .and_then(|e| e.to_str())
             .unwrap_or("")
             .to_lowercase();
@@ -489,7 +501,10 @@ async fn search_in_files(
 
         // Skip hidden files/dirs
         let is_hidden = path.components().any(|c| {
-            c.as_os_str().to_str().map(|s| s.starts_with('.')).unwrap_or(false)
+            c.as_os_str()
+                .to_str()
+                .map(|s| s.starts_with('.'))
+                .unwrap_or(false)
         });
         if is_hidden {
             continue;
@@ -545,8 +560,11 @@ fn get_system_locale() -> Result<String, String> {
     match tauri_plugin_os::locale() {
         Some(locale_str) => {
             let normalized = normalize_language(&locale_str);
-            debug_log!("🌍 System locale detected: {} → normalized to: {}", 
-                     locale_str, normalized);
+            debug_log!(
+                "🌍 System locale detected: {} → normalized to: {}",
+                locale_str,
+                normalized
+            );
             Ok(normalized)
         }
         None => {
@@ -561,7 +579,9 @@ fn get_system_locale() -> Result<String, String> {
  */
 #[tauri::command]
 fn get_language(state: State<AppState>) -> Result<String, String> {
-    let lang = state.language.lock()
+    let lang = state
+        .language
+        .lock()
         .map_err(|_| "Failed to lock language state".to_string())?;
     Ok(lang.clone())
 }
@@ -573,11 +593,13 @@ fn get_language(state: State<AppState>) -> Result<String, String> {
 #[tauri::command]
 fn set_language(state: State<AppState>, lang: String) -> Result<(), String> {
     let normalized_lang = normalize_language(&lang);
-    
-    let mut l = state.language.lock()
+
+    let mut l = state
+        .language
+        .lock()
         .map_err(|_| "Failed to lock language state".to_string())?;
     *l = normalized_lang.clone();
-    
+
     debug_log!("💾 Language state updated to: {}", normalized_lang);
     Ok(())
 }
@@ -589,7 +611,7 @@ fn set_language(state: State<AppState>, lang: String) -> Result<(), String> {
  */
 #[tauri::command]
 fn get_user_settings() -> Result<UserSettings, String> {
-    let settings = UserSettings::load()?.unwrap_or_else(|| UserSettings { language: "en".to_string() });
+    let settings = UserSettings::load()?.unwrap_or_default();
     debug_log!("📂 User settings retrieved: language={}", settings.language);
     Ok(settings)
 }
@@ -607,11 +629,15 @@ fn get_user_settings() -> Result<UserSettings, String> {
  * old language until restart.
  */
 #[tauri::command]
-fn save_language_preference(app: AppHandle, lang: String, state: State<AppState>) -> Result<(), String> {
+fn save_language_preference(
+    app: AppHandle,
+    lang: String,
+    state: State<AppState>,
+) -> Result<(), String> {
     let normalized_lang = normalize_language(&lang);
 
     // Load existing settings (to preserve other settings if any)
-    let mut settings = UserSettings::load()?.unwrap_or_else(|| UserSettings { language: "en".to_string() });
+    let mut settings = UserSettings::load()?.unwrap_or_default();
 
     // Update language
     settings.language = normalized_lang.clone();
@@ -620,7 +646,9 @@ fn save_language_preference(app: AppHandle, lang: String, state: State<AppState>
     settings.save()?;
 
     // Also update in-memory state
-    let mut l = state.language.lock()
+    let mut l = state
+        .language
+        .lock()
         .map_err(|_| "Failed to lock language state".to_string())?;
     *l = normalized_lang.clone();
     drop(l);
@@ -640,13 +668,33 @@ fn save_language_preference(app: AppHandle, lang: String, state: State<AppState>
         }
     }
 
-    debug_log!("💾 Language preference saved and state updated to: {}", normalized_lang);
+    debug_log!(
+        "💾 Language preference saved and state updated to: {}",
+        normalized_lang
+    );
     Ok(())
 }
 
+/**
+ * Save the import tuning options (see `convert::ImportOptions`), preserving
+ * the other settings. Read back via `get_user_settings`; every later import
+ * (and headless `--convert` run) picks them up.
+ */
+#[tauri::command]
+fn save_import_options(options: convert::ImportOptions) -> Result<(), String> {
+    let mut settings = UserSettings::load()?.unwrap_or_default();
+    settings.import = options;
+    settings.save()
+}
+
 // Update check menu item state
 #[tauri::command]
-fn update_menu_item_state(app: AppHandle, state: State<AppState>, id: String, checked: bool) -> Result<(), String> {
+fn update_menu_item_state(
+    app: AppHandle,
+    state: State<AppState>,
+    id: String,
+    checked: bool,
+) -> Result<(), String> {
     // Track source_mode in AppState for cross-event consistency
     if id == "view_source_code" {
         if let Ok(mut sm) = state.source_mode.lock() {
@@ -669,10 +717,18 @@ fn enable_menu_item(app: AppHandle, id: String, enabled: bool) -> Result<(), Str
     if let Some(menu) = app.menu() {
         if let Some(item) = menu.get(&id) {
             match item {
-                tauri::menu::MenuItemKind::MenuItem(mi) => mi.set_enabled(enabled).map_err(|e| e.to_string())?,
-                tauri::menu::MenuItemKind::Submenu(sm) => sm.set_enabled(enabled).map_err(|e| e.to_string())?,
-                tauri::menu::MenuItemKind::Check(cm) => cm.set_enabled(enabled).map_err(|e| e.to_string())?,
-                tauri::menu::MenuItemKind::Icon(im) => im.set_enabled(enabled).map_err(|e| e.to_string())?,
+                tauri::menu::MenuItemKind::MenuItem(mi) => {
+                    mi.set_enabled(enabled).map_err(|e| e.to_string())?
+                }
+                tauri::menu::MenuItemKind::Submenu(sm) => {
+                    sm.set_enabled(enabled).map_err(|e| e.to_string())?
+                }
+                tauri::menu::MenuItemKind::Check(cm) => {
+                    cm.set_enabled(enabled).map_err(|e| e.to_string())?
+                }
+                tauri::menu::MenuItemKind::Icon(im) => {
+                    im.set_enabled(enabled).map_err(|e| e.to_string())?
+                }
                 tauri::menu::MenuItemKind::Predefined(_) => {}
             }
         }
@@ -683,10 +739,13 @@ fn enable_menu_item(app: AppHandle, id: String, enabled: bool) -> Result<(), Str
 // Result of importing a document: the converted Markdown plus the directory
 // (if any) holding sidecar images extracted during conversion. `media_dir` is
 // empty when the import produced no images (nothing to clean up or relocate).
+// `report` lists what the converter dropped (unsupported images, truncated
+// rows, tracked changes, ...) plus page/table/image counts.
 #[derive(Serialize)]
 struct ImportResult {
     markdown: String,
     media_dir: String,
+    report: convert::report::ImportReport,
 }
 
 /// Create a fresh, empty staging directory under `imports/` for one import's
@@ -708,25 +767,71 @@ fn new_import_dir() -> Result<PathBuf, String> {
     Ok(dir)
 }
 
+#[derive(Serialize, Clone)]
+struct ImportProgressPayload {
+    id: String,
+    done: usize,
+    total: usize,
+}
+
 // Import a document from a non-markdown format and return Markdown content
 // plus the directory (if any) holding extracted sidecar images.
+//
+// `id` is chosen by the frontend: progress is emitted as `import-progress`
+// events carrying it, and `cancel_import(id)` stops the conversion at the
+// next page/slide/sheet boundary. A failed or cancelled import removes its
+// staging directory under `imports/`.
 #[tauri::command]
-async fn import_document(path: String, format: String) -> Result<ImportResult, String> {
-    tokio::task::spawn_blocking(move || {
+async fn import_document(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    format: String,
+    id: String,
+) -> Result<ImportResult, String> {
+    let cancel = convert::progress::CancelToken::default();
+    state
+        .active_imports
+        .lock()
+        .map_err(|_| "Failed to lock import state".to_string())?
+        .insert(id.clone(), cancel.clone());
+
+    let progress_id = id.clone();
+    let progress =
+        convert::progress::ImportProgress::new(cancel).with_callback(move |done, total| {
+            let payload = ImportProgressPayload {
+                id: progress_id.clone(),
+                done,
+                total,
+            };
+            let _ = app.emit("import-progress", payload);
+        });
+
+    let result = tokio::task::spawn_blocking(move || {
+        let options = UserSettings::load()?.unwrap_or_default().import;
         let import_dir = new_import_dir()?;
         let assets_dir = import_dir.join("assets");
-        // Downscaling is off by default (preserve originals verbatim). Wire a
-        // `max_image_dimension` user setting through here (e.g. default
-        // Some(2048)) once a Settings UI toggle for it exists.
-        let mut media = convert::media::MediaSink::new(assets_dir).with_max_dimension(None);
-
-        let markdown = match format.as_str() {
-            "docx" => convert::docx::docx_to_markdown(&path, &mut media).map_err(String::from),
-            "xlsx" => convert::xlsx::xlsx_to_markdown(&path, &mut media).map_err(String::from),
-            "pdf" => convert::pdf::pdf_to_markdown(&path, &mut media).map_err(String::from),
-            "pptx" => convert::pptx::pptx_to_markdown(&path, &mut media).map_err(String::from),
-            other => Err(format!("Unsupported import format: {}", other)),
-        }?;
+        // Downscaling is off by default (preserve originals verbatim); set
+        // `import.max_image_dimension` in settings.json to cap it.
+        let mut media = convert::media::MediaSink::new(assets_dir)
+            .with_max_dimension(options.max_image_dimension);
+        let mut report = convert::report::ImportReport::default();
+
+        let markdown = match convert::import_to_markdown(
+            Some(&format),
+            &path,
+            &mut media,
+            &options,
+            &mut report,
+            &progress,
+        ) {
+            Ok(markdown) => markdown,
+            Err(e) => {
+                // Nothing references a failed/cancelled import's media.
+                let _ = fs::remove_dir_all(&import_dir);
+                return Err(e.0);
+            }
+        };
 
         // Text-only import: don't leave an empty staging directory behind.
         if media.is_empty() {
@@ -734,16 +839,37 @@ async fn import_document(path: String, format: String) -> Result<ImportResult, S
             return Ok(ImportResult {
                 markdown,
                 media_dir: String::new(),
+                report,
             });
         }
 
         Ok(ImportResult {
             markdown,
             media_dir: import_dir.to_string_lossy().to_string(),
+            report,
         })
     })
     .await
-    .map_err(|e| format!("Task error: {}", e))?
+    .map_err(|e| format!("Task error: {}", e));
+
+    if let Ok(mut imports) = state.active_imports.lock() {
+        imports.remove(&id);
+    }
+    result?
+}
+
+// Cancel an in-flight `import_document` call. A no-op for an unknown or
+// already-finished id; the import itself then fails with "Import cancelled".
+#[tauri::command]
+fn cancel_import(id: String, state: State<AppState>) -> Result<(), String> {
+    let imports = state
+        .active_imports
+        .lock()
+        .map_err(|_| "Failed to lock import state".to_string())?;
+    if let Some(token) = imports.get(&id) {
+        token.cancel();
+    }
+    Ok(())
 }
 
 /// Relocate an import's staging media directory to sit next to a saved
@@ -807,19 +933,25 @@ fn copy_dir_recursive(from: &std::path::Path, to: &std::path::Path) -> std::io::
 // Export Markdown content to a non-markdown format
 #[tauri::command]
 async fn export_document(content: String, path: String, format: String) -> Result<(), String> {
-    tokio::task::spawn_blocking(move || match format.as_str() {
-        "pdf"  => convert::pdf::markdown_to_pdf(&content, &path).map_err(String::from),
-        "html" => convert::html::markdown_to_html(&content, &path).map_err(String::from),
-        other  => Err(format!("Unsupported export format: {}", other)),
+    tokio::task::spawn_blocking(move || {
+        convert::export_from_markdown(&format, &content, &path).map_err(String::from)
     })
     .await
     .map_err(|e| format!("Task error: {}", e))?
 }
 
+// List the registered import/export formats, so the frontend can build its
+// menus and file-dialog filters from the converter registry.
+#[tauri::command]
+fn list_formats() -> convert::FormatList {
+    convert::registry().formats()
+}
+
 // Drain any pending open-file requests (used on app startup).
 #[tauri::command]
 fn take_pending_open_files(state: State<AppState>) -> Result<Vec<String>, String> {
-    let mut pending = state.pending_open_files
+    let mut pending = state
+        .pending_open_files
         .lock()
         .map_err(|_| "Failed to lock pending open files".to_string())?;
     Ok(pending.drain(..).collect())
@@ -865,7 +997,7 @@ fn emit_editor_command(app: &tauri::AppHandle, command: &str, level: Option<u8>)
  * Used by menu event handlers
  */
 fn save_language_to_storage(lang: &str) -> Result<(), String> {
-    let mut settings = UserSettings::load()?.unwrap_or_else(|| UserSettings { language: "en".to_string() });
+    let mut settings = UserSettings::load()?.unwrap_or_default();
     settings.language = lang.to_string();
     settings.save()
 }
@@ -892,7 +1024,11 @@ fn normalize_open_path(arg: &str) -> Option<String> {
         return None;
     }
 
-    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_lowercase();
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase();
     if ext != "md" && ext != "markdown" {
         return None;
     }
@@ -957,20 +1093,33 @@ fn create_app_menu<R: tauri::Runtime>(handle: &AppHandle<R>, lang: &str) -> taur
     // macOS App Name Menu — the leftmost slot (app name is filled automatically by macOS)
     #[cfg(target_os = "macos")]
     {
-        let preferences_item = MenuItem::with_id(handle, "app_preferences", get_label(lang, "app_preferences"), true, Some("CmdOrCtrl+Comma"))?;
+        let preferences_item = MenuItem::with_id(
+            handle,
+            "app_preferences",
+            get_label(lang, "app_preferences"),
+            true,
+            Some("CmdOrCtrl+Comma"),
+        )?;
         let app_menu = Submenu::with_items(
             handle,
             "Pourdown",
             true,
             &[
-                &PredefinedMenuItem::about(handle, Some(&get_label(lang, "app_about")), Some(about_metadata(&pkg_version)))?,
+                &PredefinedMenuItem::about(
+                    handle,
+                    Some(&get_label(lang, "app_about")),
+                    Some(about_metadata(&pkg_version)),
+                )?,
                 &PredefinedMenuItem::separator(handle)?,
                 &preferences_item,
                 &PredefinedMenuItem::separator(handle)?,
                 &PredefinedMenuItem::services(handle, Some(&get_label(lang, "app_services")))?,
                 &PredefinedMenuItem::separator(handle)?,
                 &PredefinedMenuItem::hide(handle, Some(&get_label(lang, "app_hide")))?,
-                &PredefinedMenuItem::hide_others(handle, Some(&get_label(lang, "app_hide_others")))?,
+                &PredefinedMenuItem::hide_others(
+                    handle,
+                    Some(&get_label(lang, "app_hide_others")),
+                )?,
                 &PredefinedMenuItem::show_all(handle, Some(&get_label(lang, "app_show_all")))?,
                 &PredefinedMenuItem::separator(handle)?,
                 &PredefinedMenuItem::quit(handle, Some(&get_label(lang, "app_quit")))?,
@@ -980,25 +1129,96 @@ fn create_app_menu<R: tauri::Runtime>(handle: &AppHandle<R>, lang: &str) -> taur
     }
 
     // File Menu
-    let new_item = MenuItem::with_id(handle, "file_new", get_label(lang, "file_new"), true, Some("CmdOrCtrl+N"))?;
-    let open_item = MenuItem::with_id(handle, "file_open", get_label(lang, "file_open"), true, Some("CmdOrCtrl+O"))?;
-    let save_item = MenuItem::with_id(handle, "file_save", get_label(lang, "file_save"), true, Some("CmdOrCtrl+S"))?;
-    let save_as_item = MenuItem::with_id(handle, "file_save_as", get_label(lang, "file_save_as"), true, Some("CmdOrCtrl+Shift+S"))?;
-    let close_document_item = MenuItem::with_id(handle, "file_close_document", get_label(lang, "file_close_document"), true, Some("CmdOrCtrl+W"))?;
-
-    let import_docx_item = MenuItem::with_id(handle, "file_import_docx", get_label(lang, "file_import_docx"), true, None::<&str>)?;
-    let import_xlsx_item = MenuItem::with_id(handle, "file_import_xlsx", get_label(lang, "file_import_xlsx"), true, None::<&str>)?;
-    let import_pdf_item  = MenuItem::with_id(handle, "file_import_pdf",  get_label(lang, "file_import_pdf"),  true, None::<&str>)?;
-    let import_pptx_item = MenuItem::with_id(handle, "file_import_pptx", get_label(lang, "file_import_pptx"), true, None::<&str>)?;
+    let new_item = MenuItem::with_id(
+        handle,
+        "file_new",
+        get_label(lang, "file_new"),
+        true,
+        Some("CmdOrCtrl+N"),
+    )?;
+    let open_item = MenuItem::with_id(
+        handle,
+        "file_open",
+        get_label(lang, "file_open"),
+        true,
+        Some("CmdOrCtrl+O"),
+    )?;
+    let save_item = MenuItem::with_id(
+        handle,
+        "file_save",
+        get_label(lang, "file_save"),
+        true,
+        Some("CmdOrCtrl+S"),
+    )?;
+    let save_as_item = MenuItem::with_id(
+        handle,
+        "file_save_as",
+        get_label(lang, "file_save_as"),
+        true,
+        Some("CmdOrCtrl+Shift+S"),
+    )?;
+    let close_document_item = MenuItem::with_id(
+        handle,
+        "file_close_document",
+        get_label(lang, "file_close_document"),
+        true,
+        Some("CmdOrCtrl+W"),
+    )?;
+
+    let import_docx_item = MenuItem::with_id(
+        handle,
+        "file_import_docx",
+        get_label(lang, "file_import_docx"),
+        true,
+        None::<&str>,
+    )?;
+    let import_xlsx_item = MenuItem::with_id(
+        handle,
+        "file_import_xlsx",
+        get_label(lang, "file_import_xlsx"),
+        true,
+        None::<&str>,
+    )?;
+    let import_pdf_item = MenuItem::with_id(
+        handle,
+        "file_import_pdf",
+        get_label(lang, "file_import_pdf"),
+        true,
+        None::<&str>,
+    )?;
+    let import_pptx_item = MenuItem::with_id(
+        handle,
+        "file_import_pptx",
+        get_label(lang, "file_import_pptx"),
+        true,
+        None::<&str>,
+    )?;
     let import_submenu = Submenu::with_items(
         handle,
         get_label(lang, "file_import"),
         true,
-        &[&import_docx_item, &import_xlsx_item, &import_pdf_item, &import_pptx_item],
+        &[
+            &import_docx_item,
+            &import_xlsx_item,
+            &import_pdf_item,
+            &import_pptx_item,
+        ],
     )?;
 
-    let export_html_item = MenuItem::with_id(handle, "file_export_html", get_label(lang, "file_export_html"), true, None::<&str>)?;
-    let export_pdf_item  = MenuItem::with_id(handle, "file_export_pdf",  get_label(lang, "file_export_pdf"),  true, None::<&str>)?;
+    let export_html_item = MenuItem::with_id(
+        handle,
+        "file_export_html",
+        get_label(lang, "file_export_html"),
+        true,
+        None::<&str>,
+    )?;
+    let export_pdf_item = MenuItem::with_id(
+        handle,
+        "file_export_pdf",
+        get_label(lang, "file_export_pdf"),
+        true,
+        None::<&str>,
+    )?;
     let export_submenu = Submenu::with_items(
         handle,
         get_label(lang, "file_export"),
@@ -1025,8 +1245,20 @@ fn create_app_menu<R: tauri::Runtime>(handle: &AppHandle<R>, lang: &str) -> taur
     menu.append(&file_menu)?;
 
     // Edit Menu
-    let find_item = MenuItem::with_id(handle, "edit_find", get_label(lang, "edit_find"), true, Some("CmdOrCtrl+F"))?;
-    let find_in_files_item = MenuItem::with_id(handle, "edit_find_in_files", get_label(lang, "edit_find_in_files"), true, Some("CmdOrCtrl+Shift+F"))?;
+    let find_item = MenuItem::with_id(
+        handle,
+        "edit_find",
+        get_label(lang, "edit_find"),
+        true,
+        Some("CmdOrCtrl+F"),
+    )?;
+    let find_in_files_item = MenuItem::with_id(
+        handle,
+        "edit_find_in_files",
+        get_label(lang, "edit_find_in_files"),
+        true,
+        Some("CmdOrCtrl+Shift+F"),
+    )?;
     let edit_menu = Submenu::with_items(
         handle,
         get_label(lang, "edit"),
@@ -1048,22 +1280,118 @@ fn create_app_menu<R: tauri::Runtime>(handle: &AppHandle<R>, lang: &str) -> taur
     menu.append(&edit_menu)?;
 
     // Format Menu
-    let bold_item = MenuItem::with_id(handle, "editor_bold", get_label(lang, "format_bold"), true, Some("CmdOrCtrl+B"))?;
-    let italic_item = MenuItem::with_id(handle, "editor_italic", get_label(lang, "format_italic"), true, Some("CmdOrCtrl+I"))?;
-    let strike_item = MenuItem::with_id(handle, "editor_strike", get_label(lang, "format_strike"), true, Some("CmdOrCtrl+Shift+X"))?;
-    let inline_code_item = MenuItem::with_id(handle, "editor_inline_code", get_label(lang, "format_inline_code"), true, Some("CmdOrCtrl+Shift+C"))?;
-    let paragraph_item = MenuItem::with_id(handle, "editor_paragraph", get_label(lang, "format_paragraph"), true, None::<&str>)?;
-    let heading_1_item = MenuItem::with_id(handle, "editor_heading_1", get_label(lang, "format_heading_1"), true, Some("CmdOrCtrl+Option+1"))?;
-    let heading_2_item = MenuItem::with_id(handle, "editor_heading_2", get_label(lang, "format_heading_2"), true, Some("CmdOrCtrl+Option+2"))?;
-    let heading_3_item = MenuItem::with_id(handle, "editor_heading_3", get_label(lang, "format_heading_3"), true, Some("CmdOrCtrl+Option+3"))?;
-    let heading_4_item = MenuItem::with_id(handle, "editor_heading_4", get_label(lang, "format_heading_4"), true, Some("CmdOrCtrl+Option+4"))?;
-    let heading_5_item = MenuItem::with_id(handle, "editor_heading_5", get_label(lang, "format_heading_5"), true, Some("CmdOrCtrl+Option+5"))?;
-    let heading_6_item = MenuItem::with_id(handle, "editor_heading_6", get_label(lang, "format_heading_6"), true, Some("CmdOrCtrl+Option+6"))?;
-    let bullet_list_item = MenuItem::with_id(handle, "editor_bullet_list", get_label(lang, "format_bullet_list"), true, Some("CmdOrCtrl+Shift+8"))?;
-    let ordered_list_item = MenuItem::with_id(handle, "editor_ordered_list", get_label(lang, "format_ordered_list"), true, Some("CmdOrCtrl+Shift+7"))?;
-    let blockquote_item = MenuItem::with_id(handle, "editor_blockquote", get_label(lang, "format_blockquote"), true, None::<&str>)?;
-    let code_block_item = MenuItem::with_id(handle, "editor_code_block", get_label(lang, "format_code_block"), true, None::<&str>)?;
-    let horizontal_rule_item = MenuItem::with_id(handle, "editor_horizontal_rule", get_label(lang, "format_horizontal_rule"), true, None::<&str>)?;
+    let bold_item = MenuItem::with_id(
+        handle,
+        "editor_bold",
+        get_label(lang, "format_bold"),
+        true,
+        Some("CmdOrCtrl+B"),
+    )?;
+    let italic_item = MenuItem::with_id(
+        handle,
+        "editor_italic",
+        get_label(lang, "format_italic"),
+        true,
+        Some("CmdOrCtrl+I"),
+    )?;
+    let strike_item = MenuItem::with_id(
+        handle,
+        "editor_strike",
+        get_label(lang, "format_strike"),
+        true,
+        Some("CmdOrCtrl+Shift+X"),
+    )?;
+    let inline_code_item = MenuItem::with_id(
+        handle,
+        "editor_inline_code",
+        get_label(lang, "format_inline_code"),
+        true,
+        Some("CmdOrCtrl+Shift+C"),
+    )?;
+    let paragraph_item = MenuItem::with_id(
+        handle,
+        "editor_paragraph",
+        get_label(lang, "format_paragraph"),
+        true,
+        None::<&str>,
+    )?;
+    let heading_1_item = MenuItem::with_id(
+        handle,
+        "editor_heading_1",
+        get_label(lang, "format_heading_1"),
+        true,
+        Some("CmdOrCtrl+Option+1"),
+    )?;
+    let heading_2_item = MenuItem::with_id(
+        handle,
+        "editor_heading_2",
+        get_label(lang, "format_heading_2"),
+        true,
+        Some("CmdOrCtrl+Option+2"),
+    )?;
+    let heading_3_item = MenuItem::with_id(
+        handle,
+        "editor_heading_3",
+        get_label(lang, "format_heading_3"),
+        true,
+        Some("CmdOrCtrl+Option+3"),
+    )?;
+    let heading_4_item = MenuItem::with_id(
+        handle,
+        "editor_heading_4",
+        get_label(lang, "format_heading_4"),
+        true,
+        Some("CmdOrCtrl+Option+4"),
+    )?;
+    let heading_5_item = MenuItem::with_id(
+        handle,
+        "editor_heading_5",
+        get_label(lang, "format_heading_5"),
+        true,
+        Some("CmdOrCtrl+Option+5"),
+    )?;
+    let heading_6_item = MenuItem::with_id(
+        handle,
+        "editor_heading_6",
+        get_label(lang, "format_heading_6"),
+        true,
+        Some("CmdOrCtrl+Option+6"),
+    )?;
+    let bullet_list_item = MenuItem::with_id(
+        handle,
+        "editor_bullet_list",
+        get_label(lang, "format_bullet_list"),
+        true,
+        Some("CmdOrCtrl+Shift+8"),
+    )?;
+    let ordered_list_item = MenuItem::with_id(
+        handle,
+        "editor_ordered_list",
+        get_label(lang, "format_ordered_list"),
+        true,
+        Some("CmdOrCtrl+Shift+7"),
+    )?;
+    let blockquote_item = MenuItem::with_id(
+        handle,
+        "editor_blockquote",
+        get_label(lang, "format_blockquote"),
+        true,
+        None::<&str>,
+    )?;
+    let code_block_item = MenuItem::with_id(
+        handle,
+        "editor_code_block",
+        get_label(lang, "format_code_block"),
+        true,
+        None::<&str>,
+    )?;
+    let horizontal_rule_item = MenuItem::with_id(
+        handle,
+        "editor_horizontal_rule",
+        get_label(lang, "format_horizontal_rule"),
+        true,
+        None::<&str>,
+    )?;
 
     let text_menu = Submenu::with_items(
         handle,
@@ -1075,7 +1403,15 @@ fn create_app_menu<R: tauri::Runtime>(handle: &AppHandle<R>, lang: &str) -> taur
         handle,
         get_label(lang, "format_headings"),
         true,
-        &[&paragraph_item, &heading_1_item, &heading_2_item, &heading_3_item, &heading_4_item, &heading_5_item, &heading_6_item],
+        &[
+            &paragraph_item,
+            &heading_1_item,
+            &heading_2_item,
+            &heading_3_item,
+            &heading_4_item,
+            &heading_5_item,
+            &heading_6_item,
+        ],
     )?;
     let list_menu = Submenu::with_items(
         handle,
@@ -1098,22 +1434,70 @@ fn create_app_menu<R: tauri::Runtime>(handle: &AppHandle<R>, lang: &str) -> taur
     menu.append(&format_menu)?;
 
     // View Menu
-    let theme_github_light = MenuItem::with_id(handle, "view_theme_github_light", "GitHub Light", true, None::<&str>)?;
-    let theme_solarized_light = MenuItem::with_id(handle, "view_theme_solarized_light", "Solarized Light", true, None::<&str>)?;
-    let theme_dracula = MenuItem::with_id(handle, "view_theme_dracula", "Dracula", true, None::<&str>)?;
+    let theme_github_light = MenuItem::with_id(
+        handle,
+        "view_theme_github_light",
+        "GitHub Light",
+        true,
+        None::<&str>,
+    )?;
+    let theme_solarized_light = MenuItem::with_id(
+        handle,
+        "view_theme_solarized_light",
+        "Solarized Light",
+        true,
+        None::<&str>,
+    )?;
+    let theme_dracula =
+        MenuItem::with_id(handle, "view_theme_dracula", "Dracula", true, None::<&str>)?;
     let theme_nord = MenuItem::with_id(handle, "view_theme_nord", "Nord", true, None::<&str>)?;
-    let theme_one_dark_pro = MenuItem::with_id(handle, "view_theme_one_dark_pro", "One Dark Pro", true, None::<&str>)?;
-    let theme_tokyo_night = MenuItem::with_id(handle, "view_theme_tokyo_night", "Tokyo Night", true, None::<&str>)?;
-    let theme_gruvbox = MenuItem::with_id(handle, "view_theme_gruvbox", "Gruvbox", true, None::<&str>)?;
+    let theme_one_dark_pro = MenuItem::with_id(
+        handle,
+        "view_theme_one_dark_pro",
+        "One Dark Pro",
+        true,
+        None::<&str>,
+    )?;
+    let theme_tokyo_night = MenuItem::with_id(
+        handle,
+        "view_theme_tokyo_night",
+        "Tokyo Night",
+        true,
+        None::<&str>,
+    )?;
+    let theme_gruvbox =
+        MenuItem::with_id(handle, "view_theme_gruvbox", "Gruvbox", true, None::<&str>)?;
     let theme_menu = Submenu::with_items(
         handle,
         get_label(lang, "view_theme"),
         true,
-        &[&theme_github_light, &theme_solarized_light, &theme_dracula, &theme_nord, &theme_one_dark_pro, &theme_tokyo_night, &theme_gruvbox],
+        &[
+            &theme_github_light,
+            &theme_solarized_light,
+            &theme_dracula,
+            &theme_nord,
+            &theme_one_dark_pro,
+            &theme_tokyo_night,
+            &theme_gruvbox,
+        ],
     )?;
 
-    let lang_en_item = CheckMenuItem::with_id(handle, "lang_en", get_label(lang, "lang_en"), true, lang == "en", None::<&str>)?;
-    let lang_zh_item = CheckMenuItem::with_id(handle, "lang_zh", get_label(lang, "lang_zh"), true, lang == "zh", None::<&str>)?;
+    let lang_en_item = CheckMenuItem::with_id(
+        handle,
+        "lang_en",
+        get_label(lang, "lang_en"),
+        true,
+        lang == "en",
+        None::<&str>,
+    )?;
+    let lang_zh_item = CheckMenuItem::with_id(
+        handle,
+        "lang_zh",
+        get_label(lang, "lang_zh"),
+        true,
+        lang == "zh",
+        None::<&str>,
+    )?;
     let language_menu = Submenu::with_items(
         handle,
         get_label(lang, "view_language"),
@@ -1134,7 +1518,13 @@ fn create_app_menu<R: tauri::Runtime>(handle: &AppHandle<R>, lang: &str) -> taur
     // convention); that submenu doesn't exist on Windows/Linux, so give it a
     // home in the View menu there instead, keeping the same accelerator.
     #[cfg(not(target_os = "macos"))]
-    let preferences_view_item = MenuItem::with_id(handle, "app_preferences", get_label(lang, "app_preferences"), true, Some("CmdOrCtrl+Comma"))?;
+    let preferences_view_item = MenuItem::with_id(
+        handle,
+        "app_preferences",
+        get_label(lang, "app_preferences"),
+        true,
+        Some("CmdOrCtrl+Comma"),
+    )?;
 
     #[cfg(not(target_os = "macos"))]
     let view_menu = Submenu::with_items(
@@ -1187,29 +1577,43 @@ fn create_app_menu<R: tauri::Runtime>(handle: &AppHandle<R>, lang: &str) -> taur
         handle,
         get_label(lang, "help"),
         true,
-        &[&PredefinedMenuItem::about(handle, Some(&get_label(lang, "app_about")), Some(about_metadata(&pkg_version)))?],
+        &[&PredefinedMenuItem::about(
+            handle,
+            Some(&get_label(lang, "app_about")),
+            Some(about_metadata(&pkg_version)),
+        )?],
     )?;
     #[cfg(target_os = "macos")]
-    let help_menu = Submenu::with_items(
-        handle,
-        get_label(lang, "help"),
-        true,
-        &[],
-    )?;
+    let help_menu = Submenu::with_items(handle, get_label(lang, "help"), true, &[])?;
     menu.append(&help_menu)?;
 
     Ok(menu)
 }
 
 fn main() {
+    // Headless batch conversion (`Pourdown --convert ...`) never opens a
+    // window; it runs to completion and exits with the CLI's status code.
+    let cli_args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::is_convert_invocation(&cli_args) {
+        let options = UserSettings::load()
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+            .import;
+        std::process::exit(cli::run(&cli_args[1..], &options));
+    }
+
     // Language initialization priority (Tauri v2 best practice):
     // 1. Load from persistent storage (user saved preference)
     // 2. Fall back to system locale
     // 3. Default to English
-    
+
     let default_language = match UserSettings::load() {
         Ok(Some(settings)) => {
-            debug_log!("✅ User settings loaded from storage: language={}", settings.language);
+            debug_log!(
+                "✅ User settings loaded from storage: language={}",
+                settings.language
+            );
             settings.language
         }
         Ok(None) => {
@@ -1217,8 +1621,11 @@ fn main() {
             match tauri_plugin_os::locale() {
                 Some(locale_str) => {
                     let normalized = normalize_language(&locale_str);
-                    debug_log!("🌍 No saved preference; using system locale: {} → normalized to: {}",
-                             locale_str, normalized);
+                    debug_log!(
+                        "🌍 No saved preference; using system locale: {} → normalized to: {}",
+                        locale_str,
+                        normalized
+                    );
                     normalized
                 }
                 None => {
@@ -1233,8 +1640,11 @@ fn main() {
             match tauri_plugin_os::locale() {
                 Some(locale_str) => {
                     let normalized = normalize_language(&locale_str);
-                    debug_log!("🌍 Falling back to system locale: {} → normalized to: {}",
-                             locale_str, normalized);
+                    debug_log!(
+                        "🌍 Falling back to system locale: {} → normalized to: {}",
+                        locale_str,
+                        normalized
+                    );
                     normalized
                 }
                 None => {
@@ -1244,7 +1654,7 @@ fn main() {
             }
         }
     };
-    
+
     let app = tauri::Builder::default()
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_fs::init())
@@ -1291,10 +1701,7 @@ fn main() {
                     .resolve(dll_name, tauri::path::BaseDirectory::Resource)
                 {
                     Ok(p) if p.exists() => convert::pdf::set_pdfium_lib_path(p),
-                    Ok(p) => eprintln!(
-                        "[pdfium] resource resolved but missing on disk: {:?}",
-                        p
-                    ),
+                    Ok(p) => eprintln!("[pdfium] resource resolved but missing on disk: {:?}", p),
                     Err(e) => eprintln!("[pdfium] failed to resolve resource path: {e}"),
                 }
             }
@@ -1441,10 +1848,20 @@ fn main() {
             } else if event.id() == "edit_find_in_files" {
                 let _ = app.emit("menu-find-in-files", ());
             } else if event.id().0.starts_with("file_import_") {
-                let fmt = event.id().0.strip_prefix("file_import_").unwrap_or("").to_string();
+                let fmt = event
+                    .id()
+                    .0
+                    .strip_prefix("file_import_")
+                    .unwrap_or("")
+                    .to_string();
                 let _ = app.emit("menu-import", fmt);
             } else if event.id().0.starts_with("file_export_") {
-                let fmt = event.id().0.strip_prefix("file_export_").unwrap_or("").to_string();
+                let fmt = event
+                    .id()
+                    .0
+                    .strip_prefix("file_export_")
+                    .unwrap_or("")
+                    .to_string();
                 let _ = app.emit("menu-export", fmt);
             }
         })
@@ -1461,7 +1878,9 @@ fn main() {
             update_menu_item_state,
             enable_menu_item,
             import_document,
+            cancel_import,
             export_document,
+            list_formats,
             relocate_media,
             discard_media,
             take_pending_open_files,
@@ -1471,6 +1890,7 @@ fn main() {
             set_language,
             get_user_settings,
             save_language_preference,
+            save_import_options,
             search_in_files,
         ])
         .build(tauri::generate_context!())
@@ -12,6 +12,8 @@ import { TabBar } from '@/components/Tabs/TabBar';
 import { UnsavedCloseDialog } from '@/components/Tabs/UnsavedCloseDialog';
 import { PreferencesDialog } from '@/components/Preferences/PreferencesDialog';
 import { documentDisplayName } from '@/lib/documentTitle';
+import { formatImportWarning, type ImportReport } from '@/lib/importReport';
+import { importerForExtension, type FormatList } from '@/lib/formats';
 import { useDocumentStore } from '@/stores/documentStore';
 import { useUIStore } from '@/stores/uiStore';
 import { useEditorStore } from '@/stores/editorStore';
@@ -47,11 +49,21 @@ function App() {
   const language = useSettingsStore((state) => state.language);
   const hasInitializedDocument = useRef(false);
   const menuUnlistenersRef = useRef<Array<() => void>>([]);
+  // `list_formats`, fetched on first use: the import dialog filters and the
+  // drop-to-import extension mapping both come from the backend registry.
+  const formatListRef = useRef<Promise<FormatList> | null>(null);
   const [importExportStatus, setImportExportStatus] = useState<{
     type: 'import' | 'export';
     format: string;
     state: 'loading' | 'success' | 'error';
     message?: string;
+    // Import report warnings (see `convert::report` in the backend); a
+    // success toast carrying any stays up until dismissed, like an error.
+    warnings?: string[];
+    // In-flight import: the id passed to `import_document`/`cancel_import`
+    // and the latest `import-progress` percentage.
+    importId?: string;
+    percent?: number;
   } | null>(null);
   const [pendingClose, setPendingClose] = useState<{ id: string } | null>(null);
   const [isDragOver, setIsDragOver] = useState(false);
@@ -231,12 +243,24 @@ function App() {
   // Runs an import for a known file path (shared by the file-dialog flow in
   // `handleImport` and by dropped-file handling in `handleDroppedPaths`).
   const runImport = useCallback(async (filePath: string, format: string) => {
+    const importId = crypto.randomUUID();
+    const unlistenProgress = await listen<{ id: string; done: number; total: number }>(
+      'import-progress',
+      (event) => {
+        const { id, done, total } = event.payload;
+        if (id !== importId) return;
+        const percent = total > 0 ? Math.floor((done * 100) / total) : 100;
+        setImportExportStatus((prev) =>
+          prev?.importId === importId && prev.state === 'loading' ? { ...prev, percent } : prev
+        );
+      }
+    );
     try {
-      setImportExportStatus({ type: 'import', format, state: 'loading' });
+      setImportExportStatus({ type: 'import', format, state: 'loading', importId });
 
-      const result = await invoke<{ markdown: string; media_dir: string }>(
+      const result = await invoke<{ markdown: string; media_dir: string; report: ImportReport }>(
         'import_document',
-        { path: filePath, format }
+        { path: filePath, format, id: importId }
       );
 
       const importedDoc = {
@@ -252,9 +276,17 @@ function App() {
         activeDocumentId: importedDoc.id,
       }));
 
-      setImportExportStatus({ type: 'import', format, state: 'success' });
-      setTimeout(() => setImportExportStatus(null), 3000);
+      const warnings = result.report.warnings.map(formatImportWarning);
+      setImportExportStatus({ type: 'import', format, state: 'success', warnings });
+      if (warnings.length === 0) {
+        setTimeout(() => setImportExportStatus(null), 3000);
+      }
     } catch (err) {
+      // A user-cancelled import isn't a failure worth a sticky toast.
+      if (String(err) === 'Import cancelled') {
+        setImportExportStatus(null);
+        return;
+      }
       console.error('Import failed:', err);
       // Left on screen until manually dismissed (see the toast's close
       // button) rather than auto-clearing, so the real backend message
@@ -265,43 +297,35 @@ function App() {
         state: 'error',
         message: String(err),
       });
+    } finally {
+      unlistenProgress();
     }
   }, []);
 
+  const loadFormats = useCallback((): Promise<FormatList> => {
+    if (!formatListRef.current) {
+      formatListRef.current = invoke<FormatList>('list_formats').catch((error) => {
+        formatListRef.current = null;
+        throw error;
+      });
+    }
+    return formatListRef.current;
+  }, []);
+
   const handleImport = useCallback(async (format: string) => {
-    const extensionMap: Record<string, string[]> = {
-      docx: ['docx'],
-      xlsx: ['xlsx', 'xls', 'ods'],
-      pdf: ['pdf'],
-      pptx: ['pptx', 'ppt'],
-    };
-    const filterName: Record<string, string> = {
-      docx: 'Word Document',
-      xlsx: 'Spreadsheet',
-      pdf: 'PDF Document',
-      pptx: 'PowerPoint Presentation',
-    };
+    const formats = await loadFormats();
+    const importer = formats.importers.find((f) => f.id === format);
 
     const selected = await open({
       multiple: false,
-      filters: [{ name: filterName[format] ?? format.toUpperCase(), extensions: extensionMap[format] ?? [format] }],
+      filters: [{ name: importer?.name ?? format.toUpperCase(), extensions: importer?.extensions ?? [format] }],
     });
 
     const filePath = Array.isArray(selected) ? selected[0] : selected;
     if (!filePath || typeof filePath !== 'string') return;
 
     await runImport(filePath, format);
-  }, [runImport]);
-
-  // Maps a dropped file's extension to the import format used by
-  // `import_document`, mirroring `handleImport`'s extensionMap groups.
-  const importFormatForExtension = (ext: string): string | null => {
-    if (ext === 'docx') return 'docx';
-    if (ext === 'xlsx' || ext === 'xls' || ext === 'ods') return 'xlsx';
-    if (ext === 'pdf') return 'pdf';
-    if (ext === 'pptx' || ext === 'ppt') return 'pptx';
-    return null;
-  };
+  }, [loadFormats, runImport]);
 
   // Opens/imports files dropped onto the window. Markdown opens directly
   // (loadDocument already dedups by path via openDocument); other supported
@@ -314,16 +338,16 @@ function App() {
         if (ext === 'md' || ext === 'markdown') {
           await loadDocument(path);
         } else {
-          const format = importFormatForExtension(ext);
-          if (format) {
-            await runImport(path, format);
+          const importer = importerForExtension(await loadFormats(), ext);
+          if (importer) {
+            await runImport(path, importer.id);
           }
         }
       } catch (error) {
         console.warn('Failed to open dropped file:', path, error);
       }
     }
-  }, [loadDocument, runImport]);
+  }, [loadDocument, loadFormats, runImport]);
 
   // Listen for files dropped onto the window. Tauri's native drag-drop is on
   // by default (no `dragDropEnabled: false` in tauri.conf.json), so the OS
@@ -808,7 +832,9 @@ function App() {
       {/* Import/Export status toast */}
       {importExportStatus && (
         <div className={`fixed bottom-4 right-4 z-50 rounded-lg px-4 py-3 text-sm shadow-lg ${
-          importExportStatus.state === 'error' ? 'max-w-lg' : 'max-w-sm'
+          importExportStatus.state === 'error' || importExportStatus.warnings?.length
+            ? 'max-w-lg'
+            : 'max-w-sm'
         } ${
           importExportStatus.state === 'loading'
             ? 'bg-muted text-muted-foreground'
@@ -817,20 +843,55 @@ function App() {
             : 'bg-destructive/15 text-destructive'
         }`}>
           {importExportStatus.state === 'loading' && (
-            <span>
-              {importExportStatus.type === 'import'
-                ? t('import_export.importing')
-                : t('import_export.exporting')}{' '}
-              {importExportStatus.format.toUpperCase()}…
-            </span>
+            <div className="flex items-center gap-3">
+              <span className="flex-1">
+                {importExportStatus.type === 'import'
+                  ? t('import_export.importing')
+                  : t('import_export.exporting')}{' '}
+                {importExportStatus.format.toUpperCase()}…
+                {importExportStatus.percent !== undefined && ` ${importExportStatus.percent}%`}
+              </span>
+              {importExportStatus.importId && (
+                <button
+                  type="button"
+                  onClick={() => {
+                    void invoke('cancel_import', { id: importExportStatus.importId });
+                  }}
+                  className="shrink-0 underline hover:text-foreground"
+                >
+                  {t('import_export.cancel')}
+                </button>
+              )}
+            </div>
           )}
-          {importExportStatus.state === 'success' && (
+          {importExportStatus.state === 'success' && !importExportStatus.warnings?.length && (
             <span>
               {importExportStatus.type === 'import'
                 ? t('import_export.import_success')
                 : t('import_export.export_success')}
             </span>
           )}
+          {importExportStatus.state === 'success' && !!importExportStatus.warnings?.length && (
+            <div className="flex items-start gap-2">
+              <div className="flex-1">
+                <p>{t('import_export.import_success')}</p>
+                <p className="mt-1">{t('import_export.import_warnings')}</p>
+                <ul className="mt-1 max-h-48 list-disc overflow-y-auto pl-4 text-xs select-text">
+                  {importExportStatus.warnings.map((w, i) => (
+                    <li key={i}>{w}</li>
+                  ))}
+                </ul>
+              </div>
+              <button
+                type="button"
+                onClick={() => setImportExportStatus(null)}
+                className="shrink-0 text-muted-foreground hover:text-foreground"
+                aria-label={t('common.close')}
+              >
+                ×
+              </button>
+            </div>
+          )}
           {importExportStatus.state === 'error' && (
             <div className="flex items-start gap-2">
               {/* The raw backend message (e.g. the pdfium load diagnostic on
@@ -17,6 +17,7 @@ import { useEditorLayout } from '@/hooks/useEditorLayout';
 import { debounce } from '@/lib/utils';
 import { injectFrontmatterAsCodeBlock, restoreFrontmatterFromCodeBlock, frontmatterLength } from '@/lib/frontmatterUtils';
 import { computeSegmentAnchor, resolveSegmentScrollTop, findAnchorHeading, type HeadingLandmark } from '@/lib/editorAnchor';
+import { headingSlugs } from '@/lib/headingSlug';
 import { createMarkdownExtensions } from '@/lib/markdownExtensions';
 import { HtmlBlock } from '@/extensions/rawHtml';
 import { MathInline, MathBlock } from '@/extensions/math';
@@ -213,8 +214,10 @@ export const Editor = memo(function Editor({ documentId, active }: EditorProps)
   // Cmd (macOS) / Ctrl (Windows) + click opens the link, matching the
   // modifier-click convention in VS Code/Obsidian/Typora; a plain click keeps
   // placing the caret so link text stays editable. Only http(s)/mailto are
-  // forwarded to the OS — imported/pasted markdown is untrusted, and
-  // in-document anchors or relative links have no meaningful desktop target.
+  // forwarded to the OS — imported/pasted markdown is untrusted, and relative
+  // links have no meaningful desktop target. In-document anchors (`#slug`,
+  // e.g. an imported Word cross-reference) scroll to the heading whose
+  // GitHub-style slug matches (see headingSlug.ts).
   //
   // Implemented as a `handleDOMEvents.click` ProseMirror plugin — the same
   // pattern `FootnoteClickPlugin` (src/extensions/footnotes.ts) already uses
@@ -232,13 +235,31 @@ export const Editor = memo(function Editor({ documentId, active }: EditorProps)
           key: new PluginKey('linkClickHandler'),
           props: {
             handleDOMEvents: {
-              click(_view, event) {
+              click(view, event) {
                 if (!event.metaKey && !event.ctrlKey) return false;
                 const target = event.target as HTMLElement | null;
                 const anchor = target?.closest('a[href]');
                 const href = anchor?.getAttribute('href');
                 if (!href) return false;
 
+                if (href.startsWith('#')) {
+                  const texts: string[] = [];
+                  view.state.doc.forEach((node) => {
+                    if (node.type.name === 'heading') texts.push(node.textContent);
+                  });
+                  let fragment = href.slice(1);
+                  try {
+                    fragment = decodeURIComponent(fragment);
+                  } catch {
+                    // Keep the raw fragment if it isn't valid percent-encoding.
+                  }
+                  const index = headingSlugs(texts).indexOf(fragment.toLowerCase());
+                  if (index === -1) return false;
+                  event.preventDefault();
+                  useEditorStore.getState().requestScrollToHeading(index);
+                  return true;
+                }
+
                 let scheme: string;
                 try {
                   scheme = new URL(href).protocol;
@@ -0,0 +1,13 @@
+import { describe, expect, it } from 'vitest';
+import { relocateAssetLinks } from './assetLinks';
+
+// Same case as `test_relocate_asset_links` in src-tauri/src/cli.rs.
+describe('relocateAssetLinks', () => {
+  it('rewrites Markdown links and HTML image sources', () => {
+    const md =
+      '![Chart](assets/image1.png)\n\n<table>\n<tr><td><img src="assets/image2.png" alt="" /></td></tr>\n</table>\n';
+    expect(relocateAssetLinks(md, 'report.assets')).toBe(
+      '![Chart](report.assets/image1.png)\n\n<table>\n<tr><td><img src="report.assets/image2.png" alt="" /></td></tr>\n</table>\n',
+    );
+  });
+});
@@ -0,0 +1,16 @@
+/**
+ * Point the `assets/...` image links an import emits at `dirName` (the
+ * `<name>.assets` folder next to the saved `.md`): Markdown `](assets/` links
+ * and the `src="assets/` of `<img>` tags inside HTML tables, which imported
+ * tables with merged cells fall back to.
+ *
+ * Must stay in sync with `relocate_asset_links` in src-tauri/src/cli.rs,
+ * which does the same for batch conversion.
+ */
+export function relocateAssetLinks(content: string, dirName: string): string {
+  return content
+    .split('](assets/')
+    .join(`](${dirName}/`)
+    .split('src="assets/')
+    .join(`src="${dirName}/`);
+}
@@ -0,0 +1,22 @@
+import { describe, expect, it } from 'vitest';
+import { importerForExtension, type FormatList } from './formats';
+
+const formats: FormatList = {
+  importers: [
+    { id: 'xlsx', name: 'Spreadsheet', extensions: ['xlsx', 'xls', 'ods'], mime_types: [] },
+    { id: 'pptx', name: 'PowerPoint Presentation', extensions: ['pptx'], mime_types: [] },
+  ],
+  exporters: [{ id: 'html', name: 'HTML Document', extensions: ['html', 'htm'], mime_types: [] }],
+};
+
+describe('importerForExtension', () => {
+  it('maps an extension to the importer that lists it', () => {
+    expect(importerForExtension(formats, 'ODS')?.id).toBe('xlsx');
+    expect(importerForExtension(formats, 'pptx')?.id).toBe('pptx');
+  });
+
+  it('ignores extensions no importer lists', () => {
+    expect(importerForExtension(formats, 'ppt')).toBeUndefined();
+    expect(importerForExtension(formats, 'html')).toBeUndefined();
+  });
+});
@@ -0,0 +1,20 @@
+// Mirrors `FormatList` in src-tauri/src/convert/mod.rs, returned by the
+// `list_formats` command: the registered importers and exporters.
+
+export interface FormatInfo {
+  id: string;
+  name: string;
+  extensions: string[];
+  mime_types: string[];
+}
+
+export interface FormatList {
+  importers: FormatInfo[];
+  exporters: FormatInfo[];
+}
+
+// The importer registered for a file extension (case-insensitive), if any.
+export function importerForExtension(formats: FormatList, ext: string): FormatInfo | undefined {
+  const lower = ext.toLowerCase();
+  return formats.importers.find((format) => format.extensions.includes(lower));
+}
@@ -0,0 +1,16 @@
+import { describe, expect, it } from 'vitest';
+import { headingSlugs } from './headingSlug';
+
+// Same cases as `test_heading_slug` in src-tauri/src/convert/docx.rs — the
+// importer and the editor have to agree on every slug.
+describe('headingSlugs', () => {
+  it('slugs headings the way the DOCX importer does', () => {
+    expect(headingSlugs(['Hello, World!', 'Hello World', 'Hello World', '2.1 Über_alles – now'])).toEqual([
+      'hello-world',
+      'hello-world-1',
+      'hello-world-2',
+      '21-über_alles--now',
+    ]);
+    expect(headingSlugs(['a-1', 'a', 'a'])).toEqual(['a-1', 'a', 'a-2']);
+  });
+});
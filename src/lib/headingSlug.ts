@@ -0,0 +1,28 @@
+/**
+ * GitHub-style anchor slugs for headings: lowercased, punctuation other than
+ * `-` and `_` removed, spaces turned into `-`, and `-1`, `-2`, ... appended to
+ * repeats. Takes every heading's text in document order (repeats are only
+ * numbered correctly when the whole list is slugged together).
+ *
+ * Must stay in sync with `heading_slug` in src-tauri/src/convert/docx.rs,
+ * which rewrites imported Word cross-references (`#_Toc…` bookmarks) to these
+ * slugs so `[text](#slug)` links resolve in the editor.
+ */
+export function headingSlugs(texts: string[]): string[] {
+  const used = new Map<string, number>();
+  return texts.map((text) => {
+    const base = text
+      .trim()
+      .toLowerCase()
+      .replace(/[^\p{L}\p{N} _-]/gu, '')
+      .replace(/ /g, '-');
+    let slug = base;
+    while (used.has(slug)) {
+      const count = (used.get(base) ?? 0) + 1;
+      used.set(base, count);
+      slug = `${base}-${count}`;
+    }
+    used.set(slug, 0);
+    return slug;
+  });
+}
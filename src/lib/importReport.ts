@@ -0,0 +1,33 @@
+// Mirrors `ImportReport` in src-tauri/src/convert/report.rs, returned by the
+// `import_document` command alongside the converted Markdown.
+
+export type ImportLocation =
+  | { type: 'page'; number: number }
+  | { type: 'slide'; number: number }
+  | { type: 'sheet'; name: string };
+
+export interface ImportWarning {
+  kind: string;
+  location: ImportLocation | null;
+  message: string;
+}
+
+export interface ImportReport {
+  warnings: ImportWarning[];
+  stats: {
+    pages: number | null;
+    tables: number;
+    images_written: number;
+    images_skipped: number;
+  };
+}
+
+// One-line rendering matching the backend's `Display` impl, e.g.
+// "slide 2: Unsupported image format: image3.emf".
+export function formatImportWarning(warning: ImportWarning): string {
+  const { location } = warning;
+  if (!location) return warning.message;
+  const where =
+    location.type === 'sheet' ? `sheet '${location.name}'` : `${location.type} ${location.number}`;
+  return `${where}: ${warning.message}`;
+}
@@ -1,5 +1,6 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
+import { relocateAssetLinks } from '@/lib/assetLinks';
 
 export interface Document {
   id: string;
@@ -125,7 +126,7 @@ export const useDocumentStore = create<DocumentState>((set, get) => ({
         const sidecarDirName = `${basenameWithoutExt(doc.path)}.assets`;
         const targetDir = `${dirname(doc.path)}/${sidecarDirName}`;
         await invoke('relocate_media', { from: `${assetDir}/assets`, to: targetDir });
-        content = content.split('](assets/').join(`](${sidecarDirName}/`);
+        content = relocateAssetLinks(content, sidecarDirName);
         assetDir = null;
       }
 